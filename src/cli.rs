@@ -0,0 +1,350 @@
+use {
+    crate::{
+        commands::{
+            account::{AccountCommand, fetch_acc_data, fetch_account_balance, transfer_sol},
+            cluster::{
+                ClusterCommand, fetch_block_height, fetch_cluster_version, fetch_current_slot,
+                fetch_epoch_info, fetch_inflation_info, fetch_supply_info, fetch_validators,
+            },
+            stake::{
+                StakeCommand, delegate_stake_account, process_deactivate_stake_account,
+                process_withdraw_stake, show_stake_account,
+            },
+        },
+        context::ScillaContext,
+        misc::{helpers::SolAmount, signer::read_signer, whitelist::check_destination},
+        ui::try_spinner,
+    },
+    anyhow::bail,
+    clap::{Parser, Subcommand, ValueEnum},
+    solana_pubkey::Pubkey,
+    std::path::PathBuf,
+};
+
+/// Command line arguments. Without a subcommand Scilla starts the
+/// interactive menu, with one it runs that single command and exits.
+#[derive(Debug, Parser)]
+#[command(name = "scilla", version, about = "Hacking through the Solana matrix")]
+pub struct Cli {
+    /// How command output is printed
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+
+    /// Confirm commands the menu asks about first, such as withdrawing stake
+    #[arg(long, short, global = true)]
+    pub yes: bool,
+
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Tables and styled text
+    Text,
+    /// One JSON object per line
+    Json,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CliCommand {
+    /// Account commands
+    #[command(subcommand)]
+    Account(AccountArgs),
+    /// Cluster commands
+    #[command(subcommand)]
+    Cluster(ClusterArgs),
+    /// Stake commands
+    #[command(subcommand)]
+    Stake(StakeArgs),
+}
+
+#[derive(Debug, Subcommand)]
+pub enum AccountArgs {
+    /// Show an account's owner, balance and data
+    Fetch { pubkey: Pubkey },
+    /// Check an account's SOL balance
+    Balance { pubkey: Pubkey },
+    /// Send SOL from the configured keypair
+    Transfer {
+        #[arg(long)]
+        to: Pubkey,
+        /// Amount in SOL
+        #[arg(long)]
+        amount: SolAmount,
+        /// Memo to attach, the configured default memo if left out
+        #[arg(long)]
+        memo: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ClusterArgs {
+    /// Current epoch and its progress
+    EpochInfo,
+    /// Latest confirmed slot
+    Slot,
+    /// Current block height
+    BlockHeight,
+    /// Active and delinquent validators
+    Validators,
+    /// Total and circulating supply
+    Supply,
+    /// Inflation parameters
+    Inflation,
+    /// Solana version of the cluster
+    Version,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum StakeArgs {
+    /// Delegate a stake account to a vote account
+    Delegate {
+        #[arg(long)]
+        stake: Pubkey,
+        #[arg(long)]
+        vote: Pubkey,
+        /// Stake authority keypair, the configured keypair if left out
+        #[arg(long)]
+        authority: Option<PathBuf>,
+    },
+    /// Deactivate a stake account
+    Deactivate {
+        #[arg(long)]
+        stake: Pubkey,
+    },
+    /// Withdraw SOL from a deactivated stake account
+    Withdraw {
+        #[arg(long)]
+        stake: Pubkey,
+        #[arg(long)]
+        recipient: Pubkey,
+        /// Amount in SOL
+        #[arg(long)]
+        amount: SolAmount,
+    },
+    /// Show a stake account
+    Show { pubkey: Pubkey },
+}
+
+impl CliCommand {
+    /// Runs the command with its arguments, failing with the reason it could
+    /// not be completed.
+    ///
+    /// Confirmations the menu asks before deactivating or withdrawing stake
+    /// are given by `yes`. Safety checks such as the destination whitelist,
+    /// the duplicate send guard and transaction previews still ask at the
+    /// terminal, and are declined when there is none.
+    pub async fn run(self, ctx: &ScillaContext, yes: bool) -> anyhow::Result<()> {
+        match self {
+            CliCommand::Account(args) => args.run(ctx).await,
+            CliCommand::Cluster(args) => args.run(ctx).await,
+            CliCommand::Stake(args) => args.run(ctx, yes).await,
+        }
+    }
+}
+
+impl AccountArgs {
+    async fn run(self, ctx: &ScillaContext) -> anyhow::Result<()> {
+        match self {
+            AccountArgs::Fetch { pubkey } => {
+                try_spinner(
+                    AccountCommand::FetchAccount.spinner_msg(),
+                    fetch_acc_data(ctx, &pubkey),
+                )
+                .await
+            }
+            AccountArgs::Balance { pubkey } => {
+                try_spinner(
+                    AccountCommand::Balance.spinner_msg(),
+                    fetch_account_balance(ctx, &pubkey),
+                )
+                .await
+            }
+            AccountArgs::Transfer { to, amount, memo } => {
+                let memo = memo
+                    .or_else(|| ctx.defaults().memo.clone())
+                    .unwrap_or_default();
+                check_destination(ctx, &to)?;
+                try_spinner(
+                    AccountCommand::Transfer.spinner_msg(),
+                    transfer_sol(ctx, to, amount.value(), &memo),
+                )
+                .await
+            }
+        }
+    }
+}
+
+impl ClusterArgs {
+    async fn run(self, ctx: &ScillaContext) -> anyhow::Result<()> {
+        match self {
+            ClusterArgs::EpochInfo => {
+                try_spinner(
+                    ClusterCommand::EpochInfo.spinner_msg(),
+                    fetch_epoch_info(ctx),
+                )
+                .await
+            }
+            ClusterArgs::Slot => {
+                try_spinner(
+                    ClusterCommand::CurrentSlot.spinner_msg(),
+                    fetch_current_slot(ctx),
+                )
+                .await
+            }
+            ClusterArgs::BlockHeight => {
+                try_spinner(
+                    ClusterCommand::BlockHeight.spinner_msg(),
+                    fetch_block_height(ctx),
+                )
+                .await
+            }
+            ClusterArgs::Validators => {
+                try_spinner(
+                    ClusterCommand::Validators.spinner_msg(),
+                    fetch_validators(ctx),
+                )
+                .await
+            }
+            ClusterArgs::Supply => {
+                try_spinner(
+                    ClusterCommand::SupplyInfo.spinner_msg(),
+                    fetch_supply_info(ctx),
+                )
+                .await
+            }
+            ClusterArgs::Inflation => {
+                try_spinner(
+                    ClusterCommand::Inflation.spinner_msg(),
+                    fetch_inflation_info(ctx),
+                )
+                .await
+            }
+            ClusterArgs::Version => {
+                try_spinner(
+                    ClusterCommand::ClusterVersion.spinner_msg(),
+                    fetch_cluster_version(ctx),
+                )
+                .await
+            }
+        }
+    }
+}
+
+impl StakeArgs {
+    async fn run(self, ctx: &ScillaContext, yes: bool) -> anyhow::Result<()> {
+        match self {
+            StakeArgs::Delegate {
+                stake,
+                vote,
+                authority,
+            } => {
+                let authority = read_signer(authority.as_deref().unwrap_or(ctx.keypair_path()))?;
+                try_spinner(
+                    StakeCommand::Delegate.spinner_msg(),
+                    delegate_stake_account(ctx, &stake, &vote, &*authority),
+                )
+                .await
+            }
+            StakeArgs::Deactivate { stake } => {
+                require_yes(yes, "deactivate stake")?;
+                try_spinner(
+                    StakeCommand::Deactivate.spinner_msg(),
+                    process_deactivate_stake_account(ctx, &stake),
+                )
+                .await
+            }
+            StakeArgs::Withdraw {
+                stake,
+                recipient,
+                amount,
+            } => {
+                check_destination(ctx, &recipient)?;
+                require_yes(yes, "withdraw stake")?;
+                try_spinner(
+                    StakeCommand::Withdraw.spinner_msg(),
+                    process_withdraw_stake(ctx, &stake, &recipient, amount.value()),
+                )
+                .await
+            }
+            StakeArgs::Show { pubkey } => {
+                try_spinner(
+                    StakeCommand::Show.spinner_msg(),
+                    show_stake_account(ctx, &pubkey),
+                )
+                .await
+            }
+        }
+    }
+}
+
+/// Stands in for the confirmation the menu asks before `action`.
+fn require_yes(yes: bool, action: &str) -> anyhow::Result<()> {
+    if !yes {
+        bail!("Pass --yes to {action}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_parses_command_arguments() {
+        let stake = Pubkey::new_from_array([1; 32]);
+        let vote = Pubkey::new_from_array([2; 32]);
+
+        let cli = Cli::parse_from([
+            "scilla",
+            "stake",
+            "delegate",
+            "--stake",
+            &stake.to_string(),
+            "--vote",
+            &vote.to_string(),
+            "--output",
+            "json",
+        ]);
+        assert_eq!(cli.output, OutputFormat::Json);
+        assert!(!cli.yes);
+        assert!(matches!(
+            cli.command,
+            Some(CliCommand::Stake(StakeArgs::Delegate {
+                stake: parsed_stake,
+                vote: parsed_vote,
+                authority: None,
+            })) if parsed_stake == stake && parsed_vote == vote
+        ));
+
+        let transfer = |amount: &str| {
+            Cli::try_parse_from([
+                "scilla",
+                "account",
+                "transfer",
+                "--to",
+                &vote.to_string(),
+                "--amount",
+                amount,
+                "--memo",
+                "# invoice 42",
+            ])
+        };
+        assert!(transfer("-1").is_err());
+        let cli = transfer("1.5").expect("a valid transfer should parse");
+        assert!(matches!(
+            cli.command,
+            Some(CliCommand::Account(AccountArgs::Transfer { to, amount, memo: Some(memo) }))
+                if to == vote && amount.value() == 1.5 && memo == "# invoice 42"
+        ));
+
+        assert!(Cli::parse_from(["scilla"]).command.is_none());
+    }
+
+    #[test]
+    fn test_require_yes() {
+        assert!(require_yes(true, "deactivate stake").is_ok());
+        assert!(require_yes(false, "deactivate stake").is_err());
+    }
+}
@@ -1,32 +1,99 @@
 use {
     crate::{
-        commands::CommandFlow,
+        commands::{CommandFlow, program::decode_known_layout},
+        config::{TxTemplate, expand_tilde},
+        constants::{
+            HEX_DUMP_LINE_BYTES, LAMPORTS_PER_SOL, MAX_BATCH_INSTRUCTIONS_PER_TX,
+            MAX_MULTIPLE_ACCOUNTS, MAX_WATCH_DATA_RANGES,
+        },
         context::ScillaContext,
-        misc::helpers::{bincode_deserialize, build_and_send_tx, lamports_to_sol, sol_to_lamports},
-        prompt::prompt_input_data,
-        ui::{print_error, show_spinner},
+        misc::{
+            batch_cost::{PlannedTx, check_batch_cost},
+            batch_transfer::{
+                batch_instructions, batch_transfer_report_table, pack_transfers,
+                parse_batch_transfers, write_failed_rows,
+            },
+            broadcast::send_in_parallel,
+            helpers::{
+                DataSlice, SolAmount, build_and_send_or_export_tx, build_and_send_tx,
+                check_minimum_balance, lamports_to_sol, memo_instruction, read_keypair_from_path,
+                send_signed_tx, sign_tx, sol_to_lamports, with_compute_budget,
+            },
+            offline::{check_nonce_authority, fetch_nonce_account},
+            preview::{show_tx_preview, simulate_tx},
+            send_guard::message_fingerprint,
+            signer::{note_device_confirmation, read_signer},
+            siws::{SiwsMessage, generate_nonce, siws_table, timestamp, verify_message},
+            sns::domains_for_display,
+            stream::{AccountUpdate, StreamUpdate, TransactionUpdate, watch_address},
+            whitelist::confirm_destination,
+            withdrawals::{
+                Withdrawal, WithdrawalJournal, WithdrawalStatus, parse_queue, summary_table,
+                write_report,
+            },
+        },
+        prompt::{
+            answers::Ask, prompt_confirmation, prompt_input_data, prompt_input_data_with_default,
+            prompt_keypair_path, prompt_optional_data_with_default,
+            prompt_optional_pubkey_with_default, prompt_pubkey, prompt_pubkey_with_default,
+            prompt_refresh_interval, prompt_select_data,
+        },
+        ui::{
+            new_table, print_error,
+            render::{Renderer, field_table, header_cell, stdout},
+            show_or_watch, show_spinner,
+            table::PagedTable,
+        },
     },
-    anyhow::bail,
-    comfy_table::{Cell, Table, presets::UTF8_FULL},
-    console::style,
+    anyhow::{Context, anyhow, bail},
+    base64::{Engine, prelude::BASE64_STANDARD},
+    chrono::{TimeDelta, Utc},
+    comfy_table::{Cell, Table},
     inquire::Select,
-    solana_nonce::versions::Versions,
+    solana_account::Account,
+    solana_account_decoder_client_types::{UiAccountEncoding, UiDataSliceConfig},
+    solana_commitment_config::CommitmentConfig,
+    solana_instruction::Instruction,
+    solana_keypair::Signer,
+    solana_message::Message,
+    solana_nonce::state::{Data as NonceData, State as NonceState},
     solana_pubkey::Pubkey,
-    solana_rpc_client_api::config::{RpcLargestAccountsConfig, RpcLargestAccountsFilter},
-    solana_system_interface::instruction::transfer,
-    std::fmt,
+    solana_rpc_client_api::config::{
+        RpcAccountInfoConfig, RpcLargestAccountsConfig, RpcLargestAccountsFilter,
+    },
+    solana_sdk_ids::system_program,
+    solana_signature::Signature,
+    solana_system_interface::instruction::{
+        advance_nonce_account, authorize_nonce_account, create_nonce_account, transfer,
+        withdraw_nonce_account,
+    },
+    solana_transaction::Hash,
+    std::{
+        collections::{HashMap, hash_map::Entry},
+        fmt, fs,
+        io::Write,
+        ops::Range,
+        path::Path,
+    },
 };
 
 /// Commands related to wallet or account management
 #[derive(Debug, Clone)]
 pub enum AccountCommand {
     FetchAccount,
+    FetchAccounts,
     Balance,
+    WatchAccount,
     Transfer,
+    BatchTransfer,
+    RunTemplate,
+    ProcessWithdrawals,
     Airdrop,
     LargestAccounts,
     NonceAccount,
     Rent,
+    SignIn,
+    VerifySignIn,
     GoBack,
 }
 
@@ -34,12 +101,19 @@ impl AccountCommand {
     pub fn spinner_msg(&self) -> &'static str {
         match self {
             AccountCommand::FetchAccount => "Fetching account…",
+            AccountCommand::FetchAccounts => "Fetching accounts…",
             AccountCommand::Balance => "Checking SOL balance…",
+            AccountCommand::WatchAccount => "Watching account…",
             AccountCommand::Transfer => "Sending SOL…",
+            AccountCommand::BatchTransfer => "Sending batch transfers…",
+            AccountCommand::RunTemplate => "Sending templated transfer…",
+            AccountCommand::ProcessWithdrawals => "Processing withdrawal queue…",
             AccountCommand::Airdrop => "Requesting SOL on devnet/testnet…",
             AccountCommand::LargestAccounts => "Fetching largest accounts on the cluster…",
             AccountCommand::NonceAccount => "Inspecting or managing durable nonces…",
             AccountCommand::Rent => "Checking rent…",
+            AccountCommand::SignIn => "Signing sign-in message…",
+            AccountCommand::VerifySignIn => "Verifying sign-in message…",
             AccountCommand::GoBack => "Going back…",
         }
     }
@@ -49,12 +123,19 @@ impl fmt::Display for AccountCommand {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let command = match self {
             AccountCommand::FetchAccount => "Fetch account",
+            AccountCommand::FetchAccounts => "Fetch accounts at slot",
             AccountCommand::Balance => "Check balance",
+            AccountCommand::WatchAccount => "Watch account",
             AccountCommand::Transfer => "Transfer SOL",
+            AccountCommand::BatchTransfer => "Batch transfer from CSV",
+            AccountCommand::RunTemplate => "Run transaction template",
+            AccountCommand::ProcessWithdrawals => "Process withdrawal queue",
             AccountCommand::Airdrop => "Request airdrop",
             AccountCommand::LargestAccounts => "View largest accounts",
-            AccountCommand::NonceAccount => "View nonce account",
+            AccountCommand::NonceAccount => "Nonce account",
             AccountCommand::Rent => "Check rent",
+            AccountCommand::SignIn => "Sign In With Solana",
+            AccountCommand::VerifySignIn => "Verify sign-in message",
             AccountCommand::GoBack => "Go back",
         };
         write!(f, "{command}")
@@ -65,17 +146,107 @@ impl AccountCommand {
     pub async fn process_command(&self, ctx: &ScillaContext) -> CommandFlow<()> {
         match self {
             AccountCommand::FetchAccount => {
-                let pubkey: Pubkey = prompt_input_data("Enter Pubkey:");
+                let pubkey = prompt_pubkey(ctx, "Enter Pubkey:").await;
                 show_spinner(self.spinner_msg(), fetch_acc_data(ctx, &pubkey)).await;
             }
+            AccountCommand::FetchAccounts => {
+                let mut pubkeys = vec![prompt_pubkey(ctx, "Enter Pubkey:").await];
+                while pubkeys.len() < MAX_MULTIPLE_ACCOUNTS
+                    && let Some(pubkey) = prompt_optional_pubkey_with_default(
+                        ctx,
+                        "Enter another Pubkey (empty when done):",
+                        None,
+                    )
+                    .await
+                {
+                    pubkeys.push(pubkey);
+                }
+                let min_context_slot: Option<u64> = prompt_optional_data_with_default(
+                    "Minimum context slot (empty for any):",
+                    None,
+                );
+                let data_slice: Option<DataSlice> = prompt_optional_data_with_default(
+                    "Data slice as offset:length (empty for all data):",
+                    None,
+                );
+
+                show_spinner(
+                    self.spinner_msg(),
+                    fetch_accounts(ctx, &pubkeys, min_context_slot, data_slice),
+                )
+                .await;
+            }
             AccountCommand::Balance => {
-                let pubkey: Pubkey = prompt_input_data("Enter Pubkey :");
-                show_spinner(self.spinner_msg(), fetch_account_balance(ctx, &pubkey)).await;
+                let pubkey = prompt_pubkey(ctx, "Enter Pubkey :").await;
+                let refresh_secs = prompt_refresh_interval();
+                show_or_watch(self.spinner_msg(), refresh_secs, || {
+                    fetch_account_balance(ctx, &pubkey)
+                })
+                .await;
+            }
+            AccountCommand::WatchAccount => {
+                let pubkey = prompt_pubkey(ctx, "Enter Pubkey to watch:").await;
+                if let Err(e) = watch_account(ctx, &pubkey).await {
+                    print_error(e);
+                }
             }
             AccountCommand::Transfer => {
-                let to: Pubkey = prompt_input_data("Enter recipient Pubkey:");
+                let to = prompt_pubkey(ctx, "Enter recipient Pubkey:").await;
                 let amount: f64 = prompt_input_data("Enter amount (SOL):");
-                show_spinner(self.spinner_msg(), transfer_sol(ctx, to, amount)).await;
+                let memo: String = prompt_input_data_with_default(
+                    "Enter memo (optional):",
+                    ctx.defaults().memo.as_deref(),
+                );
+                if !confirm_destination(ctx, &to) {
+                    return CommandFlow::Process(());
+                }
+                show_spinner(self.spinner_msg(), transfer_sol(ctx, to, amount, &memo)).await;
+            }
+            AccountCommand::BatchTransfer => {
+                let path: String =
+                    prompt_input_data("Enter batch transfer file (recipient,amount,memo):");
+                show_spinner(
+                    self.spinner_msg(),
+                    process_batch_transfer(ctx, &expand_tilde(&path)),
+                )
+                .await;
+            }
+            AccountCommand::RunTemplate => {
+                let templates = ctx.templates();
+                if templates.is_empty() {
+                    print_error(
+                        "No transaction templates saved, add one under Edit ScillaConfig > \
+                         Transaction Templates",
+                    );
+                    return CommandFlow::Process(());
+                }
+
+                let name = prompt_select_data("Select template:", templates.keys().collect());
+                let template = &templates[name];
+                stdout().table(template_table(template));
+
+                match templated_transfer(ctx, template).await {
+                    Ok((to, amount, memo)) => {
+                        if confirm_destination(ctx, &to) {
+                            show_spinner(self.spinner_msg(), transfer_sol(ctx, to, amount, &memo))
+                                .await;
+                        }
+                    }
+                    Err(e) => print_error(e),
+                }
+            }
+            AccountCommand::ProcessWithdrawals => {
+                let queue_path: String =
+                    prompt_input_data("Enter withdrawal queue file (address,amount,reference):");
+                let batch_size: usize = prompt_input_data_with_default(
+                    "Withdrawals per transaction:",
+                    Some(&MAX_BATCH_INSTRUCTIONS_PER_TX.to_string()),
+                );
+                show_spinner(
+                    self.spinner_msg(),
+                    process_withdrawal_queue(ctx, &expand_tilde(&queue_path), batch_size),
+                )
+                .await;
             }
             AccountCommand::Airdrop => {
                 show_spinner(self.spinner_msg(), request_sol_airdrop(ctx)).await;
@@ -84,14 +255,62 @@ impl AccountCommand {
                 show_spinner(self.spinner_msg(), fetch_largest_accounts(ctx)).await;
             }
             AccountCommand::NonceAccount => {
-                let pubkey: Pubkey = prompt_input_data("Enter nonce account pubkey:");
-                show_spinner(self.spinner_msg(), fetch_nonce_account(ctx, &pubkey)).await;
+                let action = prompt_select_data("Nonce account action:", NonceAction::all());
+                action.process(ctx).await;
             }
             AccountCommand::Rent => {
                 // get the rent for data bytes used in account
                 let bytes: usize = prompt_input_data("Enter data size in bytes:");
                 show_spinner(self.spinner_msg(), fetch_rent(ctx, bytes)).await;
             }
+            AccountCommand::SignIn => {
+                let domain: String =
+                    prompt_input_data("Enter requesting domain (e.g. example.com):");
+                let statement: Option<String> =
+                    prompt_optional_data_with_default("Enter statement (optional):", None);
+                let uri: String = prompt_input_data_with_default(
+                    "Enter URI:",
+                    Some(&format!("https://{domain}")),
+                );
+                let chain_id: Option<String> = prompt_optional_data_with_default(
+                    "Enter chain ID (e.g. mainnet, optional):",
+                    None,
+                );
+                let nonce: String =
+                    prompt_input_data_with_default("Enter nonce:", Some(&generate_nonce()));
+                let expires_in_mins: Option<i64> =
+                    prompt_optional_data_with_default("Expires in minutes (optional):", None);
+
+                let now = Utc::now();
+                let message = SiwsMessage {
+                    domain,
+                    address: *ctx.pubkey(),
+                    statement,
+                    uri: Some(uri),
+                    version: Some("1".to_string()),
+                    chain_id,
+                    nonce: Some(nonce),
+                    issued_at: Some(timestamp(now)),
+                    expiration_time: expires_in_mins
+                        .map(|mins| timestamp(now + TimeDelta::minutes(mins))),
+                    ..SiwsMessage::default()
+                };
+                if let Err(e) = sign_in_message(ctx, &message) {
+                    print_error(e);
+                }
+            }
+            AccountCommand::VerifySignIn => {
+                let path: String = prompt_input_data("Enter path of the sign-in message file:");
+                let signature: Signature = prompt_input_data("Enter signature (base58):");
+                let domain: Option<String> =
+                    prompt_optional_data_with_default("Expected domain (optional):", None);
+
+                if let Err(e) =
+                    verify_sign_in_message(&expand_tilde(&path), &signature, domain.as_deref())
+                {
+                    print_error(e);
+                }
+            }
             AccountCommand::GoBack => {
                 return CommandFlow::GoBack;
             }
@@ -109,11 +328,9 @@ async fn request_sol_airdrop(ctx: &ScillaContext) -> anyhow::Result<()> {
         .await;
     match sig {
         Ok(signature) => {
-            println!(
-                "{} {}",
-                style("Airdrop requested successfully!").green().bold(),
-                style(format!("Signature: {signature}")).cyan()
-            );
+            let mut out = stdout();
+            out.success("Airdrop requested successfully!");
+            out.note(format!("Signature: {signature}"));
         }
         Err(err) => {
             print_error(format!("Airdrop failed: {err}"));
@@ -123,55 +340,268 @@ async fn request_sol_airdrop(ctx: &ScillaContext) -> anyhow::Result<()> {
     Ok(())
 }
 
-async fn fetch_acc_data(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Result<()> {
+pub async fn fetch_acc_data(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Result<()> {
     let acc = ctx.rpc().get_account(pubkey).await?;
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
-        .add_row(vec![
-            Cell::new("Lamports"),
-            Cell::new(format!("{}", acc.lamports)),
-        ])
-        .add_row(vec![
-            Cell::new("Data Length"),
-            Cell::new(format!("{}", acc.data.len())),
-        ])
-        .add_row(vec![
-            Cell::new("Owner"),
-            Cell::new(format!("{}", acc.owner)),
-        ])
-        .add_row(vec![
-            Cell::new("Executable"),
-            Cell::new(format!("{}", acc.executable)),
-        ])
-        .add_row(vec![
-            Cell::new("Rent Epoch"),
-            Cell::new(format!("{}", acc.rent_epoch)),
-        ]);
+    let mut out = stdout();
+    out.title("ACCOUNT INFO");
+    out.table(account_table(&acc));
 
-    println!("{}\n{}", style("ACCOUNT INFO").green().bold(), table);
+    Ok(())
+}
+
+/// Reads `pubkeys` in a single request, so they all reflect the same slot,
+/// failing if the node hasn't reached `min_context_slot` yet. With a
+/// `data_slice` only that range of each account's data is downloaded.
+async fn fetch_accounts(
+    ctx: &ScillaContext,
+    pubkeys: &[Pubkey],
+    min_context_slot: Option<u64>,
+    data_slice: Option<DataSlice>,
+) -> anyhow::Result<()> {
+    let config = RpcAccountInfoConfig {
+        // Slices only apply to binary encodings
+        encoding: Some(UiAccountEncoding::Base64),
+        data_slice: data_slice.map(UiDataSliceConfig::from),
+        commitment: Some(ctx.rpc().commitment()),
+        min_context_slot,
+    };
+    let response = ctx
+        .rpc()
+        .get_multiple_ui_accounts_with_config(pubkeys, config)
+        .await?;
+    let accounts = response
+        .value
+        .iter()
+        .zip(pubkeys)
+        .map(|(account, pubkey)| {
+            account
+                .as_ref()
+                .map(|account| {
+                    account
+                        .decode()
+                        .ok_or_else(|| anyhow!("Failed to decode account {pubkey}"))
+                })
+                .transpose()
+        })
+        .collect::<anyhow::Result<Vec<Option<Account>>>>()?;
+
+    let mut out = stdout();
+    out.title("ACCOUNTS");
+    out.note(format!("Read at slot {}", response.context.slot));
+    out.table(accounts_table(pubkeys, &accounts, data_slice));
 
     Ok(())
 }
 
-async fn fetch_account_balance(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Result<()> {
+fn accounts_table(
+    pubkeys: &[Pubkey],
+    accounts: &[Option<Account>],
+    data_slice: Option<DataSlice>,
+) -> Table {
+    let mut table = new_table();
+    table.set_header(vec![
+        header_cell("Address"),
+        header_cell("Owner"),
+        header_cell("Lamports"),
+        header_cell("Data"),
+    ]);
+
+    for (pubkey, account) in pubkeys.iter().zip(accounts) {
+        let Some(account) = account else {
+            table.add_row(vec![
+                Cell::new(pubkey),
+                Cell::new("Not found"),
+                Cell::new(""),
+                Cell::new(""),
+            ]);
+            continue;
+        };
+
+        let data = match data_slice {
+            Some(slice) => hex_dump(slice.offset, &account.data),
+            None => format!("{} bytes", account.data.len()),
+        };
+        table.add_row(vec![
+            Cell::new(pubkey),
+            Cell::new(account.owner),
+            Cell::new(account.lamports),
+            Cell::new(data),
+        ]);
+    }
+
+    table
+}
+
+/// `data` read from `offset`, 16 bytes of hex per line after their offset.
+fn hex_dump(offset: usize, data: &[u8]) -> String {
+    if data.is_empty() {
+        return "No data in range".to_string();
+    }
+
+    data.chunks(HEX_DUMP_LINE_BYTES)
+        .enumerate()
+        .map(|(idx, chunk)| {
+            let hex: String = chunk.iter().map(|byte| format!("{byte:02x}")).collect();
+            format!("{:06x}  {hex}", offset + idx * HEX_DUMP_LINE_BYTES)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn account_table(acc: &Account) -> Table {
+    field_table([
+        ("Lamports", format!("{}", acc.lamports)),
+        ("Data Length", format!("{}", acc.data.len())),
+        ("Owner", format!("{}", acc.owner)),
+        ("Executable", format!("{}", acc.executable)),
+        ("Rent Epoch", format!("{}", acc.rent_epoch)),
+    ])
+}
+
+/// Prints the account once, then only what changed on every update, along
+/// with the transactions mentioning it, as they stream in.
+pub async fn watch_account(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Result<()> {
+    let mut previous = ctx
+        .rpc()
+        .get_account_with_commitment(pubkey, ctx.rpc().commitment())
+        .await?
+        .value;
+
+    let mut out = stdout();
+    match &previous {
+        Some(account) => {
+            out.title("ACCOUNT INFO");
+            out.table(account_table(account));
+        }
+        None => out.note("The account doesn't exist yet, waiting for it to be created"),
+    }
+
+    watch_address(ctx, pubkey, |update| {
+        let mut out = stdout();
+        match update {
+            StreamUpdate::Account(AccountUpdate { slot, account }) => {
+                let changes = match &previous {
+                    Some(old) => account_changes(pubkey, old, &account),
+                    None => std::iter::once("Account created".to_string())
+                        .chain(account_changes(pubkey, &Account::default(), &account))
+                        .collect(),
+                };
+                write_account_changes(&mut out, slot, &changes);
+                previous = Some(account);
+            }
+            StreamUpdate::Transaction(update) => write_transaction_update(&mut out, &update),
+        }
+        Ok(())
+    })
+    .await
+}
+
+/// What changed between two states of the same account: the balance delta,
+/// fields of known layouts such as authorities, and otherwise the ranges of
+/// data bytes that differ.
+fn account_changes(pubkey: &Pubkey, old: &Account, new: &Account) -> Vec<String> {
+    let mut changes = Vec::new();
+
+    if old.lamports != new.lamports {
+        let delta = new.lamports as i128 - old.lamports as i128;
+        changes.push(format!(
+            "Balance: {} → {} SOL ({:+} SOL)",
+            lamports_to_sol(old.lamports),
+            lamports_to_sol(new.lamports),
+            delta as f64 / LAMPORTS_PER_SOL as f64
+        ));
+    }
+    if old.owner != new.owner {
+        changes.push(format!("Owner: {} → {}", old.owner, new.owner));
+    }
+    if old.executable != new.executable {
+        changes.push(format!(
+            "Executable: {} → {}",
+            old.executable, new.executable
+        ));
+    }
+    if old.data.len() != new.data.len() {
+        changes.push(format!(
+            "Data Length: {} → {} bytes",
+            old.data.len(),
+            new.data.len()
+        ));
+    }
+
+    let before_fields = changes.len();
+    if let Ok(new_fields) = decode_known_layout(pubkey, new) {
+        let old_fields = decode_known_layout(pubkey, old).unwrap_or_default();
+        for (field, value) in new_fields {
+            match old_fields.iter().find(|(old_field, _)| *old_field == field) {
+                Some((_, old_value)) if *old_value == value => {}
+                Some((_, old_value)) => changes.push(format!("{field}: {old_value} → {value}")),
+                None => changes.push(format!("{field}: {value}")),
+            }
+        }
+    }
+
+    // Data the decoded fields don't cover, or that has no known layout
+    if changes.len() == before_fields && old.data != new.data {
+        let ranges = changed_byte_ranges(&old.data, &new.data);
+        let mut shown: Vec<String> = ranges
+            .iter()
+            .take(MAX_WATCH_DATA_RANGES)
+            .map(|range| format!("{}..{}", range.start, range.end))
+            .collect();
+        if ranges.len() > MAX_WATCH_DATA_RANGES {
+            shown.push(format!("{} more", ranges.len() - MAX_WATCH_DATA_RANGES));
+        }
+        changes.push(format!("Data: bytes {} changed", shown.join(", ")));
+    }
+
+    changes
+}
+
+/// Ranges of bytes that differ between `old` and `new`, bytes past the end
+/// of the shorter one counting as changed.
+fn changed_byte_ranges(old: &[u8], new: &[u8]) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    for idx in 0..old.len().max(new.len()) {
+        if old.get(idx) == new.get(idx) {
+            continue;
+        }
+        match ranges.last_mut() {
+            Some(range) if range.end == idx => range.end += 1,
+            _ => ranges.push(idx..idx + 1),
+        }
+    }
+    ranges
+}
+
+fn write_account_changes<W: Write>(out: &mut Renderer<W>, slot: u64, changes: &[String]) {
+    if changes.is_empty() {
+        out.note(format!("[slot {slot}] Updated, nothing changed"));
+    }
+    for change in changes {
+        out.line(format!("[slot {slot}] {change}"));
+    }
+}
+
+fn write_transaction_update<W: Write>(out: &mut Renderer<W>, update: &TransactionUpdate) {
+    let TransactionUpdate {
+        slot,
+        signature,
+        failed,
+    } = update;
+
+    if *failed {
+        out.warning(format!("[slot {slot}] Failed transaction {signature}"));
+    } else {
+        out.success(format!("[slot {slot}] Transaction {signature}"));
+    }
+}
+
+pub async fn fetch_account_balance(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Result<()> {
     let acc = ctx.rpc().get_account(pubkey).await?;
     let acc_balance = lamports_to_sol(acc.lamports);
 
-    println!(
-        "{} {}",
-        style("Account balance in SOL:").green().bold(),
-        style(format!("{acc_balance:#?}")).cyan()
-    );
+    stdout().success(format!("Account balance in SOL: {acc_balance:#?}"));
 
     Ok(())
 }
@@ -181,7 +611,7 @@ async fn fetch_largest_accounts(ctx: &ScillaContext) -> anyhow::Result<()> {
         "Filter accounts by:",
         vec!["All", "Circulating", "Non-Circulating"],
     )
-    .prompt()?;
+    .ask()?;
 
     let filter = match filter_choice {
         "Circulating" => Some(RpcLargestAccountsFilter::Circulating),
@@ -198,84 +628,348 @@ async fn fetch_largest_accounts(ctx: &ScillaContext) -> anyhow::Result<()> {
     let response = ctx.rpc().get_largest_accounts_with_config(config).await?;
     let largest_accounts = response.value;
 
-    let mut table = Table::new();
-    table.load_preset(UTF8_FULL).set_header(vec![
-        Cell::new("#").add_attribute(comfy_table::Attribute::Bold),
-        Cell::new("Address").add_attribute(comfy_table::Attribute::Bold),
-        Cell::new("Balance (SOL)").add_attribute(comfy_table::Attribute::Bold),
-    ]);
+    let addresses: Vec<Pubkey> = largest_accounts
+        .iter()
+        .filter_map(|account| account.address.parse().ok())
+        .collect();
+    let domains = domains_for_display(ctx, &addresses).await;
 
+    let mut headers = vec!["#", "Address", "Balance (SOL)"];
+    if !domains.is_empty() {
+        headers.push("Domain");
+    }
+
+    let mut table = PagedTable::new("LARGEST ACCOUNTS", headers);
     for (idx, account) in largest_accounts.iter().enumerate() {
         let balance_sol = lamports_to_sol(account.lamports);
-        table.add_row(vec![
-            Cell::new(format!("{}", idx + 1)),
-            Cell::new(&account.address),
-            Cell::new(format!("{balance_sol:.2}")),
-        ]);
+        let mut row = vec![
+            format!("{}", idx + 1),
+            account.address.clone(),
+            format!("{balance_sol:.2}"),
+        ];
+        if !domains.is_empty() {
+            let domain = account
+                .address
+                .parse()
+                .ok()
+                .and_then(|address: Pubkey| domains.get(&address).cloned());
+            row.push(domain.unwrap_or_default());
+        }
+        table.add_row(row);
+    }
+
+    table.show()
+}
+
+/// What to do with a durable nonce account, picked under
+/// [`AccountCommand::NonceAccount`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NonceAction {
+    View,
+    Create,
+    Advance,
+    Withdraw,
+    Authorize,
+}
+
+impl NonceAction {
+    fn all() -> Vec<Self> {
+        vec![
+            NonceAction::View,
+            NonceAction::Create,
+            NonceAction::Advance,
+            NonceAction::Withdraw,
+            NonceAction::Authorize,
+        ]
+    }
+
+    fn spinner_msg(&self) -> &'static str {
+        match self {
+            NonceAction::View => "Fetching nonce account…",
+            NonceAction::Create => "Creating nonce account…",
+            NonceAction::Advance => "Advancing nonce…",
+            NonceAction::Withdraw => "Withdrawing from nonce account…",
+            NonceAction::Authorize => "Changing nonce authority…",
+        }
+    }
+
+    async fn process(&self, ctx: &ScillaContext) {
+        match self {
+            NonceAction::View => {
+                let pubkey = prompt_pubkey(ctx, "Enter nonce account pubkey:").await;
+                show_spinner(self.spinner_msg(), show_nonce_account(ctx, &pubkey)).await;
+            }
+            NonceAction::Create => {
+                let nonce_keypair_path =
+                    prompt_keypair_path("Enter Nonce Account Keypair Path:", ctx);
+                let authority = prompt_pubkey_with_default(
+                    ctx,
+                    "Enter Nonce Authority Pubkey:",
+                    Some(&ctx.pubkey().to_string()),
+                )
+                .await;
+                let amount: Option<SolAmount> = prompt_optional_data_with_default(
+                    "Amount to fund it with in SOL (empty for the rent-exempt minimum):",
+                    None,
+                );
+
+                show_spinner(
+                    self.spinner_msg(),
+                    process_create_nonce_account(ctx, &nonce_keypair_path, &authority, amount),
+                )
+                .await;
+            }
+            NonceAction::Advance => {
+                let pubkey = prompt_pubkey(ctx, "Enter nonce account pubkey:").await;
+                let authority_path =
+                    prompt_keypair_path("Enter Nonce Authority Keypair Path:", ctx);
+
+                show_spinner(
+                    self.spinner_msg(),
+                    process_advance_nonce(ctx, &pubkey, &authority_path),
+                )
+                .await;
+            }
+            NonceAction::Withdraw => {
+                let pubkey = prompt_pubkey(ctx, "Enter nonce account pubkey:").await;
+                let recipient = prompt_pubkey_with_default(
+                    ctx,
+                    "Enter recipient Pubkey:",
+                    Some(&ctx.pubkey().to_string()),
+                )
+                .await;
+                let amount: SolAmount = prompt_input_data("Enter amount to withdraw (SOL):");
+                let authority_path =
+                    prompt_keypair_path("Enter Nonce Authority Keypair Path:", ctx);
+                if !confirm_destination(ctx, &recipient) {
+                    return;
+                }
+
+                show_spinner(
+                    self.spinner_msg(),
+                    process_withdraw_nonce(ctx, &pubkey, &recipient, amount, &authority_path),
+                )
+                .await;
+            }
+            NonceAction::Authorize => {
+                let pubkey = prompt_pubkey(ctx, "Enter nonce account pubkey:").await;
+                let new_authority = prompt_pubkey(ctx, "Enter New Nonce Authority Pubkey:").await;
+                let authority_path =
+                    prompt_keypair_path("Enter Current Nonce Authority Keypair Path:", ctx);
+
+                show_spinner(
+                    self.spinner_msg(),
+                    process_authorize_nonce(ctx, &pubkey, &new_authority, &authority_path),
+                )
+                .await;
+            }
+        }
+    }
+}
+
+impl fmt::Display for NonceAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action = match self {
+            NonceAction::View => "View nonce account",
+            NonceAction::Create => "Create nonce account",
+            NonceAction::Advance => "Advance nonce",
+            NonceAction::Withdraw => "Withdraw from nonce account",
+            NonceAction::Authorize => "Change nonce authority",
+        };
+        write!(f, "{action}")
+    }
+}
+
+async fn show_nonce_account(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Result<()> {
+    let (account, data) = fetch_nonce_account(ctx, pubkey).await?;
+
+    let mut out = stdout();
+    out.title("NONCE ACCOUNT INFO");
+    out.table(nonce_account_table(pubkey, &account, &data));
+
+    Ok(())
+}
+
+async fn process_create_nonce_account(
+    ctx: &ScillaContext,
+    nonce_keypair_path: &Path,
+    authority: &Pubkey,
+    amount: Option<SolAmount>,
+) -> anyhow::Result<()> {
+    let nonce_keypair = read_keypair_from_path(nonce_keypair_path)?;
+    let nonce_pubkey = nonce_keypair.pubkey();
+
+    if ctx.pubkey() == &nonce_pubkey {
+        bail!("Nonce account {nonce_pubkey} cannot be the same as fee payer account");
+    }
+
+    let existing = ctx
+        .rpc()
+        .get_account_with_commitment(&nonce_pubkey, ctx.rpc().commitment())
+        .await?
+        .value;
+    if existing.is_some() {
+        bail!("Account {nonce_pubkey} already exists");
+    }
+
+    let rent = ctx
+        .rpc()
+        .get_minimum_balance_for_rent_exemption(NonceState::size())
+        .await?;
+    let lamports = amount.map_or(rent, |amount| amount.to_lamports());
+    if lamports < rent {
+        bail!(
+            "A nonce account needs at least {} SOL to be rent exempt",
+            lamports_to_sol(rent)
+        );
     }
+    check_minimum_balance(ctx, ctx.pubkey(), lamports).await?;
+
+    let instructions = create_nonce_account(ctx.pubkey(), &nonce_pubkey, authority, lamports);
+    let signature = build_and_send_tx(ctx, &instructions, &[ctx.signer(), &nonce_keypair]).await?;
+
+    let mut out = stdout();
+    out.success("Nonce account created successfully!");
+    out.note(format!("Signature: {signature}"));
+
+    show_nonce_account(ctx, &nonce_pubkey).await
+}
 
-    println!("\n{}", style("LARGEST ACCOUNTS").green().bold());
-    println!("{table}");
+async fn process_advance_nonce(
+    ctx: &ScillaContext,
+    nonce_pubkey: &Pubkey,
+    authority_path: &Path,
+) -> anyhow::Result<()> {
+    let authority = read_signer(authority_path)?;
+    let (_, data) = fetch_nonce_account(ctx, nonce_pubkey).await?;
+    check_nonce_authority(nonce_pubkey, &data, &authority.pubkey())?;
+
+    let instruction = advance_nonce_account(nonce_pubkey, &authority.pubkey());
+    let Some(signature) =
+        build_and_send_or_export_tx(ctx, &[instruction], &[ctx.signer(), &*authority]).await?
+    else {
+        return Ok(());
+    };
+
+    let mut out = stdout();
+    out.success("Nonce advanced successfully!");
+    out.line(format!("Previous nonce: {}", data.blockhash()));
+    out.note(format!("Signature: {signature}"));
 
     Ok(())
 }
 
-async fn fetch_nonce_account(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Result<()> {
-    let account = ctx.rpc().get_account(pubkey).await?;
+async fn process_withdraw_nonce(
+    ctx: &ScillaContext,
+    nonce_pubkey: &Pubkey,
+    recipient: &Pubkey,
+    amount: SolAmount,
+    authority_path: &Path,
+) -> anyhow::Result<()> {
+    let authority = read_signer(authority_path)?;
+    let (account, data) = fetch_nonce_account(ctx, nonce_pubkey).await?;
+    check_nonce_authority(nonce_pubkey, &data, &authority.pubkey())?;
 
-    let versions = bincode_deserialize::<Versions>(&account.data, "nonce account data")?;
+    let lamports = amount.to_lamports();
+    if lamports > account.lamports {
+        bail!(
+            "Insufficient balance. Have {:.6} SOL, trying to withdraw {:.6} SOL",
+            lamports_to_sol(account.lamports),
+            amount.value()
+        );
+    }
 
-    let solana_nonce::state::State::Initialized(data) = versions.state() else {
-        bail!("This account is not an initialized nonce account");
+    // Only emptying the account may take it below the rent-exempt minimum
+    let remaining = account.lamports - lamports;
+    if remaining > 0 {
+        let rent = ctx
+            .rpc()
+            .get_minimum_balance_for_rent_exemption(account.data.len())
+            .await?;
+        if remaining < rent {
+            bail!(
+                "Withdrawing {} SOL would leave {} SOL, below the rent-exempt minimum of {} SOL. \
+                 Withdraw the whole balance to close the account instead",
+                amount.value(),
+                lamports_to_sol(remaining),
+                lamports_to_sol(rent)
+            );
+        }
+    }
+
+    let instruction =
+        withdraw_nonce_account(nonce_pubkey, &authority.pubkey(), recipient, lamports);
+    let Some(signature) =
+        build_and_send_or_export_tx(ctx, &[instruction], &[ctx.signer(), &*authority]).await?
+    else {
+        return Ok(());
     };
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
-        .add_row(vec![Cell::new("Address"), Cell::new(pubkey)])
-        .add_row(vec![
-            Cell::new("Lamports"),
-            Cell::new(format!("{}", account.lamports)),
-        ])
-        .add_row(vec![
-            Cell::new("Balance (SOL)"),
-            Cell::new(format!("{:.6}", lamports_to_sol(account.lamports))),
-        ])
-        .add_row(vec![Cell::new("Owner"), Cell::new(account.owner)])
-        .add_row(vec![
-            Cell::new("Executable"),
-            Cell::new(format!("{}", account.executable)),
-        ])
-        .add_row(vec![
-            Cell::new("Rent Epoch"),
-            Cell::new(format!("{}", account.rent_epoch)),
-        ])
-        .add_row(vec![
-            Cell::new("Nonce blockhash"),
-            Cell::new(data.blockhash()),
-        ])
-        .add_row(vec![Cell::new("Authority"), Cell::new(data.authority)]);
-
-    println!("\n{}", style("NONCE ACCOUNT INFO").green().bold());
-    println!("{table}");
+    let mut out = stdout();
+    out.success("Withdrawn from nonce account successfully!");
+    out.line(format!("To Recipient: {recipient}"));
+    out.line(format!("Amount: {} SOL", amount.value()));
+    if remaining == 0 {
+        out.line("The nonce account was emptied and closed");
+    }
+    out.note(format!("Signature: {signature}"));
 
     Ok(())
 }
 
-async fn transfer_sol(
+async fn process_authorize_nonce(
+    ctx: &ScillaContext,
+    nonce_pubkey: &Pubkey,
+    new_authority: &Pubkey,
+    authority_path: &Path,
+) -> anyhow::Result<()> {
+    let authority = read_signer(authority_path)?;
+    let (_, data) = fetch_nonce_account(ctx, nonce_pubkey).await?;
+    check_nonce_authority(nonce_pubkey, &data, &authority.pubkey())?;
+
+    if data.authority == *new_authority {
+        bail!("{new_authority} is already the authority of nonce account {nonce_pubkey}");
+    }
+
+    let instruction = authorize_nonce_account(nonce_pubkey, &authority.pubkey(), new_authority);
+    let Some(signature) =
+        build_and_send_or_export_tx(ctx, &[instruction], &[ctx.signer(), &*authority]).await?
+    else {
+        return Ok(());
+    };
+
+    let mut out = stdout();
+    out.success("Nonce authority changed successfully!");
+    out.line(format!("New Authority: {new_authority}"));
+    out.note(format!("Signature: {signature}"));
+
+    Ok(())
+}
+
+fn nonce_account_table(pubkey: &Pubkey, account: &Account, data: &NonceData) -> Table {
+    field_table([
+        ("Address", pubkey.to_string()),
+        ("Lamports", format!("{}", account.lamports)),
+        (
+            "Balance (SOL)",
+            format!("{:.6}", lamports_to_sol(account.lamports)),
+        ),
+        ("Owner", account.owner.to_string()),
+        ("Executable", format!("{}", account.executable)),
+        ("Rent Epoch", format!("{}", account.rent_epoch)),
+        ("Nonce blockhash", data.blockhash().to_string()),
+        ("Authority", data.authority.to_string()),
+    ])
+}
+
+pub async fn transfer_sol(
     ctx: &ScillaContext,
     receiver: Pubkey,
     amount_sol: f64,
+    memo: &str,
 ) -> anyhow::Result<()> {
-    let lamports = sol_to_lamports(amount_sol);
+    // Template amounts are never typed, so they're checked here too
+    let lamports = SolAmount::try_from(amount_sol)?.to_lamports();
 
     // Validate transfer amount
     let balance = ctx.rpc().get_balance(ctx.pubkey()).await?;
@@ -287,16 +981,541 @@ async fn transfer_sol(
         );
     }
 
-    let instruction = transfer(ctx.pubkey(), &receiver, lamports);
-    let signature = build_and_send_tx(ctx, &[instruction], &[ctx.keypair()]).await?;
+    let mut instructions = vec![transfer(ctx.pubkey(), &receiver, lamports)];
+    let memo = memo.trim();
+    if !memo.is_empty() {
+        instructions.push(memo_instruction(ctx.pubkey(), memo));
+    }
+    let Some(signature) = build_and_send_or_export_tx(ctx, &instructions, &[ctx.signer()]).await?
+    else {
+        return Ok(());
+    };
+
+    let mut out = stdout();
+    out.success("Transfer successful!");
+    out.line(format!("Amount: {} SOL", amount_sol));
+    out.note(format!("Signature: {}", signature));
+    out.note(format!("Recipient Address: {}", receiver));
+
+    Ok(())
+}
+
+/// The transfer a template describes, prompting for the fields it leaves
+/// open.
+async fn templated_transfer(
+    ctx: &ScillaContext,
+    template: &TxTemplate,
+) -> anyhow::Result<(Pubkey, f64, String)> {
+    let to = match &template.recipient {
+        Some(recipient) => recipient
+            .parse()
+            .map_err(|e| anyhow!("Invalid template recipient {recipient}: {e}"))?,
+        None => prompt_pubkey(ctx, "Enter recipient Pubkey:").await,
+    };
+    let amount = match template.amount {
+        Some(amount) => amount,
+        None => prompt_input_data::<SolAmount>("Enter amount (SOL):").value(),
+    };
+    let memo = match &template.memo {
+        Some(memo) => memo.clone(),
+        None => {
+            prompt_input_data_with_default("Enter memo (optional):", ctx.defaults().memo.as_deref())
+        }
+    };
+
+    Ok((to, amount, memo))
+}
+
+fn template_table(template: &TxTemplate) -> Table {
+    let fixed = |value: Option<String>| value.unwrap_or_else(|| "Asked when run".to_string());
+
+    field_table([
+        ("Recipient", fixed(template.recipient.clone())),
+        (
+            "Amount",
+            fixed(template.amount.map(|amount| format!("{amount} SOL"))),
+        ),
+        ("Memo", fixed(template.memo.clone())),
+    ])
+}
+
+/// Pays out a withdrawal queue in batches of `batch_size` transfers per
+/// transaction, tracking every withdrawal in a journal next to the queue.
+///
+/// Running the same queue again resumes it: confirmed withdrawals are
+/// skipped and ones left unconfirmed by an interrupted run are looked up
+/// on-chain before anything is resent.
+async fn process_withdrawal_queue(
+    ctx: &ScillaContext,
+    queue_path: &Path,
+    batch_size: usize,
+) -> anyhow::Result<()> {
+    if !(1..=MAX_BATCH_INSTRUCTIONS_PER_TX).contains(&batch_size) {
+        bail!("Withdrawals per transaction must be between 1 and {MAX_BATCH_INSTRUCTIONS_PER_TX}");
+    }
+
+    let contents = fs::read_to_string(queue_path)
+        .with_context(|| format!("Failed to read {}", queue_path.display()))?;
+    let withdrawals = parse_queue(&contents)?;
+    let mut journal = WithdrawalJournal::open(queue_path)?;
+
+    resolve_submitted_withdrawals(ctx, &withdrawals, &mut journal).await?;
+
+    let failed: Vec<&Withdrawal> = withdrawals
+        .iter()
+        .filter(|withdrawal| {
+            matches!(
+                journal.status(&withdrawal.reference),
+                WithdrawalStatus::Failed { .. }
+            )
+        })
+        .collect();
+    if !failed.is_empty()
+        && prompt_confirmation(&format!("Retry {} failed withdrawals?", failed.len()))
+    {
+        for withdrawal in failed {
+            journal.record(&withdrawal.reference, WithdrawalStatus::Pending)?;
+        }
+    }
+    validate_recipients(ctx, &withdrawals, &mut journal).await?;
+
+    let pending: Vec<&Withdrawal> = withdrawals
+        .iter()
+        .filter(|withdrawal| *journal.status(&withdrawal.reference) == WithdrawalStatus::Pending)
+        .collect();
+
+    let sent = if pending.is_empty() {
+        stdout().note("No pending withdrawals left to send.");
+        Ok(())
+    } else {
+        let total: u64 = pending.iter().map(|withdrawal| withdrawal.lamports).sum();
+        check_minimum_balance(ctx, ctx.pubkey(), total).await?;
+
+        let txs: Vec<PlannedTx> = pending
+            .chunks(batch_size)
+            .map(|batch| PlannedTx::new(withdrawal_instructions(ctx, batch)))
+            .collect();
+        check_batch_cost(ctx, &txs, 0).await?;
+
+        if prompt_confirmation(&format!(
+            "Send {} withdrawals totalling {:.9} SOL in {} transactions?",
+            pending.len(),
+            lamports_to_sol(total),
+            pending.len().div_ceil(batch_size)
+        )) {
+            send_withdrawal_batches(ctx, &pending, batch_size, &mut journal).await
+        } else {
+            stdout().warning("No withdrawals were sent.");
+            Ok(())
+        }
+    };
+
+    // Reported even when a batch fails, so the queue can be reconciled
+    let report_path = write_report(queue_path, &withdrawals, &journal)?;
+
+    let mut out = stdout();
+    out.title("WITHDRAWAL RECONCILIATION");
+    out.table(summary_table(&withdrawals, &journal));
+    out.note(format!("Report: {}", report_path.display()));
+    out.note(format!("Progress: {}", journal.path().display()));
+
+    sent
+}
+
+/// Settles withdrawals an earlier run sent without seeing them confirm.
+/// Landed transactions are recorded as confirmed or failed, and ones whose
+/// blockhash expired without landing go back to pending.
+async fn resolve_submitted_withdrawals(
+    ctx: &ScillaContext,
+    withdrawals: &[Withdrawal],
+    journal: &mut WithdrawalJournal,
+) -> anyhow::Result<()> {
+    // Withdrawals sent in the same transaction share its outcome
+    let mut outcomes = HashMap::new();
+
+    for withdrawal in withdrawals {
+        let WithdrawalStatus::Submitted {
+            signature,
+            blockhash,
+        } = *journal.status(&withdrawal.reference)
+        else {
+            continue;
+        };
+
+        if let Entry::Vacant(entry) = outcomes.entry(signature) {
+            entry.insert(submitted_outcome(ctx, signature, blockhash).await?);
+        }
+
+        journal.record(&withdrawal.reference, outcomes[&signature].clone())?;
+    }
+
+    Ok(())
+}
+
+async fn submitted_outcome(
+    ctx: &ScillaContext,
+    signature: Signature,
+    blockhash: Hash,
+) -> anyhow::Result<WithdrawalStatus> {
+    // Checked first: once expired the transaction can no longer land, so a
+    // missing status below is final
+    let expired = !ctx
+        .rpc()
+        .is_blockhash_valid(&blockhash, CommitmentConfig::processed())
+        .await?;
+
+    let status = match ctx
+        .rpc()
+        .get_signature_status_with_commitment_and_history(&signature, ctx.rpc().commitment(), true)
+        .await?
+    {
+        Some(Ok(())) => WithdrawalStatus::Confirmed(signature),
+        Some(Err(e)) => WithdrawalStatus::Failed {
+            signature: Some(signature),
+            reason: e.to_string(),
+        },
+        None if expired => WithdrawalStatus::Pending,
+        None => {
+            bail!(
+                "Transaction {signature} from an earlier run may still land. Run the queue again \
+                 in a minute"
+            )
+        }
+    };
+
+    Ok(status)
+}
+
+/// Rejects withdrawals that can't be paid as plain SOL transfers: recipients
+/// owned by a program, such as token accounts, and new accounts that would be
+/// left below the rent-exempt minimum. Recipients off the destination
+/// whitelist are rejected too, or in warn mode unless the payout is
+/// confirmed. Withdrawals rejected by an earlier run are checked again, in
+/// case their recipient was fixed in the meantime.
+async fn validate_recipients(
+    ctx: &ScillaContext,
+    withdrawals: &[Withdrawal],
+    journal: &mut WithdrawalJournal,
+) -> anyhow::Result<()> {
+    let unsent: Vec<&Withdrawal> = withdrawals
+        .iter()
+        .filter(|withdrawal| {
+            matches!(
+                journal.status(&withdrawal.reference),
+                WithdrawalStatus::Pending | WithdrawalStatus::Rejected(_)
+            )
+        })
+        .collect();
+    let min_balance = ctx.rpc().get_minimum_balance_for_rent_exemption(0).await?;
+    // Valid recipients off the whitelist, which warn mode asks about
+    let mut unlisted: Vec<&Withdrawal> = Vec::new();
+
+    for chunk in unsent.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        let addresses: Vec<Pubkey> = chunk.iter().map(|withdrawal| withdrawal.address).collect();
+        let accounts = ctx.rpc().get_multiple_accounts(&addresses).await?;
+
+        for (withdrawal, account) in chunk.iter().zip(accounts) {
+            let reason = match account {
+                Some(account) if account.executable => "recipient is a program".to_string(),
+                Some(account) if account.owner != system_program::id() => {
+                    format!("recipient is owned by {}, not a wallet", account.owner)
+                }
+                None if withdrawal.lamports < min_balance => format!(
+                    "new account would be below the rent-exempt minimum of {:.9} SOL",
+                    lamports_to_sol(min_balance)
+                ),
+                _ if ctx.whitelist().refuses(&withdrawal.address) => {
+                    "recipient is not on the destination whitelist".to_string()
+                }
+                _ => {
+                    if !ctx.whitelist().allows(&withdrawal.address) {
+                        unlisted.push(*withdrawal);
+                    }
+                    if *journal.status(&withdrawal.reference) != WithdrawalStatus::Pending {
+                        journal.record(&withdrawal.reference, WithdrawalStatus::Pending)?;
+                    }
+                    continue;
+                }
+            };
+
+            stdout().warning(format!("Rejected {}: {reason}", withdrawal.reference));
+            journal.record(&withdrawal.reference, WithdrawalStatus::Rejected(reason))?;
+        }
+    }
+
+    if !unlisted.is_empty() {
+        let mut out = stdout();
+        for withdrawal in &unlisted {
+            out.warning(format!(
+                "{} pays {}, which is not on the destination whitelist",
+                withdrawal.reference, withdrawal.address
+            ));
+        }
+        if !prompt_confirmation(&format!(
+            "Pay {} withdrawals to addresses not on the whitelist?",
+            unlisted.len()
+        )) {
+            for withdrawal in unlisted {
+                journal.record(
+                    &withdrawal.reference,
+                    WithdrawalStatus::Rejected(
+                        "recipient is not on the destination whitelist".to_string(),
+                    ),
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn withdrawal_instructions(ctx: &ScillaContext, batch: &[&Withdrawal]) -> Vec<Instruction> {
+    batch
+        .iter()
+        .map(|withdrawal| transfer(ctx.pubkey(), &withdrawal.address, withdrawal.lamports))
+        .collect()
+}
+
+/// Signs a SIWS message with the session keypair, printing the text and
+/// signature a wallet-auth backend expects back.
+fn sign_in_message(ctx: &ScillaContext, message: &SiwsMessage) -> anyhow::Result<()> {
+    let text = message.to_string();
+    note_device_confirmation(&[ctx.signer()]);
+    let signature = ctx.signer().try_sign_message(text.as_bytes())?;
+
+    let mut out = stdout();
+    out.title("SIGN-IN MESSAGE");
+    out.line(&text);
+    out.table(field_table([
+        ("Address", message.address.to_string()),
+        ("Signature", signature.to_string()),
+        ("Message (base64)", BASE64_STANDARD.encode(&text)),
+    ]));
+
+    Ok(())
+}
+
+/// Verifies the SIWS message in the file at `path` against `signature`, and
+/// that it was meant for `domain` when given.
+fn verify_sign_in_message(
+    path: &Path,
+    signature: &Signature,
+    domain: Option<&str>,
+) -> anyhow::Result<()> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    // Editors end files with a newline, which is never part of the message
+    let text = contents
+        .strip_suffix('\n')
+        .map_or(contents.as_str(), |text| text.trim_end_matches('\r'));
+
+    let message = verify_message(text, signature, Utc::now())?;
+    if let Some(domain) = domain
+        && message.domain != domain
+    {
+        bail!("Message was issued for {}, not {domain}", message.domain);
+    }
+
+    let mut out = stdout();
+    out.success(format!("Valid sign-in by {}", message.address));
+    out.table(siws_table(&message));
+
+    Ok(())
+}
+
+async fn send_withdrawal_batches(
+    ctx: &ScillaContext,
+    pending: &[&Withdrawal],
+    batch_size: usize,
+    journal: &mut WithdrawalJournal,
+) -> anyhow::Result<()> {
+    let batches = pending.len().div_ceil(batch_size);
+
+    for (idx, batch) in pending.chunks(batch_size).enumerate() {
+        let instructions = withdrawal_instructions(ctx, batch);
+        let tx = sign_tx(ctx, &instructions, &[ctx.signer()]).await?;
+
+        // Recorded before sending, so an interrupted run never pays twice
+        for withdrawal in batch {
+            journal.record(
+                &withdrawal.reference,
+                WithdrawalStatus::Submitted {
+                    signature: tx.signatures[0],
+                    blockhash: tx.message.recent_blockhash,
+                },
+            )?;
+        }
+
+        let signature = send_signed_tx(ctx, &tx).await.map_err(|e| {
+            anyhow!(
+                "Batch {}/{batches} was not confirmed: {e}. Run the queue again to resume, \
+                 withdrawals that landed are not sent twice",
+                idx + 1
+            )
+        })?;
+
+        for withdrawal in batch {
+            journal.record(
+                &withdrawal.reference,
+                WithdrawalStatus::Confirmed(signature),
+            )?;
+        }
+
+        let mut out = stdout();
+        out.success(format!(
+            "Batch {}/{batches}: sent {} withdrawals",
+            idx + 1,
+            batch.len()
+        ));
+        out.note(format!("Signature: {signature}"));
+    }
+
+    Ok(())
+}
+
+/// Sends the transfers listed in a CSV file, packed into as few transactions
+/// as fit and sent in parallel through the leader TPUs, then reports how
+/// every row went.
+async fn process_batch_transfer(ctx: &ScillaContext, path: &Path) -> anyhow::Result<()> {
+    if ctx.offline_signing().is_some() {
+        bail!("Batch transfers can't be signed offline, stop offline signing to run them");
+    }
+
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let transfers = parse_batch_transfers(&contents)?;
+
+    let refused: Vec<String> = transfers
+        .iter()
+        .filter(|transfer| ctx.whitelist().refuses(&transfer.recipient))
+        .map(|transfer| format!("line {}: {}", transfer.line, transfer.recipient))
+        .collect();
+    if !refused.is_empty() {
+        bail!(
+            "Recipients not on the destination whitelist:\n{}",
+            refused.join("\n")
+        );
+    }
+    let unlisted = transfers
+        .iter()
+        .filter(|transfer| !ctx.whitelist().allows(&transfer.recipient))
+        .count();
+    if unlisted > 0
+        && !prompt_confirmation(&format!(
+            "Send {unlisted} transfers to addresses not on the whitelist?"
+        ))
+    {
+        stdout().warning("No transfers were sent.");
+        return Ok(());
+    }
+
+    let batches = pack_transfers(ctx.pubkey(), &transfers)?;
+    let total: u64 = transfers.iter().map(|transfer| transfer.lamports).sum();
+    check_minimum_balance(ctx, ctx.pubkey(), total).await?;
 
-    println!(
-        "\n{} {}\n{}\n{}",
-        style("Transfer successful!").green().bold(),
-        style(format!("Amount: {} SOL", amount_sol)).cyan(),
-        style(format!("Signature: {}", signature)).yellow(),
-        style(format!("Recipient Address: {}", receiver)).yellow()
-    );
+    let txs: Vec<PlannedTx> = batches
+        .iter()
+        .map(|batch| PlannedTx::new(batch_instructions(ctx.pubkey(), batch)))
+        .collect();
+    check_batch_cost(ctx, &txs, 0).await?;
+
+    if ctx.preview_transactions() {
+        preview_first_batch(ctx, &txs).await?;
+    }
+
+    let fingerprints: Vec<u64> = txs
+        .iter()
+        .map(|tx| message_fingerprint(ctx.pubkey(), &tx.instructions))
+        .collect();
+    let repeated = fingerprints
+        .iter()
+        .filter(|fingerprint| ctx.recent_sends().find(**fingerprint).is_some())
+        .count();
+    if repeated > 0 {
+        stdout().warning(format!(
+            "{repeated} of these {} transactions are identical to ones sent recently and may \
+             still land.",
+            txs.len()
+        ));
+    }
+
+    if !prompt_confirmation(&format!(
+        "Send {} transfers totalling {:.9} SOL in {} transactions?",
+        transfers.len(),
+        lamports_to_sol(total),
+        batches.len()
+    )) {
+        stdout().warning("No transfers were sent.");
+        return Ok(());
+    }
+
+    let blockhash = ctx.rpc().get_latest_blockhash().await?;
+    let mut messages = Vec::with_capacity(txs.len());
+    for tx in &txs {
+        let instructions = with_compute_budget(ctx, &tx.instructions).await?;
+        messages.push(Message::new_with_blockhash(
+            &instructions,
+            Some(ctx.pubkey()),
+            &blockhash,
+        ));
+    }
+
+    let errors = send_in_parallel(ctx, &messages).await.map_err(|e| {
+        anyhow!(
+            "{e}. Some transfers may have landed, check the recipients before sending the file \
+             again"
+        )
+    })?;
+
+    for (fingerprint, error) in fingerprints.into_iter().zip(&errors) {
+        if error.is_none() {
+            ctx.recent_sends().record(fingerprint, None);
+        }
+    }
+
+    let mut out = stdout();
+    out.title("BATCH TRANSFER REPORT");
+    out.table(batch_transfer_report_table(&batches, &errors));
+
+    let failed: usize = batches
+        .iter()
+        .zip(&errors)
+        .filter(|(_, error)| error.is_some())
+        .map(|(batch, _)| batch.len())
+        .sum();
+    if failed > 0 {
+        let failed_path = write_failed_rows(path, &contents, &batches, &errors)?;
+        bail!(
+            "{failed} of {} transfers failed. Only they are in {}, send that file to retry them",
+            transfers.len(),
+            failed_path.display()
+        );
+    }
+    out.success(format!("Sent all {} transfers", transfers.len()));
+
+    Ok(())
+}
+
+/// Simulates the first transaction of a batch transfer. The others only
+/// differ in recipients and amounts, so they're not previewed one by one.
+async fn preview_first_batch(ctx: &ScillaContext, txs: &[PlannedTx]) -> anyhow::Result<()> {
+    let Some(first) = txs.first() else {
+        return Ok(());
+    };
+    let instructions = with_compute_budget(ctx, &first.instructions).await?;
+    let preview = simulate_tx(ctx, &Message::new(&instructions, Some(ctx.pubkey()))).await?;
+
+    show_tx_preview(ctx, &preview);
+    if txs.len() > 1 {
+        stdout().note(format!(
+            "Previewed the first of {} transactions, the others are alike",
+            txs.len()
+        ));
+    }
+    if preview.error.is_some() && !prompt_confirmation("Simulation failed, send the batch anyway?")
+    {
+        bail!("No transfers were sent");
+    }
 
     Ok(())
 }
@@ -307,32 +1526,205 @@ async fn fetch_rent(ctx: &ScillaContext, bytes: usize) -> anyhow::Result<()> {
         .get_minimum_balance_for_rent_exemption(bytes)
         .await?;
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
-        .add_row(vec![
-            Cell::new("Data Size"),
-            Cell::new(format!("{} bytes", bytes)),
-        ])
-        .add_row(vec![
-            Cell::new("Minimum Balance (lamports)"),
-            Cell::new(format!("{}", min_balance)),
-        ])
-        .add_row(vec![
-            Cell::new("Minimum Balance (SOL)"),
-            Cell::new(format!("{:.9}", lamports_to_sol(min_balance))),
-        ]);
-
-    println!("\n{}", style("RENT EXEMPTION").green().bold());
-    println!("{table}");
+    let mut out = stdout();
+    out.title("RENT EXEMPTION");
+    out.table(rent_table(bytes, min_balance));
 
     Ok(())
 }
+
+fn rent_table(bytes: usize, min_balance: u64) -> Table {
+    field_table([
+        ("Data Size", format!("{} bytes", bytes)),
+        ("Minimum Balance (lamports)", format!("{}", min_balance)),
+        (
+            "Minimum Balance (SOL)",
+            format!("{:.9}", lamports_to_sol(min_balance)),
+        ),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::ui::render::capture,
+        insta::assert_snapshot,
+        solana_sdk_ids::stake,
+        solana_stake_interface::state::{Authorized, Meta, StakeStateV2},
+    };
+
+    fn account(lamports: u64, data_len: usize) -> Account {
+        Account {
+            lamports,
+            data: vec![0; data_len],
+            owner: Pubkey::new_from_array([1; 32]),
+            executable: false,
+            rent_epoch: u64::MAX,
+        }
+    }
+
+    #[test]
+    fn test_account_table() {
+        let output = capture(|out| out.table(account_table(&account(1_500_000_000, 165))));
+
+        assert_snapshot!("account_table", output);
+    }
+
+    #[test]
+    fn test_accounts_table() {
+        let pubkeys = [
+            Pubkey::new_from_array([3; 32]),
+            Pubkey::new_from_array([4; 32]),
+        ];
+        let mut sliced = account(2_039_280, 0);
+        sliced.data = (0..20).collect();
+        let accounts = [Some(sliced), None];
+        let slice = DataSlice {
+            offset: 32,
+            length: 20,
+        };
+
+        let output = capture(|out| out.table(accounts_table(&pubkeys, &accounts, Some(slice))));
+
+        assert_snapshot!("accounts_table", output);
+    }
+
+    #[test]
+    fn test_nonce_account_table() {
+        let data = NonceData {
+            authority: Pubkey::new_from_array([2; 32]),
+            ..NonceData::default()
+        };
+
+        let output = capture(|out| {
+            out.table(nonce_account_table(
+                &Pubkey::new_from_array([3; 32]),
+                &account(1_447_680, 80),
+                &data,
+            ))
+        });
+
+        assert_snapshot!("nonce_account_table", output);
+    }
+
+    fn stake_account(lamports: u64, withdrawer: Pubkey) -> Account {
+        let state = StakeStateV2::Initialized(Meta {
+            authorized: Authorized {
+                staker: Pubkey::new_from_array([2; 32]),
+                withdrawer,
+            },
+            ..Meta::default()
+        });
+
+        Account {
+            lamports,
+            data: bincode::serialize(&state).expect("stake state should serialize"),
+            owner: stake::id(),
+            executable: false,
+            rent_epoch: u64::MAX,
+        }
+    }
+
+    #[test]
+    fn test_account_changes_decode_known_layouts() {
+        let pubkey = Pubkey::new_from_array([9; 32]);
+        let old = stake_account(1_000_000_000, Pubkey::new_from_array([3; 32]));
+        let new = stake_account(1_500_000_000, Pubkey::new_from_array([4; 32]));
+
+        assert_eq!(
+            account_changes(&pubkey, &old, &new),
+            vec![
+                "Balance: 1 → 1.5 SOL (+0.5 SOL)".to_string(),
+                format!(
+                    "Withdraw Authority: {} → {}",
+                    Pubkey::new_from_array([3; 32]),
+                    Pubkey::new_from_array([4; 32])
+                ),
+            ]
+        );
+        assert!(account_changes(&pubkey, &old, &old).is_empty());
+    }
+
+    #[test]
+    fn test_account_changes_falls_back_to_byte_ranges() {
+        let pubkey = Pubkey::new_from_array([9; 32]);
+        let old = account(2_000_000_000, 16);
+        let mut new = account(1_750_000_000, 16);
+        new.data[2..4].copy_from_slice(&[1, 1]);
+        new.data[10] = 1;
+
+        assert_eq!(
+            account_changes(&pubkey, &old, &new),
+            vec![
+                "Balance: 2 → 1.75 SOL (-0.25 SOL)",
+                "Data: bytes 2..4, 10..11 changed",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_changed_byte_ranges() {
+        assert!(changed_byte_ranges(&[1, 2, 3], &[1, 2, 3]).is_empty());
+        assert_eq!(
+            changed_byte_ranges(&[0, 0, 0, 0], &[1, 0, 1, 1]),
+            vec![0..1, 2..4]
+        );
+        assert_eq!(changed_byte_ranges(&[0, 0], &[0, 0, 5, 5]), vec![2..4]);
+    }
+
+    #[test]
+    fn test_write_watch_updates() {
+        let changes = vec![
+            "Balance: 1 → 1.5 SOL (+0.5 SOL)".to_string(),
+            format!(
+                "Withdraw Authority: {} → {}",
+                Pubkey::new_from_array([3; 32]),
+                Pubkey::new_from_array([4; 32])
+            ),
+        ];
+
+        let output = capture(|out| {
+            write_account_changes(out, 1_000, &changes);
+            write_account_changes(out, 1_001, &[]);
+            write_transaction_update(
+                out,
+                &TransactionUpdate {
+                    slot: 1_001,
+                    signature: Signature::from([4; 64]),
+                    failed: false,
+                },
+            );
+            write_transaction_update(
+                out,
+                &TransactionUpdate {
+                    slot: 1_002,
+                    signature: Signature::from([5; 64]),
+                    failed: true,
+                },
+            );
+        });
+
+        assert_snapshot!("watch_updates", output);
+    }
+
+    #[test]
+    fn test_template_table() {
+        let template = TxTemplate {
+            recipient: Some(Pubkey::new_from_array([2; 32]).to_string()),
+            amount: None,
+            memo: Some("monthly payout".to_string()),
+        };
+
+        let output = capture(|out| out.table(template_table(&template)));
+
+        assert_snapshot!("template_table", output);
+    }
+
+    #[test]
+    fn test_rent_table() {
+        let output = capture(|out| out.table(rent_table(165, 2_039_280)));
+
+        assert_snapshot!("rent_table", output);
+    }
+}
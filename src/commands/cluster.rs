@@ -1,11 +1,27 @@
 use {
     crate::{
-        commands::CommandFlow, constants::LAMPORTS_PER_SOL, context::ScillaContext,
-        ui::show_spinner,
+        commands::CommandFlow,
+        constants::{LAMPORTS_PER_SOL, UPCOMING_LEADER_SLOTS},
+        context::ScillaContext,
+        misc::validator_info::validator_names,
+        prompt::prompt_refresh_interval,
+        ui::{
+            new_table,
+            render::{field_table, header_cell, stdout},
+            show_or_watch, show_spinner,
+            table::PagedTable,
+        },
+    },
+    comfy_table::{Cell, Table},
+    solana_epoch_info::EpochInfo,
+    solana_pubkey::Pubkey,
+    solana_rpc_client_api::response::{RpcInflationRate, RpcSupply, RpcVersionInfo},
+    std::{
+        cmp::Reverse,
+        collections::HashMap,
+        fmt,
+        ops::{Div, RangeInclusive},
     },
-    comfy_table::{Cell, Table, presets::UTF8_FULL},
-    console::style,
-    std::{fmt, ops::Div},
 };
 
 /// Commands related to cluster operations
@@ -15,6 +31,7 @@ pub enum ClusterCommand {
     CurrentSlot,
     BlockHeight,
     BlockTime,
+    SlotLeaders,
     Validators,
     SupplyInfo,
     Inflation,
@@ -29,6 +46,7 @@ impl ClusterCommand {
             ClusterCommand::CurrentSlot => "Fetching latest confirmed slot…",
             ClusterCommand::BlockHeight => "Fetching current block height…",
             ClusterCommand::BlockTime => "Fetching block timestamp…",
+            ClusterCommand::SlotLeaders => "Fetching current and upcoming slot leaders…",
             ClusterCommand::Validators => "Fetching active validators…",
             ClusterCommand::ClusterVersion => "Fetching cluster Solana version…",
             ClusterCommand::SupplyInfo => "Fetching total and circulating supply…",
@@ -45,6 +63,7 @@ impl fmt::Display for ClusterCommand {
             ClusterCommand::CurrentSlot => "Current Slot",
             ClusterCommand::BlockHeight => "Block Height",
             ClusterCommand::BlockTime => "Block Time",
+            ClusterCommand::SlotLeaders => "Slot Leaders",
             ClusterCommand::Validators => "Validators",
             ClusterCommand::ClusterVersion => "Cluster Version",
             ClusterCommand::SupplyInfo => "Supply Info",
@@ -59,7 +78,8 @@ impl ClusterCommand {
     pub async fn process_command(&self, ctx: &ScillaContext) -> CommandFlow<()> {
         match self {
             ClusterCommand::EpochInfo => {
-                show_spinner(self.spinner_msg(), fetch_epoch_info(ctx)).await;
+                let refresh_secs = prompt_refresh_interval();
+                show_or_watch(self.spinner_msg(), refresh_secs, || fetch_epoch_info(ctx)).await;
             }
             ClusterCommand::CurrentSlot => {
                 show_spinner(self.spinner_msg(), fetch_current_slot(ctx)).await;
@@ -70,6 +90,9 @@ impl ClusterCommand {
             ClusterCommand::BlockTime => {
                 show_spinner(self.spinner_msg(), fetch_block_time(ctx)).await;
             }
+            ClusterCommand::SlotLeaders => {
+                show_spinner(self.spinner_msg(), fetch_slot_leaders(ctx)).await;
+            }
             ClusterCommand::Validators => {
                 show_spinner(self.spinner_msg(), fetch_validators(ctx)).await;
             }
@@ -91,107 +114,53 @@ impl ClusterCommand {
     }
 }
 
-async fn fetch_epoch_info(ctx: &ScillaContext) -> anyhow::Result<()> {
+pub async fn fetch_epoch_info(ctx: &ScillaContext) -> anyhow::Result<()> {
     let epoch_info = ctx.rpc().get_epoch_info().await?;
 
+    let mut out = stdout();
+    out.title("EPOCH INFORMATION");
+    out.table(epoch_info_table(&epoch_info));
+
+    Ok(())
+}
+
+fn epoch_info_table(epoch_info: &EpochInfo) -> Table {
     let epoch_progress = if epoch_info.slots_in_epoch > 0 {
         (epoch_info.slot_index as f64 / epoch_info.slots_in_epoch as f64) * 100.0
     } else {
         0.0
     };
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
-        .add_row(vec![
-            Cell::new("Epoch"),
-            Cell::new(format!("{}", epoch_info.epoch)),
-        ])
-        .add_row(vec![
-            Cell::new("Epoch Progress"),
-            Cell::new(format!("{:.2}%", epoch_progress)),
-        ])
-        .add_row(vec![
-            Cell::new("Slot Index"),
-            Cell::new(format!("{}", epoch_info.slot_index)),
-        ])
-        .add_row(vec![
-            Cell::new("Slots in Epoch"),
-            Cell::new(format!("{}", epoch_info.slots_in_epoch)),
-        ])
-        .add_row(vec![
-            Cell::new("Absolute Slot"),
-            Cell::new(format!("{}", epoch_info.absolute_slot)),
-        ])
-        .add_row(vec![
-            Cell::new("Block Height"),
-            Cell::new(format!("{}", epoch_info.block_height)),
-        ])
-        .add_row(vec![
-            Cell::new("Transaction Count"),
-            Cell::new(format!("{}", epoch_info.transaction_count.unwrap_or(0))),
-        ]);
-
-    println!("\n{}", style("EPOCH INFORMATION").green().bold());
-    println!("{table}");
-
-    Ok(())
+    field_table([
+        ("Epoch", format!("{}", epoch_info.epoch)),
+        ("Epoch Progress", format!("{:.2}%", epoch_progress)),
+        ("Slot Index", format!("{}", epoch_info.slot_index)),
+        ("Slots in Epoch", format!("{}", epoch_info.slots_in_epoch)),
+        ("Absolute Slot", format!("{}", epoch_info.absolute_slot)),
+        ("Block Height", format!("{}", epoch_info.block_height)),
+        (
+            "Transaction Count",
+            format!("{}", epoch_info.transaction_count.unwrap_or(0)),
+        ),
+    ])
 }
 
-async fn fetch_current_slot(ctx: &ScillaContext) -> anyhow::Result<()> {
+pub async fn fetch_current_slot(ctx: &ScillaContext) -> anyhow::Result<()> {
     let slot = ctx.rpc().get_slot().await?;
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
-        .add_row(vec![
-            Cell::new("Current Slot"),
-            Cell::new(format!("{slot}")),
-        ]);
-
-    println!("\n{}", style("CURRENT SLOT").green().bold());
-    println!("{table}");
+    let mut out = stdout();
+    out.title("CURRENT SLOT");
+    out.table(field_table([("Current Slot", format!("{slot}"))]));
 
     Ok(())
 }
 
-async fn fetch_block_height(ctx: &ScillaContext) -> anyhow::Result<()> {
+pub async fn fetch_block_height(ctx: &ScillaContext) -> anyhow::Result<()> {
     let block_height = ctx.rpc().get_block_height().await?;
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
-        .add_row(vec![
-            Cell::new("Block Height"),
-            Cell::new(format!("{block_height}")),
-        ]);
-
-    println!("\n{}", style("BLOCK HEIGHT").green().bold());
-    println!("{table}");
+    let mut out = stdout();
+    out.title("BLOCK HEIGHT");
+    out.table(field_table([("Block Height", format!("{block_height}"))]));
 
     Ok(())
 }
@@ -200,107 +169,143 @@ async fn fetch_block_time(ctx: &ScillaContext) -> anyhow::Result<()> {
     let slot = ctx.rpc().get_slot().await?;
     let block_time = ctx.rpc().get_block_time(slot).await?;
 
+    let mut out = stdout();
+    out.title("BLOCK TIME");
+    out.table(block_time_table(slot, block_time));
+
+    Ok(())
+}
+
+fn block_time_table(slot: u64, block_time: i64) -> Table {
     let datetime = chrono::DateTime::<chrono::Utc>::from_timestamp_secs(block_time)
         .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
         .unwrap_or_else(|| "Invalid timestamp".to_string());
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
-        .add_row(vec![Cell::new("Slot"), Cell::new(format!("{slot}"))])
-        .add_row(vec![
-            Cell::new("Unix Timestamp"),
-            Cell::new(format!("{block_time}")),
-        ])
-        .add_row(vec![Cell::new("Date/Time"), Cell::new(datetime)]);
+    field_table([
+        ("Slot", format!("{slot}")),
+        ("Unix Timestamp", format!("{block_time}")),
+        ("Date/Time", datetime),
+    ])
+}
 
-    println!("\n{}", style("BLOCK TIME").green().bold());
-    println!("{table}");
+async fn fetch_slot_leaders(ctx: &ScillaContext) -> anyhow::Result<()> {
+    let slot = ctx.rpc().get_slot().await?;
+    let leaders = ctx
+        .rpc()
+        .get_slot_leaders(slot, UPCOMING_LEADER_SLOTS)
+        .await?;
+
+    // Names are a nicety, the leaders are still worth showing without them
+    let names = validator_names(ctx).await.unwrap_or_else(|e| {
+        stdout().warning(format!("Could not fetch validator names: {e}"));
+        HashMap::new()
+    });
+
+    let mut out = stdout();
+    out.title(format!("SLOT LEADERS FROM SLOT {slot}"));
+    out.table(slot_leaders_table(
+        &leader_rotations(slot, &leaders),
+        &names,
+    ));
 
     Ok(())
 }
 
-async fn fetch_validators(ctx: &ScillaContext) -> anyhow::Result<()> {
-    let validators = ctx.rpc().get_vote_accounts().await?;
+/// Groups the leaders of consecutive slots from `first_slot` into the slot
+/// ranges each of them leads in a row.
+fn leader_rotations(first_slot: u64, leaders: &[Pubkey]) -> Vec<(RangeInclusive<u64>, Pubkey)> {
+    let mut rotations: Vec<(RangeInclusive<u64>, Pubkey)> = Vec::new();
+    for (slot, leader) in (first_slot..).zip(leaders) {
+        match rotations.last_mut() {
+            Some((slots, last)) if last == leader => *slots = *slots.start()..=slot,
+            _ => rotations.push((slot..=slot, *leader)),
+        }
+    }
+    rotations
+}
 
-    // Summary table
-    let mut summary_table = Table::new();
-    summary_table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
-        .add_row(vec![
-            Cell::new("Current Validators"),
-            Cell::new(format!("{}", validators.current.len())),
-        ])
-        .add_row(vec![
-            Cell::new("Delinquent Validators"),
-            Cell::new(format!("{}", validators.delinquent.len())),
+fn slot_leaders_table(
+    rotations: &[(RangeInclusive<u64>, Pubkey)],
+    names: &HashMap<Pubkey, String>,
+) -> Table {
+    let mut table = new_table();
+    table.set_header(["", "Slots", "Leader", "Name"].map(header_cell));
+
+    for (idx, (slots, leader)) in rotations.iter().enumerate() {
+        table.add_row(vec![
+            Cell::new(if idx == 0 { "Current" } else { "" }),
+            Cell::new(format!("{}-{}", slots.start(), slots.end())),
+            Cell::new(leader),
+            Cell::new(names.get(leader).map_or("", String::as_str)),
         ]);
+    }
+
+    table
+}
 
-    println!("\n{}", style("VALIDATORS SUMMARY").green().bold());
-    println!("{summary_table}");
+pub async fn fetch_validators(ctx: &ScillaContext) -> anyhow::Result<()> {
+    let validators = ctx.rpc().get_vote_accounts().await?;
+
+    let mut out = stdout();
+    out.title("VALIDATORS SUMMARY");
+    out.table(field_table([
+        (
+            "Current Validators",
+            format!("{}", validators.current.len()),
+        ),
+        (
+            "Delinquent Validators",
+            format!("{}", validators.delinquent.len()),
+        ),
+    ]));
 
     // Validators detail table
     if !validators.current.is_empty() {
         let mut validators = validators.current;
-        validators.sort_by(|a, b| b.activated_stake.cmp(&a.activated_stake)); // descending
-
-        let mut validators_table = Table::new();
-        validators_table.load_preset(UTF8_FULL).set_header(vec![
-            Cell::new("#").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Node Pubkey").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Vote Account").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Activated Stake (SOL)").add_attribute(comfy_table::Attribute::Bold),
-        ]);
+        validators.sort_by_key(|validator| Reverse(validator.activated_stake));
+
+        let mut validators_table = PagedTable::new(
+            "TOP 10 VALIDATORS BY STAKE",
+            ["#", "Node Pubkey", "Vote Account", "Activated Stake (SOL)"],
+        );
 
         for (idx, validator) in validators.iter().take(10).enumerate() {
             let stake_sol = (validator.activated_stake as f64) / (LAMPORTS_PER_SOL as f64);
 
-            validators_table.add_row(vec![
-                Cell::new(idx + 1),
-                Cell::new(&validator.node_pubkey),
-                Cell::new(&validator.vote_pubkey),
-                Cell::new(format!("{stake_sol:.2}")),
+            validators_table.add_row([
+                (idx + 1).to_string(),
+                validator.node_pubkey.clone(),
+                validator.vote_pubkey.clone(),
+                format!("{stake_sol:.2}"),
             ]);
         }
 
-        println!("\n{}", style("TOP 10 VALIDATORS BY STAKE").green().bold());
-        println!("{validators_table}");
+        validators_table.show()?;
     }
 
     Ok(())
 }
 
-async fn fetch_supply_info(ctx: &ScillaContext) -> anyhow::Result<()> {
+pub async fn fetch_supply_info(ctx: &ScillaContext) -> anyhow::Result<()> {
     let supply = ctx.rpc().supply().await?;
 
-    let total_sol = (supply.value.total as f64).div(LAMPORTS_PER_SOL as f64);
-    let circulating_sol = (supply.value.circulating as f64).div(LAMPORTS_PER_SOL as f64);
-    let non_circulating_sol = (supply.value.non_circulating as f64).div(LAMPORTS_PER_SOL as f64);
+    let mut out = stdout();
+    out.title("SUPPLY INFORMATION");
+    out.table(supply_table(&supply.value));
+
+    Ok(())
+}
+
+fn supply_table(supply: &RpcSupply) -> Table {
+    let total_sol = (supply.total as f64).div(LAMPORTS_PER_SOL as f64);
+    let circulating_sol = (supply.circulating as f64).div(LAMPORTS_PER_SOL as f64);
+    let non_circulating_sol = (supply.non_circulating as f64).div(LAMPORTS_PER_SOL as f64);
     let circulating_pct = (circulating_sol / total_sol) * 100.0;
 
-    let mut table = Table::new();
+    let mut table = new_table();
     table
-        .load_preset(UTF8_FULL)
         .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
+            header_cell("Field"),
             Cell::new("Value (SOL)").add_attribute(comfy_table::Attribute::Bold),
             Cell::new("Percentage").add_attribute(comfy_table::Attribute::Bold),
         ])
@@ -320,66 +325,49 @@ async fn fetch_supply_info(ctx: &ScillaContext) -> anyhow::Result<()> {
             Cell::new(format!("{:.2}%", 100.0 - circulating_pct)),
         ]);
 
-    println!("\n{}", style("SUPPLY INFORMATION").green().bold());
-    println!("{table}");
-
-    Ok(())
+    table
 }
 
-async fn fetch_inflation_info(ctx: &ScillaContext) -> anyhow::Result<()> {
+pub async fn fetch_inflation_info(ctx: &ScillaContext) -> anyhow::Result<()> {
     let inflation = ctx.rpc().get_inflation_rate().await?;
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
-        .add_row(vec![
-            Cell::new("Epoch"),
-            Cell::new(format!("{}", inflation.epoch)),
-        ])
-        .add_row(vec![
-            Cell::new("Total Inflation Rate"),
-            Cell::new(format!("{:.4}%", inflation.total * 100.0)),
-        ])
-        .add_row(vec![
-            Cell::new("Validator Inflation"),
-            Cell::new(format!("{:.4}%", inflation.validator * 100.0)),
-        ])
-        .add_row(vec![
-            Cell::new("Foundation Inflation"),
-            Cell::new(format!("{:.4}%", inflation.foundation * 100.0)),
-        ]);
 
-    println!("\n{}", style("INFLATION INFORMATION").green().bold());
-    println!("{table}");
+    let mut out = stdout();
+    out.title("INFLATION INFORMATION");
+    out.table(inflation_table(&inflation));
 
     Ok(())
 }
 
-async fn fetch_cluster_version(ctx: &ScillaContext) -> anyhow::Result<()> {
+fn inflation_table(inflation: &RpcInflationRate) -> Table {
+    field_table([
+        ("Epoch", format!("{}", inflation.epoch)),
+        (
+            "Total Inflation Rate",
+            format!("{:.4}%", inflation.total * 100.0),
+        ),
+        (
+            "Validator Inflation",
+            format!("{:.4}%", inflation.validator * 100.0),
+        ),
+        (
+            "Foundation Inflation",
+            format!("{:.4}%", inflation.foundation * 100.0),
+        ),
+    ])
+}
+
+pub async fn fetch_cluster_version(ctx: &ScillaContext) -> anyhow::Result<()> {
     let version = ctx.rpc().get_version().await?;
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
-        .add_row(vec![
-            Cell::new("Solana Core"),
-            Cell::new(version.solana_core),
-        ]);
+    let mut out = stdout();
+    out.title("CLUSTER VERSION");
+    out.table(version_table(&version));
+
+    Ok(())
+}
+
+fn version_table(version: &RpcVersionInfo) -> Table {
+    let mut table = field_table([("Solana Core", version.solana_core.clone())]);
 
     if let Some(feature_set) = version.feature_set {
         table.add_row(vec![
@@ -388,8 +376,90 @@ async fn fetch_cluster_version(ctx: &ScillaContext) -> anyhow::Result<()> {
         ]);
     }
 
-    println!("\n{}", style("CLUSTER VERSION").green().bold());
-    println!("{table}");
+    table
+}
 
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::ui::render::capture, insta::assert_snapshot};
+
+    #[test]
+    fn test_epoch_info_table() {
+        let epoch_info = EpochInfo {
+            epoch: 812,
+            slot_index: 108_000,
+            slots_in_epoch: 432_000,
+            absolute_slot: 350_892_000,
+            block_height: 328_904_512,
+            transaction_count: Some(412_345_678_901),
+        };
+
+        let output = capture(|out| out.table(epoch_info_table(&epoch_info)));
+
+        assert_snapshot!("epoch_info_table", output);
+    }
+
+    #[test]
+    fn test_block_time_table() {
+        let output = capture(|out| out.table(block_time_table(350_892_000, 1_760_000_000)));
+
+        assert_snapshot!("block_time_table", output);
+    }
+
+    #[test]
+    fn test_slot_leaders_table() {
+        let leader = |n| Pubkey::new_from_array([n; 32]);
+        let leaders = [1, 1, 2, 2, 2, 2, 3, 3, 3, 3].map(leader);
+        let names = HashMap::from([
+            (leader(1), "Scilla Validator".to_string()),
+            (leader(3), "Matrix Labs".to_string()),
+        ]);
+
+        let rotations = leader_rotations(350_892_002, &leaders);
+        assert_eq!(rotations.len(), 3);
+
+        let output = capture(|out| out.table(slot_leaders_table(&rotations, &names)));
+
+        assert_snapshot!("slot_leaders_table", output);
+    }
+
+    #[test]
+    fn test_supply_table() {
+        let supply = RpcSupply {
+            total: 600_000_000 * LAMPORTS_PER_SOL,
+            circulating: 450_000_000 * LAMPORTS_PER_SOL,
+            non_circulating: 150_000_000 * LAMPORTS_PER_SOL,
+            non_circulating_accounts: vec![],
+        };
+
+        let output = capture(|out| out.table(supply_table(&supply)));
+
+        assert_snapshot!("supply_table", output);
+    }
+
+    #[test]
+    fn test_inflation_table() {
+        let inflation = RpcInflationRate {
+            total: 0.045,
+            validator: 0.045,
+            foundation: 0.0,
+            epoch: 812,
+        };
+
+        let output = capture(|out| out.table(inflation_table(&inflation)));
+
+        assert_snapshot!("inflation_table", output);
+    }
+
+    #[test]
+    fn test_version_table() {
+        let version = RpcVersionInfo {
+            solana_core: "3.0.6".to_string(),
+            feature_set: Some(3_294_202_862),
+        };
+
+        let output = capture(|out| out.table(version_table(&version)));
+
+        assert_snapshot!("version_table", output);
+    }
 }
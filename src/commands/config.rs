@@ -1,18 +1,49 @@
 use {
     crate::{
         commands::CommandFlow,
-        config::{ScillaConfig, scilla_config_path},
+        config::{
+            Cluster, DestinationWhitelist, GeyserConfig, RpcAuth, ScillaConfig, TxTemplate,
+            WhitelistMode, expand_tilde, scilla_config_path,
+        },
+        constants::{
+            COMPUTE_UNIT_LIMIT_MARGIN_PERCENT, DOCTOR_CHECK_TIMEOUT_SECS, MAX_COMPUTE_UNIT_LIMIT,
+        },
         context::ScillaContext,
-        misc::helpers::short_pubkey,
-        prompt::{prompt_input_data, prompt_keypair_path, prompt_network_rpc_url},
-        ui::print_error,
+        misc::{
+            helpers::{SolAmount, lamports_to_sol, short_pubkey, websocket_url_from_rpc},
+            mnemonic::{
+                account_derivation_path, derive_keypair, find_account_index,
+                format_derivation_path, parse_derivation_path, seed_from_mnemonic,
+            },
+            priority_fee::{ComputeUnitLimit, PriorityFee},
+            rpc_auth::{display_url, rpc_client},
+            signer::{is_hardware_wallet, read_signer},
+        },
+        prompt::{
+            answers::{Ask, AskRaw},
+            macros::{
+                MacroStep, finish_step, is_recording, parse_macro, replay_step, start_recording,
+                stop_recording,
+            },
+            prompt_confirmation, prompt_for_command, prompt_input_data,
+            prompt_input_data_with_default, prompt_keypair_path, prompt_network_rpc_url,
+            prompt_optional_data_with_default,
+        },
+        ui::{
+            new_table, print_error,
+            render::{Renderer, header_cell, stdout},
+            show_spinner,
+        },
     },
-    comfy_table::{Cell, Table, presets::UTF8_FULL},
-    console::style,
-    inquire::{Confirm, Select},
+    anyhow::{Context, anyhow, bail},
+    comfy_table::{Cell, Table},
+    inquire::{Confirm, Password, PasswordDisplayMode, Select, Text},
     serde::{Deserialize, Serialize},
-    solana_commitment_config::CommitmentLevel,
-    std::{fmt, fs, path::PathBuf},
+    solana_client::nonblocking::pubsub_client::PubsubClient,
+    solana_commitment_config::{CommitmentConfig, CommitmentLevel},
+    solana_keypair::Signer,
+    solana_pubkey::Pubkey,
+    std::{collections::BTreeMap, fmt, fs, io::Write, path::PathBuf, time::Duration},
 };
 
 /// Commands related to configuration like RPC_URL , KEYAPAIR_PATH etc
@@ -20,6 +51,14 @@ use {
 pub enum ConfigCommand {
     Show,
     Edit,
+    SwitchProfile,
+    SaveProfile,
+    Doctor,
+    Mnemonic,
+    VerifyMnemonic,
+    RecordMacro,
+    StopRecording,
+    ReplayMacro,
     GoBack,
 }
 
@@ -28,6 +67,14 @@ impl ConfigCommand {
         match self {
             ConfigCommand::Show => "Displaying current Scilla configuration…",
             ConfigCommand::Edit => "Editing existing Scilla configuration…",
+            ConfigCommand::SwitchProfile => "Switching profile…",
+            ConfigCommand::SaveProfile => "Saving profile…",
+            ConfigCommand::Doctor => "Checking Scilla setup…",
+            ConfigCommand::Mnemonic => "Deriving keypair from seed phrase…",
+            ConfigCommand::VerifyMnemonic => "Verifying seed phrase backup…",
+            ConfigCommand::RecordMacro => "Recording macro…",
+            ConfigCommand::StopRecording => "Saving recorded macro…",
+            ConfigCommand::ReplayMacro => "Replaying macro…",
             ConfigCommand::GoBack => "Going back…",
         }
     }
@@ -38,6 +85,14 @@ impl fmt::Display for ConfigCommand {
         let command = match self {
             ConfigCommand::Show => "View ScillaConfig",
             ConfigCommand::Edit => "Edit ScillaConfig",
+            ConfigCommand::SwitchProfile => "Switch Profile",
+            ConfigCommand::SaveProfile => "Save Profile",
+            ConfigCommand::Doctor => "Doctor",
+            ConfigCommand::Mnemonic => "Load Keypair From Seed Phrase",
+            ConfigCommand::VerifyMnemonic => "Verify Seed Phrase Backup",
+            ConfigCommand::RecordMacro => "Record Macro",
+            ConfigCommand::StopRecording => "Stop Recording Macro",
+            ConfigCommand::ReplayMacro => "Replay Macro",
             ConfigCommand::GoBack => "Go back",
         };
         write!(f, "{command}")
@@ -47,8 +102,22 @@ impl fmt::Display for ConfigCommand {
 #[derive(Debug, Clone)]
 enum ConfigField {
     RpcUrl,
+    WebsocketUrl,
+    RpcAuth,
     CommitmentLevel,
     KeypairPath,
+    BroadcastRpcUrls,
+    BroadcastViaTpu,
+    PreviewTransactions,
+    PriorityFee,
+    ComputeUnitLimit,
+    BatchSpendLimit,
+    PlainOutput,
+    ShowDomains,
+    Geyser,
+    TxTemplates,
+    DestinationWhitelist,
+    CommandDefaults,
     None,
 }
 
@@ -56,8 +125,22 @@ impl fmt::Display for ConfigField {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             ConfigField::RpcUrl => write!(f, "RPC URL"),
+            ConfigField::WebsocketUrl => write!(f, "Websocket URL"),
+            ConfigField::RpcAuth => write!(f, "RPC Auth"),
             ConfigField::CommitmentLevel => write!(f, "Commitment Level"),
             ConfigField::KeypairPath => write!(f, "Keypair Path"),
+            ConfigField::BroadcastRpcUrls => write!(f, "Broadcast RPC URLs"),
+            ConfigField::BroadcastViaTpu => write!(f, "Broadcast via TPU"),
+            ConfigField::PreviewTransactions => write!(f, "Preview Transactions"),
+            ConfigField::PriorityFee => write!(f, "Priority Fee"),
+            ConfigField::ComputeUnitLimit => write!(f, "Compute Unit Limit"),
+            ConfigField::BatchSpendLimit => write!(f, "Batch Spend Limit"),
+            ConfigField::PlainOutput => write!(f, "Plain Output"),
+            ConfigField::ShowDomains => write!(f, "Show .sol Domains"),
+            ConfigField::Geyser => write!(f, "Yellowstone gRPC"),
+            ConfigField::TxTemplates => write!(f, "Transaction Templates"),
+            ConfigField::DestinationWhitelist => write!(f, "Destination Whitelist"),
+            ConfigField::CommandDefaults => write!(f, "Command Defaults"),
             ConfigField::None => write!(f, "None"),
         }
     }
@@ -67,14 +150,28 @@ impl ConfigField {
     fn all() -> Vec<Self> {
         vec![
             ConfigField::RpcUrl,
+            ConfigField::WebsocketUrl,
+            ConfigField::RpcAuth,
             ConfigField::CommitmentLevel,
             ConfigField::KeypairPath,
+            ConfigField::BroadcastRpcUrls,
+            ConfigField::BroadcastViaTpu,
+            ConfigField::PreviewTransactions,
+            ConfigField::PriorityFee,
+            ConfigField::ComputeUnitLimit,
+            ConfigField::BatchSpendLimit,
+            ConfigField::PlainOutput,
+            ConfigField::ShowDomains,
+            ConfigField::Geyser,
+            ConfigField::TxTemplates,
+            ConfigField::DestinationWhitelist,
+            ConfigField::CommandDefaults,
             ConfigField::None,
         ]
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum UICommitmentOptions {
     Level(CommitmentLevel),
     None,
@@ -89,7 +186,7 @@ impl fmt::Display for UICommitmentOptions {
     }
 }
 
-fn get_commitment_levels() -> Vec<UICommitmentOptions> {
+pub fn get_commitment_levels() -> Vec<UICommitmentOptions> {
     vec![
         UICommitmentOptions::Level(CommitmentLevel::Processed),
         UICommitmentOptions::Level(CommitmentLevel::Confirmed),
@@ -99,10 +196,21 @@ fn get_commitment_levels() -> Vec<UICommitmentOptions> {
 }
 
 impl ConfigCommand {
-    pub fn process_command(&self, ctx: &mut ScillaContext) -> CommandFlow<()> {
+    pub async fn process_command(&self, ctx: &mut ScillaContext) -> CommandFlow<()> {
         let res = match self {
             ConfigCommand::Show => show_config(ctx),
-            ConfigCommand::Edit => edit_config(ctx),
+            ConfigCommand::Edit => edit_config(ctx).await,
+            ConfigCommand::SwitchProfile => switch_profile(ctx).await,
+            ConfigCommand::SaveProfile => save_profile(),
+            ConfigCommand::Doctor => {
+                show_spinner(self.spinner_msg(), run_doctor(ctx)).await;
+                Ok(())
+            }
+            ConfigCommand::Mnemonic => load_mnemonic_keypair(ctx),
+            ConfigCommand::VerifyMnemonic => verify_mnemonic_backup(),
+            ConfigCommand::RecordMacro => start_macro_recording(),
+            ConfigCommand::StopRecording => stop_macro_recording(),
+            ConfigCommand::ReplayMacro => replay_macro(ctx).await,
             ConfigCommand::GoBack => return CommandFlow::GoBack,
         };
 
@@ -115,75 +223,183 @@ impl ConfigCommand {
 }
 
 fn show_config(ctx: &ScillaContext) -> anyhow::Result<()> {
-    let mut table = Table::new();
     let config = ScillaConfig::load()?;
 
-    let wallet_pubkey = ctx.pubkey();
+    let mut out = stdout();
+    out.title("SCILLA CONFIG");
+    out.table(config_table(config, ctx.pubkey()));
+
+    Ok(())
+}
+
+fn config_table(config: ScillaConfig, wallet_pubkey: &Pubkey) -> Table {
     let keypair_display = format!(
         "{} ({})",
         config.keypair_path.display(),
         short_pubkey(wallet_pubkey),
     );
+
+    let mut table = new_table();
     table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
+        .set_header(vec![header_cell("Field"), header_cell("Value")])
+        .add_row(vec![
+            Cell::new("Profiles"),
+            Cell::new(profiles_summary(&config)),
+        ])
+        .add_row(vec![Cell::new("RPC URL"), Cell::new(&config.rpc_url)])
+        .add_row(vec![
+            Cell::new("Websocket URL"),
+            Cell::new(
+                config
+                    .websocket_url
+                    .unwrap_or_else(|| websocket_url_from_rpc(&config.rpc_url)),
+            ),
+        ])
+        .add_row(vec![
+            Cell::new("Cluster"),
+            Cell::new(
+                config
+                    .cluster
+                    .map_or("Unknown".to_string(), |cluster| cluster.to_string()),
+            ),
+        ])
+        .add_row(vec![
+            Cell::new("RPC Auth"),
+            Cell::new(rpc_auth_summary(&config.rpc_auth)),
         ])
-        .add_row(vec![Cell::new("RPC URL"), Cell::new(config.rpc_url)])
         .add_row(vec![
             Cell::new("Commitment Level"),
             Cell::new(config.commitment_level),
         ])
-        .add_row(vec![Cell::new("Keypair Path"), Cell::new(keypair_display)]);
-
-    println!("\n{}", style("SCILLA CONFIG").green().bold());
-    println!("{}", table);
+        .add_row(vec![Cell::new("Keypair Path"), Cell::new(keypair_display)])
+        .add_row(vec![
+            Cell::new("Broadcast RPC URLs"),
+            Cell::new(if config.broadcast_rpc_urls.is_empty() {
+                "None".to_string()
+            } else {
+                config.broadcast_rpc_urls.join("\n")
+            }),
+        ])
+        .add_row(vec![
+            Cell::new("Broadcast via TPU"),
+            Cell::new(config.broadcast_via_tpu),
+        ])
+        .add_row(vec![
+            Cell::new("Duplicate Send Window"),
+            Cell::new(format!("{}s", config.duplicate_send_window_secs)),
+        ])
+        .add_row(vec![
+            Cell::new("Preview Transactions"),
+            Cell::new(config.preview_transactions),
+        ])
+        .add_row(vec![
+            Cell::new("Priority Fee"),
+            Cell::new(match config.priority_fee {
+                PriorityFee::Auto => format!("auto (p{})", config.priority_fee_percentile),
+                fee => fee.to_string(),
+            }),
+        ])
+        .add_row(vec![
+            Cell::new("Compute Unit Limit"),
+            Cell::new(config.compute_unit_limit),
+        ])
+        .add_row(vec![
+            Cell::new("Batch Spend Limit"),
+            Cell::new(
+                config
+                    .batch_spend_limit
+                    .map_or("None".to_string(), |limit| format!("{limit} SOL")),
+            ),
+        ])
+        .add_row(vec![
+            Cell::new("Plain Output"),
+            Cell::new(config.plain_output),
+        ])
+        .add_row(vec![
+            Cell::new("Show .sol Domains"),
+            Cell::new(config.show_domains),
+        ])
+        .add_row(vec![
+            Cell::new("Yellowstone gRPC"),
+            Cell::new(
+                config
+                    .geyser
+                    .as_ref()
+                    .map_or("None", |geyser| geyser.endpoint.as_str()),
+            ),
+        ])
+        .add_row(vec![
+            Cell::new("Transaction Templates"),
+            Cell::new(if config.templates.is_empty() {
+                "None".to_string()
+            } else {
+                config
+                    .templates
+                    .keys()
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }),
+        ])
+        .add_row(vec![
+            Cell::new("Destination Whitelist"),
+            Cell::new(whitelist_summary(&config.whitelist)),
+        ])
+        .add_row(vec![
+            Cell::new("Default Withdraw Authority"),
+            Cell::new(
+                config
+                    .defaults
+                    .withdraw_authority_path
+                    .as_ref()
+                    .map_or("None".to_string(), |path| path.display().to_string()),
+            ),
+        ])
+        .add_row(vec![
+            Cell::new("Default Validator"),
+            Cell::new(config.defaults.validator.as_deref().unwrap_or("None")),
+        ])
+        .add_row(vec![
+            Cell::new("Default Memo"),
+            Cell::new(config.defaults.memo.as_deref().unwrap_or("None")),
+        ]);
 
-    Ok(())
+    table
 }
 
 pub fn generate_config() -> anyhow::Result<()> {
     // Check if config already exists
     let config_path = scilla_config_path();
     if config_path.exists() {
-        println!("{}", style("Config file already exists!").yellow().bold());
-        println!(
-            "{}",
-            style(format!("Location: {}", config_path.display())).cyan()
-        );
-        println!(
-            "{}",
-            style("Use the 'Edit' option to modify your existing config.").cyan()
-        );
+        let mut out = stdout();
+        out.warning("Config file already exists!");
+        out.line(format!("Location: {}", config_path.display()));
+        out.line("Use the 'Edit' option to modify your existing config.");
         return Ok(());
     }
 
-    println!("\n{}", style("Generate New Config").green().bold());
+    stdout().title("Generate New Config");
 
     // Ask if user wants to use defaults
     let use_defaults = Confirm::new("Use default config? (Devnet RPC, Confirmed commitment)")
         .with_default(true)
-        .prompt()?;
+        .ask()?;
 
     let config = if use_defaults {
         let config = ScillaConfig::default();
 
-        println!("{}", style("Using default configuration:").cyan());
-        println!("  RPC: {}", config.rpc_url);
-        println!("  Commitment: {:?}", config.commitment_level);
-        println!("  Keypair: {}", config.keypair_path.display());
+        let mut out = stdout();
+        out.line("Using default configuration:");
+        out.line(format!("  RPC: {}", config.rpc_url));
+        out.line(format!("  Commitment: {:?}", config.commitment_level));
+        out.line(format!("  Keypair: {}", config.keypair_path.display()));
 
         config
     } else {
         let rpc_url: String = prompt_input_data("Enter RPC URL:");
 
         let commitment_level =
-            match Select::new("Select commitment level:", get_commitment_levels()).prompt()? {
+            match Select::new("Select commitment level:", get_commitment_levels()).ask()? {
                 UICommitmentOptions::Level(level) => level,
                 UICommitmentOptions::None => return Ok(()),
             };
@@ -191,15 +407,11 @@ pub fn generate_config() -> anyhow::Result<()> {
         let keypair_path = loop {
             let keypair_input: PathBuf = prompt_input_data("Enter keypair path:");
 
-            if !keypair_input.exists() {
-                println!(
-                    "{}",
-                    style(format!(
-                        "Keypair file not found at: {}",
-                        keypair_input.display()
-                    ))
-                    .red()
-                );
+            if !is_hardware_wallet(&keypair_input) && !keypair_input.exists() {
+                print_error(format!(
+                    "Keypair file not found at: {}",
+                    keypair_input.display()
+                ));
                 continue;
             }
 
@@ -210,6 +422,7 @@ pub fn generate_config() -> anyhow::Result<()> {
             rpc_url,
             commitment_level,
             keypair_path,
+            ..ScillaConfig::default()
         }
     };
 
@@ -222,36 +435,247 @@ pub fn generate_config() -> anyhow::Result<()> {
     let toml_string = toml::to_string_pretty(&config)?;
     fs::write(&config_path, toml_string)?;
 
-    println!("{}", style("Config generated successfully!").green().bold());
-    println!(
-        "{}",
-        style(format!("Saved to: {}", config_path.display())).cyan()
+    let mut out = stdout();
+    out.success("Config generated successfully!");
+    out.note(format!("Saved to: {}", config_path.display()));
+
+    Ok(())
+}
+
+/// Number of account indices offered when picking a derived keypair.
+const MNEMONIC_PREVIEW_ACCOUNTS: u32 = 5;
+
+/// Asks for a seed phrase and its passphrase without echoing them, and
+/// turns them into the wallet seed.
+fn prompt_mnemonic_seed() -> anyhow::Result<Vec<u8>> {
+    let phrase = Password::new("Enter seed phrase:")
+        .without_confirmation()
+        .with_display_mode(PasswordDisplayMode::Masked)
+        .ask()?;
+    let passphrase = Password::new("Enter BIP39 passphrase (optional):")
+        .without_confirmation()
+        .with_display_mode(PasswordDisplayMode::Masked)
+        .ask()?;
+
+    seed_from_mnemonic(&phrase, &passphrase)
+}
+
+fn load_mnemonic_keypair(ctx: &mut ScillaContext) -> anyhow::Result<()> {
+    let seed = prompt_mnemonic_seed()?;
+
+    let mut table = new_table();
+    table.set_header(vec![
+        header_cell("Account"),
+        header_cell("Derivation Path"),
+        header_cell("Pubkey"),
+    ]);
+
+    let mut options = Vec::new();
+    for account in 0..MNEMONIC_PREVIEW_ACCOUNTS {
+        let path = account_derivation_path(account);
+        let pubkey = derive_keypair(&seed, &path)?.pubkey();
+        table.add_row(vec![
+            Cell::new(account),
+            Cell::new(format_derivation_path(&path)),
+            Cell::new(pubkey),
+        ]);
+        options.push(format!("Account {account} ({})", short_pubkey(&pubkey)));
+    }
+    options.push("Custom derivation path".to_string());
+
+    let mut out = stdout();
+    out.title("DERIVED ACCOUNTS");
+    out.table(table);
+
+    let choice = Select::new("Select account to sign with:", options).ask_raw()?;
+    let path = if choice.index < MNEMONIC_PREVIEW_ACCOUNTS as usize {
+        account_derivation_path(choice.index as u32)
+    } else {
+        let path: String = prompt_input_data("Enter derivation path (e.g. m/44'/501'/0'/0'):");
+        parse_derivation_path(&path)?
+    };
+
+    let keypair = derive_keypair(&seed, &path)?;
+    let pubkey = keypair.pubkey();
+    ctx.use_keypair(keypair);
+
+    out.success(format!(
+        "Now signing with {pubkey} ({})",
+        format_derivation_path(&path)
+    ));
+    out.note("The seed phrase is not saved; the configured keypair is used again next run.");
+
+    Ok(())
+}
+
+/// Checks that a seed phrase backup still derives the expected address. It
+/// runs offline and leaves the signing keypair untouched.
+fn verify_mnemonic_backup() -> anyhow::Result<()> {
+    let seed = prompt_mnemonic_seed()?;
+    let path: String = prompt_input_data_with_default(
+        "Enter derivation path:",
+        Some(&format_derivation_path(&account_derivation_path(0))),
     );
+    let path = parse_derivation_path(&path)?;
+    let expected: Pubkey = prompt_input_data("Enter expected public key:");
+
+    let derived = derive_keypair(&seed, &path)?.pubkey();
+    let path = format_derivation_path(&path);
+
+    let mut out = stdout();
+    if derived == expected {
+        out.success(format!("Backup verified: {path} derives {expected}"));
+        return Ok(());
+    }
+
+    out.error(format!(
+        "Backup does not match: {path} derives {derived}, not {expected}"
+    ));
+    match find_account_index(&seed, &expected, MNEMONIC_PREVIEW_ACCOUNTS)? {
+        Some(account) => out.warning(format!(
+            "{expected} is derived at {} instead",
+            format_derivation_path(&account_derivation_path(account))
+        )),
+        None => out.note(
+            "Check the words, their order and the passphrase: any typo derives a different key",
+        ),
+    }
 
     Ok(())
 }
 
-fn edit_config(ctx: &mut ScillaContext) -> anyhow::Result<()> {
+fn start_macro_recording() -> anyhow::Result<()> {
+    let path = expand_tilde(&prompt_input_data::<String>(
+        "Enter macro file to record to:",
+    ));
+    if path.exists() && !prompt_confirmation(&format!("Overwrite {}?", path.display())) {
+        return Ok(());
+    }
+
+    let mut out = stdout();
+    out.success(format!("Recording commands to {}", path.display()));
+    out.note(
+        "Stop with ScillaConfig > Stop Recording Macro. Passwords and seed phrases are not \
+         recorded, a replay asks for them.",
+    );
+    start_recording(path);
+
+    Ok(())
+}
+
+fn stop_macro_recording() -> anyhow::Result<()> {
+    let Some((path, steps)) = stop_recording()? else {
+        bail!("No macro is being recorded");
+    };
+
+    stdout().success(format!(
+        "Saved {steps} recorded commands to {}",
+        path.display()
+    ));
+    Ok(())
+}
+
+/// Runs the commands of a recorded macro one by one, confirming each step
+/// before it runs with the answers it was recorded with.
+async fn replay_macro(ctx: &mut ScillaContext) -> anyhow::Result<()> {
+    if is_recording() {
+        bail!("Stop recording before replaying a macro");
+    }
+
+    let path = expand_tilde(&prompt_input_data::<String>("Enter macro file to replay:"));
+    let text =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let steps = parse_macro(&text)?;
+
+    let mut out = stdout();
+    out.title("MACRO");
+    out.table(macro_table(&steps));
+
+    for (idx, step) in steps.iter().enumerate() {
+        let choice = Select::new(
+            &format!("Step {}/{}: {}", idx + 1, steps.len(), step.title()),
+            vec!["Run", "Skip", "Stop"],
+        )
+        .ask()?;
+        match choice {
+            "Run" => {}
+            "Skip" => continue,
+            _ => return Ok(()),
+        }
+
+        replay_step(step);
+        let flow = match prompt_for_command() {
+            // Boxed, as a step may itself be a config command
+            Ok(command) => Box::pin(command.process_command(ctx)).await,
+            Err(e) => {
+                finish_step();
+                return Err(e);
+            }
+        };
+
+        let unused = finish_step();
+        if unused > 0 {
+            stdout().warning(format!("Step {} left {unused} answers unused", idx + 1));
+        }
+        if let CommandFlow::Exit = flow {
+            stdout().note("Macro chose Exit, stopping the replay");
+            return Ok(());
+        }
+    }
+
+    stdout().success(format!("Replayed {}", path.display()));
+    Ok(())
+}
+
+fn macro_table(steps: &[MacroStep]) -> Table {
+    let mut table = new_table();
+    table.set_header(vec![
+        header_cell("Step"),
+        header_cell("Command"),
+        header_cell("Answers"),
+    ]);
+
+    for (idx, step) in steps.iter().enumerate() {
+        let answers = step
+            .answers
+            .iter()
+            .skip(2)
+            .map(|answer| match answer.as_str() {
+                "" => "(default)",
+                answer => answer,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        table.add_row(vec![
+            Cell::new(idx + 1),
+            Cell::new(step.title()),
+            Cell::new(answers),
+        ]);
+    }
+
+    table
+}
+
+async fn edit_config(ctx: &mut ScillaContext) -> anyhow::Result<()> {
     let mut config = ScillaConfig::load()?;
 
-    println!("\n{}", style("Edit Config").green().bold());
+    let mut out = stdout();
+    out.title("Edit Config");
 
     // Show current configuration
-    println!("\n{} {}", style("Current RPC URL:").cyan(), config.rpc_url);
-    println!(
-        "{} {:?}",
-        style("Current Commitment Level:").cyan(),
+    out.line(format!("\nCurrent RPC URL: {}", config.rpc_url));
+    out.line(format!(
+        "Current Commitment Level: {:?}",
         config.commitment_level
-    );
-    println!(
-        "{} {}",
-        style("Current Keypair Path:").cyan(),
+    ));
+    out.line(format!(
+        "Current Keypair Path: {}",
         config.keypair_path.display()
-    );
+    ));
 
     // Prompt user to select which field to edit
     let field_options = ConfigField::all();
-    let selected_field = Select::new("Select field to edit:", field_options).prompt()?;
+    let selected_field = Select::new("Select field to edit:", field_options).ask()?;
 
     match selected_field {
         ConfigField::RpcUrl => {
@@ -259,7 +683,7 @@ fn edit_config(ctx: &mut ScillaContext) -> anyhow::Result<()> {
                 "Do you want to use a custom RPC Url or one of the defaults?",
                 vec!["Default", "Custom"],
             )
-            .prompt()?;
+            .ask()?;
             let new_rpc_url = match choice {
                 "Default" => prompt_network_rpc_url()?,
                 "Custom" => prompt_input_data::<String>("Enter custom RPC URL:"),
@@ -267,10 +691,31 @@ fn edit_config(ctx: &mut ScillaContext) -> anyhow::Result<()> {
             };
 
             config.rpc_url = new_rpc_url;
+            config.cluster = None;
+            // A websocket set for the previous RPC likely belongs to another
+            // provider
+            config.websocket_url = None;
+        }
+        ConfigField::WebsocketUrl => {
+            config.websocket_url = prompt_optional_default(
+                "Websocket URL (empty to derive it from the RPC URL):",
+                config.websocket_url.clone(),
+            )?;
+        }
+        ConfigField::RpcAuth => {
+            let auth = &mut config.rpc_auth;
+
+            auth.query = prompt_key_values(
+                "API key query parameters (name=value, comma separated):",
+                &auth.query,
+            )?;
+            auth.headers =
+                prompt_key_values("HTTP headers (name=value, comma separated):", &auth.headers)?;
+            // Credentials may be what kept the cluster from being detected
+            config.cluster = None;
         }
         ConfigField::CommitmentLevel => {
-            let selected =
-                Select::new("Select Commitment Level", get_commitment_levels()).prompt()?;
+            let selected = Select::new("Select Commitment Level", get_commitment_levels()).ask()?;
 
             let level = match selected {
                 UICommitmentOptions::Level(level) => level,
@@ -282,24 +727,222 @@ fn edit_config(ctx: &mut ScillaContext) -> anyhow::Result<()> {
         ConfigField::KeypairPath => loop {
             let keypair_input = prompt_keypair_path("Enter new keypair path:", ctx);
 
-            if !keypair_input.exists() {
-                println!(
-                    "{}",
-                    style(format!(
-                        "Keypair file not found at: {}",
-                        keypair_input.display()
-                    ))
-                    .red()
-                );
+            if !is_hardware_wallet(&keypair_input) && !keypair_input.exists() {
+                print_error(format!(
+                    "Keypair file not found at: {}",
+                    keypair_input.display()
+                ));
                 continue;
             }
 
             config.keypair_path = keypair_input;
             break;
         },
+        ConfigField::BroadcastRpcUrls => {
+            let urls = Text::new("Enter broadcast RPC URLs (comma separated):")
+                .with_default(&config.broadcast_rpc_urls.join(","))
+                .with_help_message("Leave empty to only send through the primary RPC")
+                .ask()?;
+
+            config.broadcast_rpc_urls = urls
+                .split(',')
+                .map(str::trim)
+                .filter(|url| !url.is_empty())
+                .map(String::from)
+                .collect();
+        }
+        ConfigField::BroadcastViaTpu => {
+            config.broadcast_via_tpu =
+                Confirm::new("Also forward signed transactions to the leader TPUs?")
+                    .with_default(config.broadcast_via_tpu)
+                    .ask()?;
+        }
+        ConfigField::PreviewTransactions => {
+            config.preview_transactions =
+                Confirm::new("Simulate transactions and confirm them before sending?")
+                    .with_default(config.preview_transactions)
+                    .with_help_message(
+                        "Shows the fee, compute units, logs and balance changes first",
+                    )
+                    .ask()?;
+        }
+        ConfigField::PriorityFee => {
+            let choice = Select::new(
+                "How should the compute unit price be set?",
+                vec!["None", "Auto", "Fixed"],
+            )
+            .ask()?;
+
+            config.priority_fee = match choice {
+                "None" => PriorityFee::None,
+                "Auto" => loop {
+                    let percentile: u8 = prompt_input_data_with_default(
+                        "Percentile of recent fees to pay (0-100):",
+                        Some(&config.priority_fee_percentile.to_string()),
+                    );
+                    if percentile > 100 {
+                        print_error("Percentile must be between 0 and 100");
+                        continue;
+                    }
+
+                    config.priority_fee_percentile = percentile;
+                    break PriorityFee::Auto;
+                },
+                "Fixed" => PriorityFee::MicroLamports(prompt_input_data(
+                    "Enter compute unit price (micro-lamports):",
+                )),
+                _ => unreachable!(),
+            };
+        }
+        ConfigField::ComputeUnitLimit => {
+            let choice = Select::new(
+                "How should the compute unit limit be set?",
+                vec!["None", "Auto", "Fixed"],
+            )
+            .with_help_message(&format!(
+                "Auto simulates each transaction and adds a {COMPUTE_UNIT_LIMIT_MARGIN_PERCENT}% \
+                 margin"
+            ))
+            .ask()?;
+
+            config.compute_unit_limit = match choice {
+                "None" => ComputeUnitLimit::None,
+                "Auto" => ComputeUnitLimit::Auto,
+                "Fixed" => loop {
+                    let units: u32 = prompt_input_data("Enter compute unit limit:");
+                    if units == 0 || u64::from(units) > MAX_COMPUTE_UNIT_LIMIT {
+                        print_error(format!(
+                            "Compute unit limit must be between 1 and {MAX_COMPUTE_UNIT_LIMIT}"
+                        ));
+                        continue;
+                    }
+                    break ComputeUnitLimit::Units(units);
+                },
+                _ => unreachable!(),
+            };
+        }
+        ConfigField::BatchSpendLimit => {
+            let limit: Option<SolAmount> = prompt_optional_data_with_default(
+                "Most SOL a batch operation may spend on fees and rent (empty for no limit):",
+                config
+                    .batch_spend_limit
+                    .map(|limit| limit.to_string())
+                    .as_deref(),
+            );
+            config.batch_spend_limit = limit.map(|limit| limit.value());
+        }
+        ConfigField::PlainOutput => {
+            config.plain_output = Confirm::new("Print without colors and draw tables in ASCII?")
+                .with_default(config.plain_output)
+                .with_help_message("Setting NO_COLOR also enables this")
+                .ask()?;
+        }
+        ConfigField::ShowDomains => {
+            config.show_domains = Confirm::new("Show the primary .sol domain next to addresses?")
+                .with_default(config.show_domains)
+                .with_help_message("Takes extra RPC requests when listing accounts")
+                .ask()?;
+        }
+        ConfigField::Geyser => {
+            let current = config.geyser.take();
+            let endpoint = prompt_optional_default(
+                "Yellowstone gRPC endpoint:",
+                current.as_ref().map(|geyser| geyser.endpoint.clone()),
+            )?;
+
+            if let Some(endpoint) = endpoint {
+                let x_token = prompt_optional_default(
+                    "x-token (optional):",
+                    current.and_then(|geyser| geyser.x_token),
+                )?;
+                config.geyser = Some(GeyserConfig { endpoint, x_token });
+            }
+        }
+        ConfigField::TxTemplates => {
+            let choice = Select::new(
+                "Save a transfer template or remove one?",
+                vec!["Save", "Remove"],
+            )
+            .ask()?;
+
+            match choice {
+                "Save" => {
+                    let name: String = prompt_input_data("Template name:");
+                    let template = prompt_tx_template(
+                        config.templates.get(&name).cloned().unwrap_or_default(),
+                    )?;
+                    config.templates.insert(name, template);
+                }
+                "Remove" => {
+                    let names: Vec<String> = config.templates.keys().cloned().collect();
+                    if names.is_empty() {
+                        bail!("No transaction templates saved");
+                    }
+                    let name = Select::new("Template to remove:", names).ask()?;
+                    config.templates.remove(&name);
+                }
+                _ => unreachable!(),
+            }
+        }
+        ConfigField::DestinationWhitelist => {
+            let whitelist = &mut config.whitelist;
+
+            let addresses = Text::new("Approved destination addresses (comma separated):")
+                .with_default(&whitelist.addresses.join(","))
+                .with_help_message("Leave empty to allow any destination")
+                .ask()?;
+            whitelist.addresses = addresses
+                .split(',')
+                .map(str::trim)
+                .filter(|address| !address.is_empty())
+                .map(String::from)
+                .collect();
+            for address in &whitelist.addresses {
+                address
+                    .parse::<Pubkey>()
+                    .map_err(|e| anyhow!("Invalid address {address}: {e}"))?;
+            }
+
+            whitelist.mode = Select::new(
+                "When a destination is not on the list:",
+                vec![WhitelistMode::Warn, WhitelistMode::Refuse],
+            )
+            .with_help_message("warn asks before sending, refuse doesn't send")
+            .ask()?;
+        }
+        ConfigField::CommandDefaults => {
+            let defaults = &mut config.defaults;
+
+            let withdraw_authority_path = prompt_optional_default(
+                "Default withdraw authority keypair path:",
+                defaults
+                    .withdraw_authority_path
+                    .as_ref()
+                    .map(|path| path.display().to_string()),
+            )?;
+            defaults.withdraw_authority_path = withdraw_authority_path.map(PathBuf::from);
+
+            defaults.validator = prompt_optional_default(
+                "Default validator vote account:",
+                defaults.validator.clone(),
+            )?;
+            if let Some(validator) = &defaults.validator {
+                validator
+                    .parse::<Pubkey>()
+                    .map_err(|e| anyhow!("Invalid validator vote account: {e}"))?;
+            }
+
+            defaults.memo =
+                prompt_optional_default("Default transfer memo:", defaults.memo.clone())?;
+        }
         ConfigField::None => return Ok(()),
     }
 
+    if config.cluster.is_none() {
+        detect_cluster(&mut config).await;
+    }
+    config.sync_active_profile();
+
     // Write updated config
     let config_path = scilla_config_path();
     let toml_string = toml::to_string_pretty(&config)?;
@@ -307,11 +950,527 @@ fn edit_config(ctx: &mut ScillaContext) -> anyhow::Result<()> {
 
     ctx.reload(config)?;
 
-    println!("{}", style("Config updated successfully!").green().bold());
-    println!(
-        "{}",
-        style(format!("Saved to: {}", config_path.display())).cyan()
+    let mut out = stdout();
+    out.success("Config updated successfully!");
+    out.note(format!("Saved to: {}", config_path.display()));
+
+    Ok(())
+}
+
+/// Moves the session to another profile's RPC, keypair and commitment,
+/// without restarting.
+async fn switch_profile(ctx: &mut ScillaContext) -> anyhow::Result<()> {
+    let mut config = ScillaConfig::load()?;
+
+    let names: Vec<String> = config.profiles.keys().cloned().collect();
+    if names.is_empty() {
+        bail!("No profiles saved yet, save the current settings with ScillaConfig > Save Profile");
+    }
+    let name = Select::new("Switch to profile:", names).ask()?;
+
+    config.switch_profile(&name)?;
+    if config.cluster.is_none() && detect_cluster(&mut config).await {
+        config.sync_active_profile();
+    }
+    config.save()?;
+
+    ctx.reload(config)?;
+
+    stdout().success(format!(
+        "Switched to {name}: {} as {}",
+        display_url(ctx.rpc_url()),
+        ctx.pubkey()
+    ));
+    Ok(())
+}
+
+/// Stores the RPC, websocket, keypair and commitment in use as a named
+/// profile.
+fn save_profile() -> anyhow::Result<()> {
+    let mut config = ScillaConfig::load()?;
+
+    let suggested = config
+        .profile
+        .clone()
+        .or_else(|| config.cluster.map(|cluster| cluster.to_string()));
+    let name: String = prompt_input_data_with_default("Profile name:", suggested.as_deref());
+    let name = name.trim();
+    if name.is_empty() {
+        bail!("Profile name can't be empty");
+    }
+
+    if config.profiles.contains_key(name)
+        && config.profile.as_deref() != Some(name)
+        && !prompt_confirmation(&format!("Overwrite profile {name}?"))
+    {
+        return Ok(());
+    }
+
+    config.save_profile(name);
+    config.save()?;
+
+    stdout().success(format!(
+        "Saved profile {name} ({})",
+        display_url(&config.rpc_url)
+    ));
+    Ok(())
+}
+
+/// Names of the saved profiles, marking the active one.
+fn profiles_summary(config: &ScillaConfig) -> String {
+    if config.profiles.is_empty() {
+        return "None".to_string();
+    }
+
+    config
+        .profiles
+        .keys()
+        .map(|name| {
+            if config.profile.as_deref() == Some(name) {
+                format!("{name} (active)")
+            } else {
+                name.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Prompts for an optional config value, an empty answer clears it.
+fn prompt_optional_default(msg: &str, current: Option<String>) -> anyhow::Result<Option<String>> {
+    let value = Text::new(msg)
+        .with_default(current.as_deref().unwrap_or_default())
+        .with_help_message("Leave empty to clear")
+        .ask()?;
+
+    let value = value.trim();
+    Ok((!value.is_empty()).then(|| value.to_string()))
+}
+
+/// Prompts for the fixed fields of a transfer template, starting from
+/// `current`. Fields left empty are asked for whenever the template runs.
+fn prompt_tx_template(current: TxTemplate) -> anyhow::Result<TxTemplate> {
+    let recipient =
+        prompt_optional_default("Recipient (empty to ask every run):", current.recipient)?;
+    if let Some(recipient) = &recipient {
+        recipient
+            .parse::<Pubkey>()
+            .map_err(|e| anyhow!("Invalid recipient: {e}"))?;
+    }
+
+    let amount: Option<SolAmount> = prompt_optional_data_with_default(
+        "Amount in SOL (empty to ask every run):",
+        current.amount.map(|amount| amount.to_string()).as_deref(),
+    );
+    let memo = prompt_optional_default("Memo (empty to ask every run):", current.memo)?;
+
+    Ok(TxTemplate {
+        recipient,
+        amount: amount.map(|amount| amount.value()),
+        memo,
+    })
+}
+
+fn whitelist_summary(whitelist: &DestinationWhitelist) -> String {
+    match whitelist.addresses.len() {
+        0 => "None".to_string(),
+        1 => format!("1 address ({})", whitelist.mode),
+        count => format!("{count} addresses ({})", whitelist.mode),
+    }
+}
+
+/// Names of the configured credentials, leaving their secret values out.
+fn rpc_auth_summary(auth: &RpcAuth) -> String {
+    if auth.is_empty() {
+        return "None".to_string();
+    }
+
+    let query = auth.query.keys().map(|name| format!("{name} (query)"));
+    let headers = auth.headers.keys().map(|name| format!("{name} (header)"));
+    query.chain(headers).collect::<Vec<_>>().join("\n")
+}
+
+fn prompt_key_values(
+    msg: &str,
+    current: &BTreeMap<String, String>,
+) -> anyhow::Result<BTreeMap<String, String>> {
+    let current = current
+        .iter()
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>()
+        .join(",");
+    let input = Text::new(msg)
+        .with_default(&current)
+        .with_help_message("Leave empty to clear")
+        .ask()?;
+
+    input
+        .split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair
+                .split_once('=')
+                .ok_or_else(|| anyhow!("Expected name=value, got {pair}"))?;
+            Ok((name.trim().to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+enum CheckStatus {
+    Pass(String),
+    Warn { problem: String, fix: String },
+    Fail { problem: String, fix: String },
+}
+
+struct DoctorCheck {
+    name: &'static str,
+    status: CheckStatus,
+}
+
+impl DoctorCheck {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Pass(detail.into()),
+        }
+    }
+
+    fn warn(name: &'static str, problem: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Warn {
+                problem: problem.into(),
+                fix: fix.into(),
+            },
+        }
+    }
+
+    fn fail(name: &'static str, problem: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Fail {
+                problem: problem.into(),
+                fix: fix.into(),
+            },
+        }
+    }
+}
+
+/// Runs a network check, turning a hang into an error.
+async fn with_timeout<T>(fut: impl Future<Output = anyhow::Result<T>>) -> anyhow::Result<T> {
+    tokio::time::timeout(Duration::from_secs(DOCTOR_CHECK_TIMEOUT_SECS), fut)
+        .await
+        .map_err(|_| anyhow!("timed out after {DOCTOR_CHECK_TIMEOUT_SECS}s"))?
+}
+
+fn cluster_for_genesis_hash(genesis_hash: &str) -> Option<&'static str> {
+    match Cluster::from_genesis_hash(genesis_hash) {
+        Cluster::Custom => None,
+        cluster => Some(cluster.name()),
+    }
+}
+
+/// Detects the cluster `rpc-url` serves from its genesis hash and stores it
+/// in `config`. The stored cluster is kept when the RPC can't be reached.
+/// Returns whether it changed.
+pub async fn detect_cluster(config: &mut ScillaConfig) -> bool {
+    let commitment = CommitmentConfig {
+        commitment: config.commitment_level,
+    };
+    let detected = with_timeout(async {
+        let rpc = rpc_client(&config.rpc_url, commitment, &config.rpc_auth)?;
+        let genesis_hash = rpc.get_genesis_hash().await?;
+        Ok(Cluster::from_genesis_hash(&genesis_hash.to_string()))
+    })
+    .await;
+
+    match detected {
+        Ok(cluster) if config.cluster == Some(cluster) => false,
+        Ok(cluster) => {
+            if let Some(previous) = config.cluster {
+                stdout().warning(format!(
+                    "{} now serves {cluster}, it used to serve {previous}",
+                    display_url(&config.rpc_url)
+                ));
+            }
+            config.cluster = Some(cluster);
+            true
+        }
+        Err(e) => {
+            stdout().warning(format!(
+                "Could not detect the cluster of {}: {e}",
+                display_url(&config.rpc_url)
+            ));
+            false
+        }
+    }
+}
+
+/// The cluster an RPC URL claims to point at, judging by its host name.
+fn cluster_from_url(rpc_url: &str) -> Option<&'static str> {
+    ["mainnet", "devnet", "testnet"]
+        .into_iter()
+        .find(|cluster| rpc_url.contains(cluster))
+}
+
+async fn run_doctor(ctx: &ScillaContext) -> anyhow::Result<()> {
+    let config_path = scilla_config_path();
+    let mut checks = Vec::new();
+
+    let config = match ScillaConfig::load_from_path(&config_path) {
+        Ok(config) => {
+            checks.push(DoctorCheck::pass(
+                "Config file",
+                format!("{} parses", config_path.display()),
+            ));
+            Some(config)
+        }
+        Err(e) => {
+            checks.push(DoctorCheck::fail(
+                "Config file",
+                format!("{}: {e}", config_path.display()),
+                "Fix the TOML syntax or regenerate it by deleting the file and restarting Scilla",
+            ));
+            None
+        }
+    };
+
+    let keypair_path = config
+        .as_ref()
+        .map_or_else(|| ctx.keypair_path().clone(), |c| c.keypair_path.clone());
+    checks.push(
+        if !is_hardware_wallet(&keypair_path) && !keypair_path.exists() {
+            DoctorCheck::fail(
+                "Keypair",
+                format!("{} does not exist", keypair_path.display()),
+                format!(
+                    "Create one with `solana-keygen new -o {}` or point keypair-path at an \
+                     existing keypair",
+                    keypair_path.display()
+                ),
+            )
+        } else {
+            match read_signer(&keypair_path) {
+                Ok(signer) => DoctorCheck::pass("Keypair", format!("Loaded {}", signer.pubkey())),
+                Err(e) if is_hardware_wallet(&keypair_path) => DoctorCheck::fail(
+                    "Keypair",
+                    e.to_string(),
+                    if cfg!(feature = "ledger") {
+                        "Plug in and unlock the hardware wallet and open its Solana app"
+                    } else {
+                        "Rebuild Scilla with `--features ledger`"
+                    },
+                ),
+                Err(e) => DoctorCheck::fail(
+                    "Keypair",
+                    e.to_string(),
+                    "Make sure the file is a JSON array of 64 bytes as written by solana-keygen",
+                ),
+            }
+        },
     );
 
+    let rpc_url = ctx.rpc_url();
+    match with_timeout(async { Ok(ctx.rpc().get_version().await?) }).await {
+        Ok(version) => {
+            checks.push(DoctorCheck::pass(
+                "RPC",
+                format!("{rpc_url} (solana-core {})", version.solana_core),
+            ));
+
+            checks.push(
+                match with_timeout(async { Ok(ctx.rpc().get_genesis_hash().await?) }).await {
+                    Ok(genesis_hash) => {
+                        let genesis_hash = genesis_hash.to_string();
+                        match (
+                            cluster_for_genesis_hash(&genesis_hash),
+                            cluster_from_url(rpc_url),
+                        ) {
+                            (Some(actual), Some(expected)) if actual != expected => {
+                                DoctorCheck::fail(
+                                    "Cluster",
+                                    format!("RPC URL looks like {expected} but serves {actual}"),
+                                    "Double check rpc-url before sending any transaction",
+                                )
+                            }
+                            (Some(actual), _) => DoctorCheck::pass("Cluster", actual),
+                            (None, _) => DoctorCheck::pass(
+                                "Cluster",
+                                format!("Custom cluster (genesis {genesis_hash})"),
+                            ),
+                        }
+                    }
+                    Err(e) => DoctorCheck::warn(
+                        "Cluster",
+                        format!("Could not fetch genesis hash: {e}"),
+                        "The RPC may restrict some methods, try another endpoint",
+                    ),
+                },
+            );
+        }
+        Err(e) => checks.push(DoctorCheck::fail(
+            "RPC",
+            format!("{rpc_url} is unreachable: {e}"),
+            "Check your connection or set a different rpc-url via ScillaConfig > Edit",
+        )),
+    }
+
+    // Shown without the credentials it is connected with
+    let websocket_url = ctx.plain_websocket_url();
+    checks.push(
+        match with_timeout(async { Ok(PubsubClient::new(&ctx.websocket_url()?).await?) }).await {
+            Ok(client) => {
+                client.shutdown().await.ok();
+                DoctorCheck::pass("Websocket", websocket_url)
+            }
+            Err(e) => DoctorCheck::warn(
+                "Websocket",
+                format!("{websocket_url} is unreachable: {e}"),
+                "Subscriptions won't work; use an RPC provider that exposes a websocket endpoint",
+            ),
+        },
+    );
+
+    checks.push(
+        match with_timeout(async { Ok(ctx.rpc().get_balance(ctx.pubkey()).await?) }).await {
+            Ok(0) => DoctorCheck::warn(
+                "Balance",
+                format!("{} has no SOL", ctx.pubkey()),
+                "Fund the wallet, or on devnet/testnet use Account > Request airdrop",
+            ),
+            Ok(lamports) => {
+                DoctorCheck::pass("Balance", format!("{} SOL", lamports_to_sol(lamports)))
+            }
+            Err(e) => DoctorCheck::warn(
+                "Balance",
+                format!("Could not fetch balance: {e}"),
+                "Fix the RPC check above first",
+            ),
+        },
+    );
+
+    write_doctor_report(&mut stdout(), &checks);
+
     Ok(())
 }
+
+fn write_doctor_report<W: Write>(out: &mut Renderer<W>, checks: &[DoctorCheck]) {
+    let mut table = new_table();
+    table.set_header(vec![
+        header_cell("Check"),
+        header_cell("Status"),
+        header_cell("Details"),
+    ]);
+
+    let mut fixes = Vec::new();
+    for check in checks {
+        let (status, detail) = match &check.status {
+            CheckStatus::Pass(detail) => (Cell::new("OK").fg(comfy_table::Color::Green), detail),
+            CheckStatus::Warn { problem, fix } => {
+                fixes.push((check.name, fix));
+                (Cell::new("WARN").fg(comfy_table::Color::Yellow), problem)
+            }
+            CheckStatus::Fail { problem, fix } => {
+                fixes.push((check.name, fix));
+                (Cell::new("FAIL").fg(comfy_table::Color::Red), problem)
+            }
+        };
+        table.add_row(vec![Cell::new(check.name), status, Cell::new(detail)]);
+    }
+
+    out.title("SCILLA DOCTOR");
+    out.table(table);
+
+    if fixes.is_empty() {
+        out.success("Everything looks good!");
+        return;
+    }
+
+    out.warning("\nSuggested fixes:");
+    for (name, fix) in fixes {
+        out.line(format!("  {name}: {fix}"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{constants::DEVNET_GENESIS_HASH, ui::render::capture},
+        insta::assert_snapshot,
+    };
+
+    #[test]
+    fn test_cluster_mismatch_detection() {
+        assert_eq!(
+            cluster_for_genesis_hash(DEVNET_GENESIS_HASH),
+            Some("devnet")
+        );
+        assert_eq!(
+            cluster_from_url("https://api.devnet.solana.com"),
+            Some("devnet")
+        );
+        assert_eq!(cluster_from_url("http://localhost:8899"), None);
+    }
+
+    #[test]
+    fn test_config_table() {
+        let config = ScillaConfig {
+            keypair_path: PathBuf::from("/home/scilla/.config/solana/id.json"),
+            broadcast_rpc_urls: vec![
+                "https://rpc-a.example.com".to_string(),
+                "https://rpc-b.example.com".to_string(),
+            ],
+            ..ScillaConfig::default()
+        };
+
+        let output =
+            capture(|out| out.table(config_table(config, &Pubkey::new_from_array([1; 32]))));
+
+        assert_snapshot!("config_table", output);
+    }
+
+    #[test]
+    fn test_macro_table() {
+        let steps = [
+            MacroStep {
+                answers: vec![
+                    "Account".to_string(),
+                    "Transfer SOL".to_string(),
+                    Pubkey::new_from_array([2; 32]).to_string(),
+                    "1.5".to_string(),
+                    String::new(),
+                ],
+            },
+            MacroStep {
+                answers: vec!["Cluster".to_string(), "Epoch Info".to_string()],
+            },
+        ];
+
+        let output = capture(|out| out.table(macro_table(&steps)));
+
+        assert_snapshot!("macro_table", output);
+    }
+
+    #[test]
+    fn test_doctor_report() {
+        let checks = [
+            DoctorCheck::pass("RPC", "solana-core 2.3.1"),
+            DoctorCheck::warn(
+                "Balance",
+                "Wallet has no SOL",
+                "Fund the wallet, or on devnet/testnet use Account > Request airdrop",
+            ),
+            DoctorCheck::fail(
+                "Keypair",
+                "No keypair at /tmp/id.json",
+                "Point keypair-path at an existing file",
+            ),
+        ];
+
+        let output = capture(|out| write_doctor_report(out, &checks));
+
+        assert_snapshot!("doctor_report", output);
+    }
+}
@@ -2,8 +2,8 @@ use {
     crate::{
         commands::{
             account::AccountCommand, cluster::ClusterCommand, config::ConfigCommand,
-            program::ProgramCommand, stake::StakeCommand, transaction::TransactionCommand,
-            vote::VoteCommand,
+            program::ProgramCommand, stake::StakeCommand, token::TokenCommand,
+            transaction::TransactionCommand, vote::VoteCommand, watch::WatchCommand,
         },
         context::ScillaContext,
     },
@@ -19,8 +19,10 @@ pub mod cluster;
 pub mod config;
 pub mod program;
 pub mod stake;
+pub mod token;
 pub mod transaction;
 pub mod vote;
+pub mod watch;
 
 pub enum CommandFlow<T> {
     Process(T),
@@ -30,6 +32,11 @@ pub enum CommandFlow<T> {
 
 impl<T> Termination for CommandFlow<T> {
     fn report(self) -> std::process::ExitCode {
+        // A single command run from the command line ends without a goodbye
+        if !matches!(self, CommandFlow::Exit) {
+            return ExitCode::SUCCESS;
+        }
+
         println!("{}", style("Goodbye 👋").dim());
         ExitCode::SUCCESS
     }
@@ -40,9 +47,11 @@ pub enum Command {
     Cluster(ClusterCommand),
     Stake(StakeCommand),
     Account(AccountCommand),
+    Token(TokenCommand),
     Program(ProgramCommand),
     Vote(VoteCommand),
     Transaction(TransactionCommand),
+    Watch(WatchCommand),
     ScillaConfig(ConfigCommand),
     Exit,
 }
@@ -53,12 +62,14 @@ impl Command {
             Command::Cluster(cluster_command) => cluster_command.process_command(ctx).await,
             Command::Stake(stake_command) => stake_command.process_command(ctx).await,
             Command::Account(account_command) => account_command.process_command(ctx).await,
+            Command::Token(token_command) => token_command.process_command(ctx).await,
             Command::Program(program_command) => program_command.process_command(ctx).await,
             Command::Vote(vote_command) => vote_command.process_command(ctx).await,
             Command::Transaction(transaction_command) => {
                 transaction_command.process_command(ctx).await
             }
-            Command::ScillaConfig(config_command) => config_command.process_command(ctx),
+            Command::Watch(watch_command) => watch_command.process_command(ctx).await,
+            Command::ScillaConfig(config_command) => config_command.process_command(ctx).await,
             Command::Exit => CommandFlow::Exit,
         }
     }
@@ -67,11 +78,13 @@ impl Command {
 #[derive(Debug, Clone)]
 pub enum CommandGroup {
     Account,
+    Token,
     Program,
     Cluster,
     Stake,
     Vote,
     Transaction,
+    Watch,
     ScillaConfig,
     Exit,
 }
@@ -80,11 +93,13 @@ impl fmt::Display for CommandGroup {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let command = match self {
             CommandGroup::Account => "Account",
+            CommandGroup::Token => "Token",
             CommandGroup::Program => "Program",
             CommandGroup::Cluster => "Cluster",
             CommandGroup::Stake => "Stake",
             CommandGroup::Vote => "Vote",
             CommandGroup::Transaction => "Transaction",
+            CommandGroup::Watch => "Watch",
             CommandGroup::ScillaConfig => "ScillaConfig",
             CommandGroup::Exit => "Exit",
         };
@@ -1,42 +1,77 @@
 use {
     crate::{
         commands::CommandFlow,
-        constants::CHUNK_SIZE,
+        constants::{
+            CHUNK_SIZE, ELF_MAGIC, MAX_BATCH_INSTRUCTIONS_PER_TX, MAX_PROGRAM_SIZE,
+            PROGRAM_DOWNLOAD_TIMEOUT_SECS,
+        },
         context::ScillaContext,
-        misc::helpers::{build_and_send_tx, read_keypair_from_path},
-        prompt::{prompt_confirmation, prompt_input_data},
-        ui::show_spinner,
-    },
-    anyhow::{anyhow, bail},
-    console::style,
-    solana_client::{
-        connection_cache::ConnectionCache,
-        nonblocking::tpu_client::TpuClient,
-        rpc_config::RpcSendTransactionConfig,
-        send_and_confirm_transactions_in_parallel::{
-            SendAndConfirmConfigV2, send_and_confirm_transactions_in_parallel_v2,
+        misc::{
+            batch_cost::{PlannedTx, check_batch_cost},
+            broadcast::send_in_parallel,
+            helpers::{
+                bincode_deserialize, build_and_send_tx, decode_base58, fetch_owned_accounts,
+                lamports_to_sol, read_keypair_from_path,
+            },
+            idl::AnchorIdl,
+            priority_fee::compute_budget_instructions,
+            security_txt::{SECURITY_TXT_FIELDS, find_security_txt},
+            signer::read_signer,
+        },
+        prompt::{
+            prompt_confirmation, prompt_input_data, prompt_keypair_path,
+            prompt_optional_pubkey_with_default, prompt_pubkey, prompt_select_data,
+        },
+        ui::{
+            StepChecklist,
+            multi_select::{BatchItem, prompt_batch_selection},
+            new_table, print_error,
+            render::{Renderer, field_table, header_cell, stdout},
+            show_spinner,
+            table::PagedTable,
         },
     },
+    anyhow::{anyhow, bail},
+    comfy_table::{Cell, Color, Table},
+    serde_json::Value,
+    sha2::{Digest, Sha256},
+    solana_account::Account,
+    solana_account_decoder_client_types::UiAccountEncoding,
+    solana_instruction::Instruction,
     solana_keypair::{Keypair, Signer},
     solana_loader_v3_interface::{
         instruction as loader_v3_instruction, state::UpgradeableLoaderState,
     },
     solana_message::Message,
-    solana_rpc_client::nonblocking::rpc_client::RpcClient,
-    solana_tpu_client::tpu_client::TpuClientConfig,
+    solana_nonce::{state::State as NonceState, versions::Versions},
+    solana_pubkey::Pubkey,
+    solana_rpc_client_api::{
+        config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+        filter::{Memcmp, RpcFilterType},
+    },
+    solana_sdk_ids::{bpf_loader_upgradeable, stake, system_program, vote},
+    solana_stake_interface::state::StakeStateV2,
+    solana_vote_interface::state::VoteStateV4,
     std::{
-        fmt,
-        fs::File,
-        io::Read,
+        cmp::Reverse,
+        fmt, fs,
+        io::Write,
         path::{Path, PathBuf},
-        sync::Arc,
-        time::Instant,
+        time::{Duration, Instant},
     },
 };
 
 #[derive(Debug, Clone)]
 pub enum ProgramCommand {
     Deploy,
+    Upgrade,
+    Show,
+    Accounts,
+    TransferAuthority,
+    SetBufferAuthority,
+    Verify,
+    CloseBuffers,
+    Close,
     GoBack,
 }
 
@@ -44,6 +79,14 @@ impl fmt::Display for ProgramCommand {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let command = match self {
             ProgramCommand::Deploy => "Deploy Program",
+            ProgramCommand::Upgrade => "Upgrade Program",
+            ProgramCommand::Show => "Show Program",
+            ProgramCommand::Accounts => "List Program Accounts",
+            ProgramCommand::TransferAuthority => "Transfer Upgrade Authority",
+            ProgramCommand::SetBufferAuthority => "Set Buffer Authority",
+            ProgramCommand::Verify => "Compare With Local Build",
+            ProgramCommand::CloseBuffers => "Close Buffers",
+            ProgramCommand::Close => "Close Program or Buffer",
             ProgramCommand::GoBack => "Go Back",
         };
         write!(f, "{command}")
@@ -54,6 +97,14 @@ impl ProgramCommand {
     pub fn spinner_msg(&self) -> &'static str {
         match self {
             ProgramCommand::Deploy => "Deploying program via TPU...",
+            ProgramCommand::Upgrade => "Upgrading program via TPU...",
+            ProgramCommand::Show => "Fetching program...",
+            ProgramCommand::Accounts => "Fetching accounts owned by program...",
+            ProgramCommand::TransferAuthority => "Transferring upgrade authority...",
+            ProgramCommand::SetBufferAuthority => "Setting buffer authority...",
+            ProgramCommand::Verify => "Comparing deployed bytecode...",
+            ProgramCommand::CloseBuffers => "Closing buffers...",
+            ProgramCommand::Close => "Closing account...",
             ProgramCommand::GoBack => "",
         }
     }
@@ -61,21 +112,168 @@ impl ProgramCommand {
     pub async fn process_command(&self, ctx: &mut ScillaContext) -> CommandFlow<()> {
         match self {
             ProgramCommand::Deploy => {
-                let program_path: String = prompt_input_data("Enter path to program .so file:");
+                let program_source: String =
+                    prompt_input_data("Enter path or https URL to program .so file:");
+                let Some(program_data) = load_program_data(&program_source).await else {
+                    return CommandFlow::Process(());
+                };
                 let keypair_path: String = prompt_input_data("Enter program keypair path:");
-                let immutable = prompt_confirmation("Make program immutable (revoke upgrade authority)?");
+                let resume_buffer = prompt_resume_buffer(ctx).await;
+                let immutable =
+                    prompt_confirmation("Make program immutable (revoke upgrade authority)?");
+
+                if let Err(e) = deploy_program(
+                    ctx,
+                    program_data,
+                    &PathBuf::from(&keypair_path),
+                    resume_buffer.as_ref(),
+                    immutable,
+                )
+                .await
+                {
+                    print_error(e);
+                }
+            }
+            ProgramCommand::Upgrade => {
+                let program_id = prompt_pubkey(ctx, "Enter program ID:").await;
+                let program_source: String =
+                    prompt_input_data("Enter path or https URL to new program .so file:");
+                let Some(program_data) = load_program_data(&program_source).await else {
+                    return CommandFlow::Process(());
+                };
+                let authority_path =
+                    prompt_keypair_path("Enter upgrade authority keypair path:", ctx);
+                let resume_buffer = prompt_resume_buffer(ctx).await;
+
+                if let Err(e) = upgrade_program(
+                    ctx,
+                    &program_id,
+                    program_data,
+                    &authority_path,
+                    resume_buffer.as_ref(),
+                )
+                .await
+                {
+                    print_error(e);
+                }
+            }
+            ProgramCommand::Show => {
+                let program_id = prompt_pubkey(ctx, "Enter program ID:").await;
+                show_spinner(self.spinner_msg(), show_program(ctx, &program_id)).await;
+            }
+            ProgramCommand::Accounts => {
+                let program_id = prompt_pubkey(ctx, "Enter program ID:").await;
+                let filters = prompt_memcmp_filters();
+
+                if let Some(accounts) = show_spinner(
+                    self.spinner_msg(),
+                    fetch_program_accounts(ctx, &program_id, filters),
+                )
+                .await
+                    && let Err(e) = browse_program_accounts(&accounts)
+                {
+                    print_error(e);
+                }
+            }
+            ProgramCommand::TransferAuthority => {
+                let program_id = prompt_pubkey(ctx, "Enter program ID:").await;
+                let authority_path =
+                    prompt_keypair_path("Enter current upgrade authority keypair path:", ctx);
+                let new_authority_path: PathBuf =
+                    prompt_input_data("Enter new upgrade authority keypair path:");
+
+                if let Err(e) = transfer_upgrade_authority(
+                    ctx,
+                    &program_id,
+                    &authority_path,
+                    &new_authority_path,
+                    self.spinner_msg(),
+                )
+                .await
+                {
+                    print_error(e);
+                }
+            }
+            ProgramCommand::SetBufferAuthority => {
+                let buffer = prompt_pubkey(ctx, "Enter buffer address:").await;
+                let authority_path =
+                    prompt_keypair_path("Enter current buffer authority keypair path:", ctx);
+                let new_authority = prompt_pubkey(ctx, "Enter new buffer authority:").await;
+
+                if !prompt_confirmation(&format!("Hand buffer {buffer} over to {new_authority}?")) {
+                    stdout().warning("Buffer authority change cancelled.");
+                    return CommandFlow::Process(());
+                }
+
+                show_spinner(
+                    self.spinner_msg(),
+                    set_buffer_authority(ctx, &buffer, &authority_path, &new_authority),
+                )
+                .await;
+            }
+            ProgramCommand::Verify => {
+                let program_id = prompt_pubkey(ctx, "Enter program ID:").await;
+                let program_path: PathBuf = prompt_input_data("Enter path to local .so file:");
+
+                show_spinner(
+                    self.spinner_msg(),
+                    compare_program_bytecode(ctx, &program_id, &program_path),
+                )
+                .await;
+            }
+            ProgramCommand::CloseBuffers => {
+                let Some(buffers) =
+                    show_spinner("Finding buffers you own...", fetch_buffer_accounts(ctx)).await
+                else {
+                    return CommandFlow::Process(());
+                };
 
-                if !prompt_confirmation("Deploy this program?") {
-                    println!("{}", style("Deployment cancelled.").yellow());
+                if buffers.is_empty() {
+                    stdout().warning(format!("No buffers have {} as authority.", ctx.pubkey()));
                     return CommandFlow::Process(());
                 }
 
+                let metadata_len = UpgradeableLoaderState::size_of_buffer_metadata();
+                let items = buffers
+                    .into_iter()
+                    .map(|(pubkey, account)| {
+                        BatchItem::new(
+                            pubkey,
+                            pubkey.to_string(),
+                            format!(
+                                "{} bytes of program data",
+                                account.data.len().saturating_sub(metadata_len)
+                            ),
+                            account.lamports,
+                        )
+                    })
+                    .collect();
+
+                let buffer_pubkeys = match prompt_batch_selection("close", items) {
+                    Ok(buffer_pubkeys) if !buffer_pubkeys.is_empty() => buffer_pubkeys,
+                    Ok(_) => return CommandFlow::Process(()),
+                    Err(e) => {
+                        print_error(e);
+                        return CommandFlow::Process(());
+                    }
+                };
+
                 show_spinner(
                     self.spinner_msg(),
-                    deploy_program(ctx, &program_path, &PathBuf::from(&keypair_path), immutable),
+                    close_buffer_accounts(ctx, &buffer_pubkeys),
                 )
                 .await;
             }
+            ProgramCommand::Close => {
+                let address = prompt_pubkey(ctx, "Enter program ID or buffer address:").await;
+                let authority_path = prompt_keypair_path("Enter authority keypair path:", ctx);
+
+                if let Err(e) =
+                    close_program_account(ctx, &address, &authority_path, self.spinner_msg()).await
+                {
+                    print_error(e);
+                }
+            }
             ProgramCommand::GoBack => {
                 return CommandFlow::GoBack;
             }
@@ -84,46 +282,277 @@ impl ProgramCommand {
     }
 }
 
+/// Reads the program from disk, or downloads it when given an https URL and
+/// asks the user to verify its checksum before it is deployed.
+async fn load_program_data(source: &str) -> Option<Vec<u8>> {
+    if source.starts_with("http://") {
+        print_error("Only https URLs are supported for program downloads");
+        return None;
+    }
+
+    if !source.starts_with("https://") {
+        return fs::read(source)
+            .map_err(|e| print_error(format!("Failed to open program file: {e}")))
+            .ok();
+    }
+
+    let program_data = show_spinner("Downloading program...", download_program(source)).await?;
+
+    match verify_program_checksum(&program_data) {
+        Ok(()) => Some(program_data),
+        Err(e) => {
+            print_error(e);
+            None
+        }
+    }
+}
+
+async fn download_program(url: &str) -> anyhow::Result<Vec<u8>> {
+    if !url.starts_with("https://") {
+        bail!("Only https URLs are supported for program downloads");
+    }
+
+    // Redirects must not downgrade the download to plain http either
+    let client = reqwest::Client::builder()
+        .https_only(true)
+        .timeout(Duration::from_secs(PROGRAM_DOWNLOAD_TIMEOUT_SECS))
+        .build()?;
+    let mut response = client.get(url).send().await?.error_for_status()?;
+
+    let too_large =
+        || anyhow!("Downloaded file is larger than the {MAX_PROGRAM_SIZE} byte limit on programs");
+    if response
+        .content_length()
+        .is_some_and(|len| len > MAX_PROGRAM_SIZE as u64)
+    {
+        return Err(too_large());
+    }
+
+    // The length header is optional, so the body is capped as it arrives
+    let mut program_data = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if program_data.len() + chunk.len() > MAX_PROGRAM_SIZE {
+            return Err(too_large());
+        }
+        program_data.extend_from_slice(&chunk);
+    }
+
+    if !program_data.starts_with(ELF_MAGIC) {
+        bail!("Downloaded file is not a valid program (missing ELF header)");
+    }
+
+    Ok(program_data)
+}
+
+fn verify_program_checksum(program_data: &[u8]) -> anyhow::Result<()> {
+    let checksum = sha256_hex(program_data);
+
+    let mut out = stdout();
+    out.note(format!("Downloaded: {} bytes", program_data.len()));
+    out.note(format!("SHA-256: {checksum}"));
+
+    let expected: String = prompt_input_data("Enter expected SHA-256 checksum (empty to skip):");
+    let expected = expected.trim().to_lowercase();
+
+    if expected.is_empty() {
+        if !prompt_confirmation("Deploy without verifying the checksum?") {
+            bail!("Deployment cancelled, checksum not verified");
+        }
+        return Ok(());
+    }
+
+    if expected != checksum {
+        bail!("Checksum mismatch: expected {expected}, downloaded file is {checksum}");
+    }
+
+    out.success("Checksum verified.");
+    Ok(())
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// Buffer a program is written into before it's deployed or upgraded: a new
+/// one, or one left behind by an interrupted deployment.
+struct ProgramBuffer {
+    pubkey: Pubkey,
+    /// Keypair of a buffer that still has to be created
+    keypair: Option<Keypair>,
+    /// Program bytes a resumed buffer already holds
+    written: Vec<u8>,
+    /// Rent paid to create the buffer, zero when resuming
+    rent: u64,
+}
+
+impl ProgramBuffer {
+    async fn new(ctx: &ScillaContext, program_len: usize) -> anyhow::Result<Self> {
+        let keypair = Keypair::new();
+        let rent = ctx
+            .rpc()
+            .get_minimum_balance_for_rent_exemption(UpgradeableLoaderState::size_of_buffer(
+                program_len,
+            ))
+            .await?;
+
+        Ok(Self {
+            pubkey: keypair.pubkey(),
+            keypair: Some(keypair),
+            written: Vec::new(),
+            rent,
+        })
+    }
+
+    /// Picks up `buffer`, which must be sized for the program and still be
+    /// writable by the context keypair.
+    async fn resume(
+        ctx: &ScillaContext,
+        buffer: &Pubkey,
+        program_len: usize,
+    ) -> anyhow::Result<Self> {
+        let account = ctx
+            .rpc()
+            .get_account(buffer)
+            .await
+            .map_err(|_| anyhow!("{buffer} account does not exist"))?;
+
+        if account.owner != bpf_loader_upgradeable::id() {
+            bail!("{buffer} is not owned by the upgradeable loader");
+        }
+
+        let UpgradeableLoaderState::Buffer { authority_address } =
+            bincode_deserialize(&account.data, "buffer account data")?
+        else {
+            bail!("{buffer} is not a buffer account");
+        };
+
+        if authority_address != Some(*ctx.pubkey()) {
+            bail!(
+                "{buffer} can only be written by {}, not {}",
+                display_authority(authority_address),
+                ctx.pubkey()
+            );
+        }
+
+        let written = account
+            .data
+            .get(UpgradeableLoaderState::size_of_buffer_metadata()..)
+            .unwrap_or_default()
+            .to_vec();
+        if written.len() != program_len {
+            bail!(
+                "{buffer} holds {} bytes but the program is {program_len} bytes",
+                written.len()
+            );
+        }
+
+        Ok(Self {
+            pubkey: *buffer,
+            keypair: None,
+            written,
+            rent: 0,
+        })
+    }
+
+    fn create_instructions(
+        &self,
+        ctx: &ScillaContext,
+        program_len: usize,
+    ) -> anyhow::Result<Option<Vec<Instruction>>> {
+        if self.keypair.is_none() {
+            return Ok(None);
+        }
+
+        Ok(Some(loader_v3_instruction::create_buffer(
+            ctx.pubkey(),
+            &self.pubkey,
+            ctx.pubkey(),
+            self.rent,
+            program_len,
+        )?))
+    }
+
+    /// Chunks of `program_data` the buffer doesn't hold yet, with their
+    /// offsets.
+    fn pending_chunks<'a>(&self, program_data: &'a [u8]) -> Vec<(u32, &'a [u8])> {
+        pending_chunks(program_data, &self.written)
+    }
+
+    /// Warns that the buffer outlives a failed deployment, and how to pick it
+    /// up again.
+    fn resume_hint(&self) {
+        let mut out = stdout();
+        out.warning(format!(
+            "Buffer {} keeps its rent until it is closed.",
+            self.pubkey
+        ));
+        out.note("Enter it as the buffer to resume from to continue where this left off.");
+    }
+}
+
+/// Chunks of `program_data` that differ from what `written` holds at the
+/// same offset.
+fn pending_chunks<'a>(program_data: &'a [u8], written: &[u8]) -> Vec<(u32, &'a [u8])> {
+    program_data
+        .chunks(CHUNK_SIZE)
+        .enumerate()
+        .map(|(i, chunk)| (i * CHUNK_SIZE, chunk))
+        .filter(|(offset, chunk)| written.get(*offset..*offset + chunk.len()) != Some(*chunk))
+        .map(|(offset, chunk)| (offset as u32, chunk))
+        .collect()
+}
+
+async fn prompt_resume_buffer(ctx: &ScillaContext) -> Option<Pubkey> {
+    prompt_optional_pubkey_with_default(
+        ctx,
+        "Buffer to resume from (empty for a new buffer):",
+        None,
+    )
+    .await
+}
+
+/// Picks up the buffer left by an interrupted deployment when given one, or
+/// sets up a new buffer.
+async fn program_buffer(
+    ctx: &ScillaContext,
+    resume_buffer: Option<&Pubkey>,
+    program_data: &[u8],
+) -> anyhow::Result<ProgramBuffer> {
+    let Some(resume_buffer) = resume_buffer else {
+        return ProgramBuffer::new(ctx, program_data.len()).await;
+    };
+
+    let buffer = ProgramBuffer::resume(ctx, resume_buffer, program_data.len()).await?;
+    stdout().note(format!(
+        "Resuming buffer {}: {} of {} chunks left to write",
+        buffer.pubkey,
+        buffer.pending_chunks(program_data).len(),
+        program_data.len().div_ceil(CHUNK_SIZE)
+    ));
+    Ok(buffer)
+}
 
 async fn deploy_program(
     ctx: &ScillaContext,
-    program_path: &str,
+    program_data: Vec<u8>,
     keypair_path: &Path,
+    resume_buffer: Option<&Pubkey>,
     immutable: bool,
 ) -> anyhow::Result<()> {
     let start_time = Instant::now();
 
-
-    let mut file =
-        File::open(program_path).map_err(|e| anyhow!("Failed to open program file: {}", e))?;
-    let mut program_data = Vec::new();
-    file.read_to_end(&mut program_data)?;
     let program_len = program_data.len();
 
-    println!(
-        "{}",
-        style(format!("Program size: {} bytes", program_len)).dim()
-    );
-
+    let mut out = stdout();
+    out.note(format!("Program size: {} bytes", program_len));
 
     let program_keypair = read_keypair_from_path(keypair_path)?;
     let program_id = program_keypair.pubkey();
 
+    let buffer = program_buffer(ctx, resume_buffer, &program_data).await?;
+    let chunks = buffer.pending_chunks(&program_data);
 
-    let buffer_keypair = Keypair::new();
-    let buffer_pubkey = buffer_keypair.pubkey();
-
-    println!(
-        "{}",
-        style(format!("Buffer account: {}", buffer_pubkey)).dim()
-    );
-
-
-    let buffer_len = UpgradeableLoaderState::size_of_buffer(program_len);
-    let buffer_rent = ctx
-        .rpc()
-        .get_minimum_balance_for_rent_exemption(buffer_len)
-        .await?;
+    out.note(format!("Buffer account: {}", buffer.pubkey));
 
     let programdata_len = UpgradeableLoaderState::size_of_programdata(program_len);
     let programdata_rent = ctx
@@ -131,143 +560,1079 @@ async fn deploy_program(
         .get_minimum_balance_for_rent_exemption(programdata_len)
         .await?;
 
-    println!(
-        "{} {}\n{} {}",
-        style("Buffer Rent:").dim(),
-        style(format!("{:.9} SOL", buffer_rent as f64 / 1_000_000_000.0)).bold(),
-        style("Program Rent:").dim(),
-        style(format!("{:.9} SOL", programdata_rent as f64 / 1_000_000_000.0)).bold(),
-    );
+    out.note(format!(
+        "Buffer Rent: {:.9} SOL",
+        buffer.rent as f64 / 1_000_000_000.0
+    ));
+    out.note(format!(
+        "Program Rent: {:.9} SOL",
+        programdata_rent as f64 / 1_000_000_000.0
+    ));
 
+    let create_buffer_ix = buffer.create_instructions(ctx, program_len)?;
 
-    let create_buffer_ix = loader_v3_instruction::create_buffer(
+    // Note: deploy_with_max_program_len is marked deprecated internally but is
+    // the standard way to deploy programs. Loader V4 is not yet enabled on most
+    // clusters.
+    #[allow(deprecated)]
+    let deploy_ix = loader_v3_instruction::deploy_with_max_program_len(
         ctx.pubkey(),
-        &buffer_pubkey,
+        &program_id,
+        &buffer.pubkey,
         ctx.pubkey(),
-        buffer_rent,
+        programdata_rent,
         program_len,
     )?;
 
-    let sig = build_and_send_tx(ctx, &create_buffer_ix, &[ctx.keypair(), &buffer_keypair]).await?;
-    println!("{}", style(format!("Buffer created: {}", sig)).green());
+    let mut txs: Vec<PlannedTx> = create_buffer_ix
+        .iter()
+        .cloned()
+        .map(PlannedTx::new)
+        .collect();
+    txs.extend(write_txs(ctx, &buffer, chunks.len()));
+    txs.push(PlannedTx::new(deploy_ix.clone()));
+    if immutable {
+        txs.push(PlannedTx::new(vec![
+            loader_v3_instruction::set_upgrade_authority(&program_id, ctx.pubkey(), None),
+        ]));
+    }
+    check_batch_cost(ctx, &txs, buffer.rent + programdata_rent).await?;
 
+    if !prompt_confirmation("Deploy this program?") {
+        out.warning("Deployment cancelled.");
+        return Ok(());
+    }
+
+    let mut steps = buffer_steps(&buffer, chunks.len());
+    steps.push("Deploy program".to_string());
+    if immutable {
+        steps.push("Revoke upgrade authority".to_string());
+    }
+    let mut checklist = StepChecklist::new("DEPLOYMENT", steps);
 
-    let rpc_url = ctx.rpc().url();
-    let rpc_client = Arc::new(RpcClient::new(rpc_url.to_string()));
-    let blockhash = rpc_client.get_latest_blockhash().await?;
+    fill_buffer(ctx, &mut checklist, &buffer, create_buffer_ix, &chunks).await?;
 
-    let mut write_messages = Vec::new();
-    for (i, chunk) in program_data.chunks(CHUNK_SIZE).enumerate() {
-        let offset = (i * CHUNK_SIZE) as u32;
-        let write_ix = loader_v3_instruction::write(
-            &buffer_pubkey,
-            ctx.pubkey(), 
-            offset,
-            chunk.to_vec(),
+    let sig = checklist
+        .run(build_and_send_tx(
+            ctx,
+            &deploy_ix,
+            &[ctx.signer(), &program_keypair],
+        ))
+        .await
+        .inspect_err(|_| buffer.resume_hint())?;
+
+    out.success("\nProgram deployed successfully!");
+    out.line(format!("Program ID: {}", program_id));
+    out.note(format!("Signature: {}", sig));
+
+    if immutable {
+        let set_authority_ix =
+            loader_v3_instruction::set_upgrade_authority(&program_id, ctx.pubkey(), None);
+        let auth_sig = checklist
+            .run(build_and_send_tx(ctx, &[set_authority_ix], &[ctx.signer()]))
+            .await?;
+        out.error("Program is now immutable.");
+        out.note(format!("Revocation Signature: {}", auth_sig));
+    }
+
+    let duration = start_time.elapsed();
+    out.success(format!(
+        "Total deployment time: {:.2}s",
+        duration.as_secs_f64()
+    ));
+
+    Ok(())
+}
+
+/// Writes new bytecode for `program_id` into a buffer and swaps it in,
+/// growing the program data account first if the new build is larger.
+async fn upgrade_program(
+    ctx: &ScillaContext,
+    program_id: &Pubkey,
+    program_data: Vec<u8>,
+    authority_path: &Path,
+    resume_buffer: Option<&Pubkey>,
+) -> anyhow::Result<()> {
+    let authority = read_signer(authority_path)?;
+    let program_len = program_data.len();
+
+    let deployed = fetch_deployed_program(ctx, program_id).await?;
+    let Some(upgrade_authority) = deployed.upgrade_authority else {
+        bail!("{program_id} is immutable and can't be upgraded");
+    };
+    if authority.pubkey() != upgrade_authority {
+        bail!(
+            "Keypair {} is not the current upgrade authority ({})",
+            authority.pubkey(),
+            upgrade_authority
         );
-        let message = Message::new_with_blockhash(&[write_ix], Some(ctx.pubkey()), &blockhash);
-        write_messages.push(message);
     }
 
-    println!(
-        "{}",
-        style(format!(
-            "Writing {} chunks via TPU...",
-            write_messages.len()
+    let buffer = program_buffer(ctx, resume_buffer, &program_data).await?;
+    let chunks = buffer.pending_chunks(&program_data);
+
+    // The program data account only holds as many bytes as it was created
+    // with, so a larger build needs it extended first
+    let additional_bytes = program_len.saturating_sub(deployed.elf.len());
+    let extension_rent = if additional_bytes > 0 {
+        let programdata_len = UpgradeableLoaderState::size_of_programdata(program_len);
+        let current_len = UpgradeableLoaderState::size_of_programdata(deployed.elf.len());
+        ctx.rpc()
+            .get_minimum_balance_for_rent_exemption(programdata_len)
+            .await?
+            .saturating_sub(
+                ctx.rpc()
+                    .get_minimum_balance_for_rent_exemption(current_len)
+                    .await?,
+            )
+    } else {
+        0
+    };
+
+    let mut out = stdout();
+    out.title("PROGRAM UPGRADE");
+    out.table(field_table([
+        ("Program", program_id.to_string()),
+        ("Upgrade Authority", upgrade_authority.to_string()),
+        ("Buffer", buffer.pubkey.to_string()),
+        ("Deployed Size (bytes)", deployed.elf.len().to_string()),
+        ("New Size (bytes)", program_len.to_string()),
+    ]));
+
+    let create_buffer_ix = buffer.create_instructions(ctx, program_len)?;
+
+    let mut upgrade_ixs = Vec::new();
+    if additional_bytes > 0 {
+        upgrade_ixs.push(loader_v3_instruction::extend_program_checked(
+            program_id,
+            &upgrade_authority,
+            Some(ctx.pubkey()),
+            additional_bytes as u32,
+        ));
+    }
+    // Only the upgrade authority can upgrade from a buffer, so the buffer is
+    // handed over to it when the context keypair isn't the authority
+    if upgrade_authority != *ctx.pubkey() {
+        upgrade_ixs.push(loader_v3_instruction::set_buffer_authority(
+            &buffer.pubkey,
+            ctx.pubkey(),
+            &upgrade_authority,
+        ));
+    }
+    // The buffer's rent goes back to the context keypair once it's emptied
+    upgrade_ixs.push(loader_v3_instruction::upgrade(
+        program_id,
+        &buffer.pubkey,
+        &upgrade_authority,
+        ctx.pubkey(),
+    ));
+
+    let mut txs: Vec<PlannedTx> = create_buffer_ix
+        .iter()
+        .cloned()
+        .map(PlannedTx::new)
+        .collect();
+    txs.extend(write_txs(ctx, &buffer, chunks.len()));
+    txs.push(PlannedTx::new(upgrade_ixs.clone()));
+    check_batch_cost(ctx, &txs, extension_rent).await?;
+
+    if !prompt_confirmation(&format!("Upgrade {program_id}?")) {
+        out.warning("Upgrade cancelled.");
+        return Ok(());
+    }
+
+    let mut steps = buffer_steps(&buffer, chunks.len());
+    steps.push("Upgrade program".to_string());
+    let mut checklist = StepChecklist::new("UPGRADE", steps);
+
+    fill_buffer(ctx, &mut checklist, &buffer, create_buffer_ix, &chunks).await?;
+
+    let signature = checklist
+        .run(build_and_send_tx(
+            ctx,
+            &upgrade_ixs,
+            &[ctx.signer(), &*authority],
         ))
-        .dim()
-    );
+        .await
+        .inspect_err(|_| buffer.resume_hint())?;
 
-    // 7. Send write transactions via TPU/QUIC
-    let connection_cache = ConnectionCache::new_quic("scilla_program_deploy", 1);
-
-    let websocket_url = rpc_url
-        .replace("https://", "wss://")
-        .replace("http://", "ws://");
-
-    if let ConnectionCache::Quic(cache) = connection_cache {
-        let tpu_client = TpuClient::new_with_connection_cache(
-            rpc_client.clone(),
-            &websocket_url,
-            TpuClientConfig::default(),
-            cache,
-        )
-        .await?;
+    out.success("Program upgraded!");
+    out.note(format!("Signature: {signature}"));
 
-        let signers: Vec<&dyn Signer> = vec![ctx.keypair()];
-
-        let transaction_errors = send_and_confirm_transactions_in_parallel_v2(
-            rpc_client.clone(),
-            Some(tpu_client),
-            &write_messages,
-            &signers,
-            SendAndConfirmConfigV2 {
-                resign_txs_count: Some(5),
-                with_spinner: false, // Disable Solana's spinner, we have our own
-                rpc_send_transaction_config: RpcSendTransactionConfig::default(),
-            },
-        )
+    Ok(())
+}
+
+/// Checklist steps creating and writing `buffer`.
+fn buffer_steps(buffer: &ProgramBuffer, chunks: usize) -> Vec<String> {
+    let mut steps = Vec::new();
+    if buffer.keypair.is_some() {
+        steps.push("Create buffer account".to_string());
+    }
+    steps.push(format!("Write program data ({chunks} chunks via TPU)"));
+    steps
+}
+
+fn write_txs(ctx: &ScillaContext, buffer: &ProgramBuffer, chunks: usize) -> Vec<PlannedTx> {
+    let write_ix = loader_v3_instruction::write(&buffer.pubkey, ctx.pubkey(), 0, vec![]);
+    (0..chunks)
+        .map(|_| PlannedTx::new(vec![write_ix.clone()]))
+        .collect()
+}
+
+/// Runs the checklist steps from [`buffer_steps`], creating the buffer if
+/// it's new and writing the chunks it's missing.
+async fn fill_buffer(
+    ctx: &ScillaContext,
+    checklist: &mut StepChecklist,
+    buffer: &ProgramBuffer,
+    create_buffer_ix: Option<Vec<Instruction>>,
+    chunks: &[(u32, &[u8])],
+) -> anyhow::Result<()> {
+    if let (Some(create_buffer_ix), Some(buffer_keypair)) = (create_buffer_ix, &buffer.keypair) {
+        let sig = checklist
+            .run(build_and_send_tx(
+                ctx,
+                &create_buffer_ix,
+                &[ctx.signer(), buffer_keypair],
+            ))
+            .await?;
+        stdout().note(format!("Buffer created: {}", sig));
+    }
+
+    checklist
+        .run(write_buffer(ctx, &buffer.pubkey, chunks))
+        .await
+        .inspect_err(|_| buffer.resume_hint())
+}
+
+/// Writes the chunks into the buffer, sending the write transactions in
+/// parallel through the leader TPUs.
+async fn write_buffer(
+    ctx: &ScillaContext,
+    buffer_pubkey: &Pubkey,
+    chunks: &[(u32, &[u8])],
+) -> anyhow::Result<()> {
+    if chunks.is_empty() {
+        return Ok(());
+    }
+
+    let blockhash = ctx.rpc().get_latest_blockhash().await?;
+
+    let write_ix = |(offset, chunk): &(u32, &[u8])| {
+        loader_v3_instruction::write(buffer_pubkey, ctx.pubkey(), *offset, chunk.to_vec())
+    };
+
+    // Every write costs about the same, so the compute budget of the first
+    // one is used for all of them
+    let compute_budget_ixs = compute_budget_instructions(ctx, &[write_ix(&chunks[0])]).await?;
+
+    let mut write_messages = Vec::new();
+    for chunk in chunks {
+        let mut instructions = compute_budget_ixs.clone();
+        instructions.push(write_ix(chunk));
+        let message = Message::new_with_blockhash(&instructions, Some(ctx.pubkey()), &blockhash);
+        write_messages.push(message);
+    }
+
+    // 7. Send write transactions via TPU/QUIC
+    let failed = send_in_parallel(ctx, &write_messages)
         .await
         .map_err(|e| anyhow!("Write transactions failed: {}", e))?
         .into_iter()
         .flatten()
-        .collect::<Vec<_>>();
+        .count();
+
+    if failed > 0 {
+        bail!("{} write transactions failed", failed);
+    }
 
-        if !transaction_errors.is_empty() {
-            bail!("{} write transactions failed", transaction_errors.len());
+    Ok(())
+}
+
+/// Program data of a deployed upgradeable program.
+struct DeployedProgram {
+    programdata_address: Pubkey,
+    slot: u64,
+    /// `None` when the program is immutable.
+    upgrade_authority: Option<Pubkey>,
+    /// Program bytes following the program data metadata, including any
+    /// zero padding left for future upgrades.
+    elf: Vec<u8>,
+}
+
+async fn fetch_deployed_program(
+    ctx: &ScillaContext,
+    program_id: &Pubkey,
+) -> anyhow::Result<DeployedProgram> {
+    let program_account = ctx
+        .rpc()
+        .get_account(program_id)
+        .await
+        .map_err(|_| anyhow!("{program_id} account does not exist"))?;
+
+    if program_account.owner != bpf_loader_upgradeable::id() {
+        bail!("{program_id} is not an upgradeable program");
+    }
+
+    let UpgradeableLoaderState::Program {
+        programdata_address,
+    } = bincode_deserialize(&program_account.data, "program account data")?
+    else {
+        bail!("{program_id} is not a program account");
+    };
+
+    let programdata_account = ctx.rpc().get_account(&programdata_address).await?;
+
+    let UpgradeableLoaderState::ProgramData {
+        slot,
+        upgrade_authority_address,
+    } = bincode_deserialize(&programdata_account.data, "program data account")?
+    else {
+        bail!("{programdata_address} is not a program data account");
+    };
+
+    let elf = programdata_account
+        .data
+        .get(UpgradeableLoaderState::size_of_programdata_metadata()..)
+        .unwrap_or_default()
+        .to_vec();
+
+    Ok(DeployedProgram {
+        programdata_address,
+        slot,
+        upgrade_authority: upgrade_authority_address,
+        elf,
+    })
+}
+
+async fn show_program(ctx: &ScillaContext, program_id: &Pubkey) -> anyhow::Result<()> {
+    let program = fetch_deployed_program(ctx, program_id).await?;
+
+    let mut out = stdout();
+    out.title("PROGRAM INFORMATION");
+    out.table(field_table([
+        ("Program Id", program_id.to_string()),
+        (
+            "ProgramData Address",
+            program.programdata_address.to_string(),
+        ),
+        (
+            "Upgrade Authority",
+            display_authority(program.upgrade_authority),
+        ),
+        ("Last Deployed Slot", program.slot.to_string()),
+        ("Data Length (bytes)", program.elf.len().to_string()),
+    ]));
+
+    match find_security_txt(&program.elf)? {
+        Some(entries) => {
+            out.title("SECURITY.TXT");
+            out.table(security_txt_table(&entries));
         }
+        None => out.warning("This program does not embed a security.txt."),
     }
 
-    println!("{}", style("Program data written to buffer").green());
+    Ok(())
+}
 
-    // 8. Deploy from buffer
-    // Note: deploy_with_max_program_len is marked deprecated internally but is
-    // the standard way to deploy programs. Loader V4 is not yet enabled on most
-    // clusters.
-    #[allow(deprecated)]
-    let deploy_ix = loader_v3_instruction::deploy_with_max_program_len(
-        ctx.pubkey(),
-        &program_id,
-        &buffer_pubkey,
-        ctx.pubkey(),
-        programdata_rent,
-        program_len,
-    )?;
+fn security_txt_table(entries: &[(String, String)]) -> Table {
+    let mut table = new_table();
+    table.set_header(vec![header_cell("Field"), header_cell("Value")]);
+
+    // Known fields first in their canonical order, then anything else
+    for (key, label) in SECURITY_TXT_FIELDS {
+        if let Some((_, value)) = entries.iter().find(|(k, _)| k == key) {
+            table.add_row(vec![Cell::new(label), Cell::new(value)]);
+        }
+    }
+    for (key, value) in entries {
+        if !SECURITY_TXT_FIELDS.iter().any(|(known, _)| known == key) {
+            table.add_row(vec![Cell::new(key), Cell::new(value)]);
+        }
+    }
+
+    table
+}
+
+async fn transfer_upgrade_authority(
+    ctx: &ScillaContext,
+    program_id: &Pubkey,
+    authority_path: &Path,
+    new_authority_path: &Path,
+    spinner_msg: &str,
+) -> anyhow::Result<()> {
+    let authority = read_signer(authority_path)?;
+    let new_authority = read_signer(new_authority_path)?;
+
+    let Some(current_authority) = fetch_deployed_program(ctx, program_id)
+        .await?
+        .upgrade_authority
+    else {
+        bail!("{program_id} is immutable and has no upgrade authority");
+    };
+
+    if authority.pubkey() != current_authority {
+        bail!(
+            "Keypair {} is not the current upgrade authority ({})",
+            authority.pubkey(),
+            current_authority
+        );
+    }
+
+    if new_authority.pubkey() == current_authority {
+        bail!("New upgrade authority is the same as the current one");
+    }
 
-    let sig = build_and_send_tx(ctx, &deploy_ix, &[ctx.keypair(), &program_keypair]).await?;
+    let mut table = field_table([
+        ("Program", program_id.to_string()),
+        ("Current Authority", current_authority.to_string()),
+    ]);
+    table.add_row(vec![
+        Cell::new("New Authority"),
+        Cell::new(new_authority.pubkey()).fg(Color::Yellow),
+    ]);
 
-    println!(
-        "\n{}\n{}\n{}",
-        style("Program deployed successfully!").green().bold(),
-        style(format!("Program ID: {}", program_id)).cyan(),
-        style(format!("Signature: {}", sig)).dim()
+    let mut out = stdout();
+    out.title("UPGRADE AUTHORITY TRANSFER");
+    out.table(table);
+    out.warning("Once transferred, only the new authority can upgrade this program.");
+
+    if !prompt_confirmation("Transfer upgrade authority to the new authority?") {
+        out.warning("Authority transfer cancelled.");
+        return Ok(());
+    }
+
+    let typed: String = prompt_input_data("Type the program ID to confirm:");
+    if typed.trim() != program_id.to_string() {
+        bail!("Program ID does not match, authority transfer cancelled");
+    }
+
+    // The checked variant requires the new authority to sign, so the
+    // authority can't be handed to an address nobody controls.
+    let set_authority_ix = loader_v3_instruction::set_upgrade_authority_checked(
+        program_id,
+        &current_authority,
+        &new_authority.pubkey(),
     );
 
-    if immutable {
-        println!("\n{}", style("Revoking upgrade authority...").yellow());
-        let set_authority_ix = loader_v3_instruction::set_upgrade_authority(
-            &program_id,
-            &ctx.pubkey(),
-            None,
+    let Some(signature) = show_spinner(
+        spinner_msg,
+        build_and_send_tx(
+            ctx,
+            &[set_authority_ix],
+            &[ctx.signer(), &*authority, &*new_authority],
+        ),
+    )
+    .await
+    else {
+        return Ok(());
+    };
+
+    out.success("Upgrade authority transferred!");
+    out.note(format!("Signature: {signature}"));
+
+    Ok(())
+}
+
+async fn set_buffer_authority(
+    ctx: &ScillaContext,
+    buffer: &Pubkey,
+    authority_path: &Path,
+    new_authority: &Pubkey,
+) -> anyhow::Result<()> {
+    let authority = read_signer(authority_path)?;
+
+    let buffer_account = ctx
+        .rpc()
+        .get_account(buffer)
+        .await
+        .map_err(|_| anyhow!("{buffer} account does not exist"))?;
+
+    if buffer_account.owner != bpf_loader_upgradeable::id() {
+        bail!("{buffer} is not owned by the upgradeable loader");
+    }
+
+    let UpgradeableLoaderState::Buffer { authority_address } =
+        bincode_deserialize(&buffer_account.data, "buffer account data")?
+    else {
+        bail!("{buffer} is not a buffer account");
+    };
+
+    let Some(current_authority) = authority_address else {
+        bail!("{buffer} is immutable and has no authority");
+    };
+
+    if authority.pubkey() != current_authority {
+        bail!(
+            "Keypair {} is not the current buffer authority ({})",
+            authority.pubkey(),
+            current_authority
         );
-        let auth_sig = build_and_send_tx(ctx, &[set_authority_ix], &[ctx.keypair()]).await?;
-        println!(
-            "{}\n{}",
-            style("Program is now immutable.").red().bold(),
-            style(format!("Revocation Signature: {}", auth_sig)).dim()
+    }
+
+    // The new authority doesn't sign, so buffers can be handed to a multisig
+    // or cold key that performs the final deploy.
+    let set_authority_ix =
+        loader_v3_instruction::set_buffer_authority(buffer, &current_authority, new_authority);
+
+    let signature =
+        build_and_send_tx(ctx, &[set_authority_ix], &[ctx.signer(), &*authority]).await?;
+
+    let mut out = stdout();
+    out.success(format!("Buffer authority set to: {new_authority}"));
+    out.note(format!("Signature: {signature}"));
+
+    Ok(())
+}
+
+/// Closes a program's data account or a buffer, returning its rent to the
+/// context keypair. A closed program can never be deployed again.
+async fn close_program_account(
+    ctx: &ScillaContext,
+    address: &Pubkey,
+    authority_path: &Path,
+    spinner_msg: &str,
+) -> anyhow::Result<()> {
+    let authority = read_signer(authority_path)?;
+
+    let account = ctx
+        .rpc()
+        .get_account(address)
+        .await
+        .map_err(|_| anyhow!("{address} account does not exist"))?;
+
+    if account.owner != bpf_loader_upgradeable::id() {
+        bail!("{address} is not owned by the upgradeable loader");
+    }
+
+    let (close_address, current_authority, program_id, lamports) =
+        match bincode_deserialize(&account.data, "loader account data")? {
+            UpgradeableLoaderState::Program {
+                programdata_address,
+            } => {
+                let programdata = ctx.rpc().get_account(&programdata_address).await?;
+                let UpgradeableLoaderState::ProgramData {
+                    upgrade_authority_address,
+                    ..
+                } = bincode_deserialize(&programdata.data, "program data account")?
+                else {
+                    bail!("{programdata_address} is not a program data account");
+                };
+                (
+                    programdata_address,
+                    upgrade_authority_address,
+                    Some(*address),
+                    programdata.lamports,
+                )
+            }
+            UpgradeableLoaderState::Buffer { authority_address } => {
+                (*address, authority_address, None, account.lamports)
+            }
+            _ => bail!("{address} is neither a program nor a buffer"),
+        };
+
+    let Some(current_authority) = current_authority else {
+        bail!("{address} is immutable and can't be closed");
+    };
+
+    if authority.pubkey() != current_authority {
+        bail!(
+            "Keypair {} is not the authority of {address} ({current_authority})",
+            authority.pubkey()
         );
     }
 
-    let duration = start_time.elapsed();
-    println!(
-        "{}",
-        style(format!(
-            "Total deployment time: {:.2}s",
-            duration.as_secs_f64()
-        ))
-        .bold()
-        .green()
+    let mut out = stdout();
+    out.title(if program_id.is_some() {
+        "CLOSE PROGRAM"
+    } else {
+        "CLOSE BUFFER"
+    });
+    out.table(field_table([
+        ("Account", close_address.to_string()),
+        ("Authority", current_authority.to_string()),
+        (
+            "Rent Reclaimed",
+            format!("{} SOL", lamports_to_sol(lamports)),
+        ),
+        ("Recipient", ctx.pubkey().to_string()),
+    ]));
+
+    if let Some(program_id) = program_id {
+        out.warning(format!(
+            "Closing removes {program_id} for good. A program can never be deployed to its \
+             address again."
+        ));
+        if !prompt_confirmation("Close this program?") {
+            out.warning("Close cancelled.");
+            return Ok(());
+        }
+
+        let typed: String = prompt_input_data("Type the program ID to confirm:");
+        if typed.trim() != program_id.to_string() {
+            bail!("Program ID does not match, close cancelled");
+        }
+    } else if !prompt_confirmation("Close this buffer?") {
+        out.warning("Close cancelled.");
+        return Ok(());
+    }
+
+    let close_ix = loader_v3_instruction::close_any(
+        &close_address,
+        ctx.pubkey(),
+        Some(&current_authority),
+        program_id.as_ref(),
     );
 
+    let Some(signature) = show_spinner(
+        spinner_msg,
+        build_and_send_tx(ctx, &[close_ix], &[ctx.signer(), &*authority]),
+    )
+    .await
+    else {
+        return Ok(());
+    };
+
+    out.success(format!(
+        "Closed {close_address}, {} SOL reclaimed",
+        lamports_to_sol(lamports)
+    ));
+    out.note(format!("Signature: {signature}"));
+
     Ok(())
 }
+
+/// Buffers whose authority is the context keypair, largest balance first.
+async fn fetch_buffer_accounts(ctx: &ScillaContext) -> anyhow::Result<Vec<(Pubkey, Account)>> {
+    // Buffer state tag (1 as a little-endian u32) followed by Some(authority)
+    let mut buffer_prefix = vec![1, 0, 0, 0, 1];
+    buffer_prefix.extend_from_slice(ctx.pubkey().as_ref());
+
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            0,
+            &buffer_prefix,
+        ))]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(ctx.rpc().commitment()),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+
+    let mut buffers = fetch_owned_accounts(ctx, &bpf_loader_upgradeable::id(), config).await?;
+    buffers.sort_by_key(|(_, buffer)| Reverse(buffer.lamports));
+
+    Ok(buffers)
+}
+
+/// Closes the buffers, returning their rent to the context keypair.
+async fn close_buffer_accounts(
+    ctx: &ScillaContext,
+    buffer_pubkeys: &[Pubkey],
+) -> anyhow::Result<()> {
+    let txs: Vec<PlannedTx> = buffer_pubkeys
+        .chunks(MAX_BATCH_INSTRUCTIONS_PER_TX)
+        .map(|batch| {
+            PlannedTx::new(
+                batch
+                    .iter()
+                    .map(|buffer| loader_v3_instruction::close(buffer, ctx.pubkey(), ctx.pubkey()))
+                    .collect(),
+            )
+        })
+        .collect();
+    check_batch_cost(ctx, &txs, 0).await?;
+
+    for tx in &txs {
+        let signature = build_and_send_tx(ctx, &tx.instructions, &[ctx.signer()]).await?;
+
+        let mut out = stdout();
+        out.success(format!("Closed {} buffers", tx.instructions.len()));
+        out.note(format!("Signature: {signature}"));
+    }
+
+    Ok(())
+}
+
+/// Hashes program bytes without trailing zeros, since program data accounts
+/// are zero padded past the deployed binary.
+fn program_hash(program_data: &[u8]) -> (usize, String) {
+    let len = program_data
+        .iter()
+        .rposition(|&b| b != 0)
+        .map_or(0, |last| last + 1);
+
+    (len, sha256_hex(&program_data[..len]))
+}
+
+async fn compare_program_bytecode(
+    ctx: &ScillaContext,
+    program_id: &Pubkey,
+    program_path: &Path,
+) -> anyhow::Result<()> {
+    let local = fs::read(program_path).map_err(|e| anyhow!("Failed to open program file: {e}"))?;
+    let deployed = fetch_deployed_program(ctx, program_id).await?;
+
+    write_bytecode_comparison(
+        &mut stdout(),
+        program_path.display(),
+        &local,
+        program_id,
+        &deployed.elf,
+    );
+
+    Ok(())
+}
+
+fn write_bytecode_comparison<W: Write>(
+    out: &mut Renderer<W>,
+    local_name: impl fmt::Display,
+    local: &[u8],
+    program_id: &Pubkey,
+    deployed: &[u8],
+) {
+    let (local_len, local_hash) = program_hash(local);
+    let (deployed_len, deployed_hash) = program_hash(deployed);
+
+    let mut table = new_table();
+    table
+        .set_header(vec![
+            Cell::new("Source").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Size (bytes)").add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("SHA-256").add_attribute(comfy_table::Attribute::Bold),
+        ])
+        .add_row(vec![
+            Cell::new(local_name),
+            Cell::new(local_len),
+            Cell::new(&local_hash),
+        ])
+        .add_row(vec![
+            Cell::new(program_id),
+            Cell::new(deployed_len),
+            Cell::new(&deployed_hash),
+        ]);
+
+    out.title("BYTECODE COMPARISON");
+    out.table(table);
+
+    if local_hash == deployed_hash {
+        out.success("Deployed program matches the local build.");
+    } else {
+        out.error("Deployed program does NOT match the local build.");
+    }
+}
+
+fn prompt_memcmp_filters() -> Vec<RpcFilterType> {
+    let mut filters = Vec::new();
+
+    while prompt_confirmation("Add a memcmp filter?") {
+        let offset: usize = prompt_input_data("Enter byte offset:");
+        let encoded: String = prompt_input_data("Enter bytes to match (base58):");
+
+        match decode_base58(&encoded) {
+            Ok(bytes) => filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                offset, &bytes,
+            ))),
+            Err(e) => print_error(e),
+        }
+    }
+
+    filters
+}
+
+async fn fetch_program_accounts(
+    ctx: &ScillaContext,
+    program_id: &Pubkey,
+    filters: Vec<RpcFilterType>,
+) -> anyhow::Result<Vec<(Pubkey, Account)>> {
+    let config = RpcProgramAccountsConfig {
+        filters: (!filters.is_empty()).then_some(filters),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(ctx.rpc().commitment()),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+
+    let mut accounts = fetch_owned_accounts(ctx, program_id, config).await?;
+
+    if accounts.is_empty() {
+        bail!("No accounts owned by {program_id} matched the filters");
+    }
+
+    accounts.sort_by_key(|(_, account)| Reverse(account.lamports));
+
+    Ok(accounts)
+}
+
+fn browse_program_accounts(accounts: &[(Pubkey, Account)]) -> anyhow::Result<()> {
+    let total_lamports: u64 = accounts.iter().map(|(_, account)| account.lamports).sum();
+
+    let mut table = PagedTable::new(
+        format!(
+            "PROGRAM ACCOUNTS ({} accounts, {:.9} SOL total)",
+            accounts.len(),
+            lamports_to_sol(total_lamports)
+        ),
+        ["#", "Address", "Data Size (bytes)", "Balance (SOL)"],
+    );
+    for (idx, (pubkey, account)) in accounts.iter().enumerate() {
+        table.add_row([
+            (idx + 1).to_string(),
+            pubkey.to_string(),
+            account.data.len().to_string(),
+            format!("{:.9}", lamports_to_sol(account.lamports)),
+        ]);
+    }
+
+    table.show_with_action("Decode an account", |row| {
+        let (pubkey, account) = &accounts[row - 1];
+        show_decoded_account(pubkey, account)
+    })
+}
+
+fn show_decoded_account(pubkey: &Pubkey, account: &Account) -> anyhow::Result<()> {
+    let rows = match prompt_select_data("Decode using:", vec!["Known layout", "Anchor IDL file"]) {
+        "Anchor IDL file" => {
+            let idl_path: PathBuf = prompt_input_data("Enter path to IDL JSON file:");
+            let (name, decoded) = AnchorIdl::from_file(&idl_path)?.decode_account(&account.data)?;
+
+            let mut rows = vec![("Account Type".to_string(), name)];
+            match decoded {
+                Value::Object(fields) => rows.extend(
+                    fields
+                        .into_iter()
+                        .map(|(field, value)| (field, json_value_display(&value))),
+                ),
+                other => rows.push(("Value".to_string(), json_value_display(&other))),
+            }
+            rows
+        }
+        _ => decode_known_layout(pubkey, account)?,
+    };
+
+    let mut out = stdout();
+    out.title("DECODED ACCOUNT");
+    out.table(decoded_account_table(pubkey, rows));
+
+    Ok(())
+}
+
+fn decoded_account_table(pubkey: &Pubkey, rows: Vec<(String, String)>) -> Table {
+    let mut table = field_table([("Address", pubkey.to_string())]);
+    for (field, value) in rows {
+        table.add_row(vec![Cell::new(field), Cell::new(value)]);
+    }
+    table
+}
+
+fn json_value_display(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Decodes accounts of the native programs Scilla already understands.
+pub fn decode_known_layout(
+    pubkey: &Pubkey,
+    account: &Account,
+) -> anyhow::Result<Vec<(String, String)>> {
+    let mut rows: Vec<(&str, String)> = Vec::new();
+
+    if account.owner == stake::id() {
+        match bincode_deserialize::<StakeStateV2>(&account.data, "stake account data")? {
+            StakeStateV2::Uninitialized => rows.push(("Stake State", "Uninitialized".into())),
+            StakeStateV2::Initialized(meta) => rows.extend([
+                ("Stake State", "Initialized".into()),
+                ("Stake Authority", meta.authorized.staker.to_string()),
+                ("Withdraw Authority", meta.authorized.withdrawer.to_string()),
+            ]),
+            StakeStateV2::Stake(meta, stake, _) => rows.extend([
+                ("Stake State", "Delegated".into()),
+                ("Stake Authority", meta.authorized.staker.to_string()),
+                ("Withdraw Authority", meta.authorized.withdrawer.to_string()),
+                (
+                    "Delegated Vote Account",
+                    stake.delegation.voter_pubkey.to_string(),
+                ),
+                (
+                    "Delegated Stake (SOL)",
+                    lamports_to_sol(stake.delegation.stake).to_string(),
+                ),
+            ]),
+            StakeStateV2::RewardsPool => rows.push(("Stake State", "Rewards Pool".into())),
+        }
+    } else if account.owner == vote::id() {
+        let vote_state = VoteStateV4::deserialize(&account.data, pubkey)
+            .map_err(|_| anyhow!("Account data could not be deserialized to vote state"))?;
+        rows.extend([
+            ("Validator Identity", vote_state.node_pubkey.to_string()),
+            (
+                "Withdraw Authority",
+                vote_state.authorized_withdrawer.to_string(),
+            ),
+            (
+                "Commission",
+                format!("{}%", vote_state.inflation_rewards_commission_bps / 100),
+            ),
+            ("Credits", vote_state.credits().to_string()),
+        ]);
+    } else if account.owner == bpf_loader_upgradeable::id() {
+        match bincode_deserialize::<UpgradeableLoaderState>(&account.data, "loader state")? {
+            UpgradeableLoaderState::Uninitialized => {
+                rows.push(("Loader State", "Uninitialized".into()))
+            }
+            UpgradeableLoaderState::Buffer { authority_address } => rows.extend([
+                ("Loader State", "Buffer".into()),
+                ("Authority", display_authority(authority_address)),
+            ]),
+            UpgradeableLoaderState::Program {
+                programdata_address,
+            } => rows.extend([
+                ("Loader State", "Program".into()),
+                ("ProgramData Address", programdata_address.to_string()),
+            ]),
+            UpgradeableLoaderState::ProgramData {
+                slot,
+                upgrade_authority_address,
+            } => rows.extend([
+                ("Loader State", "ProgramData".into()),
+                ("Last Deployed Slot", slot.to_string()),
+                (
+                    "Upgrade Authority",
+                    display_authority(upgrade_authority_address),
+                ),
+            ]),
+        }
+    } else if account.owner == system_program::id() && !account.data.is_empty() {
+        let versions = bincode_deserialize::<Versions>(&account.data, "nonce account data")?;
+        let NonceState::Initialized(data) = versions.state() else {
+            bail!("This account is not an initialized nonce account");
+        };
+        rows.extend([
+            ("Nonce Blockhash", data.blockhash().to_string()),
+            ("Nonce Authority", data.authority.to_string()),
+        ]);
+    } else {
+        bail!(
+            "No known layout for accounts owned by {}, try an IDL instead",
+            account.owner
+        );
+    }
+
+    Ok(rows
+        .into_iter()
+        .map(|(field, value)| (field.to_string(), value))
+        .collect())
+}
+
+fn display_authority(authority: Option<Pubkey>) -> String {
+    authority
+        .map(|authority| authority.to_string())
+        .unwrap_or_else(|| "None (immutable)".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::ui::render::capture, insta::assert_snapshot};
+
+    #[test]
+    fn test_program_hash_ignores_zero_padding() {
+        let local = b"\x7fELF program bytes".to_vec();
+        let mut deployed = local.clone();
+        deployed.extend_from_slice(&[0; 64]);
+
+        assert_eq!(program_hash(&local), program_hash(&deployed));
+    }
+
+    #[test]
+    fn test_program_hash_detects_different_bytes() {
+        assert_ne!(
+            program_hash(b"\x7fELF program v1"),
+            program_hash(b"\x7fELF program v2")
+        );
+    }
+
+    #[test]
+    fn test_pending_chunks_skips_written_chunks() {
+        let program_data: Vec<u8> = (0..CHUNK_SIZE * 2 + 10).map(|i| i as u8).collect();
+        let mut written = vec![0; program_data.len()];
+        written[CHUNK_SIZE..CHUNK_SIZE * 2]
+            .copy_from_slice(&program_data[CHUNK_SIZE..CHUNK_SIZE * 2]);
+
+        let offsets: Vec<u32> = pending_chunks(&program_data, &written)
+            .iter()
+            .map(|(offset, _)| *offset)
+            .collect();
+        assert_eq!(offsets, [0, CHUNK_SIZE as u32 * 2]);
+
+        assert_eq!(pending_chunks(&program_data, &[]).len(), 3);
+        assert!(pending_chunks(&program_data, &program_data).is_empty());
+    }
+
+    #[test]
+    fn test_security_txt_table() {
+        let entries = vec![
+            ("contacts".to_string(), "email:sec@example.com".to_string()),
+            ("custom".to_string(), "extra".to_string()),
+            ("name".to_string(), "Example".to_string()),
+        ];
+
+        let output = capture(|out| out.table(security_txt_table(&entries)));
+
+        assert_snapshot!("security_txt_table", output);
+    }
+
+    #[test]
+    fn test_write_bytecode_comparison() {
+        let program_id = Pubkey::new_from_array([1; 32]);
+        let local = b"\x7fELF program v1".to_vec();
+        let mut deployed = local.clone();
+        deployed.extend_from_slice(&[0; 64]);
+
+        let output = capture(|out| {
+            write_bytecode_comparison(
+                out,
+                "target/deploy/example.so",
+                &local,
+                &program_id,
+                &deployed,
+            );
+            write_bytecode_comparison(
+                out,
+                "target/deploy/example.so",
+                b"\x7fELF program v2",
+                &program_id,
+                &deployed,
+            );
+        });
+
+        assert_snapshot!("write_bytecode_comparison", output);
+    }
+
+    #[test]
+    fn test_decoded_account_table() -> anyhow::Result<()> {
+        let buffer = Pubkey::new_from_array([2; 32]);
+        // Buffer state tag followed by Some(authority)
+        let mut data = vec![1, 0, 0, 0, 1];
+        data.extend_from_slice(&[1; 32]);
+        let account = Account {
+            lamports: 1_000_000_000,
+            data,
+            owner: bpf_loader_upgradeable::id(),
+            executable: false,
+            rent_epoch: 0,
+        };
+
+        let rows = decode_known_layout(&buffer, &account)?;
+        let output = capture(|out| out.table(decoded_account_table(&buffer, rows)));
+
+        assert_snapshot!("decoded_account_table", output);
+
+        Ok(())
+    }
+}
@@ -2,37 +2,65 @@ use {
     crate::{
         commands::CommandFlow,
         constants::{
-            ACTIVE_STAKE_EPOCH_BOUND, DEFAULT_EPOCH_LIMIT, LAMPORTS_PER_SOL,
-            STAKE_HISTORY_SYSVAR_ADDR,
+            ACTIVE_STAKE_EPOCH_BOUND, DEFAULT_EPOCH_LIMIT, DEFAULT_MS_PER_SLOT, LAMPORTS_PER_SOL,
+            MAX_BATCH_INSTRUCTIONS_PER_TX, SECONDS_PER_YEAR, SLOT_TIME_SAMPLES,
+            STAKE_AUTHORIZED_STAKER_OFFSET, STAKE_AUTHORIZED_WITHDRAWER_OFFSET, STAKE_CHART_WIDTH,
+            STAKE_DELEGATION_VOTER_OFFSET, STAKE_HISTORY_SYSVAR_ADDR,
         },
         context::ScillaContext,
-        misc::helpers::{
-            SolAmount, bincode_deserialize, bincode_deserialize_with_limit, build_and_send_tx,
-            check_minimum_balance, fetch_account_with_epoch, lamports_to_sol,
-            read_keypair_from_path, sol_to_lamports,
+        misc::{
+            batch_cost::{PlannedTx, check_batch_cost},
+            helpers::{
+                SolAmount, bincode_deserialize, bincode_deserialize_with_limit,
+                build_and_send_or_export_tx, build_and_send_tx, check_minimum_balance,
+                fetch_account_with_epoch, fetch_owned_accounts, lamports_to_sol,
+                read_keypair_from_path, short_pubkey, sol_to_lamports,
+            },
+            signer::read_signer,
+            whitelist::confirm_destination,
+        },
+        prompt::{
+            prompt_confirmation, prompt_input_data, prompt_input_data_with_default,
+            prompt_keypair_path, prompt_optional_pubkey_with_default, prompt_pubkey,
+            prompt_pubkey_with_default, prompt_refresh_interval, prompt_select_data,
+            prompt_withdraw_authority_path,
+        },
+        ui::{
+            StepChecklist,
+            multi_select::{BatchItem, prompt_batch_selection},
+            new_table, print_error,
+            render::{header_cell, stdout},
+            show_or_watch, show_spinner,
         },
-        prompt::{prompt_confirmation, prompt_input_data, prompt_keypair_path},
-        ui::show_spinner,
     },
     anyhow::{anyhow, bail},
-    comfy_table::{Cell, Table, presets::UTF8_FULL},
-    console::style,
+    comfy_table::{Cell, Table},
+    futures::TryFutureExt,
+    solana_account::Account,
+    solana_account_decoder_client_types::UiAccountEncoding,
     solana_clock::Clock,
     solana_keypair::Signer,
     solana_pubkey::Pubkey,
     solana_rpc_client_api::{
-        config::RpcGetVoteAccountsConfig, request::DELINQUENT_VALIDATOR_SLOT_DISTANCE,
-        response::RpcVoteAccountStatus,
+        config::{RpcAccountInfoConfig, RpcGetVoteAccountsConfig, RpcProgramAccountsConfig},
+        filter::{Memcmp, RpcFilterType},
+        request::DELINQUENT_VALIDATOR_SLOT_DISTANCE,
+        response::{RpcInflationReward, RpcVoteAccountStatus},
     },
     solana_sdk_ids::sysvar::stake_history,
     solana_stake_interface::{
         instruction::{self, deactivate_stake, merge, withdraw},
         program::id as stake_program_id,
         stake_history::{StakeHistory, StakeHistoryEntry},
-        state::{Authorized, Lockup, Meta, StakeActivationStatus, StakeStateV2},
+        state::{Authorized, Delegation, Lockup, Meta, StakeActivationStatus, StakeStateV2},
     },
     solana_sysvar::clock,
-    std::{fmt, ops::Div, path::PathBuf},
+    std::{
+        cmp::Reverse,
+        fmt,
+        ops::{Div, RangeInclusive},
+        path::{Path, PathBuf},
+    },
 };
 
 /// Commands related to staking operations
@@ -41,11 +69,15 @@ pub enum StakeCommand {
     Create,
     Delegate,
     Deactivate,
+    DeactivateMany,
     Withdraw,
     Merge,
     Split,
     Show,
+    ByAuthority,
     History,
+    ValidatorHistory,
+    RealizedApy,
     GoBack,
 }
 
@@ -55,11 +87,15 @@ impl StakeCommand {
             StakeCommand::Create => "Creating new stake account…",
             StakeCommand::Delegate => "Delegating stake to validator…",
             StakeCommand::Deactivate => "Deactivating stake (cooldown starting)…",
+            StakeCommand::DeactivateMany => "Deactivating selected stake accounts…",
             StakeCommand::Withdraw => "Withdrawing SOL from deactivated stake…",
             StakeCommand::Merge => "Merging stake accounts…",
             StakeCommand::Split => "Splitting stake into multiple accounts…",
             StakeCommand::Show => "Fetching stake account details…",
+            StakeCommand::ByAuthority => "Finding stake accounts of the authority…",
             StakeCommand::History => "Fetching stake account history…",
+            StakeCommand::ValidatorHistory => "Reconstructing validator stake history…",
+            StakeCommand::RealizedApy => "Computing realized APY from inflation rewards…",
             StakeCommand::GoBack => "Going back…",
         }
     }
@@ -71,11 +107,15 @@ impl fmt::Display for StakeCommand {
             StakeCommand::Create => "Create stake account",
             StakeCommand::Delegate => "Delegate stake",
             StakeCommand::Deactivate => "Deactivate stake",
+            StakeCommand::DeactivateMany => "Bulk deactivate stake",
             StakeCommand::Withdraw => "Withdraw stake",
             StakeCommand::Merge => "Merge stake accounts",
             StakeCommand::Split => "Split stake account",
             StakeCommand::Show => "Show stake",
+            StakeCommand::ByAuthority => "Stake accounts by authority",
             StakeCommand::History => "View stake history",
+            StakeCommand::ValidatorHistory => "Validator stake history",
+            StakeCommand::RealizedApy => "Realized APY",
             StakeCommand::GoBack => "Go back",
         };
         write!(f, "{command}")
@@ -90,7 +130,7 @@ impl StakeCommand {
                     prompt_keypair_path("Enter Stake Account Keypair Path: ", ctx);
                 let amount_sol: SolAmount = prompt_input_data("Enter amount to stake (in SOL):");
                 let withdraw_authority_keypair_path: PathBuf =
-                    prompt_keypair_path("Enter Withdraw Authority Keypair Path: ", ctx);
+                    prompt_withdraw_authority_path("Enter Withdraw Authority Keypair Path: ", ctx);
                 let configure_lockup: bool =
                     prompt_input_data("Would you like to set up lockup configuration? (y/n): ");
 
@@ -98,7 +138,7 @@ impl StakeCommand {
                     let epoch: u64 = prompt_input_data("Enter Lockup Epoch: ");
                     let unix_timestamp: i64 =
                         prompt_input_data("Enter Lockup Date (Unix TimeStamp): ");
-                    let custodian: Pubkey = prompt_input_data("Enter Lockup Custodian Pubkey: ");
+                    let custodian = prompt_pubkey(ctx, "Enter Lockup Custodian Pubkey: ").await;
 
                     Lockup {
                         epoch,
@@ -109,42 +149,86 @@ impl StakeCommand {
                     Lockup::default()
                 };
 
-                show_spinner(
-                    self.spinner_msg(),
-                    process_create_stake_account(
-                        ctx,
-                        stake_account_keypair_path,
-                        amount_sol,
-                        withdraw_authority_keypair_path,
-                        lockup,
-                    ),
+                let vote_account_pubkey = prompt_optional_pubkey_with_default(
+                    ctx,
+                    "Vote Account to delegate to (leave empty to skip): ",
+                    ctx.defaults().validator.as_deref(),
                 )
                 .await;
+
+                let create = process_create_stake_account(
+                    ctx,
+                    stake_account_keypair_path,
+                    amount_sol,
+                    withdraw_authority_keypair_path,
+                    lockup,
+                );
+
+                let Some(vote_account_pubkey) = vote_account_pubkey else {
+                    show_spinner(self.spinner_msg(), create).await;
+                    return CommandFlow::Process(());
+                };
+
+                let mut checklist = StepChecklist::new(
+                    "STAKE",
+                    [
+                        "Create stake account".to_string(),
+                        format!("Delegate to {vote_account_pubkey}"),
+                    ],
+                );
+
+                let result = async {
+                    let stake_account_pubkey = checklist.run(create).await?;
+                    checklist
+                        .run(delegate_stake_account(
+                            ctx,
+                            &stake_account_pubkey,
+                            &vote_account_pubkey,
+                            ctx.signer(),
+                        ))
+                        .await
+                        .inspect_err(|_| {
+                            stdout().warning(format!(
+                                "Stake account {stake_account_pubkey} was created; delegate it \
+                                 with Stake > Delegate stake."
+                            ))
+                        })
+                }
+                .await;
+
+                if let Err(e) = result {
+                    print_error(e);
+                }
             }
             StakeCommand::Delegate => {
-                let stake_account_pubkey: Pubkey =
-                    prompt_input_data("Enter Stake Account Pubkey: ");
-                let vote_account_pubkey: Pubkey = prompt_input_data("Enter Vote Account Pubkey: ");
+                let stake_account_pubkey = prompt_pubkey(ctx, "Enter Stake Account Pubkey: ").await;
+                let vote_account_pubkey = prompt_pubkey_with_default(
+                    ctx,
+                    "Enter Vote Account Pubkey: ",
+                    ctx.defaults().validator.as_deref(),
+                )
+                .await;
                 let stake_authority_keypair_path: PathBuf =
                     prompt_keypair_path("Enter Stake Authority Keypair Path: ", ctx);
 
-                show_spinner(
-                    self.spinner_msg(),
+                show_spinner(self.spinner_msg(), async {
+                    let stake_authority = read_signer(&stake_authority_keypair_path)?;
                     delegate_stake_account(
                         ctx,
                         &stake_account_pubkey,
                         &vote_account_pubkey,
-                        stake_authority_keypair_path,
-                    ),
-                )
+                        &*stake_authority,
+                    )
+                    .await
+                })
                 .await;
             }
             StakeCommand::Deactivate => {
-                let stake_pubkey: Pubkey =
-                    prompt_input_data("Enter Stake Account Pubkey to Deactivate:");
+                let stake_pubkey =
+                    prompt_pubkey(ctx, "Enter Stake Account Pubkey to Deactivate:").await;
 
                 if !prompt_confirmation("Are you sure you want to deactivate this stake?") {
-                    println!("{}", style("Deactivation cancelled.").yellow());
+                    stdout().warning("Deactivation cancelled.");
                     return CommandFlow::Process(());
                 }
 
@@ -154,17 +238,66 @@ impl StakeCommand {
                 )
                 .await;
             }
+            StakeCommand::DeactivateMany => {
+                let Some(accounts) = show_spinner(
+                    "Finding active stake accounts…",
+                    fetch_active_stake_accounts(ctx),
+                )
+                .await
+                else {
+                    return CommandFlow::Process(());
+                };
+
+                if accounts.is_empty() {
+                    stdout().warning(format!("No active stake has {} as staker.", ctx.pubkey()));
+                    return CommandFlow::Process(());
+                }
+
+                let items = accounts
+                    .into_iter()
+                    .map(|(pubkey, lamports, delegation)| {
+                        BatchItem::new(
+                            pubkey,
+                            pubkey.to_string(),
+                            format!(
+                                "{:.9} SOL delegated to {}",
+                                lamports_to_sol(delegation.stake),
+                                delegation.voter_pubkey
+                            ),
+                            lamports,
+                        )
+                    })
+                    .collect();
+
+                let stake_pubkeys = match prompt_batch_selection("deactivate", items) {
+                    Ok(stake_pubkeys) if !stake_pubkeys.is_empty() => stake_pubkeys,
+                    Ok(_) => return CommandFlow::Process(()),
+                    Err(e) => {
+                        print_error(e);
+                        return CommandFlow::Process(());
+                    }
+                };
+
+                show_spinner(
+                    self.spinner_msg(),
+                    process_deactivate_stake_accounts(ctx, &stake_pubkeys),
+                )
+                .await;
+            }
             StakeCommand::Withdraw => {
-                let stake_pubkey: Pubkey =
-                    prompt_input_data("Enter Stake Account Pubkey to Withdraw from:");
-                let recipient: Pubkey = prompt_input_data("Enter Recipient Address:");
+                let stake_pubkey =
+                    prompt_pubkey(ctx, "Enter Stake Account Pubkey to Withdraw from:").await;
+                let recipient = prompt_pubkey(ctx, "Enter Recipient Address:").await;
                 let amount: SolAmount = prompt_input_data("Enter Amount to Withdraw (SOL):");
 
+                if !confirm_destination(ctx, &recipient) {
+                    return CommandFlow::Process(());
+                }
                 if !prompt_confirmation(&format!(
                     "Are you sure you want to withdraw {} SOL?",
                     amount.value()
                 )) {
-                    println!("{}", style("Withdrawal cancelled.").yellow());
+                    stdout().warning("Withdrawal cancelled.");
                     return CommandFlow::Process(());
                 }
 
@@ -175,10 +308,10 @@ impl StakeCommand {
                 .await;
             }
             StakeCommand::Merge => {
-                let destination_stake_account_pubkey: Pubkey =
-                    prompt_input_data("Enter Stake Account Pubkey: ");
-                let source_stake_account_pubkey: Pubkey =
-                    prompt_input_data("Enter Source Stake Account Pubkey: ");
+                let destination_stake_account_pubkey =
+                    prompt_pubkey(ctx, "Enter Stake Account Pubkey: ").await;
+                let source_stake_account_pubkey =
+                    prompt_pubkey(ctx, "Enter Source Stake Account Pubkey: ").await;
                 let stake_authority_keypair_path =
                     prompt_keypair_path("Enter Stake Authority Keypair Path: ", ctx);
 
@@ -194,10 +327,9 @@ impl StakeCommand {
                 .await;
             }
             StakeCommand::Split => {
-                let stake_account_pubkey: Pubkey =
-                    prompt_input_data("Enter Stake Account Pubkey: ");
-                let split_stake_account_pubkey: Pubkey =
-                    prompt_input_data("Enter Split Stake Account Pubkey: ");
+                let stake_account_pubkey = prompt_pubkey(ctx, "Enter Stake Account Pubkey: ").await;
+                let split_stake_account_pubkey =
+                    prompt_pubkey(ctx, "Enter Split Stake Account Pubkey: ").await;
                 let stake_authority_keypair_path =
                     prompt_keypair_path("Enter Stake Authority Keypair Path: ", ctx);
                 let amount_to_split: f64 = prompt_input_data("Enter Stake Amount (SOL) to Split: ");
@@ -215,16 +347,65 @@ impl StakeCommand {
                 .await;
             }
             StakeCommand::Show => {
-                let stake_acc_pubkey: Pubkey = prompt_input_data("Enter Stake Account Pubkey:");
+                let stake_acc_pubkey = prompt_pubkey(ctx, "Enter Stake Account Pubkey:").await;
+                let refresh_secs = prompt_refresh_interval();
+                show_or_watch(self.spinner_msg(), refresh_secs, || {
+                    show_stake_account(ctx, &stake_acc_pubkey)
+                })
+                .await;
+            }
+            StakeCommand::ByAuthority => {
+                let authority = prompt_pubkey_with_default(
+                    ctx,
+                    "Enter Authority Pubkey:",
+                    Some(&ctx.pubkey().to_string()),
+                )
+                .await;
+                let role = prompt_select_data(
+                    "Which authority is it?",
+                    vec![StakeAuthorityRole::Staker, StakeAuthorityRole::Withdrawer],
+                );
+
                 show_spinner(
                     self.spinner_msg(),
-                    show_stake_account(ctx, &stake_acc_pubkey),
+                    process_stake_accounts_by_authority(ctx, &authority, role),
                 )
                 .await;
             }
             StakeCommand::History => {
                 show_spinner(self.spinner_msg(), process_stake_history(ctx)).await;
             }
+            StakeCommand::ValidatorHistory => {
+                let vote_account_pubkey = prompt_pubkey_with_default(
+                    ctx,
+                    "Enter Vote Account Pubkey: ",
+                    ctx.defaults().validator.as_deref(),
+                )
+                .await;
+                let epochs: u64 = prompt_input_data_with_default(
+                    "Number of epochs to show: ",
+                    Some(&DEFAULT_EPOCH_LIMIT.to_string()),
+                );
+
+                show_spinner(
+                    self.spinner_msg(),
+                    process_validator_stake_history(ctx, &vote_account_pubkey, epochs),
+                )
+                .await;
+            }
+            StakeCommand::RealizedApy => {
+                let stake_account_pubkey = prompt_pubkey(ctx, "Enter Stake Account Pubkey: ").await;
+                let epochs: u64 = prompt_input_data_with_default(
+                    "Number of epochs to show: ",
+                    Some(&DEFAULT_EPOCH_LIMIT.to_string()),
+                );
+
+                show_spinner(
+                    self.spinner_msg(),
+                    process_realized_apy(ctx, &stake_account_pubkey, epochs),
+                )
+                .await;
+            }
 
             StakeCommand::GoBack => return CommandFlow::GoBack,
         }
@@ -239,10 +420,9 @@ async fn process_create_stake_account(
     amount_sol: SolAmount,
     withdraw_authority_keypair_path: PathBuf,
     lockup: Lockup,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<Pubkey> {
     let stake_account_keypair = read_keypair_from_path(stake_account_keypair_path)?;
-    let withdraw_authority_pubkey =
-        read_keypair_from_path(withdraw_authority_keypair_path)?.pubkey();
+    let withdraw_authority_pubkey = read_signer(&withdraw_authority_keypair_path)?.pubkey();
 
     let lamports = amount_sol.to_lamports();
 
@@ -276,13 +456,11 @@ async fn process_create_stake_account(
         total_lamports,
     );
 
-    let signature = build_and_send_tx(ctx, &ix, &[ctx.keypair(), &stake_account_keypair]).await?;
+    let signature = build_and_send_tx(ctx, &ix, &[ctx.signer(), &stake_account_keypair]).await?;
 
-    println!(
-        "{}\n{}",
-        style("Stake Account created successfully!").yellow().bold(),
-        style(format!("Signature: {signature}")).green()
-    );
+    let mut out = stdout();
+    out.success("Stake Account created successfully!");
+    out.note(format!("Signature: {signature}"));
 
     let accounts = ctx
         .rpc()
@@ -315,17 +493,9 @@ async fn process_create_stake_account(
     let current_epoch = clock.epoch;
 
     // Add stake state specific information
-    let mut table = Table::new();
+    let mut table = new_table();
     table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
+        .set_header(vec![header_cell("Field"), header_cell("Value")])
         .add_row(vec![
             Cell::new("Stake Account Pubkey"),
             Cell::new(stake_account_keypair.pubkey()),
@@ -436,24 +606,20 @@ async fn process_create_stake_account(
         }
     }
 
-    println!(
-        "\n{}",
-        style("NEW STAKE ACCOUNT INFORMATION").green().bold()
-    );
-    println!("{table}");
+    out.title("NEW STAKE ACCOUNT INFORMATION");
+    out.table(table);
 
-    Ok(())
+    Ok(stake_account_keypair.pubkey())
 }
 
-async fn delegate_stake_account(
+pub async fn delegate_stake_account(
     ctx: &ScillaContext,
     stake_account_pubkey: &Pubkey,
     vote_account_pubkey: &Pubkey,
-    stake_authority_keypair_path: PathBuf,
+    stake_authority: &dyn Signer,
 ) -> anyhow::Result<()> {
     let stake_account = ctx.rpc().get_account(stake_account_pubkey).await?;
-    let stake_authority_keypair = read_keypair_from_path(stake_authority_keypair_path)?;
-    let stake_authority_pubkey = stake_authority_keypair.pubkey();
+    let stake_authority_pubkey = stake_authority.pubkey();
 
     if stake_account.owner != stake_program_id() {
         bail!("Account {} is not a stake account", stake_account_pubkey);
@@ -505,14 +671,15 @@ async fn delegate_stake_account(
         vote_account_pubkey,
     );
 
-    let signature =
-        build_and_send_tx(ctx, &[ix], &[ctx.keypair(), &stake_authority_keypair]).await?;
+    let Some(signature) =
+        build_and_send_or_export_tx(ctx, &[ix], &[ctx.signer(), stake_authority]).await?
+    else {
+        return Ok(());
+    };
 
-    println!(
-        "{}\n{}",
-        style("Stake Delegated successfully!").yellow().bold(),
-        style(format!("Signature: {signature}")).green()
-    );
+    let mut out = stdout();
+    out.success("Stake Delegated successfully!");
+    out.note(format!("Signature: {signature}"));
 
     let accounts = ctx
         .rpc()
@@ -542,17 +709,9 @@ async fn delegate_stake_account(
     let current_epoch = clock.epoch;
 
     // Add stake state specific information
-    let mut table = Table::new();
+    let mut table = new_table();
     table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
+        .set_header(vec![header_cell("Field"), header_cell("Value")])
         .add_row(vec![
             Cell::new("Stake Account Pubkey"),
             Cell::new(stake_account_pubkey),
@@ -692,15 +851,12 @@ async fn delegate_stake_account(
         }
     }
 
-    println!(
-        "\n{}",
-        style("DELEGATE STAKE ACCOUNT INFORMATION").green().bold()
-    );
-    println!("{table}");
+    out.title("DELEGATE STAKE ACCOUNT INFORMATION");
+    out.table(table);
     Ok(())
 }
 
-async fn process_deactivate_stake_account(
+pub async fn process_deactivate_stake_account(
     ctx: &ScillaContext,
     stake_pubkey: &Pubkey,
 ) -> anyhow::Result<()> {
@@ -739,20 +895,87 @@ async fn process_deactivate_stake_account(
     let authorized_pubkey = ctx.pubkey();
     let instruction = deactivate_stake(stake_pubkey, authorized_pubkey);
 
-    let signature = build_and_send_tx(ctx, &[instruction], &[ctx.keypair()]).await?;
+    let Some(signature) = build_and_send_or_export_tx(ctx, &[instruction], &[ctx.signer()]).await?
+    else {
+        return Ok(());
+    };
+
+    let mut out = stdout();
+    out.success("Stake Deactivated Successfully!");
+    out.warning("(Cooldown will take 1-2 epochs ≈ 2-4 days)");
+    out.line(format!("Stake Account: {stake_pubkey}"));
+    out.note(format!("Signature: {signature}"));
 
-    println!(
-        "{} {}\n{}\n{}",
-        style("Stake Deactivated Successfully!").green().bold(),
-        style("(Cooldown will take 1-2 epochs ≈ 2-4 days)").yellow(),
-        style(format!("Stake Account: {stake_pubkey}")).yellow(),
-        style(format!("Signature: {signature}")).cyan()
-    );
+    Ok(())
+}
+
+/// Stake accounts with the context keypair as staker that are delegated and
+/// not yet deactivating.
+async fn fetch_active_stake_accounts(
+    ctx: &ScillaContext,
+) -> anyhow::Result<Vec<(Pubkey, u64, Delegation)>> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            STAKE_AUTHORIZED_STAKER_OFFSET,
+            ctx.pubkey().as_ref(),
+        ))]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(ctx.rpc().commitment()),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+
+    let accounts = fetch_owned_accounts(ctx, &stake_program_id(), config).await?;
+
+    let mut active = Vec::new();
+    for (pubkey, account) in accounts {
+        let stake_state: StakeStateV2 = bincode_deserialize(&account.data, "stake account data")?;
+        if let StakeStateV2::Stake(_, stake, _) = stake_state
+            && stake.delegation.deactivation_epoch == ACTIVE_STAKE_EPOCH_BOUND
+        {
+            active.push((pubkey, account.lamports, stake.delegation));
+        }
+    }
+    active.sort_by(|(_, a, _), (_, b, _)| b.cmp(a));
+
+    Ok(active)
+}
+
+async fn process_deactivate_stake_accounts(
+    ctx: &ScillaContext,
+    stake_pubkeys: &[Pubkey],
+) -> anyhow::Result<()> {
+    let txs: Vec<PlannedTx> = stake_pubkeys
+        .chunks(MAX_BATCH_INSTRUCTIONS_PER_TX)
+        .map(|batch| {
+            PlannedTx::new(
+                batch
+                    .iter()
+                    .map(|stake_pubkey| deactivate_stake(stake_pubkey, ctx.pubkey()))
+                    .collect(),
+            )
+        })
+        .collect();
+    check_batch_cost(ctx, &txs, 0).await?;
+
+    for tx in &txs {
+        let signature = build_and_send_tx(ctx, &tx.instructions, &[ctx.signer()]).await?;
+
+        let mut out = stdout();
+        out.success(format!(
+            "Deactivated {} stake accounts",
+            tx.instructions.len()
+        ));
+        out.warning("(Cooldown will take 1-2 epochs ≈ 2-4 days)");
+        out.note(format!("Signature: {signature}"));
+    }
 
     Ok(())
 }
 
-async fn process_withdraw_stake(
+pub async fn process_withdraw_stake(
     ctx: &ScillaContext,
     stake_pubkey: &Pubkey,
     recipient: &Pubkey,
@@ -829,16 +1052,17 @@ async fn process_withdraw_stake(
         None,
     );
 
-    let signature = build_and_send_tx(ctx, &[instruction], &[ctx.keypair()]).await?;
+    let Some(signature) = build_and_send_or_export_tx(ctx, &[instruction], &[ctx.signer()]).await?
+    else {
+        return Ok(());
+    };
 
-    println!(
-        "{} {}\n{}\n{}\n{}",
-        style("Stake Withdrawn Successfully!").green().bold(),
-        style(format!("From Stake Account: {stake_pubkey}")).yellow(),
-        style(format!("To Recipient: {recipient}")).yellow(),
-        style(format!("Amount: {amount_sol} SOL")).cyan(),
-        style(format!("Signature: {signature}")).cyan()
-    );
+    let mut out = stdout();
+    out.success("Stake Withdrawn Successfully!");
+    out.line(format!("From Stake Account: {stake_pubkey}"));
+    out.line(format!("To Recipient: {recipient}"));
+    out.line(format!("Amount: {amount_sol} SOL"));
+    out.note(format!("Signature: {signature}"));
 
     Ok(())
 }
@@ -847,9 +1071,9 @@ async fn process_merge_stake(
     ctx: &ScillaContext,
     destination_stake_account_pubkey: &Pubkey,
     source_stake_account_pubkey: &Pubkey,
-    stake_authority_keypair_path: &PathBuf,
+    stake_authority_keypair_path: &Path,
 ) -> anyhow::Result<()> {
-    let stake_authority_keypair = read_keypair_from_path(stake_authority_keypair_path)?;
+    let stake_authority = read_signer(stake_authority_keypair_path)?;
 
     // checks for unique pubkeys
     if destination_stake_account_pubkey == source_stake_account_pubkey {
@@ -897,23 +1121,23 @@ async fn process_merge_stake(
     match &source_stake_state {
         StakeStateV2::Initialized(meta) => {
             // CHECK: Verify authority for initialized source
-            if meta.authorized.staker != stake_authority_keypair.pubkey() {
+            if meta.authorized.staker != stake_authority.pubkey() {
                 bail!(
                     "Provided keypair is not the stake authority for source account\nExpected: \
                      {}\nProvided: {}",
                     meta.authorized.staker,
-                    stake_authority_keypair.pubkey()
+                    stake_authority.pubkey()
                 );
             }
         }
         StakeStateV2::Stake(meta, stake, _) => {
             // CHECK: Verify authority for delegated source
-            if meta.authorized.staker != stake_authority_keypair.pubkey() {
+            if meta.authorized.staker != stake_authority.pubkey() {
                 bail!(
                     "Provided keypair is not the stake authority for source account\nExpected: \
                      {}\nProvided: {}",
                     meta.authorized.staker,
-                    stake_authority_keypair.pubkey()
+                    stake_authority.pubkey()
                 );
             }
 
@@ -928,7 +1152,7 @@ async fn process_merge_stake(
         _ => bail!("Source stake account is not in a valid state"),
     }
 
-    let stake_authority_pubkey = stake_authority_keypair.pubkey();
+    let stake_authority_pubkey = stake_authority.pubkey();
 
     let ixs = merge(
         destination_stake_account_pubkey,
@@ -936,30 +1160,24 @@ async fn process_merge_stake(
         &stake_authority_pubkey,
     );
 
-    let signature =
-        build_and_send_tx(ctx, &ixs, &[ctx.keypair(), &stake_authority_keypair]).await?;
-
-    println!(
-        "{}\n{}\n{}\n{}\n{}\n{}",
-        style("Stake Merged successfully!").yellow().bold(),
-        style(format!(
-            "Destination Stake Account: {}",
-            destination_stake_account_pubkey
-        ))
-        .yellow(),
-        style(format!(
-            "Source Stake Account: {}",
-            source_stake_account_pubkey
-        ))
-        .yellow(),
-        style(format!("Stake Authority: {}", stake_authority_pubkey)).yellow(),
-        style(format!(
-            "After Merge: {} SOL",
-            lamports_to_sol(destination_stake_account.lamports)
-        ))
-        .cyan(),
-        style(format!("Signature: {}", signature)).green()
-    );
+    let signature = build_and_send_tx(ctx, &ixs, &[ctx.signer(), &*stake_authority]).await?;
+
+    let mut out = stdout();
+    out.success("Stake Merged successfully!");
+    out.line(format!(
+        "Destination Stake Account: {}",
+        destination_stake_account_pubkey
+    ));
+    out.line(format!(
+        "Source Stake Account: {}",
+        source_stake_account_pubkey
+    ));
+    out.line(format!("Stake Authority: {}", stake_authority_pubkey));
+    out.line(format!(
+        "After Merge: {} SOL",
+        lamports_to_sol(destination_stake_account.lamports)
+    ));
+    out.note(format!("Signature: {}", signature));
 
     Ok(())
 }
@@ -968,11 +1186,11 @@ async fn process_split_stake(
     ctx: &ScillaContext,
     stake_account_pubkey: &Pubkey,
     split_stake_account_pubkey: &Pubkey,
-    stake_authority_keypair_path: &PathBuf,
+    stake_authority_keypair_path: &Path,
     amount_to_split: f64,
 ) -> anyhow::Result<()> {
-    let stake_authority_keypair = read_keypair_from_path(stake_authority_keypair_path)?;
-    let stake_authority_pubkey = stake_authority_keypair.pubkey();
+    let stake_authority = read_signer(stake_authority_keypair_path)?;
+    let stake_authority_pubkey = stake_authority.pubkey();
     let lamports: u64 = sol_to_lamports(amount_to_split);
 
     if stake_account_pubkey == split_stake_account_pubkey {
@@ -1000,20 +1218,17 @@ async fn process_split_stake(
         split_stake_account_pubkey,
     );
 
-    let signature = build_and_send_tx(ctx, &ix, &[ctx.keypair(), &stake_authority_keypair]).await?;
+    let signature = build_and_send_tx(ctx, &ix, &[ctx.signer(), &*stake_authority]).await?;
 
-    println!(
-        "{}\n{}\n{}\n{}\n{}",
-        style("Split Stake successfully!").yellow().bold(),
-        style(format!("Stake Account: {}", stake_account_pubkey)).yellow(),
-        style(format!(
-            "Split Stake Account: {}",
-            split_stake_account_pubkey
-        ))
-        .yellow(),
-        style(format!("Stake Authority: {}", stake_authority_pubkey)).yellow(),
-        style(format!("Signature: {}", signature)).green()
-    );
+    let mut out = stdout();
+    out.success("Split Stake successfully!");
+    out.line(format!("Stake Account: {}", stake_account_pubkey));
+    out.line(format!(
+        "Split Stake Account: {}",
+        split_stake_account_pubkey
+    ));
+    out.line(format!("Stake Authority: {}", stake_authority_pubkey));
+    out.note(format!("Signature: {}", signature));
 
     Ok(())
 }
@@ -1026,13 +1241,21 @@ async fn process_stake_history(ctx: &ScillaContext) -> anyhow::Result<()> {
     let stake_history: StakeHistory =
         bincode_deserialize_with_limit(account.data.len() as u64, &account.data, "stake history")?;
 
+    let mut out = stdout();
     if stake_history.is_empty() {
-        println!("{}", style("No stake history available").yellow());
+        out.warning("No stake history available");
         return Ok(());
     }
 
-    let mut table = Table::new();
-    table.load_preset(UTF8_FULL).set_header(vec![
+    out.title("CLUSTER STAKE HISTORY");
+    out.table(stake_history_table(&stake_history));
+
+    Ok(())
+}
+
+fn stake_history_table(stake_history: &StakeHistory) -> Table {
+    let mut table = new_table();
+    table.set_header(vec![
         Cell::new("Epoch").add_attribute(comfy_table::Attribute::Bold),
         Cell::new("Effective Stake").add_attribute(comfy_table::Attribute::Bold),
         Cell::new("Activating Stake").add_attribute(comfy_table::Attribute::Bold),
@@ -1054,13 +1277,314 @@ async fn process_stake_history(ctx: &ScillaContext) -> anyhow::Result<()> {
         ]);
     }
 
-    println!("\n{}", style("CLUSTER STAKE HISTORY").green().bold());
-    println!("{}", table);
+    table
+}
+
+async fn process_validator_stake_history(
+    ctx: &ScillaContext,
+    vote_account_pubkey: &Pubkey,
+    epochs: u64,
+) -> anyhow::Result<()> {
+    if epochs == 0 {
+        bail!("Number of epochs must be at least 1");
+    }
+
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            STAKE_DELEGATION_VOTER_OFFSET,
+            vote_account_pubkey.as_ref(),
+        ))]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(ctx.rpc().commitment()),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+    let (program_id, history_id) = (stake_program_id(), stake_history::id());
+    let (accounts, history_account, epoch_info) = tokio::try_join!(
+        fetch_owned_accounts(ctx, &program_id, config),
+        ctx.rpc()
+            .get_account(&history_id)
+            .map_err(anyhow::Error::from),
+        ctx.rpc().get_epoch_info().map_err(anyhow::Error::from),
+    )?;
+
+    let stake_history: StakeHistory = bincode_deserialize_with_limit(
+        history_account.data.len() as u64,
+        &history_account.data,
+        "stake history",
+    )?;
+
+    let mut delegations = Vec::new();
+    for (_, account) in accounts {
+        let stake_state: StakeStateV2 = bincode_deserialize(&account.data, "stake account data")?;
+        if let StakeStateV2::Stake(_, stake, _) = stake_state {
+            delegations.push(stake.delegation);
+        }
+    }
+
+    let first_epoch = epoch_info.epoch.saturating_sub(epochs - 1);
+    let stakes =
+        validator_stake_by_epoch(&delegations, &stake_history, first_epoch..=epoch_info.epoch);
+
+    let mut out = stdout();
+    out.title(format!("STAKE HISTORY OF {vote_account_pubkey}"));
+    out.table(validator_stake_history_table(&stakes));
+
+    if let (Some((_, first)), Some((_, last))) = (stakes.first(), stakes.last())
+        && *first > 0
+    {
+        let growth = (*last as f64 - *first as f64) / *first as f64 * 100.0;
+        out.line(format!(
+            "Change over {} epochs: {growth:+.2}%",
+            stakes.len() - 1
+        ));
+    }
+    // Closed stake accounts are gone from the chain, so are their delegations
+    out.note(format!(
+        "Reconstructed from the {} stake accounts currently delegated to this validator",
+        delegations.len()
+    ));
+
+    Ok(())
+}
+
+/// Active stake the `delegations` gave their validator in each of `epochs`,
+/// following the cluster's warmup and cooldown in `stake_history`.
+fn validator_stake_by_epoch(
+    delegations: &[Delegation],
+    stake_history: &StakeHistory,
+    epochs: RangeInclusive<u64>,
+) -> Vec<(u64, u64)> {
+    epochs
+        .map(|epoch| {
+            let stake = delegations
+                .iter()
+                .map(|delegation| delegation.stake(epoch, stake_history, None))
+                .sum();
+            (epoch, stake)
+        })
+        .collect()
+}
+
+/// A bar `STAKE_CHART_WIDTH` wide at `max`, scaled down for smaller stakes.
+fn stake_bar(stake: u64, max: u64) -> String {
+    if max == 0 {
+        return String::new();
+    }
+    let width = (stake as f64 / max as f64 * STAKE_CHART_WIDTH as f64).round() as usize;
+    "█".repeat(width)
+}
+
+fn validator_stake_history_table(stakes: &[(u64, u64)]) -> Table {
+    let max = stakes.iter().map(|(_, stake)| *stake).max().unwrap_or(0);
+
+    let mut table = new_table();
+    table.set_header(["Epoch", "Active Stake (SOL)", "Change (SOL)", ""].map(header_cell));
+
+    let mut previous = None;
+    for (epoch, stake) in stakes {
+        let change = previous.map_or(String::new(), |previous| {
+            format!("{:+}", lamports_to_sol(*stake) - lamports_to_sol(previous))
+        });
+        table.add_row(vec![
+            Cell::new(epoch),
+            Cell::new(lamports_to_sol(*stake)),
+            Cell::new(change),
+            Cell::new(stake_bar(*stake, max)),
+        ]);
+        previous = Some(*stake);
+    }
+
+    table
+}
+
+async fn process_realized_apy(
+    ctx: &ScillaContext,
+    stake_account_pubkey: &Pubkey,
+    epochs: u64,
+) -> anyhow::Result<()> {
+    if epochs == 0 {
+        bail!("Number of epochs must be at least 1");
+    }
+
+    let history_id = stake_history::id();
+    let (stake_account, epoch_info, samples, inflation, supply, history_account) = tokio::try_join!(
+        ctx.rpc().get_account(stake_account_pubkey),
+        ctx.rpc().get_epoch_info(),
+        ctx.rpc()
+            .get_recent_performance_samples(Some(SLOT_TIME_SAMPLES)),
+        ctx.rpc().get_inflation_rate(),
+        ctx.rpc().supply(),
+        ctx.rpc().get_account(&history_id),
+    )?;
+
+    if stake_account.owner != stake_program_id() {
+        bail!("Account {stake_account_pubkey} is not a stake account");
+    }
+    let stake_state: StakeStateV2 = bincode_deserialize(&stake_account.data, "stake account data")?;
+    let StakeStateV2::Stake(_, stake, _) = stake_state else {
+        bail!("Stake account {stake_account_pubkey} is not delegated");
+    };
+    let vote_account_pubkey = stake.delegation.voter_pubkey;
+
+    // Rewards for an epoch are paid out at the start of the next one
+    let mut rewards = Vec::new();
+    for epoch in epoch_info.epoch.saturating_sub(epochs)..epoch_info.epoch {
+        let reward = ctx
+            .rpc()
+            .get_inflation_reward(&[*stake_account_pubkey], Some(epoch))
+            .await?;
+        if let Some(Some(reward)) = reward.into_iter().next() {
+            rewards.push(reward);
+        }
+    }
+    if rewards.is_empty() {
+        bail!(
+            "No inflation rewards were paid to {stake_account_pubkey} in the last {epochs} epochs"
+        );
+    }
+
+    let (sample_secs, sample_slots) = samples.iter().fold((0, 0), |(secs, slots), sample| {
+        (
+            secs + u64::from(sample.sample_period_secs),
+            slots + sample.num_slots,
+        )
+    });
+    let ms_per_slot = if sample_slots > 0 {
+        sample_secs as f64 * 1000.0 / sample_slots as f64
+    } else {
+        DEFAULT_MS_PER_SLOT as f64
+    };
+    let epochs_per_year =
+        SECONDS_PER_YEAR * 1000.0 / (ms_per_slot * epoch_info.slots_in_epoch as f64);
+
+    let vote_accounts = ctx
+        .rpc()
+        .get_vote_accounts_with_config(RpcGetVoteAccountsConfig {
+            vote_pubkey: Some(vote_account_pubkey.to_string()),
+            commitment: Some(ctx.rpc().commitment()),
+            keep_unstaked_delinquents: Some(true),
+            ..RpcGetVoteAccountsConfig::default()
+        })
+        .await?;
+    let commission = vote_accounts
+        .current
+        .iter()
+        .chain(&vote_accounts.delinquent)
+        .next()
+        .map(|vote_account| vote_account.commission)
+        .ok_or_else(|| anyhow!("Vote account not found: {vote_account_pubkey}"))?;
+
+    let stake_history: StakeHistory = bincode_deserialize_with_limit(
+        history_account.data.len() as u64,
+        &history_account.data,
+        "stake history",
+    )?;
+    let staked = stake_history
+        .iter()
+        .next()
+        .map_or(0, |(_, entry)| entry.effective);
+    let staked_fraction = staked as f64 / supply.value.total as f64;
+
+    let mut out = stdout();
+    out.title(format!("REALIZED APY OF {stake_account_pubkey}"));
+    out.table(realized_apy_table(&rewards, epochs_per_year));
+
+    let realized = average_apy(&rewards, epochs_per_year);
+    let expected = expected_apy(
+        inflation.validator,
+        staked_fraction,
+        commission,
+        epochs_per_year,
+    );
+    out.line(format!(
+        "Realized APY over {} epochs: {:.2}%",
+        rewards.len(),
+        realized * 100.0
+    ));
+    out.line(format!(
+        "Expected APY at {commission}% commission from {vote_account_pubkey}: {:.2}%",
+        expected * 100.0
+    ));
+    out.note(format!(
+        "Realized is {:+.2} percentage points from expected, which assumes full vote credits",
+        (realized - expected) * 100.0
+    ));
 
     Ok(())
 }
 
-async fn show_stake_account(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Result<()> {
+/// Rate `reward` paid on the balance it was paid to.
+fn epoch_rate(reward: &RpcInflationReward) -> f64 {
+    let balance = reward.post_balance.saturating_sub(reward.amount);
+    if balance == 0 {
+        return 0.0;
+    }
+    reward.amount as f64 / balance as f64
+}
+
+/// `rate` earned every epoch, compounded over a year.
+fn annualize(rate: f64, epochs_per_year: f64) -> f64 {
+    (1.0 + rate).powf(epochs_per_year) - 1.0
+}
+
+/// APY of the mean per-epoch rate of `rewards`.
+fn average_apy(rewards: &[RpcInflationReward], epochs_per_year: f64) -> f64 {
+    let mean = rewards.iter().map(epoch_rate).sum::<f64>() / rewards.len() as f64;
+    annualize(mean, epochs_per_year)
+}
+
+/// APY the validator advertises: the inflation paid to validators spread
+/// over the staked supply, less `commission`, compounded every epoch.
+fn expected_apy(
+    validator_inflation: f64,
+    staked_fraction: f64,
+    commission: u8,
+    epochs_per_year: f64,
+) -> f64 {
+    if staked_fraction <= 0.0 {
+        return 0.0;
+    }
+    let apr = validator_inflation / staked_fraction * (1.0 - f64::from(commission) / 100.0);
+    annualize(apr / epochs_per_year, epochs_per_year)
+}
+
+fn realized_apy_table(rewards: &[RpcInflationReward], epochs_per_year: f64) -> Table {
+    let mut table = new_table();
+    table.set_header(
+        [
+            "Epoch",
+            "Reward (SOL)",
+            "Balance (SOL)",
+            "Commission",
+            "APY",
+        ]
+        .map(header_cell),
+    );
+
+    for reward in rewards {
+        table.add_row(vec![
+            Cell::new(reward.epoch),
+            Cell::new(lamports_to_sol(reward.amount)),
+            Cell::new(lamports_to_sol(reward.post_balance)),
+            Cell::new(
+                reward
+                    .commission
+                    .map_or("-".to_string(), |commission| format!("{commission}%")),
+            ),
+            Cell::new(format!(
+                "{:.2}%",
+                annualize(epoch_rate(reward), epochs_per_year) * 100.0
+            )),
+        ]);
+    }
+
+    table
+}
+
+pub async fn show_stake_account(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Result<()> {
     let accounts = ctx
         .rpc()
         .get_multiple_accounts(&[*pubkey, stake_history::id(), clock::id()])
@@ -1084,16 +1608,32 @@ async fn show_stake_account(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Res
 
     let stake_state: StakeStateV2 = bincode_deserialize(&stake_account.data, "stake account data")?;
 
+    let mut out = stdout();
+    out.title("STAKE ACCOUNT INFORMATION");
+    out.table(stake_account_table(
+        pubkey,
+        stake_account,
+        &stake_state,
+        &clock,
+        &stake_history,
+    ));
+
+    Ok(())
+}
+
+fn stake_account_table(
+    pubkey: &Pubkey,
+    stake_account: &Account,
+    stake_state: &StakeStateV2,
+    clock: &Clock,
+    stake_history: &StakeHistory,
+) -> Table {
     let current_epoch = clock.epoch;
 
     // Build main table
-    let mut table = Table::new();
+    let mut table = new_table();
     table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field").add_attribute(comfy_table::Attribute::Bold),
-            Cell::new("Value").add_attribute(comfy_table::Attribute::Bold),
-        ])
+        .set_header(vec![header_cell("Field"), header_cell("Value")])
         .add_row(vec![Cell::new("Stake Account Pubkey"), Cell::new(pubkey)])
         .add_row(vec![
             Cell::new("Account Balance (SOL)"),
@@ -1109,7 +1649,7 @@ async fn show_stake_account(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Res
         ]);
 
     // Add stake state specific information
-    match &stake_state {
+    match stake_state {
         StakeStateV2::Uninitialized => {
             table.add_row(vec![Cell::new("Stake State"), Cell::new("Uninitialized")]);
         }
@@ -1133,7 +1673,7 @@ async fn show_stake_account(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Res
                     Cell::new(authorized.withdrawer),
                 ]);
 
-            if lockup.is_in_force(&clock, None) {
+            if lockup.is_in_force(clock, None) {
                 table
                     .add_row(vec![Cell::new("Lockup Epoch"), Cell::new(lockup.epoch)])
                     .add_row(vec![
@@ -1160,7 +1700,7 @@ async fn show_stake_account(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Res
                 deactivating,
             } = stake.delegation.stake_activating_and_deactivating(
                 current_epoch,
-                &stake_history,
+                stake_history,
                 None,
             );
 
@@ -1215,7 +1755,7 @@ async fn show_stake_account(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Res
                     Cell::new(stake.credits_observed),
                 ]);
 
-            if lockup.is_in_force(&clock, None) {
+            if lockup.is_in_force(clock, None) {
                 table
                     .add_row(vec![Cell::new("Lockup Epoch"), Cell::new(lockup.epoch)])
                     .add_row(vec![
@@ -1233,8 +1773,421 @@ async fn show_stake_account(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Res
         }
     }
 
-    println!("{}\n", style("STAKE ACCOUNT INFORMATION").green().bold());
-    println!("{table}");
+    table
+}
+
+/// Authority of a stake account, as searched for by
+/// [`StakeCommand::ByAuthority`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StakeAuthorityRole {
+    Staker,
+    Withdrawer,
+}
+
+impl StakeAuthorityRole {
+    /// Byte offset of this authority in the stake account data.
+    fn offset(self) -> usize {
+        match self {
+            StakeAuthorityRole::Staker => STAKE_AUTHORIZED_STAKER_OFFSET,
+            StakeAuthorityRole::Withdrawer => STAKE_AUTHORIZED_WITHDRAWER_OFFSET,
+        }
+    }
+}
+
+impl fmt::Display for StakeAuthorityRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StakeAuthorityRole::Staker => write!(f, "Stake authority"),
+            StakeAuthorityRole::Withdrawer => write!(f, "Withdraw authority"),
+        }
+    }
+}
+
+/// One row of the stake accounts an authority controls.
+#[derive(Debug, PartialEq)]
+struct StakeSummary {
+    pubkey: Pubkey,
+    lamports: u64,
+    /// Vote account the stake is delegated to, if any
+    voter: Option<Pubkey>,
+    status: StakeActivationStatus,
+    /// Lockup still in force
+    lockup: Option<Lockup>,
+}
+
+impl StakeSummary {
+    fn new(
+        pubkey: Pubkey,
+        lamports: u64,
+        stake_state: &StakeStateV2,
+        clock: &Clock,
+        stake_history: &StakeHistory,
+    ) -> Option<Self> {
+        let (meta, stake) = match stake_state {
+            StakeStateV2::Initialized(meta) => (meta, None),
+            StakeStateV2::Stake(meta, stake, _) => (meta, Some(stake)),
+            StakeStateV2::Uninitialized | StakeStateV2::RewardsPool => return None,
+        };
+
+        Some(Self {
+            pubkey,
+            lamports,
+            voter: stake.map(|stake| stake.delegation.voter_pubkey),
+            status: stake.map_or_else(StakeActivationStatus::default, |stake| {
+                stake
+                    .delegation
+                    .stake_activating_and_deactivating(clock.epoch, stake_history, None)
+            }),
+            lockup: meta.lockup.is_in_force(clock, None).then_some(meta.lockup),
+        })
+    }
+
+    fn state(&self) -> &'static str {
+        let StakeActivationStatus {
+            effective,
+            activating,
+            deactivating,
+        } = self.status;
+
+        if self.voter.is_none() {
+            "Initialized"
+        } else if deactivating > 0 {
+            "Deactivating"
+        } else if activating > 0 {
+            "Activating"
+        } else if effective > 0 {
+            "Active"
+        } else {
+            "Inactive"
+        }
+    }
+}
+
+/// When a lockup in force ends, by epoch and/or date.
+fn lockup_description(lockup: &Lockup, clock: &Clock) -> String {
+    let mut until = Vec::new();
+    if lockup.epoch > clock.epoch {
+        until.push(format!("epoch {}", lockup.epoch));
+    }
+    if lockup.unix_timestamp > clock.unix_timestamp {
+        until.push(
+            chrono::DateTime::<chrono::Utc>::from_timestamp_secs(lockup.unix_timestamp)
+                .map_or(lockup.unix_timestamp.to_string(), |dt| {
+                    dt.format("%Y-%m-%d").to_string()
+                }),
+        );
+    }
+    format!("until {}", until.join(", "))
+}
+
+async fn process_stake_accounts_by_authority(
+    ctx: &ScillaContext,
+    authority: &Pubkey,
+    role: StakeAuthorityRole,
+) -> anyhow::Result<()> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            role.offset(),
+            authority.as_ref(),
+        ))]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(ctx.rpc().commitment()),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+
+    let program_id = stake_program_id();
+    let sysvar_ids = [stake_history::id(), clock::id()];
+    let (accounts, sysvars) = tokio::try_join!(
+        fetch_owned_accounts(ctx, &program_id, config),
+        ctx.rpc()
+            .get_multiple_accounts(&sysvar_ids)
+            .map_err(anyhow::Error::from),
+    )?;
+
+    let Some(Some(stake_history_account)) = sysvars.first() else {
+        bail!("Failed to get stake history account");
+    };
+    let Some(Some(clock_account)) = sysvars.get(1) else {
+        bail!("Failed to get clock account");
+    };
+    let stake_history: StakeHistory =
+        bincode_deserialize(&stake_history_account.data, "stake history account data")?;
+    let clock: Clock = bincode_deserialize(&clock_account.data, "clock account data")?;
+
+    let mut summaries = Vec::new();
+    for (pubkey, account) in accounts {
+        let stake_state: StakeStateV2 = bincode_deserialize(&account.data, "stake account data")?;
+        summaries.extend(StakeSummary::new(
+            pubkey,
+            account.lamports,
+            &stake_state,
+            &clock,
+            &stake_history,
+        ));
+    }
+    summaries.sort_by_key(|summary| Reverse(summary.lamports));
+
+    let mut out = stdout();
+    if summaries.is_empty() {
+        out.warning(format!("No stake accounts found ({role} {authority})"));
+        return Ok(());
+    }
+
+    out.title(format!("STAKE ACCOUNTS OF {authority}"));
+    out.table(stake_summary_table(&summaries, &clock));
 
     Ok(())
 }
+
+fn stake_summary_table(summaries: &[StakeSummary], clock: &Clock) -> Table {
+    let mut table = new_table();
+    table.set_header(
+        [
+            "Stake Account",
+            "State",
+            "Validator",
+            "Balance (SOL)",
+            "Active (SOL)",
+            "Activating (SOL)",
+            "Deactivating (SOL)",
+            "Lockup",
+        ]
+        .map(header_cell),
+    );
+
+    let mut total_lamports = 0;
+    let mut total = StakeActivationStatus::default();
+    for summary in summaries {
+        table.add_row(vec![
+            Cell::new(summary.pubkey),
+            Cell::new(summary.state()),
+            Cell::new(summary.voter.as_ref().map_or("-".to_string(), short_pubkey)),
+            Cell::new(lamports_to_sol(summary.lamports)),
+            Cell::new(lamports_to_sol(summary.status.effective)),
+            Cell::new(lamports_to_sol(summary.status.activating)),
+            Cell::new(lamports_to_sol(summary.status.deactivating)),
+            Cell::new(
+                summary
+                    .lockup
+                    .as_ref()
+                    .map_or("-".to_string(), |lockup| lockup_description(lockup, clock)),
+            ),
+        ]);
+
+        total_lamports += summary.lamports;
+        total.effective += summary.status.effective;
+        total.activating += summary.status.activating;
+        total.deactivating += summary.status.deactivating;
+    }
+
+    table.add_row(vec![
+        header_cell(&format!("Total ({} accounts)", summaries.len())),
+        Cell::new(""),
+        Cell::new(""),
+        Cell::new(lamports_to_sol(total_lamports)),
+        Cell::new(lamports_to_sol(total.effective)),
+        Cell::new(lamports_to_sol(total.activating)),
+        Cell::new(lamports_to_sol(total.deactivating)),
+        Cell::new(""),
+    ]);
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::ui::render::capture,
+        insta::assert_snapshot,
+        solana_stake_interface::{stake_flags::StakeFlags, state::Stake},
+    };
+
+    #[test]
+    fn test_stake_account_table() {
+        let meta = Meta {
+            rent_exempt_reserve: 2_282_880,
+            authorized: Authorized {
+                staker: Pubkey::new_from_array([1; 32]),
+                withdrawer: Pubkey::new_from_array([2; 32]),
+            },
+            lockup: Lockup::default(),
+        };
+        let stake = Stake {
+            delegation: Delegation {
+                voter_pubkey: Pubkey::new_from_array([3; 32]),
+                stake: 5_000_000_000,
+                activation_epoch: 700,
+                ..Delegation::default()
+            },
+            credits_observed: 1234,
+        };
+        let account = Account {
+            lamports: 5_002_282_880,
+            data: Vec::new(),
+            owner: stake_program_id(),
+            executable: false,
+            rent_epoch: u64::MAX,
+        };
+        let clock = Clock {
+            epoch: 800,
+            ..Clock::default()
+        };
+
+        let output = capture(|out| {
+            out.table(stake_account_table(
+                &Pubkey::new_from_array([4; 32]),
+                &account,
+                &StakeStateV2::Stake(meta, stake, StakeFlags::empty()),
+                &clock,
+                &StakeHistory::default(),
+            ))
+        });
+
+        assert_snapshot!("stake_account_table", output);
+    }
+
+    #[test]
+    fn test_stake_summary_table() {
+        let voter = Pubkey::new_from_array([3; 32]);
+        let meta = Meta {
+            rent_exempt_reserve: 2_282_880,
+            authorized: Authorized {
+                staker: Pubkey::new_from_array([1; 32]),
+                withdrawer: Pubkey::new_from_array([2; 32]),
+            },
+            lockup: Lockup::default(),
+        };
+        let delegated = |stake, activation_epoch| {
+            StakeStateV2::Stake(
+                meta,
+                Stake {
+                    delegation: Delegation::new(&voter, stake, activation_epoch),
+                    credits_observed: 0,
+                },
+                StakeFlags::empty(),
+            )
+        };
+        let locked = StakeStateV2::Initialized(Meta {
+            lockup: Lockup {
+                epoch: 900,
+                unix_timestamp: 0,
+                custodian: Pubkey::new_from_array([7; 32]),
+            },
+            ..meta
+        });
+        let clock = Clock {
+            epoch: 800,
+            unix_timestamp: 1_700_000_000,
+            ..Clock::default()
+        };
+        let summary = |seed, lamports, stake_state: &StakeStateV2| {
+            StakeSummary::new(
+                Pubkey::new_from_array([seed; 32]),
+                lamports,
+                stake_state,
+                &clock,
+                &StakeHistory::default(),
+            )
+        };
+
+        assert_eq!(summary(8, 1, &StakeStateV2::Uninitialized), None);
+        let summaries: Vec<StakeSummary> = [
+            summary(4, 5_002_282_880, &delegated(5_000_000_000, 700)),
+            // Delegated this epoch, so still warming up
+            summary(5, 2_000_000_000, &delegated(1_997_717_120, 800)),
+            summary(6, 1_002_282_880, &locked),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        let output = capture(|out| out.table(stake_summary_table(&summaries, &clock)));
+
+        assert_snapshot!("stake_summary_table", output);
+    }
+
+    #[test]
+    fn test_validator_stake_history() {
+        let voter = Pubkey::new_from_array([1; 32]);
+        let sol = |amount| amount * LAMPORTS_PER_SOL;
+
+        let long_standing = Delegation::new(&voter, sol(1_000), 10);
+        let new = Delegation::new(&voter, sol(500), 101);
+        let mut leaving = Delegation::new(&voter, sol(250), 10);
+        leaving.deactivation_epoch = 102;
+
+        // Without history entries stake warms up and cools down in one epoch
+        let stakes = validator_stake_by_epoch(
+            &[long_standing, new, leaving],
+            &StakeHistory::default(),
+            100..=103,
+        );
+        assert_eq!(
+            stakes,
+            vec![
+                (100, sol(1_250)),
+                (101, sol(1_250)),
+                (102, sol(1_750)),
+                (103, sol(1_500)),
+            ]
+        );
+
+        let output = capture(|out| out.table(validator_stake_history_table(&stakes)));
+
+        assert_snapshot!("validator_stake_history_table", output);
+    }
+
+    #[test]
+    fn test_realized_apy() {
+        let reward = |epoch, amount, post_balance, commission| RpcInflationReward {
+            epoch,
+            effective_slot: epoch * 432_000,
+            amount,
+            post_balance,
+            commission,
+        };
+        let rewards = [
+            reward(700, 300_000_000, 1_000_300_000_000, Some(5)),
+            reward(701, 250_000_000, 1_000_550_000_000, Some(5)),
+            reward(702, 0, 1_000_550_000_000, None),
+        ];
+
+        assert!((average_apy(&rewards, 182.5) - 0.0340).abs() < 1e-4);
+        // 4.5% validator inflation over 65% of the supply, 5% commission
+        assert!((expected_apy(0.045, 0.65, 5, 182.5) - 0.0680).abs() < 1e-4);
+        assert_eq!(expected_apy(0.045, 0.0, 5, 182.5), 0.0);
+
+        let output = capture(|out| out.table(realized_apy_table(&rewards, 182.5)));
+
+        assert_snapshot!("realized_apy_table", output);
+    }
+
+    #[test]
+    fn test_stake_history_table() {
+        let mut stake_history = StakeHistory::default();
+        stake_history.add(
+            798,
+            StakeHistoryEntry {
+                effective: 399_000_000_000_000_000,
+                activating: 2_000_000_000_000,
+                deactivating: 0,
+            },
+        );
+        stake_history.add(
+            799,
+            StakeHistoryEntry {
+                effective: 400_000_000_000_000_000,
+                activating: 1_500_000_000_000,
+                deactivating: 250_000_000_000,
+            },
+        );
+
+        let output = capture(|out| out.table(stake_history_table(&stake_history)));
+
+        assert_snapshot!("stake_history_table", output);
+    }
+}
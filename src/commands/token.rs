@@ -0,0 +1,674 @@
+use {
+    crate::{
+        commands::CommandFlow,
+        constants::{ASSOCIATED_TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID},
+        context::ScillaContext,
+        misc::{helpers::build_and_send_tx, whitelist::confirm_destination},
+        prompt::{
+            prompt_input_data, prompt_input_data_with_default, prompt_optional_pubkey_with_default,
+            prompt_pubkey, prompt_pubkey_with_default,
+        },
+        ui::{
+            render::{field_table, stdout},
+            show_spinner,
+            table::PagedTable,
+        },
+    },
+    anyhow::{anyhow, bail},
+    comfy_table::Table,
+    solana_account_decoder_client_types::UiAccountData,
+    solana_instruction::{AccountMeta, Instruction},
+    solana_keypair::{Keypair, Signer},
+    solana_program_pack::Pack,
+    solana_pubkey::Pubkey,
+    solana_rpc_client_api::{request::TokenAccountsFilter, response::RpcKeyedAccount},
+    solana_sdk_ids::system_program,
+    solana_system_interface::instruction::create_account,
+    spl_token_interface::{
+        instruction::{initialize_mint2, mint_to_checked, transfer_checked},
+        state::{Account as TokenAccount, AccountState, Mint},
+    },
+    std::{fmt, str::FromStr},
+};
+
+/// Token-2022 accounts carry their type in the byte after the base token
+/// account layout, followed by their extensions.
+const ACCOUNT_TYPE_OFFSET: usize = TokenAccount::LEN;
+const ACCOUNT_TYPE_MINT: u8 = 1;
+const ACCOUNT_TYPE_ACCOUNT: u8 = 2;
+
+/// Commands related to SPL tokens
+#[derive(Debug, Clone)]
+pub enum TokenCommand {
+    Balances,
+    Transfer,
+    CreateMint,
+    MintTo,
+    Show,
+    GoBack,
+}
+
+impl TokenCommand {
+    pub fn spinner_msg(&self) -> &'static str {
+        match self {
+            TokenCommand::Balances => "Fetching token balances…",
+            TokenCommand::Transfer => "Transferring tokens…",
+            TokenCommand::CreateMint => "Creating mint…",
+            TokenCommand::MintTo => "Minting tokens…",
+            TokenCommand::Show => "Fetching token account…",
+            TokenCommand::GoBack => "Going back…",
+        }
+    }
+}
+
+impl fmt::Display for TokenCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let command = match self {
+            TokenCommand::Balances => "Token balances",
+            TokenCommand::Transfer => "Transfer tokens",
+            TokenCommand::CreateMint => "Create mint",
+            TokenCommand::MintTo => "Mint tokens",
+            TokenCommand::Show => "Show mint or token account",
+            TokenCommand::GoBack => "Go back",
+        };
+        write!(f, "{command}")
+    }
+}
+
+impl TokenCommand {
+    pub async fn process_command(&self, ctx: &ScillaContext) -> CommandFlow<()> {
+        match self {
+            TokenCommand::Balances => {
+                let owner = prompt_pubkey_with_default(
+                    ctx,
+                    "Enter wallet Pubkey:",
+                    Some(&ctx.pubkey().to_string()),
+                )
+                .await;
+                show_spinner(self.spinner_msg(), fetch_token_balances(ctx, &owner)).await;
+            }
+            TokenCommand::Transfer => {
+                let mint = prompt_pubkey(ctx, "Enter Mint Address:").await;
+                let recipient = prompt_pubkey(ctx, "Enter recipient wallet Pubkey:").await;
+                let amount: TokenAmount = prompt_input_data("Enter amount of tokens:");
+
+                if !confirm_destination(ctx, &recipient) {
+                    return CommandFlow::Process(());
+                }
+
+                show_spinner(
+                    self.spinner_msg(),
+                    transfer_tokens(ctx, &mint, &recipient, &amount),
+                )
+                .await;
+            }
+            TokenCommand::CreateMint => {
+                let decimals: u8 = prompt_input_data_with_default("Enter decimals:", Some("9"));
+                let freeze_authority = prompt_optional_pubkey_with_default(
+                    ctx,
+                    "Enter freeze authority (empty for none):",
+                    None,
+                )
+                .await;
+
+                show_spinner(
+                    self.spinner_msg(),
+                    create_mint(ctx, decimals, freeze_authority.as_ref()),
+                )
+                .await;
+            }
+            TokenCommand::MintTo => {
+                let mint = prompt_pubkey(ctx, "Enter Mint Address:").await;
+                let recipient = prompt_pubkey_with_default(
+                    ctx,
+                    "Enter recipient wallet Pubkey:",
+                    Some(&ctx.pubkey().to_string()),
+                )
+                .await;
+                let amount: TokenAmount = prompt_input_data("Enter amount of tokens to mint:");
+
+                show_spinner(
+                    self.spinner_msg(),
+                    mint_tokens(ctx, &mint, &recipient, &amount),
+                )
+                .await;
+            }
+            TokenCommand::Show => {
+                let pubkey = prompt_pubkey(ctx, "Enter mint or token account address:").await;
+                show_spinner(self.spinner_msg(), show_token_account(ctx, &pubkey)).await;
+            }
+            TokenCommand::GoBack => return CommandFlow::GoBack,
+        }
+
+        CommandFlow::Process(())
+    }
+}
+
+/// A token amount as entered. It's kept as text so it converts to base
+/// units exactly once the mint's decimals are known.
+#[derive(Debug, Clone)]
+pub struct TokenAmount(String);
+
+impl TokenAmount {
+    pub fn to_base_units(&self, decimals: u8) -> anyhow::Result<u64> {
+        let (whole, fraction) = self.0.split_once('.').unwrap_or((&self.0, ""));
+        if fraction.len() > usize::from(decimals) {
+            bail!("{} has more than the mint's {decimals} decimals", self.0);
+        }
+
+        let digits = format!("{whole}{fraction:0<width$}", width = usize::from(decimals));
+        let amount: u64 = digits
+            .parse()
+            .map_err(|_| anyhow!("{} is too large for a token amount", self.0))?;
+        if amount == 0 {
+            bail!("Amount must be greater than zero");
+        }
+
+        Ok(amount)
+    }
+}
+
+impl FromStr for TokenAmount {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let amount = s.trim();
+        let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+
+        let is_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+        if amount.is_empty() || whole.is_empty() || !is_digits(whole) || !is_digits(fraction) {
+            bail!("Invalid token amount {amount}, expected a number such as 12.5");
+        }
+
+        Ok(TokenAmount(amount.to_string()))
+    }
+}
+
+/// `amount` base units shown with the mint's `decimals`, without trailing
+/// zeros.
+pub fn format_token_amount(amount: u64, decimals: u8) -> String {
+    let decimals = usize::from(decimals);
+    if decimals == 0 {
+        return amount.to_string();
+    }
+
+    let digits = format!("{amount:0>width$}", width = decimals + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - decimals);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{whole}.{fraction}")
+    }
+}
+
+/// Address of the associated token account of `owner` for `mint`.
+pub fn associated_token_address(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[owner.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &Pubkey::from_str_const(ASSOCIATED_TOKEN_PROGRAM_ID),
+    )
+    .0
+}
+
+/// Creates the associated token account of `owner` for `mint`, succeeding
+/// when it already exists.
+fn create_associated_token_account(
+    payer: &Pubkey,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    // 1 is the associated token program's CreateIdempotent instruction
+    Instruction::new_with_bytes(
+        Pubkey::from_str_const(ASSOCIATED_TOKEN_PROGRAM_ID),
+        &[1],
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(associated_token_address(owner, mint, token_program), false),
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(*token_program, false),
+        ],
+    )
+}
+
+/// A mint or token account, read through the base layout both token
+/// programs share.
+#[derive(Debug, PartialEq)]
+pub enum TokenState {
+    Mint(Mint),
+    Account(TokenAccount),
+}
+
+pub fn parse_token_state(data: &[u8]) -> anyhow::Result<TokenState> {
+    let account_type = match data.len() {
+        Mint::LEN => ACCOUNT_TYPE_MINT,
+        TokenAccount::LEN => ACCOUNT_TYPE_ACCOUNT,
+        len if len > ACCOUNT_TYPE_OFFSET => data[ACCOUNT_TYPE_OFFSET],
+        len => bail!("{len} bytes is neither a mint nor a token account"),
+    };
+
+    let state = match account_type {
+        ACCOUNT_TYPE_MINT => Mint::unpack_from_slice(&data[..Mint::LEN]).map(TokenState::Mint),
+        ACCOUNT_TYPE_ACCOUNT => {
+            TokenAccount::unpack_from_slice(&data[..TokenAccount::LEN]).map(TokenState::Account)
+        }
+        other => bail!("Unknown token account type {other}"),
+    };
+    state.map_err(|e| anyhow!("Failed to read token account: {e}"))
+}
+
+fn is_token_program(program: &Pubkey) -> bool {
+    *program == spl_token_interface::id()
+        || *program == Pubkey::from_str_const(TOKEN_2022_PROGRAM_ID)
+}
+
+fn token_program_name(program: &Pubkey) -> &'static str {
+    if *program == spl_token_interface::id() {
+        "Token"
+    } else {
+        "Token-2022"
+    }
+}
+
+/// Fetches `mint`, failing unless it's a mint of the token program, the one
+/// Scilla builds instructions for.
+async fn fetch_mint(ctx: &ScillaContext, mint: &Pubkey) -> anyhow::Result<Mint> {
+    let account = ctx.rpc().get_account(mint).await?;
+    if account.owner == Pubkey::from_str_const(TOKEN_2022_PROGRAM_ID) {
+        bail!("{mint} is a Token-2022 mint, only Token program mints are supported here");
+    }
+    if account.owner != spl_token_interface::id() {
+        bail!("{mint} is not a token mint");
+    }
+
+    match parse_token_state(&account.data)? {
+        TokenState::Mint(state) => Ok(state),
+        TokenState::Account(_) => bail!("{mint} is a token account, not a mint"),
+    }
+}
+
+/// A token balance as listed by the RPC's parsed token accounts.
+#[derive(Debug)]
+struct TokenBalance {
+    account: String,
+    mint: String,
+    amount: String,
+}
+
+fn parse_token_balance(keyed: &RpcKeyedAccount) -> Option<TokenBalance> {
+    let UiAccountData::Json(parsed) = &keyed.account.data else {
+        return None;
+    };
+    let info = parsed.parsed.get("info")?;
+
+    Some(TokenBalance {
+        account: keyed.pubkey.clone(),
+        mint: info.get("mint")?.as_str()?.to_string(),
+        amount: info
+            .get("tokenAmount")?
+            .get("uiAmountString")?
+            .as_str()?
+            .to_string(),
+    })
+}
+
+async fn fetch_token_balances(ctx: &ScillaContext, owner: &Pubkey) -> anyhow::Result<()> {
+    let mut table = PagedTable::new(
+        format!("TOKEN BALANCES OF {owner}"),
+        ["Mint", "Balance", "Token Account", "Program"],
+    );
+    let mut count = 0;
+
+    for program in [
+        spl_token_interface::id(),
+        Pubkey::from_str_const(TOKEN_2022_PROGRAM_ID),
+    ] {
+        let accounts = ctx
+            .rpc()
+            .get_token_accounts_by_owner(owner, TokenAccountsFilter::ProgramId(program))
+            .await?;
+
+        let mut balances: Vec<TokenBalance> =
+            accounts.iter().filter_map(parse_token_balance).collect();
+        balances.sort_by(|a, b| a.mint.cmp(&b.mint));
+        for balance in balances {
+            table.add_row([
+                balance.mint,
+                balance.amount,
+                balance.account,
+                token_program_name(&program).to_string(),
+            ]);
+            count += 1;
+        }
+    }
+
+    if count == 0 {
+        stdout().warning(format!("{owner} holds no token accounts."));
+        return Ok(());
+    }
+    table.show()
+}
+
+async fn transfer_tokens(
+    ctx: &ScillaContext,
+    mint: &Pubkey,
+    recipient: &Pubkey,
+    amount: &TokenAmount,
+) -> anyhow::Result<()> {
+    let mint_state = fetch_mint(ctx, mint).await?;
+    let amount = amount.to_base_units(mint_state.decimals)?;
+    let token_program = spl_token_interface::id();
+
+    let source = associated_token_address(ctx.pubkey(), mint, &token_program);
+    let source_account = ctx
+        .rpc()
+        .get_account(&source)
+        .await
+        .map_err(|_| anyhow!("{} has no token account for {mint}", ctx.pubkey()))?;
+    let TokenState::Account(source_state) = parse_token_state(&source_account.data)? else {
+        bail!("{source} is not a token account");
+    };
+    if source_state.amount < amount {
+        bail!(
+            "Insufficient token balance. You have {} but tried to send {}",
+            format_token_amount(source_state.amount, mint_state.decimals),
+            format_token_amount(amount, mint_state.decimals)
+        );
+    }
+
+    let destination = associated_token_address(recipient, mint, &token_program);
+    let mut instructions = Vec::new();
+    let destination_exists = ctx
+        .rpc()
+        .get_account_with_commitment(&destination, ctx.rpc().commitment())
+        .await?
+        .value
+        .is_some();
+    if !destination_exists {
+        instructions.push(create_associated_token_account(
+            ctx.pubkey(),
+            recipient,
+            mint,
+            &token_program,
+        ));
+    }
+    instructions.push(transfer_checked(
+        &token_program,
+        &source,
+        mint,
+        &destination,
+        ctx.pubkey(),
+        &[],
+        amount,
+        mint_state.decimals,
+    )?);
+
+    let signature = build_and_send_tx(ctx, &instructions, &[ctx.signer()]).await?;
+
+    let mut out = stdout();
+    out.success("Token transfer successful!");
+    out.line(format!(
+        "Amount: {}",
+        format_token_amount(amount, mint_state.decimals)
+    ));
+    if !destination_exists {
+        out.line(format!("Created recipient token account {destination}"));
+    }
+    out.note(format!("Signature: {signature}"));
+    out.note(format!("Recipient Address: {recipient}"));
+
+    Ok(())
+}
+
+async fn create_mint(
+    ctx: &ScillaContext,
+    decimals: u8,
+    freeze_authority: Option<&Pubkey>,
+) -> anyhow::Result<()> {
+    let mint_keypair = Keypair::new();
+    let mint = mint_keypair.pubkey();
+    let token_program = spl_token_interface::id();
+
+    let rent = ctx
+        .rpc()
+        .get_minimum_balance_for_rent_exemption(Mint::LEN)
+        .await?;
+    let instructions = [
+        create_account(ctx.pubkey(), &mint, rent, Mint::LEN as u64, &token_program),
+        initialize_mint2(
+            &token_program,
+            &mint,
+            ctx.pubkey(),
+            freeze_authority,
+            decimals,
+        )?,
+    ];
+
+    let signature = build_and_send_tx(ctx, &instructions, &[ctx.signer(), &mint_keypair]).await?;
+
+    let mut out = stdout();
+    out.success("Mint created successfully!");
+    out.note(format!("Signature: {signature}"));
+    out.line(format!("Mint address: {mint}"));
+
+    Ok(())
+}
+
+async fn mint_tokens(
+    ctx: &ScillaContext,
+    mint: &Pubkey,
+    recipient: &Pubkey,
+    amount: &TokenAmount,
+) -> anyhow::Result<()> {
+    let mint_state = fetch_mint(ctx, mint).await?;
+    let amount = amount.to_base_units(mint_state.decimals)?;
+    let token_program = spl_token_interface::id();
+
+    match Option::<Pubkey>::from(mint_state.mint_authority) {
+        Some(authority) if authority == *ctx.pubkey() => {}
+        Some(authority) => bail!("Mint authority of {mint} is {authority}, not your keypair"),
+        None => bail!("{mint} has a fixed supply, it has no mint authority"),
+    }
+
+    let destination = associated_token_address(recipient, mint, &token_program);
+    let instructions = [
+        create_associated_token_account(ctx.pubkey(), recipient, mint, &token_program),
+        mint_to_checked(
+            &token_program,
+            mint,
+            &destination,
+            ctx.pubkey(),
+            &[],
+            amount,
+            mint_state.decimals,
+        )?,
+    ];
+
+    let signature = build_and_send_tx(ctx, &instructions, &[ctx.signer()]).await?;
+
+    let mut out = stdout();
+    out.success("Tokens minted successfully!");
+    out.line(format!(
+        "Amount: {}",
+        format_token_amount(amount, mint_state.decimals)
+    ));
+    out.note(format!("Signature: {signature}"));
+    out.note(format!("Token account: {destination}"));
+
+    Ok(())
+}
+
+async fn show_token_account(ctx: &ScillaContext, pubkey: &Pubkey) -> anyhow::Result<()> {
+    let account = ctx.rpc().get_account(pubkey).await?;
+    if !is_token_program(&account.owner) {
+        bail!(
+            "{pubkey} is owned by {}, not a token program",
+            account.owner
+        );
+    }
+    let program = token_program_name(&account.owner);
+
+    let mut out = stdout();
+    match parse_token_state(&account.data)? {
+        TokenState::Mint(mint) => {
+            out.title("MINT INFO");
+            out.table(mint_table(pubkey, &mint, program));
+        }
+        TokenState::Account(token_account) => {
+            let mint = ctx.rpc().get_account(&token_account.mint).await?;
+            let TokenState::Mint(mint) = parse_token_state(&mint.data)? else {
+                bail!("{} is not a mint", token_account.mint);
+            };
+
+            out.title("TOKEN ACCOUNT INFO");
+            out.table(token_account_table(
+                pubkey,
+                &token_account,
+                mint.decimals,
+                program,
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn optional_pubkey(pubkey: Option<Pubkey>) -> String {
+    pubkey.map_or("None".to_string(), |pubkey| pubkey.to_string())
+}
+
+fn mint_table(pubkey: &Pubkey, mint: &Mint, program: &str) -> Table {
+    field_table([
+        ("Address", pubkey.to_string()),
+        ("Program", program.to_string()),
+        ("Supply", format_token_amount(mint.supply, mint.decimals)),
+        ("Decimals", mint.decimals.to_string()),
+        (
+            "Mint Authority",
+            optional_pubkey(mint.mint_authority.into()),
+        ),
+        (
+            "Freeze Authority",
+            optional_pubkey(mint.freeze_authority.into()),
+        ),
+    ])
+}
+
+fn token_account_table(
+    pubkey: &Pubkey,
+    account: &TokenAccount,
+    decimals: u8,
+    program: &str,
+) -> Table {
+    let state = match account.state {
+        AccountState::Uninitialized => "Uninitialized",
+        AccountState::Initialized => "Initialized",
+        AccountState::Frozen => "Frozen",
+    };
+
+    field_table([
+        ("Address", pubkey.to_string()),
+        ("Program", program.to_string()),
+        ("Mint", account.mint.to_string()),
+        ("Owner", account.owner.to_string()),
+        ("Balance", format_token_amount(account.amount, decimals)),
+        ("State", state.to_string()),
+        ("Delegate", optional_pubkey(account.delegate.into())),
+        (
+            "Delegated Amount",
+            format_token_amount(account.delegated_amount, decimals),
+        ),
+        (
+            "Close Authority",
+            optional_pubkey(account.close_authority.into()),
+        ),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::ui::render::capture, insta::assert_snapshot};
+
+    fn token_account() -> TokenAccount {
+        TokenAccount {
+            mint: Pubkey::new_from_array([2; 32]),
+            owner: Pubkey::new_from_array([3; 32]),
+            amount: 1_250_000,
+            state: AccountState::Initialized,
+            ..TokenAccount::default()
+        }
+    }
+
+    #[test]
+    fn test_token_amounts() -> anyhow::Result<()> {
+        assert_eq!("12.5".parse::<TokenAmount>()?.to_base_units(6)?, 12_500_000);
+        assert_eq!("3".parse::<TokenAmount>()?.to_base_units(0)?, 3);
+        assert!("1.2345".parse::<TokenAmount>()?.to_base_units(2).is_err());
+        assert!("0.0".parse::<TokenAmount>()?.to_base_units(2).is_err());
+        assert!("-1".parse::<TokenAmount>().is_err());
+        assert!(".5".parse::<TokenAmount>().is_err());
+
+        assert_eq!(format_token_amount(12_500_000, 6), "12.5");
+        assert_eq!(format_token_amount(5, 6), "0.000005");
+        assert_eq!(format_token_amount(7, 0), "7");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_token_state() -> anyhow::Result<()> {
+        let mut data = vec![0; TokenAccount::LEN];
+        token_account().pack_into_slice(&mut data);
+        assert_eq!(
+            parse_token_state(&data)?,
+            TokenState::Account(token_account())
+        );
+
+        // Token-2022 account with extensions after its account type
+        data.extend([ACCOUNT_TYPE_ACCOUNT, 0, 0, 0, 0]);
+        assert_eq!(
+            parse_token_state(&data)?,
+            TokenState::Account(token_account())
+        );
+
+        let mint = Mint {
+            supply: 1_000,
+            decimals: 2,
+            is_initialized: true,
+            ..Mint::default()
+        };
+        let mut data = vec![0; Mint::LEN];
+        mint.pack_into_slice(&mut data);
+        assert_eq!(parse_token_state(&data)?, TokenState::Mint(mint));
+
+        assert!(parse_token_state(&[0; 100]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_token_tables() {
+        let mint = Mint {
+            mint_authority: Some(Pubkey::new_from_array([1; 32])).into(),
+            supply: 1_000_000_000_000,
+            decimals: 6,
+            is_initialized: true,
+            freeze_authority: None.into(),
+        };
+
+        let output = capture(|out| {
+            out.table(mint_table(&Pubkey::new_from_array([2; 32]), &mint, "Token"));
+            out.table(token_account_table(
+                &Pubkey::new_from_array([4; 32]),
+                &token_account(),
+                6,
+                "Token",
+            ));
+        });
+
+        assert_snapshot!("token_tables", output);
+    }
+}
@@ -2,17 +2,48 @@ use {
     crate::{
         commands::CommandFlow,
         context::ScillaContext,
-        misc::helpers::{bincode_deserialize, decode_base58, decode_base64},
-        prompt::{prompt_input_data, prompt_select_data},
-        ui::show_spinner,
+        misc::{
+            broadcast::broadcast_and_confirm,
+            helpers::{
+                bincode_deserialize, decode_base58, decode_base64, lamports_to_sol, send_signed_tx,
+            },
+            offline::{
+                OfflineSigning, fetch_durable_nonce, missing_signers, offline_tx_table,
+                read_tx_file, sign_offline_tx, signers_table, verify_signatures, write_tx_file,
+            },
+            program_logs::write_program_logs,
+            signer::read_signer,
+            sns::domains_for_display,
+        },
+        prompt::{
+            prompt_confirmation, prompt_input_data, prompt_input_data_with_default,
+            prompt_keypair_path, prompt_pubkey, prompt_pubkey_with_default, prompt_select_data,
+        },
+        ui::{
+            new_table, print_error,
+            render::{Renderer, field_table, header_cell, stdout},
+            show_spinner,
+        },
     },
-    comfy_table::{Cell, Table, presets::UTF8_FULL},
-    console::style,
+    comfy_table::{Attribute, Cell, Color, Table},
+    serde_json::Value,
+    solana_keypair::Signer,
+    solana_pubkey::Pubkey,
     solana_rpc_client_api::config::RpcTransactionConfig,
     solana_signature::Signature,
-    solana_transaction::versioned::VersionedTransaction,
-    solana_transaction_status::{EncodedTransaction, UiMessage, UiTransactionEncoding},
-    std::fmt,
+    solana_transaction::{Transaction, versioned::VersionedTransaction},
+    solana_transaction_status::{
+        EncodedTransaction, TransactionConfirmationStatus, TransactionStatus, UiInnerInstructions,
+        UiInstruction, UiMessage, UiParsedInstruction, UiTransactionEncoding,
+        UiTransactionStatusMeta, option_serializer::OptionSerializer,
+        parse_accounts::ParsedAccount,
+    },
+    std::{
+        collections::HashMap,
+        fmt,
+        io::Write,
+        path::{Path, PathBuf},
+    },
 };
 
 #[derive(Debug, Clone)]
@@ -20,7 +51,11 @@ pub enum TransactionCommand {
     CheckConfirmation,
     FetchStatus,
     FetchTransaction,
+    Inspect,
     SendTransaction,
+    OfflineSigning,
+    SignOffline,
+    SubmitSigned,
     GoBack,
 }
 
@@ -30,7 +65,11 @@ impl TransactionCommand {
             Self::CheckConfirmation => "Checking transaction confirmation…",
             Self::FetchStatus => "Fetching transaction status…",
             Self::FetchTransaction => "Fetching full transaction data…",
+            Self::Inspect => "Fetching and decoding transaction…",
             Self::SendTransaction => "Sending transaction…",
+            Self::OfflineSigning => "Checking the nonce account…",
+            Self::SignOffline => "Signing transaction…",
+            Self::SubmitSigned => "Submitting signed transaction…",
             Self::GoBack => "Going back…",
         }
     }
@@ -42,14 +81,18 @@ impl fmt::Display for TransactionCommand {
             Self::CheckConfirmation => "Check Transaction Confirmation",
             Self::FetchStatus => "Fetch Transaction Status",
             Self::FetchTransaction => "Fetch Transaction",
+            Self::Inspect => "Inspect Transaction",
             Self::SendTransaction => "Send Transaction",
+            Self::OfflineSigning => "Offline Signing",
+            Self::SignOffline => "Sign Offline Transaction",
+            Self::SubmitSigned => "Submit Signed Transaction",
             Self::GoBack => "Go back",
         })
     }
 }
 
 impl TransactionCommand {
-    pub async fn process_command(&self, ctx: &ScillaContext) -> CommandFlow<()> {
+    pub async fn process_command(&self, ctx: &mut ScillaContext) -> CommandFlow<()> {
         match self {
             TransactionCommand::CheckConfirmation => {
                 let signature: Signature = prompt_input_data("Enter transaction signature:");
@@ -75,13 +118,16 @@ impl TransactionCommand {
                 )
                 .await;
             }
+            TransactionCommand::Inspect => {
+                let signature: Signature = prompt_input_data("Enter transaction signature:");
+                show_spinner(
+                    self.spinner_msg(),
+                    process_inspect_transaction(ctx, &signature),
+                )
+                .await;
+            }
             TransactionCommand::SendTransaction => {
-                println!(
-                    "{}",
-                    style("Note: Only VersionedTransaction format is supported")
-                        .yellow()
-                        .dim()
-                );
+                stdout().warning("Note: Only VersionedTransaction format is supported");
 
                 let encoding = prompt_select_data(
                     "Select encoding format:",
@@ -96,6 +142,56 @@ impl TransactionCommand {
                 )
                 .await;
             }
+            TransactionCommand::OfflineSigning => {
+                if let Some(offline) = ctx.offline_signing() {
+                    let signer = offline.signer;
+                    if prompt_confirmation(&format!("Offline signing for {signer} is on. Stop it?"))
+                    {
+                        ctx.stop_offline_signing();
+                        stdout().success(format!(
+                            "Offline signing stopped, signing with {} again",
+                            ctx.pubkey()
+                        ));
+                    }
+                    return CommandFlow::Process(());
+                }
+
+                let signer = prompt_pubkey(ctx, "Enter cold wallet address:").await;
+                let nonce_account = prompt_pubkey(ctx, "Enter nonce account address:").await;
+                let nonce_authority = prompt_pubkey_with_default(
+                    ctx,
+                    "Enter nonce authority address:",
+                    Some(&signer.to_string()),
+                )
+                .await;
+                let directory: PathBuf = prompt_input_data_with_default(
+                    "Directory to write unsigned transactions to:",
+                    Some("."),
+                );
+
+                let offline = OfflineSigning {
+                    signer,
+                    nonce_account,
+                    nonce_authority,
+                    directory,
+                };
+                if show_spinner(self.spinner_msg(), check_offline_signing(ctx, &offline))
+                    .await
+                    .is_some()
+                {
+                    ctx.start_offline_signing(offline);
+                }
+            }
+            TransactionCommand::SignOffline => {
+                let path: PathBuf = prompt_input_data("Enter transaction file:");
+                if let Err(e) = sign_offline(ctx, &path) {
+                    print_error(e);
+                }
+            }
+            TransactionCommand::SubmitSigned => {
+                let path: PathBuf = prompt_input_data("Enter signed transaction file:");
+                show_spinner(self.spinner_msg(), submit_signed_tx(ctx, &path)).await;
+            }
             TransactionCommand::GoBack => return CommandFlow::GoBack,
         }
 
@@ -109,32 +205,26 @@ async fn process_check_confirmation(
 ) -> anyhow::Result<()> {
     let confirmed = ctx.rpc().confirm_transaction(signature).await?;
 
-    let status_styled = if confirmed {
-        style("Confirmed").green()
-    } else {
-        style("Not Confirmed").yellow()
-    };
-
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
-        .add_row(vec![Cell::new("Signature"), Cell::new(signature)])
-        .add_row(vec![Cell::new("Status"), Cell::new(status_styled)]);
-
-    println!("\n{}", style("TRANSACTION CONFIRMATION").green().bold());
-    println!("{}", table);
+    let mut out = stdout();
+    out.title("TRANSACTION CONFIRMATION");
+    out.table(confirmation_table(signature, confirmed));
 
     Ok(())
 }
 
+fn confirmation_table(signature: &Signature, confirmed: bool) -> Table {
+    let mut table = field_table([("Signature", signature.to_string())]);
+    table.add_row(vec![
+        Cell::new("Status"),
+        if confirmed {
+            Cell::new("Confirmed").fg(Color::Green)
+        } else {
+            Cell::new("Not Confirmed").fg(Color::Yellow)
+        },
+    ]);
+    table
+}
+
 async fn process_fetch_transaction_status(
     ctx: &ScillaContext,
     signature: &Signature,
@@ -148,61 +238,51 @@ async fn process_fetch_transaction_status(
         anyhow::bail!("Transaction not found");
     };
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
-        .add_row(vec![Cell::new("Signature"), Cell::new(signature)])
-        .add_row(vec![Cell::new("Slot"), Cell::new(tx_status.slot)]);
+    let mut out = stdout();
+    out.title("TRANSACTION STATUS");
+    out.table(status_table(signature, tx_status));
+
+    Ok(())
+}
+
+fn status_table(signature: &Signature, tx_status: &TransactionStatus) -> Table {
+    let mut table = field_table([
+        ("Signature", signature.to_string()),
+        ("Slot", tx_status.slot.to_string()),
+    ]);
 
     if let Some(confirmations) = tx_status.confirmations {
         table.add_row(vec![Cell::new("Confirmations"), Cell::new(confirmations)]);
     } else {
         table.add_row(vec![
             Cell::new("Confirmations"),
-            Cell::new(style("Finalized").green()),
+            Cell::new("Finalized").fg(Color::Green),
         ]);
     }
 
     if let Some(confirmation_status) = &tx_status.confirmation_status {
         table.add_row(vec![
             Cell::new("Confirmation Status"),
-            Cell::new(match confirmation_status {
-                solana_transaction_status::TransactionConfirmationStatus::Processed => {
-                    style("Processed").yellow().to_string()
+            match confirmation_status {
+                TransactionConfirmationStatus::Processed => {
+                    Cell::new("Processed").fg(Color::Yellow)
                 }
-                solana_transaction_status::TransactionConfirmationStatus::Confirmed => {
-                    style("Confirmed").cyan().to_string()
-                }
-                solana_transaction_status::TransactionConfirmationStatus::Finalized => {
-                    style("Finalized").green().to_string()
-                }
-            }),
+                TransactionConfirmationStatus::Confirmed => Cell::new("Confirmed").fg(Color::Cyan),
+                TransactionConfirmationStatus::Finalized => Cell::new("Finalized").fg(Color::Green),
+            },
         ]);
     }
 
-    table.add_row(vec![
-        Cell::new("Status"),
-        Cell::new(if tx_status.err.is_none() {
-            style("Success").green().to_string()
-        } else {
-            style(format!("Error: {:?}", tx_status.err))
-                .red()
-                .to_string()
-        }),
-    ]);
-
-    println!("\n{}", style("TRANSACTION STATUS").green().bold());
-    println!("{}", table);
+    table.add_row(vec![Cell::new("Status"), result_cell(&tx_status.err)]);
+    table
+}
 
-    Ok(())
+fn result_cell(err: &Option<impl fmt::Debug>) -> Cell {
+    if err.is_none() {
+        Cell::new("Success").fg(Color::Green)
+    } else {
+        Cell::new(format!("Error: {err:?}")).fg(Color::Red)
+    }
 }
 
 async fn process_fetch_transaction(
@@ -221,19 +301,10 @@ async fn process_fetch_transaction(
         )
         .await?;
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
-        .add_row(vec![Cell::new("Signature"), Cell::new(signature)])
-        .add_row(vec![Cell::new("Slot"), Cell::new(tx.slot)]);
+    let mut table = field_table([
+        ("Signature", signature.to_string()),
+        ("Slot", tx.slot.to_string()),
+    ]);
 
     if let Some(block_time) = tx.block_time {
         table.add_row(vec![Cell::new("Block Time"), Cell::new(block_time)]);
@@ -241,18 +312,12 @@ async fn process_fetch_transaction(
 
     if let Some(meta) = &tx.transaction.meta {
         table.add_row(vec![Cell::new("Fee (lamports)"), Cell::new(meta.fee)]);
-        table.add_row(vec![
-            Cell::new("Status"),
-            Cell::new(if meta.err.is_none() {
-                style("Success").green().to_string()
-            } else {
-                style(format!("Error: {:?}", meta.err)).red().to_string()
-            }),
-        ]);
+        table.add_row(vec![Cell::new("Status"), result_cell(&meta.err)]);
     }
 
-    println!("\n{}", style("TRANSACTION DETAILS").green().bold());
-    println!("{}", table);
+    let mut out = stdout();
+    out.title("TRANSACTION DETAILS");
+    out.table(table);
 
     let EncodedTransaction::Json(ui_tx) = &tx.transaction.transaction else {
         anyhow::bail!("Transaction encoding is not JSON");
@@ -260,88 +325,313 @@ async fn process_fetch_transaction(
 
     match &ui_tx.message {
         UiMessage::Parsed(parsed_msg) => {
-            println!("\n{}", style("TRANSACTION MESSAGE").cyan().bold());
-
-            let mut msg_table = Table::new();
-            msg_table
-                .load_preset(UTF8_FULL)
-                .set_header(vec![
-                    Cell::new("Field")
-                        .add_attribute(comfy_table::Attribute::Bold)
-                        .fg(comfy_table::Color::Cyan),
-                    Cell::new("Value")
-                        .add_attribute(comfy_table::Attribute::Bold)
-                        .fg(comfy_table::Color::Cyan),
-                ])
-                .add_row(vec![
-                    Cell::new("Account Keys"),
-                    Cell::new(parsed_msg.account_keys.len()),
-                ])
-                .add_row(vec![
-                    Cell::new("Recent Blockhash"),
-                    Cell::new(&parsed_msg.recent_blockhash),
-                ]);
-
-            println!("{}", msg_table);
+            out.title("TRANSACTION MESSAGE");
+            out.table(message_table(
+                parsed_msg.account_keys.len(),
+                &parsed_msg.recent_blockhash,
+            ));
 
             if !parsed_msg.account_keys.is_empty() {
-                println!("\n{}", style("ACCOUNT KEYS").cyan().bold());
-                let mut accounts_table = Table::new();
-                accounts_table.load_preset(UTF8_FULL).set_header(vec![
-                    Cell::new("Index").add_attribute(comfy_table::Attribute::Bold),
-                    Cell::new("Pubkey").add_attribute(comfy_table::Attribute::Bold),
-                    Cell::new("Signer").add_attribute(comfy_table::Attribute::Bold),
-                    Cell::new("Writable").add_attribute(comfy_table::Attribute::Bold),
-                ]);
-
-                for (idx, account) in parsed_msg.account_keys.iter().enumerate() {
-                    accounts_table.add_row(vec![
-                        Cell::new(idx),
-                        Cell::new(&account.pubkey),
-                        Cell::new(if account.signer { "✓" } else { "" }),
-                        Cell::new(if account.writable { "✓" } else { "" }),
-                    ]);
-                }
-                println!("{}", accounts_table);
+                let addresses: Vec<Pubkey> = parsed_msg
+                    .account_keys
+                    .iter()
+                    .filter_map(|account| account.pubkey.parse().ok())
+                    .collect();
+                let domains = domains_for_display(ctx, &addresses).await;
+
+                out.title("ACCOUNT KEYS");
+                out.table(account_keys_table(&parsed_msg.account_keys, &domains));
             }
         }
         UiMessage::Raw(raw_msg) => {
-            println!("\n{}", style("TRANSACTION MESSAGE (Raw)").cyan().bold());
-
-            let mut msg_table = Table::new();
-            msg_table
-                .load_preset(UTF8_FULL)
-                .set_header(vec![
-                    Cell::new("Field")
-                        .add_attribute(comfy_table::Attribute::Bold)
-                        .fg(comfy_table::Color::Cyan),
-                    Cell::new("Value")
-                        .add_attribute(comfy_table::Attribute::Bold)
-                        .fg(comfy_table::Color::Cyan),
-                ])
-                .add_row(vec![
-                    Cell::new("Account Keys"),
-                    Cell::new(raw_msg.account_keys.len()),
-                ])
-                .add_row(vec![
-                    Cell::new("Recent Blockhash"),
-                    Cell::new(&raw_msg.recent_blockhash),
-                ]);
-
-            println!("{}", msg_table);
+            out.title("TRANSACTION MESSAGE (Raw)");
+            out.table(message_table(
+                raw_msg.account_keys.len(),
+                &raw_msg.recent_blockhash,
+            ));
 
             if !raw_msg.account_keys.is_empty() {
-                println!("\n{}", style("ACCOUNT KEYS").cyan().bold());
+                out.title("ACCOUNT KEYS");
                 for (idx, key) in raw_msg.account_keys.iter().enumerate() {
-                    println!("  {}. {}", idx, key);
+                    out.line(format!("  {}. {}", idx, key));
                 }
             }
         }
     }
 
+    if let Some(meta) = &tx.transaction.meta
+        && let OptionSerializer::Some(logs) = &meta.log_messages
+        && !logs.is_empty()
+    {
+        out.title("PROGRAM LOGS");
+        write_program_logs(&mut out, logs);
+    }
+
     Ok(())
 }
 
+fn message_table(account_keys: usize, recent_blockhash: &str) -> Table {
+    field_table([
+        ("Account Keys", account_keys.to_string()),
+        ("Recent Blockhash", recent_blockhash.to_string()),
+    ])
+}
+
+/// Account keys of a message, with a `Domain` column when any of them has a
+/// primary `.sol` domain in `domains`.
+fn account_keys_table(account_keys: &[ParsedAccount], domains: &HashMap<Pubkey, String>) -> Table {
+    let mut headers = vec!["Index", "Pubkey", "Signer", "Writable"];
+    if !domains.is_empty() {
+        headers.push("Domain");
+    }
+
+    let mut table = new_table();
+    table.set_header(
+        headers
+            .into_iter()
+            .map(|header| Cell::new(header).add_attribute(Attribute::Bold)),
+    );
+
+    for (idx, account) in account_keys.iter().enumerate() {
+        let mut row = vec![
+            Cell::new(idx),
+            Cell::new(&account.pubkey),
+            Cell::new(if account.signer { "✓" } else { "" }),
+            Cell::new(if account.writable { "✓" } else { "" }),
+        ];
+        if !domains.is_empty() {
+            let domain = account
+                .pubkey
+                .parse()
+                .ok()
+                .and_then(|pubkey: Pubkey| domains.get(&pubkey));
+            row.push(Cell::new(domain.map_or("", String::as_str)));
+        }
+        table.add_row(row);
+    }
+    table
+}
+
+async fn process_inspect_transaction(
+    ctx: &ScillaContext,
+    signature: &Signature,
+) -> anyhow::Result<()> {
+    let tx = ctx
+        .rpc()
+        .get_transaction_with_config(
+            signature,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::JsonParsed),
+                commitment: Some(ctx.rpc().commitment()),
+                max_supported_transaction_version: Some(0),
+            },
+        )
+        .await?;
+
+    let Some(meta) = &tx.transaction.meta else {
+        anyhow::bail!("The RPC node returned {signature} without its status metadata");
+    };
+    let EncodedTransaction::Json(ui_tx) = &tx.transaction.transaction else {
+        anyhow::bail!("Transaction encoding is not JSON");
+    };
+    let UiMessage::Parsed(message) = &ui_tx.message else {
+        anyhow::bail!("The RPC node did not return a parsed transaction message");
+    };
+
+    let mut out = stdout();
+    out.title("TRANSACTION SUMMARY");
+    out.table(inspect_summary_table(
+        signature,
+        tx.slot,
+        tx.block_time,
+        meta,
+    ));
+
+    let account_keys: Vec<&str> = message
+        .account_keys
+        .iter()
+        .map(|account| account.pubkey.as_str())
+        .collect();
+    out.title("BALANCE CHANGES");
+    out.table(balance_changes_table(
+        &account_keys,
+        &meta.pre_balances,
+        &meta.post_balances,
+    ));
+
+    let inner_instructions: &[UiInnerInstructions] = match &meta.inner_instructions {
+        OptionSerializer::Some(inner) => inner.as_slice(),
+        _ => &[],
+    };
+    out.title("INSTRUCTIONS");
+    out.table(decoded_instructions_table(
+        &message.instructions,
+        inner_instructions,
+        &account_keys,
+    ));
+
+    if let OptionSerializer::Some(logs) = &meta.log_messages
+        && !logs.is_empty()
+    {
+        out.title("PROGRAM LOGS");
+        write_program_logs(&mut out, logs);
+    }
+    if let Some(err) = &meta.err {
+        out.error(format!("Transaction failed: {err:?}"));
+    }
+
+    Ok(())
+}
+
+fn inspect_summary_table(
+    signature: &Signature,
+    slot: u64,
+    block_time: Option<i64>,
+    meta: &UiTransactionStatusMeta,
+) -> Table {
+    let block_time = block_time
+        .and_then(chrono::DateTime::<chrono::Utc>::from_timestamp_secs)
+        .map_or("Unknown".to_string(), |dt| {
+            dt.format("%Y-%m-%d %H:%M:%S UTC").to_string()
+        });
+    let compute_units = match meta.compute_units_consumed {
+        OptionSerializer::Some(units) => units.to_string(),
+        _ => "Unknown".to_string(),
+    };
+
+    let mut table = field_table([
+        ("Signature", signature.to_string()),
+        ("Slot", slot.to_string()),
+        ("Block Time", block_time),
+        ("Fee (SOL)", lamports_to_sol(meta.fee).to_string()),
+        ("Compute Units", compute_units),
+    ]);
+    table.add_row(vec![Cell::new("Status"), result_cell(&meta.err)]);
+    table
+}
+
+/// SOL balance of every account the transaction changed, before and after.
+pub fn balance_changes_table(
+    account_keys: &[&str],
+    pre_balances: &[u64],
+    post_balances: &[u64],
+) -> Table {
+    let mut table = new_table();
+    table.set_header(["Account", "Before (SOL)", "After (SOL)", "Change (SOL)"].map(header_cell));
+
+    for ((account, pre), post) in account_keys.iter().zip(pre_balances).zip(post_balances) {
+        if pre == post {
+            continue;
+        }
+        let change = lamports_to_sol(pre.abs_diff(*post));
+        let change = if post > pre {
+            Cell::new(format!("+{change}")).fg(Color::Green)
+        } else {
+            Cell::new(format!("-{change}")).fg(Color::Red)
+        };
+        table.add_row(vec![
+            Cell::new(account),
+            Cell::new(lamports_to_sol(*pre)),
+            Cell::new(lamports_to_sol(*post)),
+            change,
+        ]);
+    }
+    table
+}
+
+/// Program, instruction name and arguments of an instruction, as decoded by
+/// the RPC node for the programs it knows (system, stake, vote, memo,
+/// upgradeable loader, tokens…).
+fn decode_instruction(
+    instruction: &UiInstruction,
+    account_keys: &[&str],
+) -> (String, String, Vec<String>) {
+    let raw = |program: String, accounts: Vec<String>, data: &str| {
+        let data_len = decode_base58(data).map_or(0, |data| data.len());
+        let mut details: Vec<String> = accounts
+            .into_iter()
+            .enumerate()
+            .map(|(idx, account)| format!("account {idx}: {account}"))
+            .collect();
+        details.push(format!("data: {data_len} bytes"));
+        (program, "Unknown".to_string(), details)
+    };
+    let key = |index: u8| {
+        account_keys
+            .get(index as usize)
+            .map_or("?".to_string(), ToString::to_string)
+    };
+
+    match instruction {
+        UiInstruction::Parsed(UiParsedInstruction::Parsed(parsed)) => {
+            let (kind, details) = match &parsed.parsed {
+                // Memos are parsed into their text
+                Value::String(memo) => ("memo".to_string(), vec![memo.clone()]),
+                value => {
+                    let kind = value["type"].as_str().unwrap_or("Unknown").to_string();
+                    let details = match &value["info"] {
+                        Value::Object(info) => info
+                            .iter()
+                            .map(|(field, value)| match value {
+                                Value::String(value) => format!("{field}: {value}"),
+                                value => format!("{field}: {value}"),
+                            })
+                            .collect(),
+                        Value::Null => Vec::new(),
+                        info => vec![info.to_string()],
+                    };
+                    (kind, details)
+                }
+            };
+            (parsed.program.clone(), kind, details)
+        }
+        UiInstruction::Parsed(UiParsedInstruction::PartiallyDecoded(decoded)) => raw(
+            decoded.program_id.clone(),
+            decoded.accounts.clone(),
+            &decoded.data,
+        ),
+        UiInstruction::Compiled(compiled) => raw(
+            key(compiled.program_id_index),
+            compiled.accounts.iter().map(|index| key(*index)).collect(),
+            &compiled.data,
+        ),
+    }
+}
+
+/// Every instruction of a confirmed transaction, with the inner instructions
+/// it invoked numbered under it.
+fn decoded_instructions_table(
+    instructions: &[UiInstruction],
+    inner_instructions: &[UiInnerInstructions],
+    account_keys: &[&str],
+) -> Table {
+    let mut table = new_table();
+    table.set_header(["#", "Program", "Instruction", "Details"].map(header_cell));
+
+    let mut add_row = |number: String, instruction: &UiInstruction| {
+        let (program, kind, details) = decode_instruction(instruction, account_keys);
+        table.add_row(vec![
+            Cell::new(number),
+            Cell::new(program),
+            Cell::new(kind),
+            Cell::new(details.join("\n")),
+        ]);
+    };
+
+    for (idx, instruction) in instructions.iter().enumerate() {
+        add_row((idx + 1).to_string(), instruction);
+
+        let inner = inner_instructions
+            .iter()
+            .filter(|inner| usize::from(inner.index) == idx)
+            .flat_map(|inner| &inner.instructions);
+        for (inner_idx, instruction) in inner.enumerate() {
+            add_row(format!("{}.{}", idx + 1, inner_idx + 1), instruction);
+        }
+    }
+    table
+}
+
 async fn process_send_transaction(
     ctx: &ScillaContext,
     encoding: UiTransactionEncoding,
@@ -356,13 +646,263 @@ async fn process_send_transaction(
     let tx: VersionedTransaction =
         bincode_deserialize(&tx_bytes, "encoded transaction to VersionedTransaction")?;
 
-    let signature = ctx.rpc().send_transaction(&tx).await?;
+    let signature = if ctx.broadcast_enabled() {
+        broadcast_and_confirm(ctx, &tx).await?
+    } else {
+        ctx.rpc().send_transaction(&tx).await?
+    };
+
+    let mut out = stdout();
+    out.success("Transaction sent successfully!");
+    out.note(format!("Signature: {signature}"));
+
+    Ok(())
+}
+
+/// Checks the nonce account of an offline signing session before starting
+/// it.
+async fn check_offline_signing(
+    ctx: &ScillaContext,
+    offline: &OfflineSigning,
+) -> anyhow::Result<()> {
+    if !offline.directory.is_dir() {
+        anyhow::bail!("{} is not a directory", offline.directory.display());
+    }
 
-    println!(
-        "{} {}",
-        style("Transaction sent successfully!").green().bold(),
-        style(signature).cyan()
+    let nonce = fetch_durable_nonce(ctx, &offline.nonce_account).await?;
+    if nonce.authority != offline.nonce_authority {
+        anyhow::bail!(
+            "{} is not the authority of nonce account {}, {} is",
+            offline.nonce_authority,
+            offline.nonce_account,
+            nonce.authority
+        );
+    }
+
+    let mut out = stdout();
+    out.title("OFFLINE SIGNING");
+    out.table(field_table([
+        ("Cold Wallet", offline.signer.to_string()),
+        ("Nonce Account", offline.nonce_account.to_string()),
+        ("Nonce Authority", offline.nonce_authority.to_string()),
+        ("Durable Nonce", nonce.blockhash().to_string()),
+        ("Directory", offline.directory.display().to_string()),
+    ]));
+    out.warning(
+        "Transfers, stake delegation, deactivation and withdrawals and vote account withdrawals \
+         are now written out unsigned instead of sent. Other commands that sign refuse to run \
+         until offline signing is stopped.",
     );
 
     Ok(())
 }
+
+/// Shows what a transaction file does and adds a signature to it, without
+/// touching the network.
+fn sign_offline(ctx: &ScillaContext, path: &Path) -> anyhow::Result<()> {
+    let mut tx = read_tx_file(path)?;
+    write_offline_tx(&mut stdout(), &tx);
+
+    if missing_signers(&tx).is_empty() {
+        stdout().success("Transaction is already fully signed");
+        return Ok(());
+    }
+
+    let keypair_path = prompt_keypair_path("Enter signer keypair path:", ctx);
+    let signer = read_signer(&keypair_path)?;
+    if !prompt_confirmation("Sign this transaction?") {
+        stdout().warning("Signing cancelled.");
+        return Ok(());
+    }
+
+    sign_offline_tx(&mut tx, &*signer)?;
+    write_tx_file(path, &tx)?;
+
+    let mut out = stdout();
+    out.success(format!("Signed by {}", signer.pubkey()));
+    match missing_signers(&tx).len() {
+        0 => out.note("Submit it with Transaction > Submit Signed Transaction"),
+        missing => out.warning(format!("{missing} more signature(s) needed")),
+    }
+
+    Ok(())
+}
+
+async fn submit_signed_tx(ctx: &ScillaContext, path: &Path) -> anyhow::Result<()> {
+    let tx = read_tx_file(path)?;
+    verify_signatures(&tx)?;
+
+    let signature = send_signed_tx(ctx, &tx).await?;
+
+    let mut out = stdout();
+    out.success("Transaction sent successfully!");
+    out.note(format!("Signature: {signature}"));
+
+    Ok(())
+}
+
+fn write_offline_tx<W: Write>(out: &mut Renderer<W>, tx: &Transaction) {
+    out.title("OFFLINE TRANSACTION");
+    out.table(offline_tx_table(tx));
+    out.title("INSTRUCTIONS");
+    out.table(instructions_table(tx));
+    out.title("SIGNERS");
+    out.table(signers_table(tx));
+}
+
+/// Program, accounts and data size of every instruction in `tx`.
+fn instructions_table(tx: &Transaction) -> Table {
+    let mut table = new_table();
+    table.set_header(vec![
+        header_cell("#"),
+        header_cell("Program"),
+        header_cell("Accounts"),
+        header_cell("Data"),
+    ]);
+
+    let keys = &tx.message.account_keys;
+    let key = |index: u8| {
+        keys.get(index as usize)
+            .map_or("?".to_string(), ToString::to_string)
+    };
+    for (idx, instruction) in tx.message.instructions.iter().enumerate() {
+        let accounts: Vec<String> = instruction
+            .accounts
+            .iter()
+            .map(|index| key(*index))
+            .collect();
+        table.add_row(vec![
+            Cell::new(idx + 1),
+            Cell::new(key(instruction.program_id_index)),
+            Cell::new(accounts.join("\n")),
+            Cell::new(format!("{} bytes", instruction.data.len())),
+        ]);
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::ui::render::capture, insta::assert_snapshot, solana_pubkey::Pubkey};
+
+    #[test]
+    fn test_confirmation_table() {
+        let signature = Signature::from([1; 64]);
+
+        let output = capture(|out| {
+            out.table(confirmation_table(&signature, true));
+            out.table(confirmation_table(&signature, false));
+        });
+
+        assert_snapshot!("confirmation_table", output);
+    }
+
+    #[test]
+    fn test_status_table() {
+        let status = TransactionStatus {
+            slot: 312_000_000,
+            confirmations: Some(12),
+            status: Ok(()),
+            err: None,
+            confirmation_status: Some(TransactionConfirmationStatus::Confirmed),
+        };
+
+        let output = capture(|out| out.table(status_table(&Signature::from([1; 64]), &status)));
+
+        assert_snapshot!("status_table", output);
+    }
+
+    #[test]
+    fn test_message_and_account_keys_tables() {
+        let account_keys = [
+            ParsedAccount {
+                pubkey: Pubkey::new_from_array([1; 32]).to_string(),
+                writable: true,
+                signer: true,
+                source: None,
+            },
+            ParsedAccount {
+                pubkey: Pubkey::new_from_array([2; 32]).to_string(),
+                writable: true,
+                signer: false,
+                source: None,
+            },
+            ParsedAccount {
+                pubkey: Pubkey::new_from_array([3; 32]).to_string(),
+                writable: false,
+                signer: false,
+                source: None,
+            },
+        ];
+
+        let output = capture(|out| {
+            out.title("TRANSACTION MESSAGE");
+            out.table(message_table(
+                account_keys.len(),
+                &Pubkey::new_from_array([3; 32]).to_string(),
+            ));
+            out.title("ACCOUNT KEYS");
+            out.table(account_keys_table(&account_keys, &HashMap::new()));
+        });
+
+        assert_snapshot!("message_and_account_keys_tables", output);
+    }
+
+    #[test]
+    fn test_inspect_tables() -> anyhow::Result<()> {
+        let key = |seed| Pubkey::new_from_array([seed; 32]).to_string();
+        let account_keys = [key(1), key(2), key(3)];
+        let account_keys: Vec<&str> = account_keys.iter().map(String::as_str).collect();
+
+        let instructions: Vec<UiInstruction> = serde_json::from_value(serde_json::json!([
+            {
+                "program": "system",
+                "programId": "11111111111111111111111111111111",
+                "parsed": {
+                    "type": "transfer",
+                    "info": { "destination": key(2), "lamports": 1_000_000, "source": key(1) },
+                },
+                "stackHeight": null,
+            },
+            {
+                "program": "spl-memo",
+                "programId": "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr",
+                "parsed": "order 42",
+                "stackHeight": null,
+            },
+            {
+                "programId": key(3),
+                "accounts": [key(1)],
+                "data": bs58::encode([1, 2, 3, 4]).into_string(),
+                "stackHeight": null,
+            },
+        ]))?;
+        let inner_instructions: Vec<UiInnerInstructions> =
+            serde_json::from_value(serde_json::json!([{
+                "index": 2,
+                "instructions": [{
+                    "program": "system",
+                    "programId": "11111111111111111111111111111111",
+                    "parsed": { "type": "allocate", "info": { "account": key(4), "space": 100 } },
+                    "stackHeight": 2,
+                }],
+            }]))?;
+
+        let output = capture(|out| {
+            out.table(balance_changes_table(
+                &account_keys,
+                &[5_000_000_000, 0, 1],
+                &[4_998_995_000, 1_000_000, 1],
+            ));
+            out.table(decoded_instructions_table(
+                &instructions,
+                &inner_instructions,
+                &account_keys,
+            ));
+        });
+
+        assert_snapshot!("inspect_tables", output);
+
+        Ok(())
+    }
+}
@@ -2,24 +2,42 @@ use {
     crate::{
         ScillaContext,
         commands::CommandFlow,
-        misc::helpers::{
-            Commission, SolAmount, build_and_send_tx, fetch_account_with_epoch, lamports_to_sol,
-            read_keypair_from_path,
+        constants::DEFAULT_MS_PER_SLOT,
+        misc::{
+            helpers::{
+                Commission, SolAmount, build_and_send_or_export_tx, build_and_send_tx,
+                fetch_account_with_epoch, lamports_to_sol, read_keypair_from_path,
+            },
+            signer::read_signer,
+            whitelist::confirm_destination,
+        },
+        prompt::{
+            prompt_confirmation, prompt_input_data, prompt_keypair_path, prompt_pubkey,
+            prompt_refresh_interval, prompt_withdraw_authority_path,
+        },
+        ui::{
+            render::{field_table, stdout},
+            show_or_watch, show_spinner,
         },
-        prompt::{prompt_confirmation, prompt_input_data, prompt_keypair_path},
-        ui::show_spinner,
     },
     anyhow::{anyhow, bail},
-    comfy_table::{Cell, Table, presets::UTF8_FULL},
-    console::style,
+    comfy_table::Table,
+    solana_epoch_info::EpochInfo,
     solana_keypair::Signer,
     solana_pubkey::Pubkey,
     solana_rpc_client_api::config::RpcGetVoteAccountsConfig,
     solana_vote_interface::{
-        instruction::{CreateVoteAccountConfig, authorize, create_account_with_config, withdraw},
+        instruction::{
+            CreateVoteAccountConfig, authorize, authorize_checked, create_account_with_config,
+            withdraw,
+        },
         state::{VoteAuthorize, VoteInit, VoteStateV4},
     },
-    std::{fmt, path::PathBuf},
+    std::{
+        fmt,
+        path::{Path, PathBuf},
+        time::Duration,
+    },
 };
 
 /// Commands related to validator/vote account operations
@@ -27,6 +45,7 @@ use {
 pub enum VoteCommand {
     CreateVoteAccount,
     AuthorizeVoter,
+    RotateVoter,
     WithdrawFromVoteAccount,
     ShowVoteAccount,
     CloseVoteAccount,
@@ -38,6 +57,7 @@ impl VoteCommand {
         match self {
             VoteCommand::CreateVoteAccount => "Creating vote account…",
             VoteCommand::AuthorizeVoter => "Authorizing voter…",
+            VoteCommand::RotateVoter => "Rotating authorized voter…",
             VoteCommand::WithdrawFromVoteAccount => "Withdrawing SOL from vote account…",
             VoteCommand::ShowVoteAccount => "Fetching vote account details…",
             VoteCommand::CloseVoteAccount => "Closing vote account…",
@@ -51,6 +71,7 @@ impl fmt::Display for VoteCommand {
         let text = match self {
             VoteCommand::CreateVoteAccount => "Create vote account",
             VoteCommand::AuthorizeVoter => "Authorize voter",
+            VoteCommand::RotateVoter => "Rotate voter key",
             VoteCommand::WithdrawFromVoteAccount => "Withdraw from vote account",
             VoteCommand::ShowVoteAccount => "Show vote account",
             VoteCommand::CloseVoteAccount => "Close vote account",
@@ -69,7 +90,7 @@ impl VoteCommand {
                 let identity_keypair_path =
                     prompt_keypair_path("Enter Identity Keypair Path:", ctx);
                 let withdraw_keypair_path =
-                    prompt_keypair_path("Enter Withdraw Keypair Path:", ctx);
+                    prompt_withdraw_authority_path("Enter Withdraw Keypair Path:", ctx);
                 let commission: Commission =
                     prompt_input_data("Enter Commission 0-100 (default 0):");
 
@@ -86,11 +107,11 @@ impl VoteCommand {
                 .await;
             }
             VoteCommand::AuthorizeVoter => {
-                let vote_account_pubkey: Pubkey = prompt_input_data("Enter Vote Account Address:");
+                let vote_account_pubkey = prompt_pubkey(ctx, "Enter Vote Account Address:").await;
                 let authorized_keypair_path =
                     prompt_keypair_path("Enter Authorized Keypair Path:", ctx);
-                let new_authorized_pubkey: Pubkey =
-                    prompt_input_data("Enter New Authorized Address:");
+                let new_authorized_pubkey =
+                    prompt_pubkey(ctx, "Enter New Authorized Address:").await;
 
                 show_spinner(
                     self.spinner_msg(),
@@ -103,13 +124,36 @@ impl VoteCommand {
                 )
                 .await;
             }
+            VoteCommand::RotateVoter => {
+                let vote_account_pubkey = prompt_pubkey(ctx, "Enter Vote Account Address:").await;
+                let authorized_keypair_path = prompt_keypair_path(
+                    "Enter Current Voter or Withdraw Authority Keypair Path:",
+                    ctx,
+                );
+                let new_voter_keypair_path =
+                    prompt_keypair_path("Enter New Voter Keypair Path:", ctx);
+
+                show_spinner(
+                    self.spinner_msg(),
+                    process_rotate_voter(
+                        ctx,
+                        &vote_account_pubkey,
+                        &authorized_keypair_path,
+                        &new_voter_keypair_path,
+                    ),
+                )
+                .await;
+            }
             VoteCommand::WithdrawFromVoteAccount => {
-                let vote_account_pubkey: Pubkey = prompt_input_data("Enter Vote Account Address:");
+                let vote_account_pubkey = prompt_pubkey(ctx, "Enter Vote Account Address:").await;
                 let authorized_withdrawer_keypair_path =
-                    prompt_keypair_path("Enter Authorized Withdraw Keypair Path:", ctx);
-                let recipient_address: Pubkey = prompt_input_data("Enter Recipient Address:");
+                    prompt_withdraw_authority_path("Enter Authorized Withdraw Keypair Path:", ctx);
+                let recipient_address = prompt_pubkey(ctx, "Enter Recipient Address:").await;
 
                 let amount: SolAmount = prompt_input_data("Enter withdraw amount in SOL:");
+                if !confirm_destination(ctx, &recipient_address) {
+                    return CommandFlow::Process(());
+                }
 
                 show_spinner(
                     self.spinner_msg(),
@@ -124,21 +168,24 @@ impl VoteCommand {
                 .await;
             }
             VoteCommand::ShowVoteAccount => {
-                let vote_account_pubkey: Pubkey = prompt_input_data("Enter Vote Account Address:");
-                show_spinner(
-                    self.spinner_msg(),
-                    process_fetch_vote_account(ctx, &vote_account_pubkey),
-                )
+                let vote_account_pubkey = prompt_pubkey(ctx, "Enter Vote Account Address:").await;
+                let refresh_secs = prompt_refresh_interval();
+                show_or_watch(self.spinner_msg(), refresh_secs, || {
+                    process_fetch_vote_account(ctx, &vote_account_pubkey)
+                })
                 .await;
             }
             VoteCommand::CloseVoteAccount => {
-                let vote_account_pubkey: Pubkey = prompt_input_data("Enter Vote Account Address:");
+                let vote_account_pubkey = prompt_pubkey(ctx, "Enter Vote Account Address:").await;
                 let withdraw_authority_keypair_path =
-                    prompt_keypair_path("Enter Withdraw Authority Keypair Path:", ctx);
-                let destination_pubkey: Pubkey = prompt_input_data("Enter Destination Address:");
+                    prompt_withdraw_authority_path("Enter Withdraw Authority Keypair Path:", ctx);
+                let destination_pubkey = prompt_pubkey(ctx, "Enter Destination Address:").await;
+                if !confirm_destination(ctx, &destination_pubkey) {
+                    return CommandFlow::Process(());
+                }
 
                 if !prompt_confirmation("Are you sure you want to close this vote account?") {
-                    println!("{}", style("Close vote account cancelled.").yellow());
+                    stdout().warning("Close vote account cancelled.");
                     return CommandFlow::Process(());
                 }
 
@@ -220,20 +267,14 @@ async fn process_create_vote_account(
     let signature = build_and_send_tx(
         ctx,
         &instructions,
-        &[ctx.keypair(), &vote_account_keypair, &identity_keypair],
+        &[ctx.signer(), &vote_account_keypair, &identity_keypair],
     )
     .await?;
 
-    println!(
-        "{} {}",
-        style("Vote account created successfully!").green().bold(),
-        style(format!("Signature: {signature}")).cyan()
-    );
-    println!(
-        "{} {}",
-        style("Vote account address:").green(),
-        style(vote_account_pubkey).cyan()
-    );
+    let mut out = stdout();
+    out.success("Vote account created successfully!");
+    out.note(format!("Signature: {signature}"));
+    out.line(format!("Vote account address: {vote_account_pubkey}"));
 
     Ok(())
 }
@@ -241,10 +282,10 @@ async fn process_create_vote_account(
 async fn process_authorize_voter(
     ctx: &ScillaContext,
     vote_account_pubkey: &Pubkey,
-    authorized_keypair_path: &PathBuf,
+    authorized_keypair_path: &Path,
     new_authorized_pubkey: &Pubkey,
 ) -> anyhow::Result<()> {
-    let authorized = read_keypair_from_path(authorized_keypair_path)?;
+    let authorized = read_signer(authorized_keypair_path)?;
     let authorized_pubkey = authorized.pubkey();
 
     let (vote_account, epoch_info) = fetch_account_with_epoch(ctx, vote_account_pubkey).await?;
@@ -281,13 +322,159 @@ async fn process_authorize_voter(
         VoteAuthorize::Voter,
     );
 
-    let signature = build_and_send_tx(ctx, &[vote_ix], &[ctx.keypair(), &authorized]).await?;
+    let signature = build_and_send_tx(ctx, &[vote_ix], &[ctx.signer(), &*authorized]).await?;
+
+    stdout().success(format!("Signature: {signature}"));
+
+    Ok(())
+}
+
+/// Checks a voter rotation to `new_voter`, signed by `signer`, can be
+/// scheduled for the next epoch and returns the voter it replaces.
+///
+/// A new voter only takes over at the start of the next epoch, and only one
+/// change may be pending at a time.
+fn check_voter_rotation(
+    vote_state: &VoteStateV4,
+    current_epoch: u64,
+    signer: &Pubkey,
+    new_voter: &Pubkey,
+) -> anyhow::Result<Pubkey> {
+    let current_voter = vote_state
+        .authorized_voters
+        .get_authorized_voter(current_epoch)
+        .ok_or_else(|| anyhow!("Invalid vote account state; no authorized voters found"))?;
+
+    if signer != &current_voter && signer != &vote_state.authorized_withdrawer {
+        bail!(
+            "Keypair {signer} is not the current authorized voter ({current_voter}) or withdrawer \
+             ({})",
+            vote_state.authorized_withdrawer
+        );
+    }
+    if let Some((epoch, pending)) = vote_state.authorized_voters.last()
+        && *epoch > current_epoch
+    {
+        bail!(
+            "A rotation to {pending} is already scheduled for epoch {epoch}, wait for it to take \
+             effect before rotating again"
+        );
+    }
+    if new_voter == &current_voter {
+        bail!("{new_voter} is already the authorized voter");
+    }
+
+    Ok(current_voter)
+}
+
+/// Rough time left until the next epoch starts, at the target slot time.
+fn time_to_next_epoch(epoch_info: &EpochInfo) -> Duration {
+    let slots_left = epoch_info
+        .slots_in_epoch
+        .saturating_sub(epoch_info.slot_index);
+    Duration::from_millis(slots_left * DEFAULT_MS_PER_SLOT)
+}
+
+fn voter_rotation_table(
+    current_voter: &Pubkey,
+    new_voter: &Pubkey,
+    epoch_info: &EpochInfo,
+) -> Table {
+    let secs = time_to_next_epoch(epoch_info).as_secs();
+
+    field_table([
+        (
+            "Current Voter",
+            format!("{current_voter} (through epoch {})", epoch_info.epoch),
+        ),
+        (
+            "New Voter",
+            format!("{new_voter} (from epoch {})", epoch_info.epoch + 1),
+        ),
+        (
+            "Switch In",
+            format!(
+                "~{}h {:02}m ({} slots)",
+                secs / 3600,
+                secs % 3600 / 60,
+                epoch_info
+                    .slots_in_epoch
+                    .saturating_sub(epoch_info.slot_index)
+            ),
+        ),
+    ])
+}
+
+/// Schedules the authorized voter of a vote account to switch to a new
+/// keypair at the next epoch, then reads the account back to confirm it.
+async fn process_rotate_voter(
+    ctx: &ScillaContext,
+    vote_account_pubkey: &Pubkey,
+    authorized_keypair_path: &Path,
+    new_voter_keypair_path: &Path,
+) -> anyhow::Result<()> {
+    let authorized = read_signer(authorized_keypair_path)?;
+    let new_voter = read_signer(new_voter_keypair_path)?;
+    let new_voter_pubkey = new_voter.pubkey();
+
+    let (vote_account, epoch_info) = fetch_account_with_epoch(ctx, vote_account_pubkey).await?;
+    if vote_account.owner != solana_vote_interface::program::id() {
+        bail!("{vote_account_pubkey} is not a vote account");
+    }
+    let vote_state = VoteStateV4::deserialize(&vote_account.data, vote_account_pubkey)
+        .map_err(|_| anyhow!("Account data could not be deserialized to vote state"))?;
+
+    let current_voter = check_voter_rotation(
+        &vote_state,
+        epoch_info.epoch,
+        &authorized.pubkey(),
+        &new_voter_pubkey,
+    )?;
+
+    let mut out = stdout();
+    out.title("VOTER ROTATION");
+    out.table(voter_rotation_table(
+        &current_voter,
+        &new_voter_pubkey,
+        &epoch_info,
+    ));
+    if !prompt_confirmation("Schedule this voter rotation?") {
+        out.warning("Voter rotation cancelled.");
+        return Ok(());
+    }
 
-    println!(
-        "{} {}",
-        style("Signature:").green().bold(),
-        style(signature).cyan()
+    // The checked variant has the new voter sign, proving its keypair works
+    let vote_ix = authorize_checked(
+        vote_account_pubkey,
+        &authorized.pubkey(),
+        &new_voter_pubkey,
+        VoteAuthorize::Voter,
     );
+    let signature =
+        build_and_send_tx(ctx, &[vote_ix], &[ctx.signer(), &*authorized, &*new_voter]).await?;
+    out.note(format!("Signature: {signature}"));
+
+    let vote_account = ctx.rpc().get_account(vote_account_pubkey).await?;
+    let vote_state = VoteStateV4::deserialize(&vote_account.data, vote_account_pubkey)
+        .map_err(|_| anyhow!("Account data could not be deserialized to vote state"))?;
+    let scheduled = vote_state
+        .authorized_voters
+        .get_authorized_voter(epoch_info.epoch + 1);
+    if scheduled != Some(new_voter_pubkey) {
+        bail!(
+            "Vote account does not list {new_voter_pubkey} as the voter for epoch {}",
+            epoch_info.epoch + 1
+        );
+    }
+
+    out.success(format!(
+        "{new_voter_pubkey} takes over voting at the start of epoch {}",
+        epoch_info.epoch + 1
+    ));
+    out.warning(format!(
+        "Add {new_voter_pubkey} to the validator's authorized voters before then, and keep \
+         {current_voter} until the epoch has turned, or votes will fail"
+    ));
 
     Ok(())
 }
@@ -295,11 +482,11 @@ async fn process_authorize_voter(
 async fn process_sol_withdraw_from_vote_account(
     ctx: &ScillaContext,
     vote_account_pubkey: &Pubkey,
-    authorized_withdrawer_keypair_path: &PathBuf,
+    authorized_withdrawer_keypair_path: &Path,
     recipient_address: &Pubkey,
     amount: u64,
 ) -> anyhow::Result<()> {
-    let authorized_withdrawer = read_keypair_from_path(authorized_withdrawer_keypair_path)?;
+    let authorized_withdrawer = read_signer(authorized_withdrawer_keypair_path)?;
     let withdrawer_pubkey = authorized_withdrawer.pubkey();
 
     let vote_account = ctx
@@ -330,18 +517,17 @@ async fn process_sol_withdraw_from_vote_account(
         recipient_address,
     );
 
-    let signature = build_and_send_tx(
+    let Some(signature) = build_and_send_or_export_tx(
         ctx,
         &[withdraw_ix],
-        &[ctx.keypair(), &authorized_withdrawer],
+        &[ctx.signer(), &*authorized_withdrawer],
     )
-    .await?;
+    .await?
+    else {
+        return Ok(());
+    };
 
-    println!(
-        "{} {}",
-        style("Signature:").green().bold(),
-        style(signature).cyan()
-    );
+    stdout().success(format!("Signature: {signature}"));
 
     Ok(())
 }
@@ -349,10 +535,10 @@ async fn process_sol_withdraw_from_vote_account(
 async fn close_vote_account(
     ctx: &ScillaContext,
     vote_account_pubkey: &Pubkey,
-    withdraw_authority_keypair_path: &PathBuf,
+    withdraw_authority_keypair_path: &Path,
     destination_pubkey: &Pubkey,
 ) -> anyhow::Result<()> {
-    let withdraw_authority = read_keypair_from_path(withdraw_authority_keypair_path)?;
+    let withdraw_authority = read_signer(withdraw_authority_keypair_path)?;
     let vote_account_status = ctx
         .rpc()
         .get_vote_accounts_with_config(RpcGetVoteAccountsConfig {
@@ -387,14 +573,16 @@ async fn close_vote_account(
         destination_pubkey,
     );
 
-    let signature =
-        build_and_send_tx(ctx, &[withdraw_ix], &[ctx.keypair(), &withdraw_authority]).await?;
+    let Some(signature) =
+        build_and_send_or_export_tx(ctx, &[withdraw_ix], &[ctx.signer(), &*withdraw_authority])
+            .await?
+    else {
+        return Ok(());
+    };
 
-    println!(
-        "{} {}",
-        style("Vote account closed! Signature:").green().bold(),
-        style(signature).cyan()
-    );
+    let mut out = stdout();
+    out.success("Vote account closed!");
+    out.note(format!("Signature: {signature}"));
 
     Ok(())
 }
@@ -416,8 +604,14 @@ async fn process_fetch_vote_account(
     let vote_state = VoteStateV4::deserialize(&vote_account.data, vote_account_pubkey)
         .map_err(|_| anyhow!("Account data could not be deserialized to vote state"))?;
 
-    let balance_sol = lamports_to_sol(vote_account.lamports);
+    let mut out = stdout();
+    out.title("VOTE ACCOUNT INFORMATION");
+    out.table(vote_account_table(vote_account.lamports, &vote_state));
 
+    Ok(())
+}
+
+fn vote_account_table(lamports: u64, vote_state: &VoteStateV4) -> Table {
     let root_slot = match vote_state.root_slot {
         Some(slot) => slot.to_string(),
         None => "~".to_string(),
@@ -434,49 +628,95 @@ async fn process_fetch_vote_account(
         .map(|(_, v)| v.to_string())
         .unwrap_or_else(|| vote_state.node_pubkey.to_string());
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL)
-        .set_header(vec![
-            Cell::new("Field")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-            Cell::new("Value")
-                .add_attribute(comfy_table::Attribute::Bold)
-                .fg(comfy_table::Color::Cyan),
-        ])
-        .add_row(vec![
-            Cell::new("Account Balance"),
-            Cell::new(format!("{balance_sol} SOL")),
-        ])
-        .add_row(vec![
-            Cell::new("Validator Identity"),
-            Cell::new(vote_state.node_pubkey),
-        ])
-        .add_row(vec![Cell::new("Vote Authority"), Cell::new(vote_authority)])
-        .add_row(vec![
-            Cell::new("Withdraw Authority"),
-            Cell::new(vote_state.authorized_withdrawer),
-        ])
-        .add_row(vec![Cell::new("Credits"), Cell::new(vote_state.credits())])
-        .add_row(vec![
-            Cell::new("Commission"),
-            Cell::new(format!(
-                "{}%",
-                vote_state.inflation_rewards_commission_bps / 100
-            )),
-        ])
-        .add_row(vec![Cell::new("Root Slot"), Cell::new(root_slot)])
-        .add_row(vec![
-            Cell::new("Recent Timestamp"),
-            Cell::new(format!(
-                "{} from slot {}",
-                timestamp, vote_state.last_timestamp.slot
-            )),
-        ]);
-
-    println!("\n{}", style("VOTE ACCOUNT INFORMATION").green().bold());
-    println!("{table}");
+    field_table([
+        (
+            "Account Balance",
+            format!("{} SOL", lamports_to_sol(lamports)),
+        ),
+        ("Validator Identity", vote_state.node_pubkey.to_string()),
+        ("Vote Authority", vote_authority),
+        (
+            "Withdraw Authority",
+            vote_state.authorized_withdrawer.to_string(),
+        ),
+        ("Credits", vote_state.credits().to_string()),
+        (
+            "Commission",
+            format!("{}%", vote_state.inflation_rewards_commission_bps / 100),
+        ),
+        ("Root Slot", root_slot),
+        (
+            "Recent Timestamp",
+            format!("{} from slot {}", timestamp, vote_state.last_timestamp.slot),
+        ),
+    ])
+}
 
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, crate::ui::render::capture, insta::assert_snapshot,
+        solana_vote_interface::authorized_voters::AuthorizedVoters,
+    };
+
+    #[test]
+    fn test_check_voter_rotation() {
+        let voter = Pubkey::new_from_array([1; 32]);
+        let withdrawer = Pubkey::new_from_array([2; 32]);
+        let new_voter = Pubkey::new_from_array([3; 32]);
+        let mut vote_state = VoteStateV4 {
+            authorized_voters: AuthorizedVoters::new(700, voter),
+            authorized_withdrawer: withdrawer,
+            ..VoteStateV4::default()
+        };
+
+        assert_eq!(
+            check_voter_rotation(&vote_state, 750, &voter, &new_voter).ok(),
+            Some(voter)
+        );
+        assert!(check_voter_rotation(&vote_state, 750, &withdrawer, &new_voter).is_ok());
+        assert!(check_voter_rotation(&vote_state, 750, &new_voter, &new_voter).is_err());
+        assert!(check_voter_rotation(&vote_state, 750, &voter, &voter).is_err());
+
+        // Only one rotation may be pending
+        vote_state.authorized_voters.insert(751, new_voter);
+        assert!(check_voter_rotation(&vote_state, 750, &voter, &new_voter).is_err());
+    }
+
+    #[test]
+    fn test_voter_rotation_table() {
+        let epoch_info = EpochInfo {
+            epoch: 750,
+            slot_index: 400_500,
+            slots_in_epoch: 432_000,
+            absolute_slot: 324_400_500,
+            block_height: 302_000_000,
+            transaction_count: None,
+        };
+
+        let output = capture(|out| {
+            out.table(voter_rotation_table(
+                &Pubkey::new_from_array([1; 32]),
+                &Pubkey::new_from_array([3; 32]),
+                &epoch_info,
+            ))
+        });
+
+        assert_snapshot!("voter_rotation_table", output);
+    }
+
+    #[test]
+    fn test_vote_account_table() {
+        let vote_state = VoteStateV4 {
+            node_pubkey: Pubkey::new_from_array([1; 32]),
+            authorized_withdrawer: Pubkey::new_from_array([2; 32]),
+            inflation_rewards_commission_bps: 500,
+            root_slot: Some(311_999_968),
+            ..VoteStateV4::default()
+        };
+
+        let output = capture(|out| out.table(vote_account_table(2_500_000_000, &vote_state)));
+
+        assert_snapshot!("vote_account_table", output);
+    }
 }
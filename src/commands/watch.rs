@@ -0,0 +1,210 @@
+use {
+    crate::{
+        commands::{
+            CommandFlow,
+            account::watch_account,
+            config::{UICommitmentOptions, get_commitment_levels},
+        },
+        constants::DEFAULT_MS_PER_SLOT,
+        context::ScillaContext,
+        misc::{
+            program_logs::write_program_logs,
+            stream::{
+                LogsUpdate, SignatureUpdate, wait_for_signature, watch_program_logs, watch_slots,
+            },
+        },
+        prompt::{prompt_input_data, prompt_pubkey, prompt_select_data},
+        ui::{
+            print_error,
+            render::{Renderer, stdout},
+            set_spinner_message, show_spinner,
+        },
+    },
+    solana_commitment_config::{CommitmentConfig, CommitmentLevel},
+    solana_epoch_info::EpochInfo,
+    solana_signature::Signature,
+    std::{fmt, io::Write},
+};
+
+/// Commands streaming live updates from the RPC websocket
+#[derive(Debug, Clone)]
+pub enum WatchCommand {
+    Account,
+    ProgramLogs,
+    Slots,
+    Signature,
+    GoBack,
+}
+
+impl WatchCommand {
+    pub fn spinner_msg(&self) -> &'static str {
+        match self {
+            WatchCommand::Account => "Watching account…",
+            WatchCommand::ProgramLogs => "Tailing program logs…",
+            WatchCommand::Slots => "Following slots… (press any key to stop)",
+            WatchCommand::Signature => "Waiting for signature…",
+            WatchCommand::GoBack => "Going back…",
+        }
+    }
+}
+
+impl fmt::Display for WatchCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let command = match self {
+            WatchCommand::Account => "Watch account",
+            WatchCommand::ProgramLogs => "Tail program logs",
+            WatchCommand::Slots => "Follow slots and epoch",
+            WatchCommand::Signature => "Wait for signature",
+            WatchCommand::GoBack => "Go back",
+        };
+        write!(f, "{command}")
+    }
+}
+
+impl WatchCommand {
+    pub async fn process_command(&self, ctx: &ScillaContext) -> CommandFlow<()> {
+        let result = match self {
+            WatchCommand::Account => {
+                let pubkey = prompt_pubkey(ctx, "Enter Pubkey to watch:").await;
+                watch_account(ctx, &pubkey).await
+            }
+            WatchCommand::ProgramLogs => {
+                let program_id = prompt_pubkey(ctx, "Enter program ID:").await;
+                watch_program_logs(ctx, &program_id, |update| {
+                    write_logs_update(&mut stdout(), &update);
+                    Ok(())
+                })
+                .await
+            }
+            WatchCommand::Slots => {
+                show_spinner(self.spinner_msg(), follow_slots(ctx)).await;
+                Ok(())
+            }
+            WatchCommand::Signature => {
+                let signature: Signature = prompt_input_data("Enter transaction signature:");
+                let UICommitmentOptions::Level(level) =
+                    prompt_select_data("Wait until the transaction is:", get_commitment_levels())
+                else {
+                    return CommandFlow::Process(());
+                };
+
+                wait_for_signature(
+                    ctx,
+                    &signature,
+                    CommitmentConfig { commitment: level },
+                    |update| {
+                        write_signature_update(&mut stdout(), &signature, level, &update);
+                        Ok(())
+                    },
+                )
+                .await
+            }
+            WatchCommand::GoBack => return CommandFlow::GoBack,
+        };
+
+        if let Err(e) = result {
+            print_error(e);
+        }
+
+        CommandFlow::Process(())
+    }
+}
+
+/// Keeps the spinner showing the latest slot and how far into its epoch it
+/// is, until a key is pressed.
+async fn follow_slots(ctx: &ScillaContext) -> anyhow::Result<()> {
+    let epoch_info = ctx.rpc().get_epoch_info().await?;
+
+    watch_slots(ctx, |slot| {
+        set_spinner_message(slot_status(&epoch_info, slot));
+        Ok(())
+    })
+    .await
+}
+
+/// Epoch and slot index of `slot`, counted on from a known `epoch_info`.
+fn epoch_at_slot(epoch_info: &EpochInfo, slot: u64) -> (u64, u64) {
+    if epoch_info.slots_in_epoch == 0 {
+        return (epoch_info.epoch, epoch_info.slot_index);
+    }
+
+    let slot_index = epoch_info.slot_index + slot.saturating_sub(epoch_info.absolute_slot);
+    (
+        epoch_info.epoch + slot_index / epoch_info.slots_in_epoch,
+        slot_index % epoch_info.slots_in_epoch,
+    )
+}
+
+fn slot_status(epoch_info: &EpochInfo, slot: u64) -> String {
+    let (epoch, slot_index) = epoch_at_slot(epoch_info, slot);
+    let progress = if epoch_info.slots_in_epoch > 0 {
+        slot_index as f64 / epoch_info.slots_in_epoch as f64 * 100.0
+    } else {
+        0.0
+    };
+    let secs = epoch_info.slots_in_epoch.saturating_sub(slot_index) * DEFAULT_MS_PER_SLOT / 1000;
+
+    format!(
+        "Slot {slot} · Epoch {epoch} at {progress:.2}%, next epoch in ~{}h {:02}m (press any key \
+         to stop)",
+        secs / 3600,
+        secs % 3600 / 60
+    )
+}
+
+fn write_logs_update<W: Write>(out: &mut Renderer<W>, update: &LogsUpdate) {
+    let LogsUpdate {
+        slot,
+        signature,
+        failed,
+        logs,
+    } = update;
+
+    if *failed {
+        out.warning(format!("[slot {slot}] Failed transaction {signature}"));
+    } else {
+        out.success(format!("[slot {slot}] Transaction {signature}"));
+    }
+    write_program_logs(out, logs);
+}
+
+fn write_signature_update<W: Write>(
+    out: &mut Renderer<W>,
+    signature: &Signature,
+    level: CommitmentLevel,
+    update: &SignatureUpdate,
+) {
+    let SignatureUpdate { slot, error } = update;
+
+    match error {
+        Some(error) => out.error(format!(
+            "{signature} reached {level:?} in slot {slot} but failed: {error}"
+        )),
+        None => out.success(format!("{signature} reached {level:?} in slot {slot}")),
+    }
+    out.note("Press any key to go back");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slot_status_rolls_over_epochs() {
+        let epoch_info = EpochInfo {
+            epoch: 700,
+            slot_index: 431_000,
+            slots_in_epoch: 432_000,
+            absolute_slot: 302_831_000,
+            block_height: 280_000_000,
+            transaction_count: None,
+        };
+
+        assert_eq!(epoch_at_slot(&epoch_info, 302_831_500), (700, 431_500));
+        assert_eq!(epoch_at_slot(&epoch_info, 302_832_000), (701, 0));
+        assert_eq!(
+            slot_status(&epoch_info, 302_831_000),
+            "Slot 302831000 · Epoch 700 at 99.77%, next epoch in ~0h 06m (press any key to stop)"
+        );
+    }
+}
@@ -1,13 +1,18 @@
 use {
     crate::{
         commands::config::generate_config,
-        constants::{DEFAULT_KEYPAIR_PATH, DEVNET_RPC, SCILLA_CONFIG_RELATIVE_PATH},
+        constants::{
+            DEFAULT_DUPLICATE_SEND_WINDOW_SECS, DEFAULT_KEYPAIR_PATH,
+            DEFAULT_PRIORITY_FEE_PERCENTILE, DEVNET_GENESIS_HASH, DEVNET_RPC, MAINNET_GENESIS_HASH,
+            SCILLA_CONFIG_RELATIVE_PATH, TESTNET_GENESIS_HASH,
+        },
         error::ScillaError,
+        misc::priority_fee::{ComputeUnitLimit, PriorityFee},
+        ui::render::stdout,
     },
-    console::style,
     serde::{Deserialize, Serialize},
     solana_commitment_config::CommitmentLevel,
-    std::{env::home_dir, fs, path::PathBuf},
+    std::{collections::BTreeMap, env::home_dir, fmt, fs, path::PathBuf},
 };
 
 pub fn scilla_config_path() -> PathBuf {
@@ -34,13 +39,259 @@ where
     Ok(expand_tilde(&s))
 }
 
+fn deserialize_optional_path_with_tilde<'de, D>(
+    deserializer: D,
+) -> Result<Option<PathBuf>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s: Option<String> = Deserialize::deserialize(deserializer)?;
+    Ok(s.as_deref().map(expand_tilde))
+}
+
+fn default_duplicate_send_window_secs() -> u64 {
+    DEFAULT_DUPLICATE_SEND_WINDOW_SECS
+}
+
+fn default_priority_fee_percentile() -> u8 {
+    DEFAULT_PRIORITY_FEE_PERCENTILE
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "kebab-case")]
 pub struct ScillaConfig {
+    /// Profile the connection settings below were last switched to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
     pub rpc_url: String,
+    /// Websocket endpoint, derived from `rpc-url` when left out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub websocket_url: Option<String>,
+    /// Cluster `rpc-url` serves, detected from its genesis hash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cluster: Option<Cluster>,
     pub commitment_level: CommitmentLevel,
     #[serde(deserialize_with = "deserialize_path_with_tilde")]
     pub keypair_path: PathBuf,
+    /// Extra RPC endpoints a signed transaction is also broadcast to. They
+    /// carry their own credentials in their query string, `rpc_auth` is only
+    /// sent to `rpc_url`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub broadcast_rpc_urls: Vec<String>,
+    /// Also forward signed transactions straight to the leader TPUs
+    #[serde(default)]
+    pub broadcast_via_tpu: bool,
+    /// Window in which sending an identical transaction again requires
+    /// explicit confirmation, 0 disables the check
+    #[serde(default = "default_duplicate_send_window_secs")]
+    pub duplicate_send_window_secs: u64,
+    /// Simulate transactions and show their fee, compute units, logs and
+    /// balance changes before asking to send them
+    #[serde(default)]
+    pub preview_transactions: bool,
+    /// Compute unit price added to sent transactions: `"none"`, `"auto"` or
+    /// a fixed price in micro-lamports
+    #[serde(default)]
+    pub priority_fee: PriorityFee,
+    /// Percentile of recent fees picked by the `auto` priority fee
+    #[serde(default = "default_priority_fee_percentile")]
+    pub priority_fee_percentile: u8,
+    /// Compute unit limit set on sent transactions: `"none"`, `"auto"` to
+    /// size it by simulating the transaction, or a fixed number of units
+    #[serde(default)]
+    pub compute_unit_limit: ComputeUnitLimit,
+    /// Most SOL a batch operation may spend on fees and rent before it's
+    /// refused
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batch_spend_limit: Option<f64>,
+    /// Print without colors and draw tables in ASCII, also enabled by the
+    /// `NO_COLOR` environment variable
+    #[serde(default)]
+    pub plain_output: bool,
+    /// Look up the primary `.sol` domain of addresses listed in tables
+    #[serde(default)]
+    pub show_domains: bool,
+    /// Yellowstone gRPC endpoint watch commands stream from, instead of the
+    /// RPC websocket
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geyser: Option<GeyserConfig>,
+    /// Credentials for `rpc-url`. Like `defaults` it serializes to its own
+    /// table, so it stays after the plain values.
+    #[serde(default, skip_serializing_if = "RpcAuth::is_empty")]
+    pub rpc_auth: RpcAuth,
+    /// Connection settings by name, each in its own `[profiles.<name>]`
+    /// table
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Profile>,
+    /// Saved transfers by name, each in its own `[templates.<name>]` table
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub templates: BTreeMap<String, TxTemplate>,
+    /// Approved destinations of transfers and withdrawals, in its own
+    /// `[whitelist]` table
+    #[serde(default, skip_serializing_if = "DestinationWhitelist::is_empty")]
+    pub whitelist: DestinationWhitelist,
+    /// Values used to pre-fill prompts of routine commands. Kept last as it
+    /// serializes to its own `[defaults]` table.
+    #[serde(default, skip_serializing_if = "CommandDefaults::is_empty")]
+    pub defaults: CommandDefaults,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct CommandDefaults {
+    /// Withdraw authority keypair offered by stake and vote commands
+    #[serde(
+        default,
+        deserialize_with = "deserialize_optional_path_with_tilde",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub withdraw_authority_path: Option<PathBuf>,
+    /// Vote account offered when delegating stake
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validator: Option<String>,
+    /// Memo attached to transfers unless changed at the prompt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// Named connection settings, such as `mainnet` or `localnet`, switched
+/// between without restarting.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct Profile {
+    pub rpc_url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub websocket_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cluster: Option<Cluster>,
+    pub commitment_level: CommitmentLevel,
+    #[serde(deserialize_with = "deserialize_path_with_tilde")]
+    pub keypair_path: PathBuf,
+    /// Credentials of this profile's RPC, so an API key never reaches
+    /// another provider
+    #[serde(default, skip_serializing_if = "RpcAuth::is_empty")]
+    pub rpc_auth: RpcAuth,
+}
+
+/// Cluster an RPC endpoint serves, told apart by its genesis hash.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Cluster {
+    Mainnet,
+    Devnet,
+    Testnet,
+    Custom,
+}
+
+impl Cluster {
+    pub fn from_genesis_hash(genesis_hash: &str) -> Self {
+        match genesis_hash {
+            MAINNET_GENESIS_HASH => Cluster::Mainnet,
+            DEVNET_GENESIS_HASH => Cluster::Devnet,
+            TESTNET_GENESIS_HASH => Cluster::Testnet,
+            _ => Cluster::Custom,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Cluster::Mainnet => "mainnet",
+            Cluster::Devnet => "devnet",
+            Cluster::Testnet => "testnet",
+            Cluster::Custom => "custom",
+        }
+    }
+}
+
+impl fmt::Display for Cluster {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// A saved transfer. Fields left out are asked for every time it runs, so a
+/// monthly payout can fix the recipient and memo but leave the amount open.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct TxTemplate {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recipient: Option<String>,
+    /// Amount in SOL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// Addresses funds are expected to be sent to. Transfers and withdrawals to
+/// any other address are warned about or refused, depending on `mode`. An
+/// empty list turns the check off.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct DestinationWhitelist {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub addresses: Vec<String>,
+    #[serde(default)]
+    pub mode: WhitelistMode,
+}
+
+/// What happens to a destination that isn't whitelisted.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum WhitelistMode {
+    /// Ask for confirmation before sending
+    #[default]
+    Warn,
+    /// Don't send at all
+    Refuse,
+}
+
+impl fmt::Display for WhitelistMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WhitelistMode::Warn => write!(f, "warn"),
+            WhitelistMode::Refuse => write!(f, "refuse"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct GeyserConfig {
+    pub endpoint: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub x_token: Option<String>,
+}
+
+/// API keys and headers of an authenticated RPC provider. They are only sent
+/// to `rpc-url` and its websocket, never to the broadcast endpoints.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct RpcAuth {
+    /// Query parameters appended to the RPC and websocket URLs, e.g. `api-key`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub query: BTreeMap<String, String>,
+    /// HTTP headers sent with every RPC request, e.g. `x-token`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+}
+
+impl RpcAuth {
+    pub fn is_empty(&self) -> bool {
+        self.query.is_empty() && self.headers.is_empty()
+    }
+}
+
+impl DestinationWhitelist {
+    pub fn is_empty(&self) -> bool {
+        self.addresses.is_empty() && self.mode == WhitelistMode::default()
+    }
+}
+
+impl CommandDefaults {
+    pub fn is_empty(&self) -> bool {
+        self.withdraw_authority_path.is_none() && self.validator.is_none() && self.memo.is_none()
+    }
 }
 
 impl Default for ScillaConfig {
@@ -50,9 +301,28 @@ impl Default for ScillaConfig {
             .join(DEFAULT_KEYPAIR_PATH);
 
         Self {
+            profile: None,
             rpc_url: DEVNET_RPC.to_string(),
+            websocket_url: None,
+            cluster: None,
             commitment_level: CommitmentLevel::Confirmed,
             keypair_path: default_keypair_path,
+            broadcast_rpc_urls: vec![],
+            broadcast_via_tpu: false,
+            duplicate_send_window_secs: DEFAULT_DUPLICATE_SEND_WINDOW_SECS,
+            preview_transactions: false,
+            priority_fee: PriorityFee::None,
+            priority_fee_percentile: DEFAULT_PRIORITY_FEE_PERCENTILE,
+            compute_unit_limit: ComputeUnitLimit::None,
+            batch_spend_limit: None,
+            plain_output: false,
+            show_domains: false,
+            geyser: None,
+            rpc_auth: RpcAuth::default(),
+            profiles: BTreeMap::new(),
+            templates: BTreeMap::new(),
+            whitelist: DestinationWhitelist::default(),
+            defaults: CommandDefaults::default(),
         }
     }
 }
@@ -62,39 +332,80 @@ impl ScillaConfig {
         let scilla_config_path = scilla_config_path();
 
         if !scilla_config_path.exists() {
-            println!("{}", style("No configuration file found!").yellow().bold());
-            println!(
-                "{}",
-                style(format!(
-                    "Creating config at: {}",
-                    scilla_config_path.display()
-                ))
-                .cyan()
-            );
-            println!(
-                "{}",
-                style("Let's set up your configuration to get started.").cyan()
-            );
+            let mut out = stdout();
+            out.warning("No configuration file found!");
+            out.line(format!(
+                "Creating config at: {}",
+                scilla_config_path.display()
+            ));
+            out.line("Let's set up your configuration to get started.");
 
             generate_config()?;
 
-            println!(
-                "{}",
-                style("Configuration complete! Starting Scilla...")
-                    .green()
-                    .bold()
-            );
+            out.success("Configuration complete! Starting Scilla...");
         }
 
-        println!(
-            "{}",
-            style(format!("Using Scilla config path : {scilla_config_path:?}")).dim()
-        );
+        stdout().note(format!("Using Scilla config path : {scilla_config_path:?}"));
         let data = fs::read_to_string(scilla_config_path)?;
         let config: ScillaConfig = toml::from_str(&data)?;
         Ok(config)
     }
 
+    /// Writes the config back to the Scilla config path.
+    pub fn save(&self) -> anyhow::Result<()> {
+        fs::write(scilla_config_path(), toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// The connection settings in use, as a profile.
+    pub fn current_profile(&self) -> Profile {
+        Profile {
+            rpc_url: self.rpc_url.clone(),
+            websocket_url: self.websocket_url.clone(),
+            cluster: self.cluster,
+            commitment_level: self.commitment_level,
+            keypair_path: self.keypair_path.clone(),
+            rpc_auth: self.rpc_auth.clone(),
+        }
+    }
+
+    /// Saves the connection settings in use as profile `name` and makes it
+    /// the active one.
+    pub fn save_profile(&mut self, name: &str) {
+        self.profiles
+            .insert(name.to_string(), self.current_profile());
+        self.profile = Some(name.to_string());
+    }
+
+    /// Replaces the connection settings with those of profile `name`.
+    pub fn switch_profile(&mut self, name: &str) -> anyhow::Result<()> {
+        let Some(profile) = self.profiles.get(name).cloned() else {
+            anyhow::bail!("No profile named {name}");
+        };
+
+        self.rpc_url = profile.rpc_url;
+        self.websocket_url = profile.websocket_url;
+        self.cluster = profile.cluster;
+        self.commitment_level = profile.commitment_level;
+        self.keypair_path = profile.keypair_path;
+        self.rpc_auth = profile.rpc_auth;
+        self.profile = Some(name.to_string());
+        Ok(())
+    }
+
+    /// Copies edits of the connection settings into the active profile, so
+    /// switching back to it later doesn't undo them.
+    pub fn sync_active_profile(&mut self) {
+        let current = self.current_profile();
+        if let Some(profile) = self
+            .profile
+            .as_ref()
+            .and_then(|name| self.profiles.get_mut(name))
+        {
+            *profile = current;
+        }
+    }
+
     pub fn load_from_path(path: &std::path::Path) -> Result<ScillaConfig, ScillaError> {
         if !path.exists() {
             return Err(ScillaError::ConfigPathDoesNotExist);
@@ -149,5 +460,302 @@ commitment-level = "confirmed"
         assert_eq!(config.rpc_url, "https://api.mainnet-beta.solana.com");
         assert_eq!(config.commitment_level, CommitmentLevel::Confirmed);
         assert_eq!(config.keypair_path, home.join("my/key.json"));
+        assert_eq!(
+            config.duplicate_send_window_secs,
+            DEFAULT_DUPLICATE_SEND_WINDOW_SECS
+        );
+    }
+
+    #[test]
+    fn test_load_from_path_with_broadcast_endpoints() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let config_path = temp_dir.path().join("config.toml");
+
+        fs::write(
+            &config_path,
+            r#"
+rpc-url = "https://api.mainnet-beta.solana.com"
+keypair-path = "/tmp/key.json"
+commitment-level = "confirmed"
+broadcast-rpc-urls = ["https://rpc-a.example.com", "https://rpc-b.example.com"]
+broadcast-via-tpu = true
+"#,
+        )
+        .expect("Failed to write file");
+
+        let config = ScillaConfig::load_from_path(&config_path)
+            .expect("Valid config should load successfully");
+
+        assert_eq!(
+            config.broadcast_rpc_urls,
+            vec!["https://rpc-a.example.com", "https://rpc-b.example.com"]
+        );
+        assert!(config.broadcast_via_tpu);
+    }
+
+    #[test]
+    fn test_load_from_path_with_priority_fee() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let config_path = temp_dir.path().join("config.toml");
+
+        fs::write(
+            &config_path,
+            r#"
+rpc-url = "https://api.mainnet-beta.solana.com"
+keypair-path = "/tmp/key.json"
+commitment-level = "confirmed"
+priority-fee = "auto"
+priority-fee-percentile = 90
+compute-unit-limit = "auto"
+"#,
+        )
+        .expect("Failed to write file");
+
+        let config = ScillaConfig::load_from_path(&config_path)
+            .expect("Valid config should load successfully");
+
+        assert_eq!(config.priority_fee, PriorityFee::Auto);
+        assert_eq!(config.priority_fee_percentile, 90);
+        assert_eq!(config.compute_unit_limit, ComputeUnitLimit::Auto);
+    }
+
+    #[test]
+    fn test_load_from_path_with_rpc_auth() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let config_path = temp_dir.path().join("config.toml");
+
+        fs::write(
+            &config_path,
+            r#"
+rpc-url = "https://mainnet.helius-rpc.com"
+keypair-path = "/tmp/key.json"
+commitment-level = "confirmed"
+
+[rpc-auth.query]
+api-key = "secret-key"
+
+[rpc-auth.headers]
+x-token = "secret-token"
+"#,
+        )
+        .expect("Failed to write file");
+
+        let config = ScillaConfig::load_from_path(&config_path)
+            .expect("Valid config should load successfully");
+
+        assert_eq!(config.rpc_auth.query["api-key"], "secret-key");
+        assert_eq!(config.rpc_auth.headers["x-token"], "secret-token");
+        assert!(config.defaults.is_empty());
+
+        let serialized = toml::to_string_pretty(&config).expect("Config should serialize");
+        let reloaded: ScillaConfig = toml::from_str(&serialized).expect("Config should reload");
+        assert_eq!(reloaded.rpc_auth.headers, config.rpc_auth.headers);
+    }
+
+    #[test]
+    fn test_load_from_path_with_geyser() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let config_path = temp_dir.path().join("config.toml");
+
+        fs::write(
+            &config_path,
+            r#"
+rpc-url = "https://api.mainnet-beta.solana.com"
+keypair-path = "/tmp/key.json"
+commitment-level = "confirmed"
+
+[geyser]
+endpoint = "https://grpc.example.com:443"
+x-token = "secret-token"
+"#,
+        )
+        .expect("Failed to write file");
+
+        let config = ScillaConfig::load_from_path(&config_path)
+            .expect("Valid config should load successfully");
+        let geyser = config.geyser.expect("Geyser endpoint should be set");
+
+        assert_eq!(geyser.endpoint, "https://grpc.example.com:443");
+        assert_eq!(geyser.x_token.as_deref(), Some("secret-token"));
+    }
+
+    #[test]
+    fn test_load_from_path_with_command_defaults() {
+        let home = env::home_dir().expect("HOME should be set");
+
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let config_path = temp_dir.path().join("config.toml");
+
+        fs::write(
+            &config_path,
+            r#"
+rpc-url = "https://api.mainnet-beta.solana.com"
+keypair-path = "/tmp/key.json"
+commitment-level = "confirmed"
+
+[defaults]
+withdraw-authority-path = "~/cold/withdrawer.json"
+validator = "Vote111111111111111111111111111111111111111"
+"#,
+        )
+        .expect("Failed to write file");
+
+        let config = ScillaConfig::load_from_path(&config_path)
+            .expect("Valid config should load successfully");
+
+        assert_eq!(
+            config.defaults.withdraw_authority_path,
+            Some(home.join("cold/withdrawer.json"))
+        );
+        assert_eq!(
+            config.defaults.validator.as_deref(),
+            Some("Vote111111111111111111111111111111111111111")
+        );
+        assert!(config.defaults.memo.is_none());
+
+        let serialized = toml::to_string_pretty(&config).expect("Config should serialize");
+        assert!(serialized.contains("[defaults]"));
+    }
+
+    #[test]
+    fn test_load_from_path_with_templates() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let config_path = temp_dir.path().join("config.toml");
+
+        fs::write(
+            &config_path,
+            r#"
+rpc-url = "https://api.mainnet-beta.solana.com"
+keypair-path = "/tmp/key.json"
+commitment-level = "confirmed"
+
+[templates.monthly-payout]
+recipient = "Vote111111111111111111111111111111111111111"
+memo = "monthly payout"
+
+[defaults]
+memo = "ops payout"
+"#,
+        )
+        .expect("Failed to write file");
+
+        let config = ScillaConfig::load_from_path(&config_path)
+            .expect("Valid config should load successfully");
+
+        assert_eq!(
+            config.templates["monthly-payout"],
+            TxTemplate {
+                recipient: Some("Vote111111111111111111111111111111111111111".to_string()),
+                amount: None,
+                memo: Some("monthly payout".to_string()),
+            }
+        );
+
+        let serialized = toml::to_string_pretty(&config).expect("Config should serialize");
+        let reloaded: ScillaConfig = toml::from_str(&serialized).expect("Config should reload");
+        assert_eq!(reloaded.templates, config.templates);
+        assert_eq!(reloaded.defaults.memo.as_deref(), Some("ops payout"));
+    }
+
+    #[test]
+    fn test_switch_profile() -> anyhow::Result<()> {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let config_path = temp_dir.path().join("config.toml");
+
+        fs::write(
+            &config_path,
+            r#"
+profile = "devnet"
+rpc-url = "https://api.devnet.solana.com"
+keypair-path = "/tmp/dev.json"
+commitment-level = "confirmed"
+
+[profiles.devnet]
+rpc-url = "https://api.devnet.solana.com"
+keypair-path = "/tmp/dev.json"
+commitment-level = "confirmed"
+
+[profiles.mainnet]
+rpc-url = "https://mainnet.helius-rpc.com"
+websocket-url = "wss://mainnet.helius-rpc.com"
+cluster = "mainnet"
+keypair-path = "/tmp/main.json"
+commitment-level = "finalized"
+
+[profiles.mainnet.rpc-auth.query]
+api-key = "secret-key"
+"#,
+        )
+        .expect("Failed to write file");
+
+        let mut config = ScillaConfig::load_from_path(&config_path)
+            .expect("Valid config should load successfully");
+
+        config.switch_profile("mainnet")?;
+        assert_eq!(config.profile.as_deref(), Some("mainnet"));
+        assert_eq!(config.rpc_url, "https://mainnet.helius-rpc.com");
+        assert_eq!(
+            config.websocket_url.as_deref(),
+            Some("wss://mainnet.helius-rpc.com")
+        );
+        assert_eq!(config.cluster, Some(Cluster::Mainnet));
+        assert_eq!(config.commitment_level, CommitmentLevel::Finalized);
+        assert_eq!(config.keypair_path, PathBuf::from("/tmp/main.json"));
+        assert_eq!(config.rpc_auth.query["api-key"], "secret-key");
+        assert!(config.switch_profile("localnet").is_err());
+
+        // Edits made while a profile is active are kept in it
+        config.commitment_level = CommitmentLevel::Confirmed;
+        config.sync_active_profile();
+        config.switch_profile("devnet")?;
+        assert!(config.rpc_auth.is_empty());
+        assert_eq!(
+            config.profiles["mainnet"].commitment_level,
+            CommitmentLevel::Confirmed
+        );
+
+        let serialized = toml::to_string_pretty(&config).expect("Config should serialize");
+        let reloaded: ScillaConfig = toml::from_str(&serialized).expect("Config should reload");
+        assert_eq!(reloaded.profiles, config.profiles);
+        assert_eq!(reloaded.profile.as_deref(), Some("devnet"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_from_path_with_whitelist() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let config_path = temp_dir.path().join("config.toml");
+
+        fs::write(
+            &config_path,
+            r#"
+rpc-url = "https://api.mainnet-beta.solana.com"
+keypair-path = "/tmp/key.json"
+commitment-level = "confirmed"
+
+[whitelist]
+addresses = ["Vote111111111111111111111111111111111111111"]
+mode = "refuse"
+"#,
+        )
+        .expect("Failed to write file");
+
+        let config = ScillaConfig::load_from_path(&config_path)
+            .expect("Valid config should load successfully");
+
+        assert_eq!(
+            config.whitelist.addresses,
+            vec!["Vote111111111111111111111111111111111111111".to_string()]
+        );
+        assert_eq!(config.whitelist.mode, WhitelistMode::Refuse);
+
+        let serialized = toml::to_string_pretty(&config).expect("Config should serialize");
+        assert!(serialized.contains("[whitelist]"));
+        assert!(
+            !toml::to_string_pretty(&ScillaConfig::default())
+                .expect("Config should serialize")
+                .contains("[whitelist]")
+        );
     }
 }
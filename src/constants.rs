@@ -6,18 +6,151 @@ pub const DEFAULT_KEYPAIR_PATH: &str = ".config/solana/id.json";
 
 pub const ACTIVE_STAKE_EPOCH_BOUND: u64 = u64::MAX;
 
+/// Byte offset of the authorized staker in a stake account: after the `u32`
+/// state tag and the `u64` rent exempt reserve.
+pub const STAKE_AUTHORIZED_STAKER_OFFSET: usize = 12;
+
+/// Byte offset of the authorized withdrawer in a stake account, right after
+/// the staker.
+pub const STAKE_AUTHORIZED_WITHDRAWER_OFFSET: usize = 44;
+
+/// Byte offset of the delegated vote account in a stake account: after the
+/// state tag and the rest of the meta (reserve, authorities and lockup).
+pub const STAKE_DELEGATION_VOTER_OFFSET: usize = 124;
+
+/// Width in characters of the longest bar in stake charts.
+pub const STAKE_CHART_WIDTH: usize = 30;
+
+/// Length of a Julian year, for annualizing per-epoch rates.
+pub const SECONDS_PER_YEAR: f64 = 31_557_600.0;
+
+/// Slot time assumed when the cluster reports no performance samples.
+pub const DEFAULT_MS_PER_SLOT: u64 = 400;
+
+/// Minute-long performance samples averaged to measure the slot time.
+pub const SLOT_TIME_SAMPLES: usize = 60;
+
+/// How many accounts a batch operation handles per transaction, keeping each
+/// one well under the transaction size limit.
+pub const MAX_BATCH_INSTRUCTIONS_PER_TX: usize = 10;
+
+/// Most bytes a serialized transaction may take.
+pub const MAX_TRANSACTION_SIZE: usize = 1232;
+
+/// Changed data byte ranges listed per update when watching an account whose
+/// layout isn't known.
+pub const MAX_WATCH_DATA_RANGES: usize = 5;
+
+/// Bytes of account data shown per line of a hex dump.
+pub const HEX_DUMP_LINE_BYTES: usize = 16;
+
+/// Most accounts a single `getMultipleAccounts` request may ask for.
+pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// Most accounts a single `getRecentPrioritizationFees` request may ask for.
+pub const MAX_PRIORITIZATION_FEE_ACCOUNTS: usize = 128;
+
+/// Percentile of recent prioritization fees used by the `auto` priority fee.
+pub const DEFAULT_PRIORITY_FEE_PERCENTILE: u8 = 75;
+
+/// Compute units each instruction may use when a transaction sets no limit.
+pub const DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT: u64 = 200_000;
+
+/// Most compute units a transaction may use.
+pub const MAX_COMPUTE_UNIT_LIMIT: u64 = 1_400_000;
+
+/// Headroom added to the compute units a simulation consumed when sizing the
+/// compute unit limit, in percent.
+pub const COMPUTE_UNIT_LIMIT_MARGIN_PERCENT: u64 = 10;
+
+/// Compute unit prices are set in micro-lamports.
+pub const MICRO_LAMPORTS_PER_LAMPORT: u64 = 1_000_000;
+
 pub const DEVNET_RPC: &str = "https://api.devnet.solana.com";
 
 pub const MAINNET_RPC: &str = "https://api.mainnet-beta.solana.com";
 
 pub const TESTNET_RPC: &str = "https://api.testnet.solana.com";
 
+/// Timeout of a single RPC request sent with custom headers.
+pub const RPC_REQUEST_TIMEOUT_SECS: u64 = 30;
+
+pub const MAINNET_GENESIS_HASH: &str = "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d";
+
+pub const DEVNET_GENESIS_HASH: &str = "EtWTRABZaYq6iMfeYKouRu166VU2xqa1wcaWoxPkrZBG";
+
+pub const TESTNET_GENESIS_HASH: &str = "4uhcVJyU9pJkvQyS88uRDiswHXSCkY3zQawwpjk2NsNY";
+
+/// Key marking config accounts that hold validator info.
+pub const VALIDATOR_INFO_KEY: &str = "Va1idator1nfo111111111111111111111111111111";
+
+/// Slots from the current one whose leaders Slot Leaders lists, 10 leader
+/// rotations of 4 slots.
+pub const UPCOMING_LEADER_SLOTS: u64 = 40;
+
+/// Length of generated Sign-In With Solana nonces, well over the 8
+/// characters the format asks for.
+pub const SIWS_NONCE_LEN: usize = 16;
+
+/// How long the config doctor waits on a network check before failing it.
+pub const DOCTOR_CHECK_TIMEOUT_SECS: u64 = 10;
+
 pub const DEFAULT_EPOCH_LIMIT: usize = 10;
 
+/// Rows shown per page by the shared table renderer.
+pub const TABLE_PAGE_SIZE: usize = 20;
+
+/// Narrowest a truncated table cell gets, however many columns are shown.
+pub const MIN_TABLE_CELL_WIDTH: usize = 12;
+
+/// Every SBF program binary starts with the ELF magic number.
+pub const ELF_MAGIC: &[u8] = b"\x7fELF";
+
+/// Largest program the loader accepts, the 10 MiB limit on account data.
+pub const MAX_PROGRAM_SIZE: usize = 10 * 1024 * 1024;
+
+/// How long a program download may take before it's abandoned.
+pub const PROGRAM_DOWNLOAD_TIMEOUT_SECS: u64 = 120;
+
+/// How long (in seconds) a sent transaction is remembered for duplicate-send
+/// protection.
+pub const DEFAULT_DUPLICATE_SEND_WINDOW_SECS: u64 = 120;
+
+/// Environment variable naming a file (or `-` for stdin) to answer prompts
+/// from instead of the terminal.
+pub const ANSWERS_ENV_VAR: &str = "SCILLA_ANSWERS";
+
+/// Prefix of keypair paths that name a hardware wallet, as in
+/// `usb://ledger?key=0/0`.
+pub const HARDWARE_WALLET_SCHEME: &str = "usb://";
+
 pub const STAKE_HISTORY_SYSVAR_ADDR: &str = "SysvarStakeHistory1111111111111111111111111";
 
+/// Solana Name Service program owning `.sol` domain name accounts.
+pub const NAME_SERVICE_PROGRAM_ID: &str = "namesLPneVptA9Z5rqUDD9tMTWEJwofgaYwp8cawRkX";
+
+/// Name account of the `.sol` top-level domain, parent of every `.sol` domain.
+pub const SOL_TLD_ACCOUNT: &str = "58PwtjSDuFHuUkYjH9BYnnQKHfwo9reZhC2zMJv9JPkx";
+
+/// Program holding `.sol` domains that were tokenized into NFTs.
+pub const NAME_TOKENIZER_PROGRAM_ID: &str = "nftD3vbNkNqfj2Sd3HZwbpw4BxxKWr4AjGb9X38JeZk";
+
+/// Program recording the primary ("favourite") domain chosen by a wallet.
+pub const NAME_OFFERS_PROGRAM_ID: &str = "85iDfUvr3HJyLM2zcq5BXSiDvUWfw6cSE1FfNBo8Ap29";
+
+/// Class of the reverse lookup accounts mapping a name account back to its
+/// domain name.
+pub const REVERSE_LOOKUP_CLASS: &str = "33m47vH6Eav6jhp6rGVRhJ8GpQ1Hqhw5t1ZDwvPhpEFh";
+
+/// Prepended to a domain name before hashing it into its account seed.
+pub const NAME_SERVICE_HASH_PREFIX: &str = "SPL Name Service";
+
 pub const MEMO_PROGRAM_ID: &str = "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr";
 
+pub const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PRP2rsQ7VjbePEb";
+
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+
 /// Maximum chunk size for memo data in bytes.
 ///
 /// Solana transactions have a 1232 byte limit. After accounting for:
@@ -1,29 +1,93 @@
 use {
-    crate::config::ScillaConfig,
-    anyhow::anyhow,
+    crate::{
+        config::{Cluster, CommandDefaults, GeyserConfig, RpcAuth, ScillaConfig, TxTemplate},
+        constants::MAX_COMPUTE_UNIT_LIMIT,
+        misc::{
+            broadcast::{QuicTpuClient, new_tpu_client},
+            helpers::{SolAmount, sol_to_lamports, websocket_url_from_rpc},
+            offline::OfflineSigning,
+            priority_fee::{ComputeUnitLimit, PriorityFee},
+            rpc_auth::{authenticated_url, rpc_client},
+            send_guard::RecentSends,
+            signer::read_signer,
+            whitelist::Whitelist,
+        },
+        ui::set_plain_output,
+    },
+    anyhow::{anyhow, bail},
     solana_commitment_config::CommitmentConfig,
-    solana_keypair::{EncodableKey, Keypair, Signer},
+    solana_keypair::{Keypair, Signer},
     solana_pubkey::Pubkey,
     solana_rpc_client::nonblocking::rpc_client::RpcClient,
-    std::path::PathBuf,
+    std::{collections::BTreeMap, path::PathBuf, time::Duration},
+    tokio::sync::OnceCell,
 };
 
 pub struct ScillaContext {
     rpc_client: RpcClient,
-    keypair: Keypair,
+    rpc_url: String,
+    websocket_url: Option<String>,
+    cluster: Option<Cluster>,
+    rpc_auth: RpcAuth,
+    signer: Box<dyn Signer>,
     pubkey: Pubkey,
     keypair_path: PathBuf,
+    broadcast_rpcs: Vec<RpcClient>,
+    broadcast_via_tpu: bool,
+    tpu_client: OnceCell<QuicTpuClient>,
+    recent_sends: RecentSends,
+    preview_transactions: bool,
+    priority_fee: PriorityFee,
+    priority_fee_percentile: u8,
+    compute_unit_limit: ComputeUnitLimit,
+    batch_spend_limit: Option<u64>,
+    show_domains: bool,
+    geyser: Option<GeyserConfig>,
+    templates: BTreeMap<String, TxTemplate>,
+    whitelist: Whitelist,
+    defaults: CommandDefaults,
+    offline_signing: Option<OfflineSigning>,
 }
 
 impl ScillaContext {
-    pub fn keypair(&self) -> &Keypair {
-        &self.keypair
+    /// Signer of the session, a keypair file or a hardware wallet.
+    pub fn signer(&self) -> &dyn Signer {
+        self.signer.as_ref()
     }
 
     pub fn rpc(&self) -> &RpcClient {
         &self.rpc_client
     }
 
+    /// The configured RPC URL, without the credentials added to requests.
+    pub fn rpc_url(&self) -> &str {
+        &self.rpc_url
+    }
+
+    /// Cluster the configured RPC serves, if it could be detected.
+    pub fn cluster(&self) -> Option<Cluster> {
+        self.cluster
+    }
+
+    /// A new client for the configured RPC with its credentials, for APIs
+    /// that need to own their client.
+    pub fn new_rpc_client(&self) -> anyhow::Result<RpcClient> {
+        rpc_client(&self.rpc_url, self.rpc_client.commitment(), &self.rpc_auth)
+    }
+
+    /// Websocket endpoint of the configured RPC, with its query parameters.
+    pub fn websocket_url(&self) -> anyhow::Result<String> {
+        authenticated_url(&self.plain_websocket_url(), &self.rpc_auth.query)
+    }
+
+    /// The configured websocket URL, or the one derived from the RPC URL,
+    /// without credentials.
+    pub fn plain_websocket_url(&self) -> String {
+        self.websocket_url
+            .clone()
+            .unwrap_or_else(|| websocket_url_from_rpc(&self.rpc_url))
+    }
+
     pub fn pubkey(&self) -> &Pubkey {
         &self.pubkey
     }
@@ -32,7 +96,107 @@ impl ScillaContext {
         &self.keypair_path
     }
 
+    /// Additional RPC clients signed transactions are fanned out to.
+    pub fn broadcast_rpcs(&self) -> &[RpcClient] {
+        &self.broadcast_rpcs
+    }
+
+    pub fn broadcast_via_tpu(&self) -> bool {
+        self.broadcast_via_tpu
+    }
+
+    /// TPU client broadcasts are forwarded through, connected on first use
+    /// and kept for the rest of the session.
+    pub async fn tpu_client(&self) -> anyhow::Result<&QuicTpuClient> {
+        self.tpu_client
+            .get_or_try_init(|| new_tpu_client(self, "scilla_broadcast"))
+            .await
+    }
+
+    pub fn broadcast_enabled(&self) -> bool {
+        !self.broadcast_rpcs.is_empty() || self.broadcast_via_tpu
+    }
+
+    pub fn recent_sends(&self) -> &RecentSends {
+        &self.recent_sends
+    }
+
+    /// Whether transactions are simulated and confirmed before they're sent.
+    pub fn preview_transactions(&self) -> bool {
+        self.preview_transactions
+    }
+
+    pub fn priority_fee(&self) -> PriorityFee {
+        self.priority_fee
+    }
+
+    /// Percentile of recent fees used when the priority fee is `auto`.
+    pub fn priority_fee_percentile(&self) -> u8 {
+        self.priority_fee_percentile
+    }
+
+    pub fn compute_unit_limit(&self) -> ComputeUnitLimit {
+        self.compute_unit_limit
+    }
+
+    /// Most lamports a batch operation may spend on fees and rent.
+    pub fn batch_spend_limit(&self) -> Option<u64> {
+        self.batch_spend_limit
+    }
+
+    /// Signs with `keypair` for the rest of the session without touching the
+    /// config file.
+    pub fn use_keypair(&mut self, keypair: Keypair) {
+        self.pubkey = keypair.pubkey();
+        self.signer = Box::new(keypair);
+        self.offline_signing = None;
+    }
+
+    /// Acts as the cold wallet of `offline` for the rest of the session,
+    /// writing transactions out for offline signing instead of sending them.
+    pub fn start_offline_signing(&mut self, offline: OfflineSigning) {
+        self.pubkey = offline.signer;
+        self.offline_signing = Some(offline);
+    }
+
+    /// Goes back to signing with the session signer.
+    pub fn stop_offline_signing(&mut self) {
+        self.pubkey = self.signer.pubkey();
+        self.offline_signing = None;
+    }
+
+    /// The offline signing session, if one is running.
+    pub fn offline_signing(&self) -> Option<&OfflineSigning> {
+        self.offline_signing.as_ref()
+    }
+
+    /// Whether tables show the primary `.sol` domain next to addresses.
+    pub fn show_domains(&self) -> bool {
+        self.show_domains
+    }
+
+    /// Yellowstone gRPC endpoint to stream watch updates from, if any.
+    pub fn geyser(&self) -> Option<&GeyserConfig> {
+        self.geyser.as_ref()
+    }
+
+    /// Saved transfer templates by name.
+    pub fn templates(&self) -> &BTreeMap<String, TxTemplate> {
+        &self.templates
+    }
+
+    /// Approved destinations of transfers and withdrawals.
+    pub fn whitelist(&self) -> &Whitelist {
+        &self.whitelist
+    }
+
+    /// Per-command values used to pre-fill prompts.
+    pub fn defaults(&self) -> &CommandDefaults {
+        &self.defaults
+    }
+
     pub fn reload(&mut self, new_config: ScillaConfig) -> anyhow::Result<()> {
+        set_plain_output(new_config.plain_output);
         *self = ScillaContext::try_from(new_config)?;
         Ok(())
     }
@@ -42,28 +206,76 @@ impl TryFrom<ScillaConfig> for ScillaContext {
     type Error = anyhow::Error;
 
     fn try_from(config: ScillaConfig) -> anyhow::Result<Self> {
-        let rpc_client = RpcClient::new_with_commitment(
-            config.rpc_url,
-            CommitmentConfig {
-                commitment: config.commitment_level,
-            },
-        );
-
-        let keypair = Keypair::read_from_file(&config.keypair_path).map_err(|e| {
-            anyhow!(
-                "Failed to read keypair from {}: {}",
-                config.keypair_path.display(),
-                e
-            )
-        })?;
-
-        let pubkey = keypair.pubkey();
+        let commitment = CommitmentConfig {
+            commitment: config.commitment_level,
+        };
+        let rpc_client = rpc_client(&config.rpc_url, commitment, &config.rpc_auth)?;
+
+        if config.priority_fee_percentile > 100 {
+            bail!(
+                "priority-fee-percentile must be between 0 and 100, got {}",
+                config.priority_fee_percentile
+            );
+        }
+
+        if let ComputeUnitLimit::Units(units) = config.compute_unit_limit
+            && (units == 0 || u64::from(units) > MAX_COMPUTE_UNIT_LIMIT)
+        {
+            bail!("compute-unit-limit must be between 1 and {MAX_COMPUTE_UNIT_LIMIT}, got {units}");
+        }
+
+        if let Some(limit) = config.batch_spend_limit
+            && !(limit.is_finite() && limit >= 0.0)
+        {
+            bail!("batch-spend-limit must be a non-negative amount of SOL, got {limit}");
+        }
+
+        // Templates edited by hand skip the checks of the template editor
+        for (name, template) in &config.templates {
+            if let Some(amount) = template.amount {
+                SolAmount::try_from(amount)
+                    .map_err(|e| anyhow!("Invalid amount of template {name}: {e}"))?;
+            }
+        }
+
+        let whitelist = Whitelist::try_from(&config.whitelist)?;
+
+        // The credentials of `rpc_url` must not leak to other providers, the
+        // broadcast URLs carry their own
+        let broadcast_rpcs = config
+            .broadcast_rpc_urls
+            .into_iter()
+            .map(|url| RpcClient::new_with_commitment(url, commitment))
+            .collect();
+
+        let signer = read_signer(&config.keypair_path)?;
+
+        let pubkey = signer.pubkey();
 
         Ok(Self {
             rpc_client,
-            keypair,
+            rpc_url: config.rpc_url,
+            websocket_url: config.websocket_url,
+            cluster: config.cluster,
+            rpc_auth: config.rpc_auth,
+            signer,
             pubkey,
             keypair_path: config.keypair_path,
+            broadcast_rpcs,
+            broadcast_via_tpu: config.broadcast_via_tpu,
+            tpu_client: OnceCell::new(),
+            recent_sends: RecentSends::new(Duration::from_secs(config.duplicate_send_window_secs)),
+            preview_transactions: config.preview_transactions,
+            priority_fee: config.priority_fee,
+            priority_fee_percentile: config.priority_fee_percentile,
+            compute_unit_limit: config.compute_unit_limit,
+            batch_spend_limit: config.batch_spend_limit.map(sol_to_lamports),
+            show_domains: config.show_domains,
+            geyser: config.geyser,
+            templates: config.templates,
+            whitelist,
+            defaults: config.defaults,
+            offline_signing: None,
         })
     }
 }
@@ -1,11 +1,22 @@
 use {
     crate::{
-        commands::CommandFlow, config::ScillaConfig, context::ScillaContext, error::ScillaResult,
-        prompt::prompt_for_command,
+        cli::{Cli, OutputFormat},
+        commands::{CommandFlow, config::detect_cluster},
+        config::ScillaConfig,
+        context::ScillaContext,
+        error::ScillaResult,
+        prompt::{answers::load_answers_from_env, prompt_for_command},
+        ui::{
+            print_error, render::stdout, set_json_output, set_plain_output, set_unattended,
+            write_menu_header,
+        },
     },
+    clap::Parser,
     console::style,
+    std::process::exit,
 };
 
+pub mod cli;
 pub mod commands;
 pub mod config;
 pub mod constants;
@@ -17,6 +28,30 @@ pub mod ui;
 
 #[tokio::main(flavor = "multi_thread")]
 async fn main() -> ScillaResult<()> {
+    let cli = Cli::parse();
+    // Scripted answers are for the menu, a command takes its arguments
+    if cli.command.is_none() {
+        load_answers_from_env()?;
+    }
+
+    // Set before loading the config, whose notes are output too
+    let json = cli.output == OutputFormat::Json;
+    set_json_output(json);
+    let mut config = ScillaConfig::load()?;
+    set_plain_output(config.plain_output || json);
+
+    // Arguments run a single command without the menu or its prompts
+    if let Some(command) = cli.command {
+        set_unattended();
+
+        let ctx = ScillaContext::try_from(config)?;
+        if let Err(e) = command.run(&ctx, cli.yes).await {
+            print_error(format!("{e:#}"));
+            exit(1);
+        }
+        return Ok(CommandFlow::Process(()));
+    }
+
     println!(
         "{}",
         style("⚡ Scilla — Hacking Through the Solana Matrix")
@@ -24,10 +59,14 @@ async fn main() -> ScillaResult<()> {
             .cyan()
     );
 
-    let config = ScillaConfig::load()?;
+    if detect_cluster(&mut config).await {
+        config.save()?;
+    }
+
     let mut ctx = ScillaContext::try_from(config)?;
 
     loop {
+        write_menu_header(&mut stdout(), ctx.cluster(), ctx.rpc_url(), ctx.pubkey());
         let command = prompt_for_command()?;
 
         let res = command.process_command(&mut ctx).await;
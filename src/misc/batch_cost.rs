@@ -0,0 +1,224 @@
+use {
+    crate::{
+        constants::{
+            DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT, MAX_COMPUTE_UNIT_LIMIT,
+            MICRO_LAMPORTS_PER_LAMPORT,
+        },
+        context::ScillaContext,
+        misc::{
+            helpers::lamports_to_sol,
+            priority_fee::{ComputeUnitLimit, compute_unit_price},
+        },
+        ui::render::{field_table, stdout},
+    },
+    anyhow::bail,
+    comfy_table::Table,
+    solana_instruction::Instruction,
+    solana_message::Message,
+    solana_pubkey::Pubkey,
+    solana_sdk_ids::compute_budget,
+};
+
+/// A transaction a batch operation is about to send with the configured
+/// compute budget.
+pub struct PlannedTx {
+    pub instructions: Vec<Instruction>,
+}
+
+impl PlannedTx {
+    pub fn new(instructions: Vec<Instruction>) -> Self {
+        Self { instructions }
+    }
+}
+
+/// What a batch operation costs the fee payer, besides the amounts it moves.
+#[derive(Debug, Default, PartialEq)]
+pub struct BatchCost {
+    pub transactions: usize,
+    pub signature_fees: u64,
+    pub priority_fees: u64,
+    pub rent: u64,
+}
+
+impl BatchCost {
+    pub fn total(&self) -> u64 {
+        self.signature_fees + self.priority_fees + self.rent
+    }
+}
+
+/// Compute units `instructions` may use when no compute unit limit is set.
+fn default_compute_unit_limit(instructions: &[Instruction]) -> u64 {
+    let count = instructions
+        .iter()
+        .filter(|ix| ix.program_id != compute_budget::ID)
+        .count() as u64;
+    (count * DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT).min(MAX_COMPUTE_UNIT_LIMIT)
+}
+
+fn batch_cost(
+    payer: &Pubkey,
+    txs: &[PlannedTx],
+    lamports_per_signature: u64,
+    compute_unit_price: u64,
+    compute_unit_limit: ComputeUnitLimit,
+    rent: u64,
+) -> BatchCost {
+    let mut cost = BatchCost {
+        transactions: txs.len(),
+        rent,
+        ..BatchCost::default()
+    };
+
+    for tx in txs {
+        let message = Message::new(&tx.instructions, Some(payer));
+        cost.signature_fees +=
+            u64::from(message.header.num_required_signatures) * lamports_per_signature;
+        // An `auto` limit is only known once simulated, so assume the default
+        let units = match compute_unit_limit {
+            ComputeUnitLimit::Units(units) => u64::from(units),
+            ComputeUnitLimit::None | ComputeUnitLimit::Auto => {
+                default_compute_unit_limit(&tx.instructions)
+            }
+        };
+        cost.priority_fees += (compute_unit_price * units).div_ceil(MICRO_LAMPORTS_PER_LAMPORT);
+    }
+
+    cost
+}
+
+/// Estimates the fees of sending `txs` paid by the context keypair, plus the
+/// `rent` the operation locks up.
+///
+/// Priority fees are charged for the whole compute unit limit, so they are
+/// an upper bound. An `auto` priority fee is estimated once for the batch.
+pub async fn estimate_batch_cost(
+    ctx: &ScillaContext,
+    txs: &[PlannedTx],
+    rent: u64,
+) -> anyhow::Result<BatchCost> {
+    let Some(first) = txs.first() else {
+        return Ok(BatchCost {
+            rent,
+            ..BatchCost::default()
+        });
+    };
+
+    // The fee of a message is only known for a recent blockhash
+    let blockhash = ctx.rpc().get_latest_blockhash().await?;
+    let message = Message::new_with_blockhash(&first.instructions, Some(ctx.pubkey()), &blockhash);
+    let fee = ctx.rpc().get_fee_for_message(&message).await?;
+    let lamports_per_signature = fee / u64::from(message.header.num_required_signatures);
+
+    let instructions: Vec<Instruction> = txs
+        .iter()
+        .flat_map(|tx| tx.instructions.iter().cloned())
+        .collect();
+    let price = compute_unit_price(ctx, &instructions).await?;
+
+    Ok(batch_cost(
+        ctx.pubkey(),
+        txs,
+        lamports_per_signature,
+        price,
+        ctx.compute_unit_limit(),
+        rent,
+    ))
+}
+
+fn batch_cost_table(cost: &BatchCost, spend_limit: Option<u64>) -> Table {
+    let sol = |lamports| format!("{:.9} SOL", lamports_to_sol(lamports));
+
+    field_table([
+        ("Transactions", cost.transactions.to_string()),
+        ("Signature Fees", sol(cost.signature_fees)),
+        ("Priority Fees", sol(cost.priority_fees)),
+        ("Rent", sol(cost.rent)),
+        ("Total", sol(cost.total())),
+        ("Spend Limit", spend_limit.map_or("None".to_string(), sol)),
+    ])
+}
+
+/// Shows what sending `txs` will cost and fails if it's over the configured
+/// batch spend limit. Batch operations call this before sending anything.
+pub async fn check_batch_cost(
+    ctx: &ScillaContext,
+    txs: &[PlannedTx],
+    rent: u64,
+) -> anyhow::Result<()> {
+    let cost = estimate_batch_cost(ctx, txs, rent).await?;
+
+    let mut out = stdout();
+    out.title("ESTIMATED COST");
+    out.table(batch_cost_table(&cost, ctx.batch_spend_limit()));
+
+    if let Some(limit) = ctx.batch_spend_limit()
+        && cost.total() > limit
+    {
+        bail!(
+            "Estimated cost of {:.9} SOL is over the batch spend limit of {:.9} SOL",
+            lamports_to_sol(cost.total()),
+            lamports_to_sol(limit)
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, crate::ui::render::capture, insta::assert_snapshot,
+        solana_instruction::AccountMeta,
+    };
+
+    #[test]
+    fn test_batch_cost() {
+        let payer = Pubkey::new_from_array([1; 32]);
+        let cosigner = Pubkey::new_from_array([2; 32]);
+        let program = Pubkey::new_from_array([3; 32]);
+        let instruction = |signer| {
+            Instruction::new_with_bytes(program, &[], vec![AccountMeta::new(signer, true)])
+        };
+
+        let txs = [
+            PlannedTx::new(vec![instruction(payer), instruction(payer)]),
+            PlannedTx::new(vec![instruction(cosigner)]),
+            PlannedTx::new(vec![instruction(payer)]),
+        ];
+        let cost = batch_cost(
+            &payer,
+            &txs,
+            5_000,
+            10_000,
+            ComputeUnitLimit::None,
+            1_000_000,
+        );
+
+        assert_eq!(
+            cost,
+            BatchCost {
+                transactions: 3,
+                // The second transaction is also signed by the cosigner
+                signature_fees: 20_000,
+                // 400k, 200k and 200k compute units at 10k micro-lamports each
+                priority_fees: 8_000,
+                rent: 1_000_000,
+            }
+        );
+
+        // A fixed limit applies to every transaction whatever it holds
+        let limited = batch_cost(
+            &payer,
+            &txs,
+            5_000,
+            10_000,
+            ComputeUnitLimit::Units(50_000),
+            1_000_000,
+        );
+        assert_eq!(limited.priority_fees, 1_500);
+
+        let output = capture(|out| out.table(batch_cost_table(&cost, Some(2_000_000))));
+
+        assert_snapshot!("batch_cost_table", output);
+    }
+}
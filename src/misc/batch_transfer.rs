@@ -0,0 +1,306 @@
+use {
+    crate::{
+        constants::{MAX_BATCH_INSTRUCTIONS_PER_TX, MAX_TRANSACTION_SIZE},
+        misc::{
+            csv::parse_rows,
+            helpers::{SolAmount, lamports_to_sol, memo_instruction},
+            priority_fee::{set_compute_unit_limit, set_compute_unit_price},
+        },
+        ui::{new_table, render::header_cell},
+    },
+    anyhow::{Context, anyhow, bail},
+    comfy_table::{Cell, Color, Table},
+    solana_instruction::Instruction,
+    solana_message::Message,
+    solana_pubkey::Pubkey,
+    solana_system_interface::instruction::transfer,
+    solana_transaction::Transaction,
+    std::{
+        fs,
+        path::{Path, PathBuf},
+    },
+};
+
+/// One row of a batch transfer file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchTransfer {
+    /// Line of the file the row was read from, to report it by
+    pub line: usize,
+    pub recipient: Pubkey,
+    pub lamports: u64,
+    pub memo: Option<String>,
+}
+
+impl BatchTransfer {
+    /// The transfer from `payer`, followed by its memo if it has one.
+    pub fn instructions(&self, payer: &Pubkey) -> Vec<Instruction> {
+        let mut instructions = vec![transfer(payer, &self.recipient, self.lamports)];
+        if let Some(memo) = &self.memo {
+            instructions.push(memo_instruction(payer, memo));
+        }
+        instructions
+    }
+}
+
+/// Parses a batch transfer file, one `recipient,amount,memo` line per
+/// transfer with the amount in SOL, as described in [`parse_rows`]. The memo
+/// is optional and runs to the end of the line, so it may contain commas.
+pub fn parse_batch_transfers(contents: &str) -> anyhow::Result<Vec<BatchTransfer>> {
+    parse_rows(contents, "batch transfer file", "recipient", parse_line)
+}
+
+fn parse_line(line_number: usize, line: &str) -> anyhow::Result<BatchTransfer> {
+    let fields: Vec<&str> = line.splitn(3, ',').map(str::trim).collect();
+    let (recipient, amount, memo) = match fields[..] {
+        [recipient, amount] => (recipient, amount, ""),
+        [recipient, amount, memo] => (recipient, amount, memo),
+        _ => bail!("expected recipient,amount,memo"),
+    };
+
+    let recipient = recipient
+        .parse()
+        .map_err(|_| anyhow!("invalid recipient {recipient}"))?;
+    let amount: SolAmount = amount.parse()?;
+
+    Ok(BatchTransfer {
+        line: line_number,
+        recipient,
+        lamports: amount.to_lamports(),
+        memo: (!memo.is_empty()).then(|| memo.to_string()),
+    })
+}
+
+/// Splits `transfers` into the transactions they're sent in, packing as
+/// many consecutive transfers into each as fit next to the compute budget
+/// instructions, up to [`MAX_BATCH_INSTRUCTIONS_PER_TX`].
+pub fn pack_transfers<'a>(
+    payer: &Pubkey,
+    transfers: &'a [BatchTransfer],
+) -> anyhow::Result<Vec<&'a [BatchTransfer]>> {
+    let mut batches = Vec::new();
+    let mut start = 0;
+
+    while start < transfers.len() {
+        if !fits_in_transaction(payer, &transfers[start..=start]) {
+            bail!(
+                "line {}: memo is too long to fit in a transaction",
+                transfers[start].line
+            );
+        }
+
+        let mut end = start + 1;
+        while end < transfers.len()
+            && end - start < MAX_BATCH_INSTRUCTIONS_PER_TX
+            && fits_in_transaction(payer, &transfers[start..=end])
+        {
+            end += 1;
+        }
+
+        batches.push(&transfers[start..end]);
+        start = end;
+    }
+
+    Ok(batches)
+}
+
+/// Instructions of every transfer in `batch`, in order.
+pub fn batch_instructions(payer: &Pubkey, batch: &[BatchTransfer]) -> Vec<Instruction> {
+    batch
+        .iter()
+        .flat_map(|transfer| transfer.instructions(payer))
+        .collect()
+}
+
+fn fits_in_transaction(payer: &Pubkey, batch: &[BatchTransfer]) -> bool {
+    // Room is kept for the compute unit limit and price the configuration
+    // may add
+    let mut instructions = vec![set_compute_unit_limit(0), set_compute_unit_price(0)];
+    instructions.extend(batch_instructions(payer, batch));
+
+    let tx = Transaction::new_unsigned(Message::new(&instructions, Some(payer)));
+    bincode::serialize(&tx).is_ok_and(|bytes| bytes.len() <= MAX_TRANSACTION_SIZE)
+}
+
+/// Writes the rows of `contents` whose batch failed to a `.failed.csv` file
+/// next to the batch file, so they can be sent again without repeating the
+/// transfers that landed. `errors` holds the error of each batch.
+pub fn write_failed_rows(
+    path: &Path,
+    contents: &str,
+    batches: &[&[BatchTransfer]],
+    errors: &[Option<String>],
+) -> anyhow::Result<PathBuf> {
+    let path = path.with_extension("failed.csv");
+    fs::write(&path, failed_rows_csv(contents, batches, errors))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+fn failed_rows_csv(
+    contents: &str,
+    batches: &[&[BatchTransfer]],
+    errors: &[Option<String>],
+) -> String {
+    let lines: Vec<&str> = contents.lines().collect();
+
+    let mut csv = String::from("recipient,amount,memo\n");
+    for (batch, _) in batches.iter().zip(errors).filter(|(_, e)| e.is_some()) {
+        for transfer in *batch {
+            csv.push_str(lines[transfer.line - 1].trim());
+            csv.push('\n');
+        }
+    }
+    csv
+}
+
+/// One row per transfer with the outcome of the transaction it was sent in.
+/// `errors` holds the error of each batch, `None` when it landed.
+pub fn batch_transfer_report_table(
+    batches: &[&[BatchTransfer]],
+    errors: &[Option<String>],
+) -> Table {
+    let mut table = new_table();
+    table.set_header(vec![
+        header_cell("Line"),
+        header_cell("Recipient"),
+        header_cell("Amount (SOL)"),
+        header_cell("Memo"),
+        header_cell("Tx"),
+        header_cell("Status"),
+    ]);
+
+    for (idx, (batch, error)) in batches.iter().zip(errors).enumerate() {
+        let status = match error {
+            None => Cell::new("Sent").fg(Color::Green),
+            Some(e) => Cell::new(format!("Failed: {e}")).fg(Color::Red),
+        };
+
+        for transfer in *batch {
+            table.add_row(vec![
+                Cell::new(transfer.line),
+                Cell::new(transfer.recipient),
+                Cell::new(format!("{:.9}", lamports_to_sol(transfer.lamports))),
+                Cell::new(transfer.memo.as_deref().unwrap_or("-")),
+                Cell::new(idx + 1),
+                status.clone(),
+            ]);
+        }
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::ui::render::capture, insta::assert_snapshot};
+
+    fn batch_transfer(line: usize, n: u8, memo: Option<&str>) -> BatchTransfer {
+        BatchTransfer {
+            line,
+            recipient: Pubkey::new_from_array([n; 32]),
+            lamports: 1_000_000_000,
+            memo: memo.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_parse_batch_transfers() -> anyhow::Result<()> {
+        let file = format!(
+            "recipient,amount,memo\n# payroll\n{},1,march, team a\n\n{},1\n",
+            Pubkey::new_from_array([1; 32]),
+            Pubkey::new_from_array([2; 32]),
+        );
+
+        assert_eq!(
+            parse_batch_transfers(&file)?,
+            vec![
+                batch_transfer(3, 1, Some("march, team a")),
+                batch_transfer(5, 2, None),
+            ]
+        );
+
+        let error = parse_batch_transfers(&format!(
+            "not-an-address,1\n{pk},0\n{pk}\n",
+            pk = Pubkey::new_from_array([1; 32])
+        ))
+        .unwrap_err()
+        .to_string();
+        assert!(error.contains("line 1: invalid recipient"));
+        assert!(error.contains("line 2: Amount must be a positive"));
+        assert!(error.contains("line 3: expected recipient,amount,memo"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pack_transfers() -> anyhow::Result<()> {
+        let payer = Pubkey::new_from_array([9; 32]);
+
+        let transfers: Vec<BatchTransfer> = (1..=25)
+            .map(|n| batch_transfer(n as usize, n, None))
+            .collect();
+        let batches = pack_transfers(&payer, &transfers)?;
+        assert!(batches.len() > 1);
+        assert_eq!(batches.iter().map(|batch| batch.len()).sum::<usize>(), 25);
+        assert!(
+            batches
+                .iter()
+                .all(|batch| batch.len() <= MAX_BATCH_INSTRUCTIONS_PER_TX
+                    && fits_in_transaction(&payer, batch))
+        );
+
+        // Long memos leave room for fewer transfers
+        let memo = "m".repeat(400);
+        let transfers: Vec<BatchTransfer> = (1..=3)
+            .map(|n| batch_transfer(n as usize, n, Some(&memo)))
+            .collect();
+        assert_eq!(pack_transfers(&payer, &transfers)?.len(), 2);
+
+        let memo = "m".repeat(MAX_TRANSACTION_SIZE);
+        assert!(pack_transfers(&payer, &[batch_transfer(1, 1, Some(&memo))]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_failed_rows_csv() -> anyhow::Result<()> {
+        let file = format!(
+            "recipient,amount,memo\n{},1,march, team a\n{},1\n{},1,bonus\n",
+            Pubkey::new_from_array([1; 32]),
+            Pubkey::new_from_array([2; 32]),
+            Pubkey::new_from_array([3; 32]),
+        );
+        let transfers = parse_batch_transfers(&file)?;
+        let batches = [&transfers[..2], &transfers[2..]];
+        let errors = [Some("insufficient funds".to_string()), None];
+
+        let failed = failed_rows_csv(&file, &batches, &errors);
+
+        assert_eq!(parse_batch_transfers(&failed)?.len(), 2);
+        assert_eq!(
+            failed,
+            format!(
+                "recipient,amount,memo\n{},1,march, team a\n{},1\n",
+                Pubkey::new_from_array([1; 32]),
+                Pubkey::new_from_array([2; 32]),
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_batch_transfer_report_table() {
+        let transfers = [
+            batch_transfer(2, 1, Some("march payroll")),
+            batch_transfer(3, 2, None),
+            batch_transfer(4, 3, Some("bonus")),
+        ];
+        let batches = [&transfers[..2], &transfers[2..]];
+        let errors = [None, Some("insufficient funds".to_string())];
+
+        let output = capture(|out| out.table(batch_transfer_report_table(&batches, &errors)));
+
+        assert_snapshot!("batch_transfer_report_table", output);
+    }
+}
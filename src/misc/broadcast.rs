@@ -0,0 +1,324 @@
+use {
+    crate::{
+        context::ScillaContext,
+        misc::{rpc_auth::display_url, signer::note_device_confirmation},
+        ui::render::stdout,
+    },
+    anyhow::{anyhow, bail},
+    futures::future::join_all,
+    serde::Serialize,
+    solana_client::{
+        connection_cache::ConnectionCache,
+        nonblocking::tpu_client::TpuClient,
+        send_and_confirm_transactions_in_parallel::{
+            SendAndConfirmConfigV2, send_and_confirm_transactions_in_parallel_v2,
+        },
+    },
+    solana_commitment_config::CommitmentConfig,
+    solana_keypair::Signer,
+    solana_message::Message,
+    solana_quic_client::{QuicConfig, QuicConnectionManager, QuicPool},
+    solana_rpc_client::{nonblocking::rpc_client::RpcClient, rpc_client::SerializableTransaction},
+    solana_rpc_client_api::{
+        client_error::{Error as ClientError, Result as ClientResult},
+        config::RpcSendTransactionConfig,
+    },
+    solana_signature::Signature,
+    solana_tpu_client::tpu_client::TpuClientConfig,
+    std::{collections::BTreeSet, sync::Arc, time::Duration},
+};
+
+/// Delay between signature status polls while waiting for a broadcast
+/// transaction to land.
+const CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// TPU client sending over QUIC, the only protocol leaders accept.
+pub type QuicTpuClient = TpuClient<QuicPool, QuicConnectionManager, QuicConfig>;
+
+/// Sends an already signed transaction to the primary RPC, every configured
+/// broadcast endpoint and, when enabled, the upcoming leader TPUs. All
+/// endpoints report the same signature for the same transaction, so the
+/// acceptances are deduplicated and the transaction is confirmed only once.
+pub async fn broadcast_and_confirm<T>(ctx: &ScillaContext, tx: &T) -> anyhow::Result<Signature>
+where
+    T: SerializableTransaction + Sync,
+{
+    let mut sent = send_to_rpcs(ctx.rpc(), ctx.broadcast_rpcs(), tx).await?;
+
+    if ctx.broadcast_via_tpu() {
+        match send_via_tpu(ctx, tx).await {
+            Ok(()) => sent.accept("leader TPUs", *tx.get_signature()),
+            Err(e) => sent.reject("leader TPUs", e.to_string()),
+        }
+    }
+
+    sent.ensure_accepted()?;
+
+    let mut out = stdout();
+    out.note(format!("{}, awaiting confirmation…", sent.summary()));
+    if !sent.failed.is_empty() {
+        out.warning(format!("Not accepted by {}", sent.failed.join(", ")));
+    }
+
+    wait_for_confirmation(ctx.rpc(), tx).await
+}
+
+/// What the endpoints a transaction was broadcast to made of it.
+#[derive(Debug, Default)]
+struct Sent {
+    signatures: BTreeSet<Signature>,
+    accepted_by: Vec<String>,
+    failed: Vec<String>,
+    last_error: Option<String>,
+}
+
+impl Sent {
+    fn record(&mut self, endpoint: &str, result: ClientResult<Signature>) {
+        match result {
+            Ok(signature) => self.accept(endpoint, signature),
+            Err(e) => self.reject(endpoint, e.to_string()),
+        }
+    }
+
+    fn accept(&mut self, endpoint: &str, signature: Signature) {
+        stdout().note(format!("Accepted by {endpoint}"));
+        self.signatures.insert(signature);
+        self.accepted_by.push(endpoint.to_string());
+    }
+
+    fn reject(&mut self, endpoint: &str, error: String) {
+        stdout().warning(format!("Rejected by {endpoint}: {error}"));
+        self.failed.push(endpoint.to_string());
+        self.last_error = Some(error);
+    }
+
+    fn ensure_accepted(&self) -> anyhow::Result<()> {
+        if self.accepted_by.is_empty() {
+            match &self.last_error {
+                Some(e) => bail!("Transaction was rejected by every endpoint: {e}"),
+                None => bail!("Transaction was not accepted by any endpoint"),
+            }
+        }
+        Ok(())
+    }
+
+    /// How many of the endpoints the transaction was sent to accepted it.
+    fn summary(&self) -> String {
+        format!(
+            "Accepted by {} of {} endpoint(s)",
+            self.accepted_by.len(),
+            self.accepted_by.len() + self.failed.len()
+        )
+    }
+}
+
+/// Sends `tx` to the `primary` RPC, then fans it out to the `extras` unless
+/// the primary's preflight found it failing.
+async fn send_to_rpcs<T>(primary: &RpcClient, extras: &[RpcClient], tx: &T) -> anyhow::Result<Sent>
+where
+    T: SerializableTransaction + Sync,
+{
+    let mut sent = Sent::default();
+
+    let url = display_url(&primary.url()).to_string();
+    let extra_config = match primary
+        .send_transaction_with_config(tx, RpcSendTransactionConfig::default())
+        .await
+    {
+        Ok(signature) => {
+            sent.record(&url, Ok(signature));
+            // Their simulation would only report the transaction as already
+            // processed
+            RpcSendTransactionConfig {
+                skip_preflight: true,
+                ..RpcSendTransactionConfig::default()
+            }
+        }
+        // Other endpoints would land a transaction that's known to fail
+        Err(e) if is_rejected_transaction(&e) => bail!("Transaction was rejected by {url}: {e}"),
+        // The primary couldn't be reached, the others still simulate it
+        Err(e) => {
+            sent.record(&url, Err(e));
+            RpcSendTransactionConfig::default()
+        }
+    };
+
+    let sends = extras.iter().map(|rpc| async move {
+        (
+            display_url(&rpc.url()).to_string(),
+            rpc.send_transaction_with_config(tx, extra_config).await,
+        )
+    });
+    for (url, result) in join_all(sends).await {
+        sent.record(&url, result);
+    }
+
+    Ok(sent)
+}
+
+/// Whether the RPC rejected the transaction itself, rather than failing to
+/// handle the request.
+fn is_rejected_transaction(error: &ClientError) -> bool {
+    error.kind().get_transaction_error().is_some()
+}
+
+async fn send_via_tpu<T>(ctx: &ScillaContext, tx: &T) -> anyhow::Result<()>
+where
+    T: Serialize,
+{
+    let wire_transaction = bincode::serialize(tx)?;
+
+    ctx.tpu_client()
+        .await?
+        .try_send_wire_transaction(wire_transaction)
+        .await?;
+
+    Ok(())
+}
+
+/// Connects a TPU client to the upcoming leaders of the configured cluster.
+/// It follows the leader schedule over the websocket until it's dropped.
+pub async fn new_tpu_client(
+    ctx: &ScillaContext,
+    name: &'static str,
+) -> anyhow::Result<QuicTpuClient> {
+    let rpc_client = Arc::new(ctx.new_rpc_client()?);
+    let websocket_url = ctx.websocket_url()?;
+
+    let ConnectionCache::Quic(cache) = ConnectionCache::new_quic(name, 1) else {
+        bail!("QUIC connection cache is unavailable");
+    };
+
+    Ok(TpuClient::new_with_connection_cache(
+        rpc_client,
+        &websocket_url,
+        TpuClientConfig::default(),
+        cache,
+    )
+    .await?)
+}
+
+/// Polls the primary RPC until the transaction reaches the configured
+/// commitment or its blockhash expires.
+async fn wait_for_confirmation<T>(rpc: &RpcClient, tx: &T) -> anyhow::Result<Signature>
+where
+    T: SerializableTransaction,
+{
+    let signature = *tx.get_signature();
+    let commitment = rpc.commitment();
+
+    loop {
+        if let Some(status) = rpc
+            .get_signature_status_with_commitment(&signature, commitment)
+            .await?
+        {
+            status?;
+            return Ok(signature);
+        }
+
+        let blockhash_valid = rpc
+            .is_blockhash_valid(tx.get_recent_blockhash(), CommitmentConfig::processed())
+            .await?;
+
+        if !blockhash_valid {
+            // The transaction may have landed just before the blockhash expired
+            if let Some(status) = rpc
+                .get_signature_status_with_commitment(&signature, commitment)
+                .await?
+            {
+                status?;
+                return Ok(signature);
+            }
+            bail!("Blockhash expired before transaction {signature} was confirmed");
+        }
+
+        tokio::time::sleep(CONFIRMATION_POLL_INTERVAL).await;
+    }
+}
+
+/// Signs `messages` with the session signer and sends them in parallel
+/// through the leader TPUs, re-signing the ones whose blockhash expires
+/// before they land. Returns the error of each message, in order, `None`
+/// for those that were confirmed.
+pub async fn send_in_parallel(
+    ctx: &ScillaContext,
+    messages: &[Message],
+) -> anyhow::Result<Vec<Option<String>>> {
+    let rpc_client = Arc::new(ctx.new_rpc_client()?);
+    let tpu_client = new_tpu_client(ctx, "scilla_parallel_send").await?;
+
+    let signers: Vec<&dyn Signer> = vec![ctx.signer()];
+    note_device_confirmation(&signers);
+
+    let errors = send_and_confirm_transactions_in_parallel_v2(
+        rpc_client,
+        Some(tpu_client),
+        messages,
+        &signers,
+        SendAndConfirmConfigV2 {
+            resign_txs_count: Some(5),
+            with_spinner: false, // Disable Solana's spinner, we have our own
+            rpc_send_transaction_config: RpcSendTransactionConfig::default(),
+        },
+    )
+    .await
+    .map_err(|e| anyhow!("Parallel send failed: {e}"))?;
+
+    Ok(errors
+        .into_iter()
+        .map(|error| error.map(|e| e.to_string()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_keypair::Keypair,
+        solana_rpc_client_api::client_error::TransactionError,
+        solana_transaction::{Hash, Transaction},
+        std::io,
+    };
+
+    fn signed_tx() -> Transaction {
+        let payer = Keypair::new();
+        Transaction::new_signed_with_payer(&[], Some(&payer.pubkey()), &[&payer], Hash::default())
+    }
+
+    #[tokio::test]
+    async fn test_send_to_rpcs() -> anyhow::Result<()> {
+        let tx = signed_tx();
+        let ok = || RpcClient::new_mock("succeeds".to_string());
+        let down = || RpcClient::new_mock("fails".to_string());
+
+        // Every acceptance is the same signature
+        let sent = send_to_rpcs(&ok(), &[ok(), down()], &tx).await?;
+        assert_eq!(sent.signatures, BTreeSet::from([*tx.get_signature()]));
+        assert_eq!(sent.summary(), "Accepted by 2 of 3 endpoint(s)");
+        assert_eq!(sent.failed, ["MockSender: fails"]);
+        sent.ensure_accepted()?;
+
+        // An unreachable primary still fans out
+        let sent = send_to_rpcs(&down(), &[ok()], &tx).await?;
+        assert_eq!(sent.summary(), "Accepted by 1 of 2 endpoint(s)");
+        sent.ensure_accepted()?;
+
+        let sent = send_to_rpcs(&down(), &[down()], &tx).await?;
+        let error = sent.ensure_accepted().unwrap_err().to_string();
+        assert!(error.starts_with("Transaction was rejected by every endpoint"));
+
+        assert!(Sent::default().ensure_accepted().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_rejected_transaction() {
+        assert!(is_rejected_transaction(&ClientError::from(
+            TransactionError::InsufficientFundsForFee
+        )));
+        assert!(!is_rejected_transaction(&ClientError::from(
+            io::Error::other("connection refused")
+        )));
+    }
+}
@@ -0,0 +1,75 @@
+use anyhow::bail;
+
+/// Parses the rows of a CSV file with `parse_row`, which is given the line
+/// number of each row along with its text. `name` is what the file is called
+/// in errors.
+///
+/// An optional header line, whose first column reads `header`, empty lines
+/// and `#` comments are skipped. Every invalid row is reported at once so the
+/// file can be fixed in one go.
+pub fn parse_rows<T>(
+    contents: &str,
+    name: &str,
+    header: &str,
+    mut parse_row: impl FnMut(usize, &str) -> anyhow::Result<T>,
+) -> anyhow::Result<Vec<T>> {
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+
+    for (idx, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if rows.is_empty() && errors.is_empty() && is_header(line, header) {
+            continue;
+        }
+
+        match parse_row(idx + 1, line) {
+            Ok(row) => rows.push(row),
+            Err(e) => errors.push(format!("line {}: {e}", idx + 1)),
+        }
+    }
+
+    if !errors.is_empty() {
+        bail!("Invalid {name}:\n{}", errors.join("\n"));
+    }
+    if rows.is_empty() {
+        bail!("The {name} is empty");
+    }
+
+    Ok(rows)
+}
+
+fn is_header(line: &str, header: &str) -> bool {
+    line.split(',')
+        .next()
+        .is_some_and(|field| field.trim().eq_ignore_ascii_case(header))
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, anyhow::anyhow};
+
+    fn parse_number(_: usize, line: &str) -> anyhow::Result<u64> {
+        line.parse().map_err(|_| anyhow!("invalid number {line}"))
+    }
+
+    #[test]
+    fn test_parse_rows() -> anyhow::Result<()> {
+        let file = "value\n# comment\n\n1\n 2 \n";
+        assert_eq!(parse_rows(file, "list", "value", parse_number)?, vec![1, 2]);
+
+        let error = parse_rows("1\nvalue\nx\n", "list", "value", parse_number)
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            error,
+            "Invalid list:\nline 2: invalid number value\nline 3: invalid number x"
+        );
+
+        assert!(parse_rows("value\n", "list", "value", parse_number).is_err());
+
+        Ok(())
+    }
+}
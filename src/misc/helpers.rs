@@ -1,15 +1,28 @@
 use {
-    crate::{ScillaContext, constants::LAMPORTS_PER_SOL},
+    crate::{
+        ScillaContext,
+        constants::{LAMPORTS_PER_SOL, MEMO_PROGRAM_ID},
+        misc::{
+            broadcast::broadcast_and_confirm, offline::export_unsigned_tx,
+            preview::confirm_tx_preview, priority_fee::compute_budget_instructions,
+            send_guard::message_fingerprint, signer::note_device_confirmation,
+        },
+        prompt::prompt_confirmation,
+        ui::render::stdout,
+    },
     anyhow::{Context, anyhow, bail},
     base64::Engine,
     bincode::Options,
+    reqwest::Url,
     solana_account::Account,
+    solana_account_decoder_client_types::UiDataSliceConfig,
     solana_epoch_info::EpochInfo,
-    solana_instruction::Instruction,
+    solana_instruction::{AccountMeta, Instruction},
     solana_keypair::{EncodableKey, Keypair, Signature, Signer},
     solana_message::Message,
     solana_pubkey::Pubkey,
-    solana_transaction::Transaction,
+    solana_rpc_client_api::config::RpcProgramAccountsConfig,
+    solana_transaction::{Hash, Transaction},
     std::{path::Path, str::FromStr},
     tokio::try_join,
 };
@@ -69,7 +82,14 @@ impl FromStr for SolAmount {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let sol = trim_and_parse::<f64>(s, "amount")?
             .ok_or_else(|| anyhow!("Amount cannot be empty. Please enter a SOL amount"))?;
+        SolAmount::try_from(sol)
+    }
+}
 
+impl TryFrom<f64> for SolAmount {
+    type Error = anyhow::Error;
+
+    fn try_from(sol: f64) -> Result<Self, Self::Error> {
         if sol <= 0.0 || !sol.is_finite() {
             bail!("Amount must be a positive finite number, got {sol}");
         }
@@ -80,6 +100,39 @@ impl FromStr for SolAmount {
     }
 }
 
+/// Byte range of account data to fetch, entered as `offset:length`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DataSlice {
+    pub offset: usize,
+    pub length: usize,
+}
+
+impl FromStr for DataSlice {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (offset, length) = s
+            .trim()
+            .split_once(':')
+            .ok_or_else(|| anyhow!("Data slice must be offset:length, got {s}"))?;
+        let offset = trim_and_parse(offset, "offset")?
+            .ok_or_else(|| anyhow!("Data slice offset cannot be empty"))?;
+        let length = trim_and_parse(length, "length")?
+            .ok_or_else(|| anyhow!("Data slice length cannot be empty"))?;
+
+        Ok(DataSlice { offset, length })
+    }
+}
+
+impl From<DataSlice> for UiDataSliceConfig {
+    fn from(slice: DataSlice) -> Self {
+        UiDataSliceConfig {
+            offset: slice.offset,
+            length: slice.length,
+        }
+    }
+}
+
 pub async fn check_minimum_balance(
     ctx: &ScillaContext,
     payer: &Pubkey,
@@ -118,16 +171,153 @@ pub async fn build_and_send_tx(
     instruction: &[Instruction],
     signers: &[&dyn Signer],
 ) -> anyhow::Result<Signature> {
-    let recent_blockhash = ctx.rpc().get_latest_blockhash().await?;
-    let message = Message::new(instruction, Some(ctx.pubkey()));
+    let tx = sign_tx(ctx, instruction, signers).await?;
+    send_signed_tx(ctx, &tx).await
+}
+
+/// Like [`build_and_send_tx`], for commands that can be signed offline.
+/// During an offline signing session the transaction is written out for the
+/// air-gapped machine instead, and `None` is returned.
+pub async fn build_and_send_or_export_tx(
+    ctx: &ScillaContext,
+    instruction: &[Instruction],
+    signers: &[&dyn Signer],
+) -> anyhow::Result<Option<Signature>> {
+    let Some(offline) = ctx.offline_signing() else {
+        return build_and_send_tx(ctx, instruction, signers).await.map(Some);
+    };
+
+    let path = export_unsigned_tx(ctx, offline, instruction, signers).await?;
+
+    let mut out = stdout();
+    out.success("Transaction written for offline signing, it was not sent");
+    out.note(format!("File: {}", path.display()));
+    out.note("Sign it with Transaction > Sign Offline Transaction on the air-gapped machine");
+
+    Ok(None)
+}
+
+/// `instruction` with the configured compute unit limit and priority fee
+/// prepended.
+pub async fn with_compute_budget(
+    ctx: &ScillaContext,
+    instruction: &[Instruction],
+) -> anyhow::Result<Vec<Instruction>> {
+    let mut instructions = compute_budget_instructions(ctx, instruction).await?;
+    instructions.extend_from_slice(instruction);
+    Ok(instructions)
+}
+
+/// Builds a transaction paid by the session keypair and signs it with the
+/// latest blockhash, without sending it. The configured compute unit limit
+/// and priority fee are prepended to `instruction`. Sending an identical
+/// transaction again is confirmed first and, when previews are on, it's
+/// simulated and confirmed before it's signed.
+pub async fn sign_tx(
+    ctx: &ScillaContext,
+    instruction: &[Instruction],
+    signers: &[&dyn Signer],
+) -> anyhow::Result<Transaction> {
+    let fingerprint = message_fingerprint(ctx.pubkey(), instruction);
+    if let Some((previous, elapsed)) = ctx.recent_sends().find(fingerprint) {
+        let mut out = stdout();
+        out.warning(format!(
+            "\nAn identical transaction was sent {}s ago and may still land.",
+            elapsed.as_secs()
+        ));
+        if let Some(previous) = previous {
+            out.warning(format!("Previous signature: {previous}"));
+        }
+
+        if !prompt_confirmation("Send this identical transaction again?") {
+            bail!("Duplicate transaction not sent");
+        }
+    }
+
+    let message = tx_message(ctx, instruction).await?;
+    if ctx.preview_transactions() {
+        confirm_tx_preview(ctx, &message).await?;
+    }
+    let tx = sign_message(message, signers, ctx.rpc().get_latest_blockhash().await?)?;
+
+    // Recorded before sending so a timed-out send still counts as a duplicate
+    ctx.recent_sends()
+        .record(fingerprint, Some(tx.signatures[0]));
+
+    Ok(tx)
+}
+
+/// Message paid by the session keypair, with the configured compute unit
+/// limit and priority fee prepended to `instruction`.
+async fn tx_message(ctx: &ScillaContext, instruction: &[Instruction]) -> anyhow::Result<Message> {
+    if ctx.offline_signing().is_some() {
+        bail!("This command can't be signed offline, stop offline signing to run it");
+    }
+
+    let instructions = with_compute_budget(ctx, instruction).await?;
+    Ok(Message::new(&instructions, Some(ctx.pubkey())))
+}
+
+fn sign_message(
+    message: Message,
+    signers: &[&dyn Signer],
+    recent_blockhash: Hash,
+) -> anyhow::Result<Transaction> {
     let mut tx = Transaction::new_unsigned(message);
+    note_device_confirmation(signers);
     tx.try_sign(&signers.to_vec(), recent_blockhash)?;
 
-    let signature = ctx.rpc().send_and_confirm_transaction(&tx).await?;
+    Ok(tx)
+}
+
+/// Sends a signed transaction and waits for it to confirm, fanning it out to
+/// the broadcast endpoints when configured.
+pub async fn send_signed_tx(ctx: &ScillaContext, tx: &Transaction) -> anyhow::Result<Signature> {
+    if ctx.broadcast_enabled() {
+        return broadcast_and_confirm(ctx, tx).await;
+    }
+
+    let signature = ctx.rpc().send_and_confirm_transaction(tx).await?;
 
     Ok(signature)
 }
 
+/// Builds a memo program instruction signed by `signer`.
+pub fn memo_instruction(signer: &Pubkey, memo: &str) -> Instruction {
+    Instruction::new_with_bytes(
+        Pubkey::from_str_const(MEMO_PROGRAM_ID),
+        memo.as_bytes(),
+        vec![AccountMeta::new_readonly(*signer, true)],
+    )
+}
+
+/// Derives the websocket endpoint served alongside an RPC URL, following the
+/// validator convention of listening on the RPC port + 1. The port is kept
+/// when it can't be bumped.
+pub fn websocket_url_from_rpc(rpc_url: &str) -> String {
+    let Ok(mut url) = Url::parse(rpc_url) else {
+        return rpc_url.to_string();
+    };
+
+    let scheme = match url.scheme() {
+        "https" => "wss",
+        "http" => "ws",
+        _ => return rpc_url.to_string(),
+    };
+    // Neither setter fails on an http(s) URL, which always has a host
+    let _ = url.set_scheme(scheme);
+    if let Some(port) = url.port().and_then(|port| port.checked_add(1)) {
+        let _ = url.set_port(Some(port));
+    }
+
+    // `Url` always serializes a path, drop the one the RPC URL didn't have
+    let url = url.to_string();
+    match url.strip_suffix('/') {
+        Some(base) if !rpc_url.ends_with('/') => base.to_string(),
+        _ => url,
+    }
+}
+
 /// Fetches account data and current epoch info in parallel.
 pub async fn fetch_account_with_epoch(
     ctx: &ScillaContext,
@@ -149,6 +339,26 @@ pub async fn fetch_account_with_epoch(
     )
 }
 
+/// Accounts owned by `program_id` that match `config`. The RPC returns them
+/// in their UI encoding, so `config` must ask for a binary one to decode.
+pub async fn fetch_owned_accounts(
+    ctx: &ScillaContext,
+    program_id: &Pubkey,
+    config: RpcProgramAccountsConfig,
+) -> anyhow::Result<Vec<(Pubkey, Account)>> {
+    ctx.rpc()
+        .get_program_ui_accounts_with_config(program_id, config)
+        .await?
+        .into_iter()
+        .map(|(pubkey, account)| {
+            let account = account
+                .decode()
+                .ok_or_else(|| anyhow!("Failed to decode account {pubkey}"))?;
+            Ok((pubkey, account))
+        })
+        .collect()
+}
+
 /// Generic helper to deserialize bincode data with consistent error
 /// context
 pub fn bincode_deserialize<T>(data: &[u8], ctx: &str) -> anyhow::Result<T>
@@ -215,6 +425,41 @@ mod tests {
         solana_transaction::versioned::VersionedTransaction,
     };
 
+    #[test]
+    fn test_websocket_url_from_rpc() {
+        assert_eq!(
+            websocket_url_from_rpc("https://api.devnet.solana.com"),
+            "wss://api.devnet.solana.com"
+        );
+        assert_eq!(
+            websocket_url_from_rpc("http://localhost:8899"),
+            "ws://localhost:8900"
+        );
+        assert_eq!(
+            websocket_url_from_rpc("http://localhost:8899/rpc?key=1"),
+            "ws://localhost:8900/rpc?key=1"
+        );
+        assert_eq!(
+            websocket_url_from_rpc("https://rpc.example.com:65535/"),
+            "wss://rpc.example.com:65535/"
+        );
+        assert_eq!(websocket_url_from_rpc("not a url"), "not a url");
+    }
+
+    #[test]
+    fn test_data_slice_from_str() {
+        assert_eq!(
+            " 64 : 32 ".parse::<DataSlice>().ok(),
+            Some(DataSlice {
+                offset: 64,
+                length: 32
+            })
+        );
+        assert!("64".parse::<DataSlice>().is_err());
+        assert!("64:".parse::<DataSlice>().is_err());
+        assert!("-1:32".parse::<DataSlice>().is_err());
+    }
+
     #[test]
     fn test_lamports_to_sol_exact_one_sol() {
         assert_eq!(lamports_to_sol(1_000_000_000), 1.0);
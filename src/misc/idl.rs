@@ -0,0 +1,333 @@
+use {
+    anyhow::{Context, anyhow, bail},
+    serde_json::{Map, Value, json},
+    sha2::{Digest, Sha256},
+    solana_pubkey::Pubkey,
+    std::{fs, path::Path},
+};
+
+const DISCRIMINATOR_LEN: usize = 8;
+
+/// Minimal Anchor IDL reader able to decode account data.
+///
+/// Both the legacy (< 0.30) and the current IDL formats are supported: legacy
+/// IDLs inline the account type and derive the discriminator from the account
+/// name, newer ones list explicit discriminators and keep types in `types`.
+pub struct AnchorIdl {
+    accounts: Vec<Value>,
+    types: Vec<Value>,
+}
+
+impl AnchorIdl {
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let data = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read IDL from {}", path.display()))?;
+        Self::from_json(&data)
+    }
+
+    pub fn from_json(data: &str) -> anyhow::Result<Self> {
+        let idl: Value = serde_json::from_str(data).context("Failed to parse IDL JSON")?;
+
+        let accounts = idl
+            .get("accounts")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+        if accounts.is_empty() {
+            bail!("IDL does not define any accounts");
+        }
+
+        let types = idl
+            .get("types")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+
+        Ok(Self { accounts, types })
+    }
+
+    /// Matches the account discriminator against the IDL and decodes the
+    /// remaining bytes, returning the account type name and its fields.
+    pub fn decode_account(&self, data: &[u8]) -> anyhow::Result<(String, Value)> {
+        if data.len() < DISCRIMINATOR_LEN {
+            bail!("Account data is too short to contain an Anchor discriminator");
+        }
+        let (discriminator, mut body) = data.split_at(DISCRIMINATOR_LEN);
+
+        for account in &self.accounts {
+            let name = type_name(account)?;
+
+            let expected = match account.get("discriminator").and_then(Value::as_array) {
+                Some(bytes) => bytes
+                    .iter()
+                    .map(|b| b.as_u64().map(|b| b as u8))
+                    .collect::<Option<Vec<u8>>>()
+                    .ok_or_else(|| anyhow!("Invalid discriminator for account {name}"))?,
+                None => account_discriminator(name).to_vec(),
+            };
+            if expected != discriminator {
+                continue;
+            }
+
+            let type_def = match account.get("type") {
+                Some(ty) => ty,
+                None => self.type_def(name)?,
+            };
+            let decoded = self.decode_type_def(type_def, &mut body)?;
+
+            return Ok((name.to_string(), decoded));
+        }
+
+        bail!("Account discriminator does not match any account in the IDL")
+    }
+
+    fn type_def(&self, name: &str) -> anyhow::Result<&Value> {
+        self.types
+            .iter()
+            .find(|ty| ty.get("name").and_then(Value::as_str) == Some(name))
+            .and_then(|ty| ty.get("type"))
+            .ok_or_else(|| anyhow!("Type {name} is not defined in the IDL"))
+    }
+
+    fn decode_type_def(&self, type_def: &Value, data: &mut &[u8]) -> anyhow::Result<Value> {
+        match type_def.get("kind").and_then(Value::as_str) {
+            Some("struct") => self.decode_fields(type_def.get("fields"), data),
+            Some("enum") => {
+                let variants = type_def
+                    .get("variants")
+                    .and_then(Value::as_array)
+                    .ok_or_else(|| anyhow!("Enum type has no variants"))?;
+                let index = take(data, 1)?[0] as usize;
+                let variant = variants
+                    .get(index)
+                    .ok_or_else(|| anyhow!("Enum variant index {index} out of range"))?;
+                let name = type_name(variant)?;
+
+                match variant.get("fields") {
+                    Some(fields) => {
+                        let mut object = Map::new();
+                        object.insert(name.to_string(), self.decode_fields(Some(fields), data)?);
+                        Ok(Value::Object(object))
+                    }
+                    None => Ok(Value::String(name.to_string())),
+                }
+            }
+            Some(kind) => bail!("Unsupported IDL type kind: {kind}"),
+            None => bail!("IDL type definition is missing its kind"),
+        }
+    }
+
+    /// Decodes named (`[{name, type}]`) or tuple (`[type]`) fields.
+    fn decode_fields(&self, fields: Option<&Value>, data: &mut &[u8]) -> anyhow::Result<Value> {
+        let Some(fields) = fields.and_then(Value::as_array) else {
+            return Ok(Value::Object(Map::new()));
+        };
+
+        if fields.iter().all(|field| field.get("name").is_some()) {
+            let mut object = Map::new();
+            for field in fields {
+                let ty = field
+                    .get("type")
+                    .ok_or_else(|| anyhow!("Field is missing its type"))?;
+                object.insert(type_name(field)?.to_string(), self.decode(ty, data)?);
+            }
+            Ok(Value::Object(object))
+        } else {
+            fields
+                .iter()
+                .map(|ty| self.decode(ty, data))
+                .collect::<anyhow::Result<Vec<_>>>()
+                .map(Value::Array)
+        }
+    }
+
+    fn decode(&self, ty: &Value, data: &mut &[u8]) -> anyhow::Result<Value> {
+        if let Some(primitive) = ty.as_str() {
+            return decode_primitive(primitive, data);
+        }
+
+        if let Some(inner) = ty.get("vec") {
+            let len = u32::from_le_bytes(take(data, 4)?.try_into()?);
+            return (0..len)
+                .map(|_| self.decode(inner, data))
+                .collect::<anyhow::Result<Vec<_>>>()
+                .map(Value::Array);
+        }
+
+        if let Some(inner) = ty.get("option") {
+            return match take(data, 1)?[0] {
+                0 => Ok(Value::Null),
+                _ => self.decode(inner, data),
+            };
+        }
+
+        if let Some([inner, len]) = ty.get("array").and_then(Value::as_array).map(Vec::as_slice) {
+            let len = len
+                .as_u64()
+                .ok_or_else(|| anyhow!("Unsupported array length: {len}"))?;
+            return (0..len)
+                .map(|_| self.decode(inner, data))
+                .collect::<anyhow::Result<Vec<_>>>()
+                .map(Value::Array);
+        }
+
+        if let Some(defined) = ty.get("defined") {
+            // Legacy IDLs use `"defined": "Name"`, newer ones `{ "name": "Name" }`
+            let name = defined
+                .as_str()
+                .or_else(|| defined.get("name").and_then(Value::as_str))
+                .ok_or_else(|| anyhow!("Invalid defined type: {defined}"))?;
+            return self.decode_type_def(self.type_def(name)?, data);
+        }
+
+        bail!("Unsupported IDL type: {ty}")
+    }
+}
+
+/// Anchor account discriminator: first 8 bytes of `sha256("account:<Name>")`.
+pub fn account_discriminator(name: &str) -> [u8; DISCRIMINATOR_LEN] {
+    let hash = Sha256::digest(format!("account:{name}"));
+    let mut discriminator = [0u8; DISCRIMINATOR_LEN];
+    discriminator.copy_from_slice(&hash[..DISCRIMINATOR_LEN]);
+    discriminator
+}
+
+fn type_name(value: &Value) -> anyhow::Result<&str> {
+    value
+        .get("name")
+        .and_then(Value::as_str)
+        .ok_or_else(|| anyhow!("IDL entry is missing its name"))
+}
+
+fn take<'a>(data: &mut &'a [u8], len: usize) -> anyhow::Result<&'a [u8]> {
+    if data.len() < len {
+        bail!("Unexpected end of account data");
+    }
+    let (head, tail) = data.split_at(len);
+    *data = tail;
+    Ok(head)
+}
+
+fn decode_primitive(primitive: &str, data: &mut &[u8]) -> anyhow::Result<Value> {
+    macro_rules! int {
+        ($ty:ty) => {
+            <$ty>::from_le_bytes(take(data, size_of::<$ty>())?.try_into()?)
+        };
+    }
+
+    let value = match primitive {
+        "bool" => Value::Bool(take(data, 1)?[0] != 0),
+        "u8" => json!(int!(u8)),
+        "i8" => json!(int!(i8)),
+        "u16" => json!(int!(u16)),
+        "i16" => json!(int!(i16)),
+        "u32" => json!(int!(u32)),
+        "i32" => json!(int!(i32)),
+        "u64" => json!(int!(u64)),
+        "i64" => json!(int!(i64)),
+        // JSON numbers cannot hold 128-bit integers losslessly
+        "u128" => Value::String(int!(u128).to_string()),
+        "i128" => Value::String(int!(i128).to_string()),
+        "f32" => json!(f32::from_le_bytes(take(data, 4)?.try_into()?)),
+        "f64" => json!(f64::from_le_bytes(take(data, 8)?.try_into()?)),
+        "publicKey" | "pubkey" => {
+            let bytes: [u8; 32] = take(data, 32)?.try_into()?;
+            Value::String(Pubkey::new_from_array(bytes).to_string())
+        }
+        "string" => {
+            let len = u32::from_le_bytes(take(data, 4)?.try_into()?) as usize;
+            Value::String(String::from_utf8(take(data, len)?.to_vec())?)
+        }
+        "bytes" => {
+            let len = u32::from_le_bytes(take(data, 4)?.try_into()?) as usize;
+            Value::String(bs58::encode(take(data, len)?).into_string())
+        }
+        other => bail!("Unsupported IDL primitive type: {other}"),
+    };
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counter_data(discriminator: [u8; 8]) -> Vec<u8> {
+        let mut data = discriminator.to_vec();
+        data.extend_from_slice(&[9; 32]); // authority
+        data.extend_from_slice(&42u64.to_le_bytes()); // count
+        data.push(1); // Some(label)
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(b"hi");
+        data
+    }
+
+    #[test]
+    fn test_decode_account_legacy_idl() -> anyhow::Result<()> {
+        let idl = AnchorIdl::from_json(
+            r#"{
+                "accounts": [{
+                    "name": "Counter",
+                    "type": {
+                        "kind": "struct",
+                        "fields": [
+                            { "name": "authority", "type": "publicKey" },
+                            { "name": "count", "type": "u64" },
+                            { "name": "label", "type": { "option": "string" } }
+                        ]
+                    }
+                }]
+            }"#,
+        )?;
+
+        let (name, decoded) =
+            idl.decode_account(&counter_data(account_discriminator("Counter")))?;
+
+        assert_eq!(name, "Counter");
+        assert_eq!(
+            decoded["authority"],
+            Pubkey::new_from_array([9; 32]).to_string()
+        );
+        assert_eq!(decoded["count"], 42);
+        assert_eq!(decoded["label"], "hi");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_account_explicit_discriminator() -> anyhow::Result<()> {
+        let idl = AnchorIdl::from_json(
+            r#"{
+                "accounts": [{ "name": "Counter", "discriminator": [1, 2, 3, 4, 5, 6, 7, 8] }],
+                "types": [{
+                    "name": "Counter",
+                    "type": {
+                        "kind": "struct",
+                        "fields": [
+                            { "name": "authority", "type": "pubkey" },
+                            { "name": "count", "type": "u64" },
+                            { "name": "label", "type": { "option": "string" } }
+                        ]
+                    }
+                }]
+            }"#,
+        )?;
+
+        let (_, decoded) = idl.decode_account(&counter_data([1, 2, 3, 4, 5, 6, 7, 8]))?;
+
+        assert_eq!(decoded["count"], 42);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_account_unknown_discriminator() -> anyhow::Result<()> {
+        let idl = AnchorIdl::from_json(
+            r#"{ "accounts": [{ "name": "Counter", "discriminator": [1, 1, 1, 1, 1, 1, 1, 1] }] }"#,
+        )?;
+
+        assert!(idl.decode_account(&counter_data([0; 8])).is_err());
+
+        Ok(())
+    }
+}
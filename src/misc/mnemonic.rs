@@ -0,0 +1,119 @@
+use {
+    anyhow::{anyhow, bail},
+    bip39::{Language, Mnemonic},
+    solana_derivation_path::DerivationPath,
+    solana_keypair::{Keypair, Signer, seed_derivable::keypair_from_seed_and_derivation_path},
+    solana_pubkey::Pubkey,
+    solana_seed_phrase::generate_seed_from_seed_phrase_and_passphrase,
+};
+
+/// BIP39 phrases are 12 to 24 words, in steps of 3.
+const MNEMONIC_WORD_COUNTS: [usize; 5] = [12, 15, 18, 21, 24];
+
+/// Turns a BIP39 seed phrase and optional passphrase into a wallet seed. The
+/// phrase must use the English wordlist and pass its checksum, so a typo is
+/// caught instead of deriving an unrelated wallet.
+pub fn seed_from_mnemonic(phrase: &str, passphrase: &str) -> anyhow::Result<Vec<u8>> {
+    let words: Vec<&str> = phrase.split_whitespace().collect();
+
+    if !MNEMONIC_WORD_COUNTS.contains(&words.len()) {
+        bail!(
+            "Seed phrase has {} words, expected 12, 15, 18, 21 or 24",
+            words.len()
+        );
+    }
+
+    let mnemonic =
+        Mnemonic::parse_in_normalized(Language::English, &words.join(" ").to_lowercase())
+            .map_err(|e| anyhow!("Invalid seed phrase: {e}"))?;
+
+    Ok(generate_seed_from_seed_phrase_and_passphrase(
+        &mnemonic.to_string(),
+        passphrase,
+    ))
+}
+
+/// Solana's standard path for an account index: `m/44'/501'/<account>'/0'`.
+pub fn account_derivation_path(account: u32) -> DerivationPath {
+    DerivationPath::new_bip44(Some(account), Some(0))
+}
+
+pub fn parse_derivation_path(path: &str) -> anyhow::Result<DerivationPath> {
+    DerivationPath::from_absolute_path_str(path.trim())
+        .map_err(|e| anyhow!("Invalid derivation path {path}: {e}"))
+}
+
+/// `path` in the `m/44'/501'/0'/0'` form it's entered in. `DerivationPath`
+/// only prints that way through `Debug`.
+pub fn format_derivation_path(path: &DerivationPath) -> String {
+    format!("{path:?}")
+}
+
+pub fn derive_keypair(seed: &[u8], path: &DerivationPath) -> anyhow::Result<Keypair> {
+    keypair_from_seed_and_derivation_path(seed, Some(path.clone()))
+        .map_err(|e| anyhow!("Failed to derive keypair: {e}"))
+}
+
+/// Index of the first of `accounts` standard account paths deriving
+/// `expected` from `seed`, for telling where a backup's key actually is.
+pub fn find_account_index(
+    seed: &[u8],
+    expected: &Pubkey,
+    accounts: u32,
+) -> anyhow::Result<Option<u32>> {
+    for account in 0..accounts {
+        if derive_keypair(seed, &account_derivation_path(account))?.pubkey() == *expected {
+            return Ok(Some(account));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PHRASE: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+                          abandon abandon about";
+
+    #[test]
+    fn test_seed_from_mnemonic_rejects_invalid_phrases() {
+        assert!(seed_from_mnemonic("abandon about", "").is_err());
+        // A word outside the wordlist, then a failing checksum
+        assert!(seed_from_mnemonic(&PHRASE.replace("about", "abut"), "").is_err());
+        assert!(seed_from_mnemonic(&PHRASE.replace("about", "abandon"), "").is_err());
+        assert!(seed_from_mnemonic(&PHRASE.to_uppercase(), "").is_ok());
+    }
+
+    #[test]
+    fn test_derive_keypair_per_account() -> anyhow::Result<()> {
+        let seed = seed_from_mnemonic(PHRASE, "")?;
+
+        let first = derive_keypair(&seed, &account_derivation_path(0))?;
+        let again = derive_keypair(&seed, &parse_derivation_path("m/44'/501'/0'/0'")?)?;
+        let second = derive_keypair(&seed, &account_derivation_path(1))?;
+
+        assert_eq!(first.pubkey(), again.pubkey());
+        assert_ne!(first.pubkey(), second.pubkey());
+        assert_eq!(
+            format_derivation_path(&account_derivation_path(1)),
+            "m/44'/501'/1'/0'"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_account_index() -> anyhow::Result<()> {
+        let seed = seed_from_mnemonic(PHRASE, "")?;
+        let third = derive_keypair(&seed, &account_derivation_path(2))?.pubkey();
+
+        assert_eq!(find_account_index(&seed, &third, 5)?, Some(2));
+        assert_eq!(find_account_index(&seed, &third, 2)?, None);
+
+        let with_passphrase = seed_from_mnemonic(PHRASE, "backup")?;
+        assert_eq!(find_account_index(&with_passphrase, &third, 5)?, None);
+
+        Ok(())
+    }
+}
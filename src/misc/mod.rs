@@ -1 +1,21 @@
+pub mod batch_cost;
+pub mod batch_transfer;
+pub mod broadcast;
+pub mod csv;
 pub mod helpers;
+pub mod idl;
+pub mod mnemonic;
+pub mod offline;
+pub mod preview;
+pub mod priority_fee;
+pub mod program_logs;
+pub mod rpc_auth;
+pub mod security_txt;
+pub mod send_guard;
+pub mod signer;
+pub mod siws;
+pub mod sns;
+pub mod stream;
+pub mod validator_info;
+pub mod whitelist;
+pub mod withdrawals;
@@ -0,0 +1,331 @@
+use {
+    crate::{
+        context::ScillaContext,
+        misc::{
+            helpers::{bincode_deserialize, decode_base64, with_compute_budget},
+            signer::note_device_confirmation,
+        },
+        ui::{
+            new_table,
+            render::{field_table, header_cell},
+        },
+    },
+    anyhow::{Context, bail},
+    base64::Engine,
+    comfy_table::{Cell, Color, Table},
+    solana_account::Account,
+    solana_instruction::Instruction,
+    solana_keypair::{Signature, Signer},
+    solana_message::Message,
+    solana_nonce::{
+        state::{Data as NonceData, State as NonceState},
+        versions::Versions,
+    },
+    solana_pubkey::Pubkey,
+    solana_sdk_ids::system_program,
+    solana_system_interface::instruction::advance_nonce_account,
+    solana_transaction::Transaction,
+    std::{
+        fs,
+        path::{Path, PathBuf},
+    },
+};
+
+/// Discriminant of the system program's `AdvanceNonceAccount` instruction.
+const ADVANCE_NONCE_DISCRIMINANT: [u8; 4] = [4, 0, 0, 0];
+
+/// Offline signing session started from Transaction > Offline Signing.
+/// While it runs, commands that support it write their transactions out
+/// unsigned instead of sending them.
+#[derive(Debug, Clone)]
+pub struct OfflineSigning {
+    /// Cold wallet paying for and authorizing the transactions
+    pub signer: Pubkey,
+    /// Nonce account whose durable nonce replaces the recent blockhash
+    pub nonce_account: Pubkey,
+    pub nonce_authority: Pubkey,
+    /// Directory unsigned transactions are written to
+    pub directory: PathBuf,
+}
+
+/// `nonce_account` and the nonce state it holds.
+pub async fn fetch_nonce_account(
+    ctx: &ScillaContext,
+    nonce_account: &Pubkey,
+) -> anyhow::Result<(Account, NonceData)> {
+    let account = ctx.rpc().get_account(nonce_account).await?;
+    if account.owner != system_program::id() {
+        bail!("{nonce_account} is not a nonce account");
+    }
+
+    let versions = bincode_deserialize::<Versions>(&account.data, "nonce account data")?;
+    let NonceState::Initialized(data) = versions.state() else {
+        bail!("{nonce_account} is not an initialized nonce account");
+    };
+    let data = data.clone();
+    Ok((account, data))
+}
+
+/// The durable nonce stored in `nonce_account`.
+pub async fn fetch_durable_nonce(
+    ctx: &ScillaContext,
+    nonce_account: &Pubkey,
+) -> anyhow::Result<NonceData> {
+    let (_, data) = fetch_nonce_account(ctx, nonce_account).await?;
+    Ok(data)
+}
+
+/// Fails unless `authority` is the authority of the nonce account holding
+/// `nonce`.
+pub fn check_nonce_authority(
+    nonce_account: &Pubkey,
+    nonce: &NonceData,
+    authority: &Pubkey,
+) -> anyhow::Result<()> {
+    if nonce.authority != *authority {
+        bail!(
+            "{authority} is not the authority of nonce account {nonce_account}, {} is",
+            nonce.authority
+        );
+    }
+    Ok(())
+}
+
+/// Builds a transaction on the session's durable nonce, signs it with those
+/// of `signers` it needs and writes it to the session directory for the
+/// air-gapped machine. Returns where it was written.
+pub async fn export_unsigned_tx(
+    ctx: &ScillaContext,
+    offline: &OfflineSigning,
+    instruction: &[Instruction],
+    signers: &[&dyn Signer],
+) -> anyhow::Result<PathBuf> {
+    let nonce = fetch_durable_nonce(ctx, &offline.nonce_account).await?;
+    check_nonce_authority(&offline.nonce_account, &nonce, &offline.nonce_authority)?;
+
+    // Advancing the nonce has to come first for the nonce to be accepted
+    let mut instructions = vec![advance_nonce_account(
+        &offline.nonce_account,
+        &offline.nonce_authority,
+    )];
+    instructions.extend(with_compute_budget(ctx, instruction).await?);
+
+    let blockhash = nonce.blockhash();
+    let message = Message::new_with_blockhash(&instructions, Some(&offline.signer), &blockhash);
+    let mut tx = Transaction::new_unsigned(message);
+
+    // Keypairs at hand, such as new accounts, sign now
+    let available: Vec<&dyn Signer> = signers
+        .iter()
+        .filter(|signer| required_signers(&tx).contains(&signer.pubkey()))
+        .copied()
+        .collect();
+    note_device_confirmation(&available);
+    tx.try_partial_sign(&available, blockhash)?;
+
+    let path = offline.directory.join(format!(
+        "scilla-tx-{}.txt",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    write_tx_file(&path, &tx)?;
+
+    Ok(path)
+}
+
+/// Base64 of the transaction's wire format, as written to transaction files.
+pub fn encode_tx(tx: &Transaction) -> anyhow::Result<String> {
+    let bytes = bincode::serialize(tx)?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(bytes))
+}
+
+pub fn decode_tx(encoded: &str) -> anyhow::Result<Transaction> {
+    let bytes = decode_base64(encoded.trim())?;
+    bincode_deserialize(&bytes, "transaction file")
+}
+
+pub fn read_tx_file(path: &Path) -> anyhow::Result<Transaction> {
+    let encoded =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    decode_tx(&encoded)
+}
+
+pub fn write_tx_file(path: &Path, tx: &Transaction) -> anyhow::Result<()> {
+    fs::write(path, format!("{}\n", encode_tx(tx)?))
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Addresses that have to sign `tx`, fee payer first.
+pub fn required_signers(tx: &Transaction) -> &[Pubkey] {
+    let signers = tx.message.header.num_required_signatures as usize;
+    &tx.message.account_keys[..signers.min(tx.message.account_keys.len())]
+}
+
+/// Required signers whose signature is still missing.
+pub fn missing_signers(tx: &Transaction) -> Vec<Pubkey> {
+    required_signers(tx)
+        .iter()
+        .zip(&tx.signatures)
+        .filter(|(_, signature)| **signature == Signature::default())
+        .map(|(signer, _)| *signer)
+        .collect()
+}
+
+/// Fails unless every required signature is there and valid.
+pub fn verify_signatures(tx: &Transaction) -> anyhow::Result<()> {
+    let missing = missing_signers(tx);
+    if !missing.is_empty() {
+        bail!(
+            "Transaction is still missing signatures from {}",
+            missing
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
+    let message = tx.message_data();
+    for (signer, signature) in required_signers(tx).iter().zip(&tx.signatures) {
+        if !signature.verify(signer.as_ref(), &message) {
+            bail!("Signature of {signer} does not match the transaction");
+        }
+    }
+    Ok(())
+}
+
+/// Adds the signature of `signer`, keeping those already there.
+pub fn sign_offline_tx(tx: &mut Transaction, signer: &dyn Signer) -> anyhow::Result<()> {
+    if !required_signers(tx).contains(&signer.pubkey()) {
+        bail!("{} is not a signer of this transaction", signer.pubkey());
+    }
+
+    let blockhash = tx.message.recent_blockhash;
+    note_device_confirmation(&[signer]);
+    tx.try_partial_sign(&[signer], blockhash)?;
+    Ok(())
+}
+
+/// Nonce account the transaction advances, if it is built on a durable
+/// nonce.
+fn nonce_account(tx: &Transaction) -> Option<Pubkey> {
+    let advance = tx.message.instructions.first()?;
+    let program = tx
+        .message
+        .account_keys
+        .get(advance.program_id_index as usize)?;
+    if *program != system_program::id() || !advance.data.starts_with(&ADVANCE_NONCE_DISCRIMINANT) {
+        return None;
+    }
+    tx.message
+        .account_keys
+        .get(*advance.accounts.first()? as usize)
+        .copied()
+}
+
+pub fn offline_tx_table(tx: &Transaction) -> Table {
+    let mut rows = vec![(
+        "Fee Payer",
+        tx.message
+            .account_keys
+            .first()
+            .map_or("None".to_string(), ToString::to_string),
+    )];
+    match nonce_account(tx) {
+        Some(nonce_account) => rows.extend([
+            ("Nonce Account", nonce_account.to_string()),
+            ("Durable Nonce", tx.message.recent_blockhash.to_string()),
+        ]),
+        None => rows.push(("Recent Blockhash", tx.message.recent_blockhash.to_string())),
+    }
+    rows.push(("Instructions", tx.message.instructions.len().to_string()));
+
+    field_table(rows)
+}
+
+pub fn signers_table(tx: &Transaction) -> Table {
+    let missing = missing_signers(tx);
+
+    let mut table = new_table();
+    table.set_header(vec![header_cell("Signer"), header_cell("Status")]);
+    for signer in required_signers(tx) {
+        let status = if missing.contains(signer) {
+            Cell::new("Missing").fg(Color::Yellow)
+        } else {
+            Cell::new("Signed").fg(Color::Green)
+        };
+        table.add_row(vec![Cell::new(signer), status]);
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{misc::helpers::memo_instruction, ui::render::capture},
+        insta::assert_snapshot,
+        solana_keypair::Keypair,
+        solana_system_interface::instruction::transfer,
+    };
+
+    fn nonce_tx(payer: &Pubkey, other: &Pubkey) -> Transaction {
+        let nonce_account = Pubkey::new_from_array([3; 32]);
+        let instructions = [
+            advance_nonce_account(&nonce_account, payer),
+            transfer(payer, &Pubkey::new_from_array([4; 32]), 1_000_000),
+            memo_instruction(other, "cold storage"),
+        ];
+        Transaction::new_unsigned(Message::new_with_blockhash(
+            &instructions,
+            Some(payer),
+            &Default::default(),
+        ))
+    }
+
+    #[test]
+    fn test_offline_signing_round_trip() -> anyhow::Result<()> {
+        let payer = Keypair::new();
+        let other = Keypair::new();
+        let mut tx = nonce_tx(&payer.pubkey(), &other.pubkey());
+
+        sign_offline_tx(&mut tx, &other)?;
+        let mut tx = decode_tx(&encode_tx(&tx)?)?;
+        assert_eq!(missing_signers(&tx), [payer.pubkey()]);
+        assert!(verify_signatures(&tx).is_err());
+
+        assert!(sign_offline_tx(&mut tx, &Keypair::new()).is_err());
+        sign_offline_tx(&mut tx, &payer)?;
+        assert!(verify_signatures(&tx).is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_nonce_authority() {
+        let nonce_account = Pubkey::new_from_array([3; 32]);
+        let authority = Pubkey::new_from_array([1; 32]);
+        let nonce = NonceData {
+            authority,
+            ..NonceData::default()
+        };
+
+        assert!(check_nonce_authority(&nonce_account, &nonce, &authority).is_ok());
+        let err = check_nonce_authority(&nonce_account, &nonce, &Pubkey::new_from_array([2; 32]))
+            .expect_err("only the authority may use the nonce");
+        assert!(err.to_string().contains(&authority.to_string()));
+    }
+
+    #[test]
+    fn test_offline_tx_tables() {
+        let payer = Pubkey::new_from_array([1; 32]);
+        let mut tx = nonce_tx(&payer, &Pubkey::new_from_array([2; 32]));
+        tx.signatures[1] = Signature::from([1; 64]);
+
+        let output = capture(|out| {
+            out.table(offline_tx_table(&tx));
+            out.table(signers_table(&tx));
+        });
+
+        assert_snapshot!("offline_tx_tables", output);
+    }
+}
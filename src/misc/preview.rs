@@ -0,0 +1,182 @@
+use {
+    crate::{
+        commands::transaction::balance_changes_table,
+        config::Cluster,
+        context::ScillaContext,
+        misc::{helpers::lamports_to_sol, program_logs::write_program_logs},
+        prompt::prompt_confirmation,
+        ui::{
+            render::{field_table, stdout},
+            suspend_spinner,
+        },
+    },
+    anyhow::bail,
+    comfy_table::{Cell, Color, Table},
+    solana_account_decoder_client_types::UiAccountEncoding,
+    solana_message::Message,
+    solana_rpc_client_api::config::{
+        RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig,
+    },
+    solana_transaction::Transaction,
+};
+
+/// What simulating a transaction predicts, shown before it's sent.
+#[derive(Debug, Default)]
+pub struct TxPreview {
+    pub fee: u64,
+    pub units_consumed: Option<u64>,
+    /// Why the simulation failed, `None` when it succeeded
+    pub error: Option<String>,
+    pub logs: Vec<String>,
+    pub account_keys: Vec<String>,
+    pub pre_balances: Vec<u64>,
+    pub post_balances: Vec<u64>,
+}
+
+/// Simulates `message` against the current state of the cluster, reading
+/// every account it touches before and after.
+pub async fn simulate_tx(ctx: &ScillaContext, message: &Message) -> anyhow::Result<TxPreview> {
+    let mut message = message.clone();
+    // The fee of a message is only known for a recent blockhash
+    message.recent_blockhash = ctx.rpc().get_latest_blockhash().await?;
+    let fee = ctx.rpc().get_fee_for_message(&message).await?;
+
+    let pre_accounts = ctx
+        .rpc()
+        .get_multiple_accounts(&message.account_keys)
+        .await?;
+    let account_keys: Vec<String> = message
+        .account_keys
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+
+    let tx = Transaction::new_unsigned(message);
+    let result = ctx
+        .rpc()
+        .simulate_transaction_with_config(
+            &tx,
+            RpcSimulateTransactionConfig {
+                sig_verify: false,
+                replace_recent_blockhash: true,
+                commitment: Some(ctx.rpc().commitment()),
+                accounts: Some(RpcSimulateTransactionAccountsConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    addresses: account_keys.clone(),
+                }),
+                ..RpcSimulateTransactionConfig::default()
+            },
+        )
+        .await?
+        .value;
+
+    let pre_balances = pre_accounts
+        .iter()
+        .map(|account| account.as_ref().map_or(0, |account| account.lamports))
+        .collect();
+    // Accounts are only returned when the simulation succeeds
+    let post_balances = match result.accounts {
+        Some(accounts) => accounts
+            .iter()
+            .map(|account| account.as_ref().map_or(0, |account| account.lamports))
+            .collect(),
+        None => Vec::new(),
+    };
+
+    Ok(TxPreview {
+        fee,
+        units_consumed: result.units_consumed,
+        error: result.err.map(|err| format!("{err:?}")),
+        logs: result.logs.unwrap_or_default(),
+        account_keys,
+        pre_balances,
+        post_balances,
+    })
+}
+
+pub fn preview_table(preview: &TxPreview) -> Table {
+    let mut table = field_table([
+        ("Fee (SOL)", lamports_to_sol(preview.fee).to_string()),
+        (
+            "Compute Units",
+            preview
+                .units_consumed
+                .map_or("Unknown".to_string(), |units| units.to_string()),
+        ),
+    ]);
+    let result = match &preview.error {
+        None => Cell::new("Success").fg(Color::Green),
+        Some(error) => Cell::new(format!("Error: {error}")).fg(Color::Red),
+    };
+    table.add_row(vec![Cell::new("Simulation"), result]);
+    table
+}
+
+/// Simulates `message`, shows its fee, compute units, logs and balance
+/// changes, and asks whether to send it. Fails when the answer is no.
+pub async fn confirm_tx_preview(ctx: &ScillaContext, message: &Message) -> anyhow::Result<()> {
+    let preview = simulate_tx(ctx, message).await?;
+    show_tx_preview(ctx, &preview);
+
+    let question = match preview.error {
+        None => "Send this transaction?",
+        Some(_) => "Simulation failed, send this transaction anyway?",
+    };
+    if !prompt_confirmation(question) {
+        bail!("Transaction not sent");
+    }
+
+    Ok(())
+}
+
+/// Shows what simulating a transaction predicts, with a warning on mainnet.
+pub fn show_tx_preview(ctx: &ScillaContext, preview: &TxPreview) {
+    suspend_spinner(|| {
+        let mut out = stdout();
+        out.title("TRANSACTION PREVIEW");
+        out.table(preview_table(preview));
+
+        let account_keys: Vec<&str> = preview.account_keys.iter().map(String::as_str).collect();
+        if !preview.post_balances.is_empty() {
+            out.table(balance_changes_table(
+                &account_keys,
+                &preview.pre_balances,
+                &preview.post_balances,
+            ));
+        }
+        if !preview.logs.is_empty() {
+            out.title("LOGS");
+            write_program_logs(&mut out, &preview.logs);
+        }
+
+        if ctx.cluster() == Some(Cluster::Mainnet) {
+            out.warning("This transaction runs on MAINNET and moves real funds.");
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::ui::render::capture, insta::assert_snapshot};
+
+    #[test]
+    fn test_preview_table() {
+        let preview = TxPreview {
+            fee: 10_000,
+            units_consumed: Some(450),
+            ..TxPreview::default()
+        };
+        let failed = TxPreview {
+            fee: 5_000,
+            error: Some("InsufficientFundsForFee".to_string()),
+            ..TxPreview::default()
+        };
+
+        let output = capture(|out| {
+            out.table(preview_table(&preview));
+            out.table(preview_table(&failed));
+        });
+
+        assert_snapshot!("preview_table", output);
+    }
+}
@@ -0,0 +1,319 @@
+use {
+    crate::{
+        constants::{
+            COMPUTE_UNIT_LIMIT_MARGIN_PERCENT, MAX_COMPUTE_UNIT_LIMIT,
+            MAX_PRIORITIZATION_FEE_ACCOUNTS,
+        },
+        context::ScillaContext,
+    },
+    anyhow::{anyhow, bail},
+    serde::{Deserialize, Serialize},
+    solana_instruction::Instruction,
+    solana_message::Message,
+    solana_pubkey::Pubkey,
+    solana_rpc_client_api::config::RpcSimulateTransactionConfig,
+    solana_sdk_ids::compute_budget,
+    solana_transaction::Transaction,
+    std::fmt,
+};
+
+/// `SetComputeUnitLimit` tag of the compute budget instruction enum.
+const SET_COMPUTE_UNIT_LIMIT_TAG: u8 = 2;
+
+/// `SetComputeUnitPrice` tag of the compute budget instruction enum.
+const SET_COMPUTE_UNIT_PRICE_TAG: u8 = 3;
+
+/// Compute unit price attached to sent transactions.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum PriorityFee {
+    /// No compute unit price instruction is added
+    #[default]
+    None,
+    /// Estimated from recent fees paid for the accounts being written
+    Auto,
+    /// Fixed price in micro-lamports per compute unit
+    #[serde(untagged)]
+    MicroLamports(u64),
+}
+
+impl fmt::Display for PriorityFee {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PriorityFee::None => write!(f, "none"),
+            PriorityFee::Auto => write!(f, "auto"),
+            PriorityFee::MicroLamports(price) => write!(f, "{price} micro-lamports/CU"),
+        }
+    }
+}
+
+/// Compute unit limit set on sent transactions.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ComputeUnitLimit {
+    /// No compute unit limit instruction is added, so every instruction may
+    /// use the runtime default
+    #[default]
+    None,
+    /// Sized from the compute units a simulation of the transaction consumes
+    Auto,
+    /// Fixed number of compute units
+    #[serde(untagged)]
+    Units(u32),
+}
+
+impl fmt::Display for ComputeUnitLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComputeUnitLimit::None => write!(f, "none"),
+            ComputeUnitLimit::Auto => write!(f, "auto"),
+            ComputeUnitLimit::Units(units) => write!(f, "{units} CU"),
+        }
+    }
+}
+
+/// Builds a compute budget instruction setting the compute unit limit.
+pub fn set_compute_unit_limit(units: u32) -> Instruction {
+    let mut data = vec![SET_COMPUTE_UNIT_LIMIT_TAG];
+    data.extend(units.to_le_bytes());
+
+    Instruction::new_with_bytes(compute_budget::ID, &data, vec![])
+}
+
+/// Builds a compute budget instruction setting the compute unit price.
+pub fn set_compute_unit_price(micro_lamports: u64) -> Instruction {
+    let mut data = vec![SET_COMPUTE_UNIT_PRICE_TAG];
+    data.extend(micro_lamports.to_le_bytes());
+
+    Instruction::new_with_bytes(compute_budget::ID, &data, vec![])
+}
+
+/// Accounts written by `instructions` paid by `payer`, which are the ones
+/// whose recent fees matter for landing the transaction.
+fn writable_accounts(payer: &Pubkey, instructions: &[Instruction]) -> Vec<Pubkey> {
+    let mut accounts = vec![*payer];
+    for meta in instructions.iter().flat_map(|ix| &ix.accounts) {
+        if meta.is_writable && !accounts.contains(&meta.pubkey) {
+            accounts.push(meta.pubkey);
+        }
+    }
+    accounts.truncate(MAX_PRIORITIZATION_FEE_ACCOUNTS);
+    accounts
+}
+
+/// The `percentile`th value of `fees` by nearest rank, 0 when there are none.
+pub fn fee_percentile(mut fees: Vec<u64>, percentile: u8) -> u64 {
+    if fees.is_empty() {
+        return 0;
+    }
+    fees.sort_unstable();
+
+    let rank = (usize::from(percentile.min(100)) * fees.len()).div_ceil(100);
+    fees[rank.saturating_sub(1)]
+}
+
+/// Samples the fees recently paid to write the same accounts as
+/// `instructions` and returns the configured percentile.
+pub async fn estimate_priority_fee(
+    ctx: &ScillaContext,
+    instructions: &[Instruction],
+) -> anyhow::Result<u64> {
+    let accounts = writable_accounts(ctx.pubkey(), instructions);
+    let recent = ctx.rpc().get_recent_prioritization_fees(&accounts).await?;
+    let fees = recent
+        .into_iter()
+        .map(|fee| fee.prioritization_fee)
+        .collect();
+
+    Ok(fee_percentile(fees, ctx.priority_fee_percentile()))
+}
+
+/// Compute unit price in micro-lamports the configured priority fee sets for
+/// `instructions`, 0 when there is none.
+pub async fn compute_unit_price(
+    ctx: &ScillaContext,
+    instructions: &[Instruction],
+) -> anyhow::Result<u64> {
+    match ctx.priority_fee() {
+        PriorityFee::None => Ok(0),
+        PriorityFee::Auto => estimate_priority_fee(ctx, instructions).await,
+        PriorityFee::MicroLamports(price) => Ok(price),
+    }
+}
+
+/// Compute unit price instruction to prepend to `instructions`, if any.
+pub async fn priority_fee_instruction(
+    ctx: &ScillaContext,
+    instructions: &[Instruction],
+) -> anyhow::Result<Option<Instruction>> {
+    let price = compute_unit_price(ctx, instructions).await?;
+
+    Ok((price > 0).then(|| set_compute_unit_price(price)))
+}
+
+/// Compute unit limit leaving some headroom over what a simulation
+/// consumed, as the accounts may change before the transaction lands.
+fn padded_compute_unit_limit(units_consumed: u64) -> u32 {
+    let padded = units_consumed + units_consumed * COMPUTE_UNIT_LIMIT_MARGIN_PERCENT / 100;
+    padded.min(MAX_COMPUTE_UNIT_LIMIT) as u32
+}
+
+/// Compute units `instructions` paid by the context keypair consume when
+/// simulated with the highest limit.
+pub async fn simulate_compute_units(
+    ctx: &ScillaContext,
+    instructions: &[Instruction],
+) -> anyhow::Result<u64> {
+    let mut simulated = vec![set_compute_unit_limit(MAX_COMPUTE_UNIT_LIMIT as u32)];
+    simulated.extend_from_slice(instructions);
+    let tx = Transaction::new_unsigned(Message::new(&simulated, Some(ctx.pubkey())));
+
+    let result = ctx
+        .rpc()
+        .simulate_transaction_with_config(
+            &tx,
+            RpcSimulateTransactionConfig {
+                sig_verify: false,
+                replace_recent_blockhash: true,
+                commitment: Some(ctx.rpc().commitment()),
+                ..RpcSimulateTransactionConfig::default()
+            },
+        )
+        .await?
+        .value;
+
+    if let Some(err) = result.err {
+        bail!("Transaction simulation failed: {err:?}");
+    }
+    result
+        .units_consumed
+        .ok_or_else(|| anyhow!("The RPC node did not report the compute units consumed"))
+}
+
+/// Compute unit limit the configuration sets for `instructions`, if any.
+pub async fn compute_unit_limit(
+    ctx: &ScillaContext,
+    instructions: &[Instruction],
+) -> anyhow::Result<Option<u32>> {
+    match ctx.compute_unit_limit() {
+        ComputeUnitLimit::None => Ok(None),
+        ComputeUnitLimit::Auto => Ok(Some(padded_compute_unit_limit(
+            simulate_compute_units(ctx, instructions).await?,
+        ))),
+        ComputeUnitLimit::Units(units) => Ok(Some(units)),
+    }
+}
+
+/// Compute budget instructions to prepend to `instructions`: the configured
+/// compute unit limit, then the priority fee.
+pub async fn compute_budget_instructions(
+    ctx: &ScillaContext,
+    instructions: &[Instruction],
+) -> anyhow::Result<Vec<Instruction>> {
+    let mut budget = Vec::new();
+    if let Some(units) = compute_unit_limit(ctx, instructions).await? {
+        budget.push(set_compute_unit_limit(units));
+    }
+    if let Some(priority_fee) = priority_fee_instruction(ctx, instructions).await? {
+        budget.push(priority_fee);
+    }
+    Ok(budget)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_instruction::AccountMeta};
+
+    #[test]
+    fn test_fee_percentile() {
+        let fees: Vec<u64> = (1..=10).map(|fee| fee * 100).collect();
+
+        assert_eq!(fee_percentile(fees.clone(), 50), 500);
+        assert_eq!(fee_percentile(fees.clone(), 75), 800);
+        assert_eq!(fee_percentile(fees.clone(), 100), 1000);
+        assert_eq!(fee_percentile(fees, 0), 100);
+        assert_eq!(fee_percentile(vec![], 75), 0);
+    }
+
+    #[test]
+    fn test_writable_accounts() {
+        let payer = Pubkey::new_from_array([1; 32]);
+        let recipient = Pubkey::new_from_array([2; 32]);
+        let program = Pubkey::new_from_array([3; 32]);
+        let instruction = Instruction::new_with_bytes(
+            program,
+            &[],
+            vec![
+                AccountMeta::new(payer, true),
+                AccountMeta::new(recipient, false),
+                AccountMeta::new_readonly(program, false),
+            ],
+        );
+
+        assert_eq!(
+            writable_accounts(&payer, &[instruction]),
+            vec![payer, recipient]
+        );
+    }
+
+    #[test]
+    fn test_priority_fee_config_values() {
+        #[derive(Deserialize)]
+        struct Config {
+            priority_fee: PriorityFee,
+        }
+
+        let parse = |toml: &str| toml::from_str::<Config>(toml).map(|config| config.priority_fee);
+
+        assert_eq!(
+            parse(r#"priority_fee = "auto""#).ok(),
+            Some(PriorityFee::Auto)
+        );
+        assert_eq!(
+            parse(r#"priority_fee = "none""#).ok(),
+            Some(PriorityFee::None)
+        );
+        assert_eq!(
+            parse("priority_fee = 5000").ok(),
+            Some(PriorityFee::MicroLamports(5000))
+        );
+        assert!(parse(r#"priority_fee = "fast""#).is_err());
+    }
+
+    #[test]
+    fn test_compute_unit_limit() {
+        let instruction = set_compute_unit_limit(300_000);
+        assert_eq!(instruction.program_id, compute_budget::ID);
+        assert_eq!(instruction.data, [2, 224, 147, 4, 0]);
+
+        assert_eq!(padded_compute_unit_limit(100_000), 110_000);
+        assert_eq!(
+            padded_compute_unit_limit(1_300_000),
+            MAX_COMPUTE_UNIT_LIMIT as u32
+        );
+
+        #[derive(Deserialize)]
+        struct Config {
+            compute_unit_limit: ComputeUnitLimit,
+        }
+        let parse =
+            |toml: &str| toml::from_str::<Config>(toml).map(|config| config.compute_unit_limit);
+        assert_eq!(
+            parse(r#"compute_unit_limit = "auto""#).ok(),
+            Some(ComputeUnitLimit::Auto)
+        );
+        assert_eq!(
+            parse("compute_unit_limit = 300000").ok(),
+            Some(ComputeUnitLimit::Units(300_000))
+        );
+    }
+
+    #[test]
+    fn test_set_compute_unit_price() {
+        let instruction = set_compute_unit_price(5000);
+
+        assert_eq!(instruction.program_id, compute_budget::ID);
+        assert_eq!(instruction.data, [3, 136, 19, 0, 0, 0, 0, 0, 0]);
+        assert!(instruction.accounts.is_empty());
+    }
+}
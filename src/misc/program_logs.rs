@@ -0,0 +1,250 @@
+use {crate::ui::render::Renderer, std::io::Write};
+
+/// A line of a transaction's logs, with the invocations it made nested.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LogEntry {
+    Invocation(Invocation),
+    /// `Program log:` message
+    Log(String),
+    /// `Program data:` or `Program return:` payload
+    Data(String),
+    /// Anything else, such as `Log truncated`
+    Other(String),
+}
+
+/// One program invocation and everything it logged.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Invocation {
+    pub program: String,
+    pub entries: Vec<LogEntry>,
+    /// Compute units consumed and the budget it had left
+    pub compute_units: Option<(u64, u64)>,
+    /// `None` when the logs end before the invocation does
+    pub outcome: Option<Result<(), String>>,
+}
+
+impl Invocation {
+    fn new(program: &str) -> Self {
+        Self {
+            program: program.to_string(),
+            entries: Vec::new(),
+            compute_units: None,
+            outcome: None,
+        }
+    }
+}
+
+/// `consumed X of Y compute units`, as logged when an invocation returns.
+fn parse_compute_units(rest: &str) -> Option<(u64, u64)> {
+    let (consumed, budget) = rest
+        .strip_prefix("consumed ")?
+        .strip_suffix(" compute units")?
+        .split_once(" of ")?;
+    Some((consumed.parse().ok()?, budget.parse().ok()?))
+}
+
+/// Groups raw log lines by the invocation that logged them. Compute unit
+/// lines are folded into their invocation, and the per-instruction
+/// `Program consumption:` lines are dropped.
+pub fn parse_program_logs(logs: &[String]) -> Vec<LogEntry> {
+    let mut root = Vec::new();
+    let mut stack: Vec<Invocation> = Vec::new();
+
+    for line in logs {
+        let entry = if let Some(message) = line.strip_prefix("Program log: ") {
+            LogEntry::Log(message.to_string())
+        } else if let Some(data) = line.strip_prefix("Program data: ") {
+            LogEntry::Data(data.to_string())
+        } else if let Some(data) = line.strip_prefix("Program return: ") {
+            LogEntry::Data(format!("return {data}"))
+        } else if line.starts_with("Program consumption: ") {
+            continue;
+        } else if let Some((program, rest)) = line
+            .strip_prefix("Program ")
+            .and_then(|line| line.split_once(' '))
+        {
+            if rest.starts_with("invoke [") {
+                stack.push(Invocation::new(program));
+                continue;
+            }
+
+            if stack
+                .last()
+                .is_some_and(|invocation| invocation.program == program)
+            {
+                if let Some(compute_units) = parse_compute_units(rest) {
+                    if let Some(invocation) = stack.last_mut() {
+                        invocation.compute_units = Some(compute_units);
+                    }
+                    continue;
+                }
+                if rest == "success" {
+                    finish(&mut stack, &mut root, Ok(()));
+                    continue;
+                }
+                if let Some(error) = rest.strip_prefix("failed: ") {
+                    finish(&mut stack, &mut root, Err(error.to_string()));
+                    continue;
+                }
+            }
+            LogEntry::Other(line.clone())
+        } else {
+            LogEntry::Other(line.clone())
+        };
+
+        match stack.last_mut() {
+            Some(invocation) => invocation.entries.push(entry),
+            None => root.push(entry),
+        }
+    }
+
+    // Truncated logs leave invocations open
+    while !stack.is_empty() {
+        close(&mut stack, &mut root);
+    }
+
+    root
+}
+
+fn finish(stack: &mut Vec<Invocation>, root: &mut Vec<LogEntry>, outcome: Result<(), String>) {
+    if let Some(invocation) = stack.last_mut() {
+        invocation.outcome = Some(outcome);
+    }
+    close(stack, root);
+}
+
+/// Moves the innermost open invocation into its caller.
+fn close(stack: &mut Vec<Invocation>, root: &mut Vec<LogEntry>) {
+    let Some(invocation) = stack.pop() else {
+        return;
+    };
+    match stack.last_mut() {
+        Some(caller) => caller.entries.push(LogEntry::Invocation(invocation)),
+        None => root.push(LogEntry::Invocation(invocation)),
+    }
+}
+
+/// Writes transaction logs nested by invocation depth, each invocation
+/// ending with its result and the compute units it used.
+pub fn write_program_logs<W: Write>(out: &mut Renderer<W>, logs: &[String]) {
+    for entry in parse_program_logs(logs) {
+        write_entry(out, &entry, "");
+    }
+}
+
+fn write_entry<W: Write>(out: &mut Renderer<W>, entry: &LogEntry, indent: &str) {
+    match entry {
+        LogEntry::Invocation(invocation) => write_invocation(out, invocation, indent),
+        LogEntry::Log(message) => out.line(format!("{indent}{message}")),
+        LogEntry::Data(data) => out.note(format!("{indent}{data}")),
+        LogEntry::Other(line) => out.warning(format!("{indent}{line}")),
+    }
+}
+
+fn write_invocation<W: Write>(out: &mut Renderer<W>, invocation: &Invocation, indent: &str) {
+    let compute_units = invocation
+        .compute_units
+        .map(|(consumed, budget)| format!(" ({consumed} of {budget} CU)"))
+        .unwrap_or_default();
+    let outcome = match &invocation.outcome {
+        Some(Ok(())) => format!("✓ success{compute_units}"),
+        Some(Err(error)) => format!("✗ failed: {error}{compute_units}"),
+        None => "… no result logged".to_string(),
+    };
+
+    // Invocations that logged nothing take a single line
+    if invocation.entries.is_empty() {
+        let line = format!("{indent}▸ {} {outcome}", invocation.program);
+        return write_outcome(out, &invocation.outcome, line);
+    }
+
+    out.line(format!("{indent}▸ {}", invocation.program));
+    let nested = format!("{indent}│ ");
+    for entry in &invocation.entries {
+        write_entry(out, entry, &nested);
+    }
+    write_outcome(out, &invocation.outcome, format!("{indent}└ {outcome}"));
+}
+
+fn write_outcome<W: Write>(
+    out: &mut Renderer<W>,
+    outcome: &Option<Result<(), String>>,
+    line: String,
+) {
+    match outcome {
+        Some(Ok(())) => out.success(line),
+        Some(Err(_)) => out.error(line),
+        None => out.warning(line),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::ui::render::capture, insta::assert_snapshot};
+
+    const TOKEN: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+    const ROUTER: &str = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4";
+
+    fn logs(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    fn swap_logs() -> Vec<String> {
+        logs(&[
+            "Program ComputeBudget111111111111111111111111111111 invoke [1]",
+            "Program ComputeBudget111111111111111111111111111111 success",
+            &format!("Program {ROUTER} invoke [1]"),
+            "Program log: Instruction: Route",
+            &format!("Program {TOKEN} invoke [2]"),
+            "Program log: Instruction: Transfer",
+            &format!("Program {TOKEN} consumed 4645 of 180000 compute units"),
+            &format!("Program {TOKEN} success"),
+            "Program data: QMbN6CYIceKNs3CH7q8sHW==",
+            "Program consumption: 150000 units remaining",
+            &format!("Program {TOKEN} invoke [2]"),
+            &format!("Program {TOKEN} consumed 2000 of 170000 compute units"),
+            &format!("Program {TOKEN} failed: insufficient funds"),
+            &format!("Program {ROUTER} consumed 30000 of 200000 compute units"),
+            &format!("Program {ROUTER} failed: custom program error: 0x1"),
+        ])
+    }
+
+    #[test]
+    fn test_parse_program_logs() {
+        let entries = parse_program_logs(&swap_logs());
+
+        assert_eq!(entries.len(), 2);
+        let LogEntry::Invocation(router) = &entries[1] else {
+            panic!("expected the router invocation, got {:?}", entries[1]);
+        };
+        assert_eq!(router.program, ROUTER);
+        assert_eq!(router.compute_units, Some((30_000, 200_000)));
+        assert_eq!(
+            router.outcome,
+            Some(Err("custom program error: 0x1".to_string()))
+        );
+        // Its log, two token invocations and the event between them
+        assert_eq!(router.entries.len(), 4);
+
+        let truncated = parse_program_logs(&logs(&[
+            &format!("Program {ROUTER} invoke [1]"),
+            &format!("Program {TOKEN} invoke [2]"),
+            "Log truncated",
+        ]));
+        let [LogEntry::Invocation(router)] = truncated.as_slice() else {
+            panic!("expected a single open invocation, got {truncated:?}");
+        };
+        assert_eq!(router.outcome, None);
+        assert!(matches!(
+            &router.entries[0],
+            LogEntry::Invocation(Invocation { outcome: None, .. })
+        ));
+    }
+
+    #[test]
+    fn test_write_program_logs() {
+        let output = capture(|out| write_program_logs(out, &swap_logs()));
+
+        assert_snapshot!("program_logs", output);
+    }
+}
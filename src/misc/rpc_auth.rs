@@ -0,0 +1,113 @@
+use {
+    crate::{config::RpcAuth, constants::RPC_REQUEST_TIMEOUT_SECS},
+    anyhow::{Context, anyhow},
+    reqwest::{
+        Url,
+        header::{HeaderMap, HeaderName, HeaderValue},
+    },
+    solana_commitment_config::CommitmentConfig,
+    solana_rpc_client::{
+        http_sender::HttpSender, nonblocking::rpc_client::RpcClient, rpc_client::RpcClientConfig,
+    },
+    std::{collections::BTreeMap, time::Duration},
+};
+
+/// Appends the `query` parameters to `url`, URL-encoded.
+pub fn authenticated_url(url: &str, query: &BTreeMap<String, String>) -> anyhow::Result<String> {
+    if query.is_empty() {
+        return Ok(url.to_string());
+    }
+
+    let mut url = Url::parse(url).with_context(|| format!("Invalid RPC URL {url}"))?;
+    url.query_pairs_mut().extend_pairs(query);
+
+    Ok(url.into())
+}
+
+/// `url` without its query string, which is where API keys usually live, for
+/// showing it in output.
+pub fn display_url(url: &str) -> &str {
+    url.split_once('?').map_or(url, |(base, _)| base)
+}
+
+fn header_map(headers: &BTreeMap<String, String>) -> anyhow::Result<HeaderMap> {
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| anyhow!("Invalid RPC header name {name}"))?;
+        let mut value = HeaderValue::from_str(value)
+            .map_err(|_| anyhow!("Invalid value for RPC header {name}"))?;
+        value.set_sensitive(true);
+        map.insert(name, value);
+    }
+    Ok(map)
+}
+
+/// RPC client for `url` sending the `auth` query parameters and headers with
+/// every request.
+pub fn rpc_client(
+    url: &str,
+    commitment: CommitmentConfig,
+    auth: &RpcAuth,
+) -> anyhow::Result<RpcClient> {
+    let url = authenticated_url(url, &auth.query)?;
+    if auth.headers.is_empty() {
+        return Ok(RpcClient::new_with_commitment(url, commitment));
+    }
+
+    let client = reqwest::Client::builder()
+        .default_headers(header_map(&auth.headers)?)
+        .timeout(Duration::from_secs(RPC_REQUEST_TIMEOUT_SECS))
+        .build()?;
+
+    Ok(RpcClient::new_sender(
+        HttpSender::new_with_client(url, client),
+        RpcClientConfig::with_commitment(commitment),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authenticated_url() -> anyhow::Result<()> {
+        let query = BTreeMap::from([("api-key".to_string(), "a b&c".to_string())]);
+
+        assert_eq!(
+            authenticated_url("https://mainnet.helius-rpc.com/", &query)?,
+            "https://mainnet.helius-rpc.com/?api-key=a+b%26c"
+        );
+        assert_eq!(
+            authenticated_url("wss://rpc.example.com:8900/?cluster=main", &query)?,
+            "wss://rpc.example.com:8900/?cluster=main&api-key=a+b%26c"
+        );
+        assert_eq!(
+            authenticated_url("https://rpc.example.com", &BTreeMap::new())?,
+            "https://rpc.example.com"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_display_url_hides_query() {
+        assert_eq!(
+            display_url("https://mainnet.helius-rpc.com/?api-key=secret"),
+            "https://mainnet.helius-rpc.com/"
+        );
+        assert_eq!(
+            display_url("https://api.devnet.solana.com"),
+            "https://api.devnet.solana.com"
+        );
+    }
+
+    #[test]
+    fn test_header_map_rejects_invalid_names() {
+        let valid = BTreeMap::from([("x-token".to_string(), "secret".to_string())]);
+        let invalid = BTreeMap::from([("x token".to_string(), "secret".to_string())]);
+
+        assert!(header_map(&valid).is_ok_and(|map| map["x-token"].is_sensitive()));
+        assert!(header_map(&invalid).is_err());
+    }
+}
@@ -0,0 +1,93 @@
+use anyhow::bail;
+
+const BEGIN_MARKER: &[u8] = b"=======BEGIN SECURITY.TXT V1=======\0";
+const END_MARKER: &[u8] = b"=======END SECURITY.TXT V1=======\0";
+
+/// Fields a program can embed with the `security_txt!` macro, in the order
+/// they are displayed.
+pub const SECURITY_TXT_FIELDS: &[(&str, &str)] = &[
+    ("name", "Name"),
+    ("project_url", "Project URL"),
+    ("contacts", "Contacts"),
+    ("policy", "Policy"),
+    ("preferred_languages", "Preferred Languages"),
+    ("encryption", "Encryption"),
+    ("source_code", "Source Code"),
+    ("source_release", "Source Release"),
+    ("source_revision", "Source Revision"),
+    ("auditors", "Auditors"),
+    ("acknowledgements", "Acknowledgements"),
+    ("expiry", "Expiry"),
+];
+
+/// Extracts the `security.txt` key/value pairs embedded in a program binary.
+///
+/// The section is a list of null-terminated strings alternating between keys
+/// and values, wrapped in begin/end markers. Returns `Ok(None)` when the
+/// program does not embed one.
+pub fn find_security_txt(program_data: &[u8]) -> anyhow::Result<Option<Vec<(String, String)>>> {
+    let Some(start) = find(program_data, BEGIN_MARKER) else {
+        return Ok(None);
+    };
+    let body = &program_data[start + BEGIN_MARKER.len()..];
+
+    let Some(end) = find(body, END_MARKER) else {
+        bail!("security.txt begin marker found without an end marker");
+    };
+
+    let mut parts = body[..end]
+        .split(|&b| b == 0)
+        .map(|part| String::from_utf8_lossy(part).into_owned());
+
+    let mut entries = Vec::new();
+    while let Some(key) = parts.next() {
+        if key.is_empty() {
+            continue;
+        }
+        let Some(value) = parts.next() else {
+            bail!("security.txt field {key} has no value");
+        };
+        entries.push((key, value));
+    }
+
+    Ok(Some(entries))
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_security_txt() -> anyhow::Result<()> {
+        let mut data = b"\x7fELF padding".to_vec();
+        data.extend_from_slice(BEGIN_MARKER);
+        data.extend_from_slice(b"name\0Example\0contacts\0email:sec@example.com\0");
+        data.extend_from_slice(END_MARKER);
+        data.extend_from_slice(b"trailing");
+
+        let entries = find_security_txt(&data)?.expect("security.txt should be found");
+
+        assert_eq!(
+            entries,
+            vec![
+                ("name".to_string(), "Example".to_string()),
+                ("contacts".to_string(), "email:sec@example.com".to_string()),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_security_txt_missing() -> anyhow::Result<()> {
+        assert!(find_security_txt(b"\x7fELF no section here")?.is_none());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+use {
+    solana_instruction::Instruction,
+    solana_pubkey::Pubkey,
+    solana_signature::Signature,
+    std::{
+        hash::{DefaultHasher, Hash, Hasher},
+        sync::Mutex,
+        time::{Duration, Instant},
+    },
+};
+
+/// Fingerprint of a transaction message that ignores the blockhash, so the
+/// same transfer signed twice maps to the same value.
+pub fn message_fingerprint(fee_payer: &Pubkey, instructions: &[Instruction]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    fee_payer.hash(&mut hasher);
+    for ix in instructions {
+        ix.program_id.hash(&mut hasher);
+        for meta in &ix.accounts {
+            meta.pubkey.hash(&mut hasher);
+            meta.is_signer.hash(&mut hasher);
+            meta.is_writable.hash(&mut hasher);
+        }
+        ix.data.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+struct RecentSend {
+    fingerprint: u64,
+    /// `None` for transactions signed by the parallel sender
+    signature: Option<Signature>,
+    sent_at: Instant,
+}
+
+/// Short-lived, in-memory record of the transactions sent during this session.
+pub struct RecentSends {
+    window: Duration,
+    entries: Mutex<Vec<RecentSend>>,
+}
+
+impl RecentSends {
+    /// A zero window disables duplicate detection.
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            entries: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Returns the signature of an identical message sent within the window,
+    /// if it's known, and how long ago it was sent.
+    pub fn find(&self, fingerprint: u64) -> Option<(Option<Signature>, Duration)> {
+        let mut entries = self.entries.lock().expect("recent sends lock poisoned");
+        entries.retain(|entry| entry.sent_at.elapsed() < self.window);
+
+        entries
+            .iter()
+            .rev()
+            .find(|entry| entry.fingerprint == fingerprint)
+            .map(|entry| (entry.signature, entry.sent_at.elapsed()))
+    }
+
+    pub fn record(&self, fingerprint: u64, signature: Option<Signature>) {
+        if self.window.is_zero() {
+            return;
+        }
+
+        self.entries
+            .lock()
+            .expect("recent sends lock poisoned")
+            .push(RecentSend {
+                fingerprint,
+                signature,
+                sent_at: Instant::now(),
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_system_interface::instruction::transfer};
+
+    #[test]
+    fn test_fingerprint_matches_identical_transfer() {
+        let from = Pubkey::new_from_array([1; 32]);
+        let to = Pubkey::new_from_array([2; 32]);
+
+        assert_eq!(
+            message_fingerprint(&from, &[transfer(&from, &to, 1_000)]),
+            message_fingerprint(&from, &[transfer(&from, &to, 1_000)])
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_differs_by_amount() {
+        let from = Pubkey::new_from_array([1; 32]);
+        let to = Pubkey::new_from_array([2; 32]);
+
+        assert_ne!(
+            message_fingerprint(&from, &[transfer(&from, &to, 1_000)]),
+            message_fingerprint(&from, &[transfer(&from, &to, 1_001)])
+        );
+    }
+
+    #[test]
+    fn test_recent_sends_finds_recorded_message() {
+        let recent = RecentSends::new(Duration::from_secs(60));
+        let signature = Signature::from([7; 64]);
+
+        recent.record(42, Some(signature));
+
+        assert_eq!(recent.find(42).map(|(sig, _)| sig), Some(Some(signature)));
+        assert!(recent.find(7).is_none());
+    }
+
+    #[test]
+    fn test_recent_sends_zero_window_disables_detection() {
+        let recent = RecentSends::new(Duration::ZERO);
+
+        recent.record(42, Some(Signature::from([7; 64])));
+
+        assert!(recent.find(42).is_none());
+    }
+}
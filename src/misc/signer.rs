@@ -0,0 +1,124 @@
+#[cfg(feature = "ledger")]
+use {
+    crate::misc::mnemonic::format_derivation_path,
+    anyhow::anyhow,
+    solana_derivation_path::DerivationPath,
+    solana_remote_wallet::{
+        locator::Locator, remote_keypair::generate_remote_keypair,
+        remote_wallet::maybe_wallet_manager,
+    },
+};
+use {
+    crate::{
+        constants::HARDWARE_WALLET_SCHEME,
+        misc::helpers::read_keypair_from_path,
+        ui::{render::stdout, suspend_spinner},
+    },
+    anyhow::bail,
+    solana_keypair::Signer,
+    std::path::Path,
+};
+
+/// Whether `path` names a hardware wallet, such as `usb://ledger?key=0/0`,
+/// rather than a keypair file.
+pub fn is_hardware_wallet(path: &Path) -> bool {
+    path.to_string_lossy().starts_with(HARDWARE_WALLET_SCHEME)
+}
+
+/// Signer behind `path`: a keypair file, or a Ledger addressed as
+/// `usb://ledger[/<wallet pubkey>][?key=<account>[/<change>]]`.
+pub fn read_signer(path: &Path) -> anyhow::Result<Box<dyn Signer>> {
+    if !is_hardware_wallet(path) {
+        return Ok(Box::new(read_keypair_from_path(path)?));
+    }
+
+    read_hardware_wallet(path)
+}
+
+#[cfg(not(feature = "ledger"))]
+fn read_hardware_wallet(path: &Path) -> anyhow::Result<Box<dyn Signer>> {
+    bail!(
+        "Scilla was built without the `ledger` feature, rebuild it with `--features ledger` to \
+         sign with {}",
+        path.display()
+    )
+}
+
+#[cfg(feature = "ledger")]
+fn read_hardware_wallet(path: &Path) -> anyhow::Result<Box<dyn Signer>> {
+    let url = path.to_string_lossy();
+    let (locator, derivation_path) = parse_hardware_wallet_url(&url)?;
+    let wallet_manager = maybe_wallet_manager()?.ok_or_else(|| {
+        anyhow!("No hardware wallet found. Plug it in, unlock it and open the Solana app")
+    })?;
+
+    stdout().note(format!(
+        "Reading the address at {} from the device…",
+        format_derivation_path(&derivation_path)
+    ));
+    let signer =
+        generate_remote_keypair(locator, derivation_path, &wallet_manager, false, "signer")
+            .map_err(|e| anyhow!("Failed to use hardware wallet {url}: {e}"))?;
+
+    Ok(Box::new(signer))
+}
+
+/// Device and derivation path of a `usb://` URL. Without `key` the wallet's
+/// root account `m/44'/501'` is used, as the Solana CLI does.
+#[cfg(feature = "ledger")]
+pub fn parse_hardware_wallet_url(url: &str) -> anyhow::Result<(Locator, DerivationPath)> {
+    let (device, query) = url.split_once('?').unwrap_or((url, ""));
+    let locator = Locator::new_from_path(device)
+        .map_err(|e| anyhow!("Invalid hardware wallet URL {url}: {e}"))?;
+
+    let mut derivation_path = DerivationPath::default();
+    for parameter in query.split('&').filter(|parameter| !parameter.is_empty()) {
+        match parameter.split_once('=') {
+            Some(("key", key)) => {
+                derivation_path = DerivationPath::from_key_str(key)
+                    .map_err(|e| anyhow!("Invalid derivation path {key}: {e}"))?;
+            }
+            _ => bail!("Unknown hardware wallet URL parameter {parameter}, expected key=<path>"),
+        }
+    }
+
+    Ok((locator, derivation_path))
+}
+
+/// Hardware wallets wait for the user to approve every signature on the
+/// device, so say so before signing with one.
+pub fn note_device_confirmation(signers: &[&dyn Signer]) {
+    if signers.iter().any(|signer| signer.is_interactive()) {
+        suspend_spinner(|| {
+            stdout().warning("Review and approve the transaction on your hardware wallet…")
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "ledger")]
+    #[test]
+    fn test_parse_hardware_wallet_url() -> anyhow::Result<()> {
+        let (_, derivation_path) = parse_hardware_wallet_url("usb://ledger")?;
+        assert_eq!(derivation_path, DerivationPath::default());
+
+        let (_, derivation_path) = parse_hardware_wallet_url("usb://ledger?key=1/0")?;
+        assert_eq!(derivation_path, DerivationPath::from_key_str("1/0")?);
+
+        assert!(parse_hardware_wallet_url("usb://ledger?account=1").is_err());
+        assert!(parse_hardware_wallet_url("usb://trezor").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_hardware_wallet() {
+        assert!(is_hardware_wallet(Path::new("usb://ledger?key=0")));
+        assert!(!is_hardware_wallet(Path::new(
+            "/home/me/.config/solana/id.json"
+        )));
+    }
+}
@@ -0,0 +1,324 @@
+use {
+    crate::{constants::SIWS_NONCE_LEN, ui::render::field_table},
+    anyhow::{anyhow, bail},
+    chrono::{DateTime, SecondsFormat, Utc},
+    comfy_table::Table,
+    rand::{Rng, distributions::Alphanumeric},
+    solana_pubkey::Pubkey,
+    solana_signature::Signature,
+    std::{fmt, str::FromStr},
+};
+
+const HEADER_SUFFIX: &str = " wants you to sign in with your Solana account:";
+
+/// Labels of the optional fields, in the order they're written.
+const FIELD_LABELS: [&str; 8] = [
+    "URI",
+    "Version",
+    "Chain ID",
+    "Nonce",
+    "Issued At",
+    "Expiration Time",
+    "Not Before",
+    "Request ID",
+];
+
+/// A Sign-In With Solana message, laid out as wallets render it for
+/// `signIn`. Timestamps are kept as written, since the signature covers the
+/// exact text.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SiwsMessage {
+    pub domain: String,
+    pub address: Pubkey,
+    pub statement: Option<String>,
+    pub uri: Option<String>,
+    pub version: Option<String>,
+    pub chain_id: Option<String>,
+    pub nonce: Option<String>,
+    pub issued_at: Option<String>,
+    pub expiration_time: Option<String>,
+    pub not_before: Option<String>,
+    pub request_id: Option<String>,
+    pub resources: Vec<String>,
+}
+
+impl SiwsMessage {
+    /// The optional fields as label and value.
+    fn fields(&self) -> [(&'static str, Option<&str>); 8] {
+        let values = [
+            &self.uri,
+            &self.version,
+            &self.chain_id,
+            &self.nonce,
+            &self.issued_at,
+            &self.expiration_time,
+            &self.not_before,
+            &self.request_id,
+        ];
+        let mut values = values.into_iter().map(Option::as_deref);
+        FIELD_LABELS.map(|label| (label, values.next().flatten()))
+    }
+
+    fn set_field(&mut self, label: &str, value: &str) -> anyhow::Result<()> {
+        let field = match label {
+            "URI" => &mut self.uri,
+            "Version" => &mut self.version,
+            "Chain ID" => &mut self.chain_id,
+            "Nonce" => &mut self.nonce,
+            "Issued At" => &mut self.issued_at,
+            "Expiration Time" => &mut self.expiration_time,
+            "Not Before" => &mut self.not_before,
+            "Request ID" => &mut self.request_id,
+            _ => bail!("Unknown sign-in message field {label}"),
+        };
+        *field = Some(value.to_string());
+        Ok(())
+    }
+
+    /// Fails if the message isn't valid at `now` per its expiration and not
+    /// before times.
+    pub fn check_time(&self, now: DateTime<Utc>) -> anyhow::Result<()> {
+        let parse = |time: &str| {
+            DateTime::parse_from_rfc3339(time).map_err(|_| anyhow!("Invalid timestamp {time}"))
+        };
+
+        if let Some(expiration_time) = &self.expiration_time
+            && parse(expiration_time)? <= now
+        {
+            bail!("Sign-in message expired at {expiration_time}");
+        }
+        if let Some(not_before) = &self.not_before
+            && parse(not_before)? > now
+        {
+            bail!("Sign-in message is not valid before {not_before}");
+        }
+        if let Some(issued_at) = &self.issued_at {
+            parse(issued_at)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for SiwsMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{HEADER_SUFFIX}\n{}", self.domain, self.address)?;
+        if let Some(statement) = &self.statement {
+            write!(f, "\n\n{statement}")?;
+        }
+
+        let mut lines: Vec<String> = self
+            .fields()
+            .into_iter()
+            .filter_map(|(label, value)| Some(format!("{label}: {}", value?)))
+            .collect();
+        if !self.resources.is_empty() {
+            lines.push("Resources:".to_string());
+            lines.extend(
+                self.resources
+                    .iter()
+                    .map(|resource| format!("- {resource}")),
+            );
+        }
+        if !lines.is_empty() {
+            write!(f, "\n\n{}", lines.join("\n"))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for SiwsMessage {
+    type Err = anyhow::Error;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut lines = text.split('\n');
+        let domain = lines
+            .next()
+            .and_then(|header| header.strip_suffix(HEADER_SUFFIX))
+            .filter(|domain| !domain.is_empty())
+            .ok_or_else(|| anyhow!("Not a Sign-In With Solana message"))?;
+        let address = lines.next().unwrap_or_default();
+        let mut message = SiwsMessage {
+            domain: domain.to_string(),
+            address: address
+                .parse()
+                .map_err(|_| anyhow!("Invalid address {address}"))?,
+            ..SiwsMessage::default()
+        };
+
+        // The statement and the fields are blocks after a blank line each,
+        // told apart by the fields starting with a label
+        let rest: Vec<&str> = lines.collect();
+        let mut blocks = rest.split(|line| line.is_empty()).skip(1);
+        let mut block = blocks.next();
+        if let Some([statement]) = block
+            && !is_field_line(statement)
+        {
+            message.statement = Some(statement.to_string());
+            block = blocks.next();
+        }
+
+        let mut in_resources = false;
+        for line in block.unwrap_or_default() {
+            if in_resources {
+                let resource = line
+                    .strip_prefix("- ")
+                    .ok_or_else(|| anyhow!("Invalid resource line {line}"))?;
+                message.resources.push(resource.to_string());
+            } else if *line == "Resources:" {
+                in_resources = true;
+            } else {
+                let (label, value) = line
+                    .split_once(": ")
+                    .ok_or_else(|| anyhow!("Invalid sign-in message line {line}"))?;
+                message.set_field(label, value)?;
+            }
+        }
+
+        // Rendering it back must give the same text, or the layout is off
+        if message.to_string() != text {
+            bail!("Message is not laid out as a Sign-In With Solana message");
+        }
+
+        Ok(message)
+    }
+}
+
+fn is_field_line(line: &str) -> bool {
+    line == "Resources:"
+        || line
+            .split_once(": ")
+            .is_some_and(|(label, _)| FIELD_LABELS.contains(&label))
+}
+
+/// A random alphanumeric nonce, as SIWS asks for at least 8 characters.
+pub fn generate_nonce() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(SIWS_NONCE_LEN)
+        .map(char::from)
+        .collect()
+}
+
+/// `time` in the RFC 3339 form used for the message timestamps.
+pub fn timestamp(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// Checks `signature` was made over `text` by the address it names and that
+/// the message is valid at `now`.
+pub fn verify_message(
+    text: &str,
+    signature: &Signature,
+    now: DateTime<Utc>,
+) -> anyhow::Result<SiwsMessage> {
+    let message: SiwsMessage = text.parse()?;
+
+    if !signature.verify(message.address.as_ref(), text.as_bytes()) {
+        bail!("Signature was not made by {}", message.address);
+    }
+    message.check_time(now)?;
+
+    Ok(message)
+}
+
+pub fn siws_table(message: &SiwsMessage) -> Table {
+    let mut rows = vec![
+        ("Domain", message.domain.clone()),
+        ("Address", message.address.to_string()),
+    ];
+    if let Some(statement) = &message.statement {
+        rows.push(("Statement", statement.clone()));
+    }
+    rows.extend(
+        message
+            .fields()
+            .into_iter()
+            .filter_map(|(label, value)| Some((label, value?.to_string()))),
+    );
+    if !message.resources.is_empty() {
+        rows.push(("Resources", message.resources.join("\n")));
+    }
+
+    field_table(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::ui::render::capture,
+        insta::assert_snapshot,
+        solana_keypair::{Keypair, Signer},
+    };
+
+    fn message(address: Pubkey) -> SiwsMessage {
+        SiwsMessage {
+            domain: "example.com".to_string(),
+            address,
+            statement: Some("Sign in to the dashboard".to_string()),
+            uri: Some("https://example.com/login".to_string()),
+            version: Some("1".to_string()),
+            chain_id: Some("mainnet".to_string()),
+            nonce: Some("k3Jd8sLq0PzX2mNb".to_string()),
+            issued_at: Some("2026-10-16T09:30:00.000Z".to_string()),
+            expiration_time: Some("2026-10-16T09:40:00.000Z".to_string()),
+            resources: vec!["https://example.com/terms".to_string()],
+            ..SiwsMessage::default()
+        }
+    }
+
+    fn at(time: &str) -> DateTime<Utc> {
+        time.parse().expect("test timestamp should parse")
+    }
+
+    #[test]
+    fn test_siws_message_round_trip() -> anyhow::Result<()> {
+        let message = message(Pubkey::new_from_array([1; 32]));
+        let text = message.to_string();
+
+        assert_eq!(text.parse::<SiwsMessage>()?, message);
+
+        let minimal = SiwsMessage {
+            domain: "example.com".to_string(),
+            address: Pubkey::new_from_array([1; 32]),
+            nonce: Some("k3Jd8sLq0PzX2mNb".to_string()),
+            ..SiwsMessage::default()
+        };
+        assert_eq!(minimal.to_string().parse::<SiwsMessage>()?, minimal);
+
+        assert!(format!("{text}\n").parse::<SiwsMessage>().is_err());
+        assert!(text.replace("URI:", "Url:").parse::<SiwsMessage>().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_message() -> anyhow::Result<()> {
+        let keypair = Keypair::new();
+        let text = message(keypair.pubkey()).to_string();
+        let signature = keypair.sign_message(text.as_bytes());
+
+        assert!(verify_message(&text, &signature, at("2026-10-16T09:35:00Z")).is_ok());
+        // Expired
+        assert!(verify_message(&text, &signature, at("2026-10-16T09:45:00Z")).is_err());
+
+        let other = Keypair::new().sign_message(text.as_bytes());
+        assert!(verify_message(&text, &other, at("2026-10-16T09:35:00Z")).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_siws_message_text() {
+        let message = message(Pubkey::new_from_array([1; 32]));
+
+        let output = capture(|out| {
+            out.line(message.to_string());
+            out.table(siws_table(&message));
+        });
+
+        assert_snapshot!("siws_message", output);
+    }
+}
@@ -0,0 +1,281 @@
+use {
+    crate::{
+        constants::{
+            MAX_MULTIPLE_ACCOUNTS, NAME_OFFERS_PROGRAM_ID, NAME_SERVICE_HASH_PREFIX,
+            NAME_SERVICE_PROGRAM_ID, NAME_TOKENIZER_PROGRAM_ID, REVERSE_LOOKUP_CLASS,
+            SOL_TLD_ACCOUNT,
+        },
+        context::ScillaContext,
+        ui::render::stdout,
+    },
+    anyhow::{anyhow, bail},
+    sha2::{Digest, Sha256},
+    solana_account::Account,
+    solana_pubkey::Pubkey,
+    solana_rpc_client::nonblocking::rpc_client::RpcClient,
+    std::{collections::HashMap, str::FromStr},
+};
+
+/// Size of the name registry header: parent name, owner and class.
+const NAME_REGISTRY_HEADER_LEN: usize = 96;
+
+/// What was typed at an address prompt: a base58 address, or a `.sol`
+/// domain still to be resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AddressInput {
+    Pubkey(Pubkey),
+    Domain(String),
+}
+
+impl FromStr for AddressInput {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = s.trim();
+        if is_sol_domain(input) {
+            let domain = input.to_ascii_lowercase();
+            domain_labels(&domain)?;
+            return Ok(AddressInput::Domain(domain));
+        }
+
+        input
+            .parse()
+            .map(AddressInput::Pubkey)
+            .map_err(|_| anyhow!("{input} is neither a valid address nor a .sol domain"))
+    }
+}
+
+fn is_sol_domain(input: &str) -> bool {
+    input.to_ascii_lowercase().ends_with(".sol")
+}
+
+/// The labels of `domain` from its top-level name down, without `.sol`:
+/// `dex.bonfida.sol` gives `["bonfida", "dex"]`.
+fn domain_labels(domain: &str) -> anyhow::Result<Vec<&str>> {
+    let name = domain
+        .strip_suffix(".sol")
+        .ok_or_else(|| anyhow!("{domain} is not a .sol domain"))?;
+
+    let labels: Vec<&str> = name.split('.').rev().collect();
+    if labels.iter().any(|label| label.is_empty()) {
+        bail!("{domain} is not a valid .sol domain");
+    }
+    if labels.len() > 2 {
+        bail!("Only domains and their direct subdomains can be resolved, got {domain}");
+    }
+
+    Ok(labels)
+}
+
+fn name_account(name: &str, parent: &Pubkey) -> Pubkey {
+    name_account_with_class(name, &Pubkey::default(), parent)
+}
+
+fn name_account_with_class(name: &str, class: &Pubkey, parent: &Pubkey) -> Pubkey {
+    let hashed_name = Sha256::digest(format!("{NAME_SERVICE_HASH_PREFIX}{name}"));
+
+    Pubkey::find_program_address(
+        &[hashed_name.as_slice(), class.as_ref(), parent.as_ref()],
+        &Pubkey::from_str_const(NAME_SERVICE_PROGRAM_ID),
+    )
+    .0
+}
+
+/// Name registry account of a domain such as `alice.sol` or
+/// `dex.alice.sol`.
+pub fn domain_account(domain: &str) -> anyhow::Result<Pubkey> {
+    let labels = domain_labels(domain)?;
+
+    let mut account = name_account(labels[0], &Pubkey::from_str_const(SOL_TLD_ACCOUNT));
+    if let Some(subdomain) = labels.get(1) {
+        // Subdomain names are hashed with a leading zero byte
+        account = name_account(&format!("\0{subdomain}"), &account);
+    }
+
+    Ok(account)
+}
+
+/// Resolves a `.sol` domain to the wallet owning it.
+pub async fn resolve_domain(rpc: &RpcClient, domain: &str) -> anyhow::Result<Pubkey> {
+    let account_key = domain_account(domain)?;
+    let account = rpc
+        .get_account_with_commitment(&account_key, rpc.commitment())
+        .await?
+        .value
+        .ok_or_else(|| anyhow!("{domain} is not registered"))?;
+
+    if account.owner != Pubkey::from_str_const(NAME_SERVICE_PROGRAM_ID)
+        || account.data.len() < NAME_REGISTRY_HEADER_LEN
+    {
+        bail!("{account_key} is not a name account");
+    }
+    let owner = Pubkey::try_from(&account.data[32..64])?;
+
+    // Tokenized domains are owned by the tokenizer escrow, not the holder
+    let owner_account = rpc
+        .get_account_with_commitment(&owner, rpc.commitment())
+        .await?
+        .value;
+    if owner_account
+        .is_some_and(|account| account.owner == Pubkey::from_str_const(NAME_TOKENIZER_PROGRAM_ID))
+    {
+        bail!("{domain} is tokenized as an NFT, its holder can't be resolved from the domain");
+    }
+
+    Ok(owner)
+}
+
+/// Account recording the primary domain a wallet picked.
+fn primary_domain_account(wallet: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"favourite_domain", wallet.as_ref()],
+        &Pubkey::from_str_const(NAME_OFFERS_PROGRAM_ID),
+    )
+    .0
+}
+
+/// Account mapping `name_account` back to the name it was derived from.
+fn reverse_lookup_account(name_account: &Pubkey) -> Pubkey {
+    name_account_with_class(
+        &name_account.to_string(),
+        &Pubkey::from_str_const(REVERSE_LOOKUP_CLASS),
+        &Pubkey::default(),
+    )
+}
+
+/// The name stored in a reverse lookup account, a borsh string after the
+/// name registry header.
+fn reverse_lookup_name(data: &[u8]) -> Option<String> {
+    let len = data.get(NAME_REGISTRY_HEADER_LEN..NAME_REGISTRY_HEADER_LEN + 4)?;
+    let len = u32::from_le_bytes(len.try_into().ok()?) as usize;
+    let start = NAME_REGISTRY_HEADER_LEN + 4;
+    let name = data.get(start..start + len)?;
+
+    String::from_utf8(name.to_vec()).ok()
+}
+
+async fn fetch_accounts(rpc: &RpcClient, keys: &[Pubkey]) -> anyhow::Result<Vec<Option<Account>>> {
+    let mut accounts = Vec::with_capacity(keys.len());
+    for chunk in keys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        accounts.extend(rpc.get_multiple_accounts(chunk).await?);
+    }
+    Ok(accounts)
+}
+
+/// Primary `.sol` domains set by `wallets`. Wallets without one are left out,
+/// as are primary domains that have since changed hands or are subdomains.
+pub async fn primary_domains(
+    rpc: &RpcClient,
+    wallets: &[Pubkey],
+) -> anyhow::Result<HashMap<Pubkey, String>> {
+    let primary_accounts: Vec<Pubkey> = wallets.iter().map(primary_domain_account).collect();
+    let primaries = fetch_accounts(rpc, &primary_accounts).await?;
+
+    // The primary domain account holds a tag byte, then the name account
+    let candidates: Vec<(Pubkey, Pubkey)> = wallets
+        .iter()
+        .zip(primaries)
+        .filter_map(|(wallet, account)| {
+            let account = account.filter(|account| {
+                account.owner == Pubkey::from_str_const(NAME_OFFERS_PROGRAM_ID)
+            })?;
+            let name_account = Pubkey::try_from(account.data.get(1..33)?).ok()?;
+            Some((*wallet, name_account))
+        })
+        .collect();
+
+    let keys: Vec<Pubkey> = candidates
+        .iter()
+        .flat_map(|(_, name_account)| [*name_account, reverse_lookup_account(name_account)])
+        .collect();
+    let accounts = fetch_accounts(rpc, &keys).await?;
+
+    let domains = candidates
+        .into_iter()
+        .zip(accounts.chunks(2))
+        .filter_map(|((wallet, _), pair)| {
+            let [Some(name), Some(reverse)] = pair else {
+                return None;
+            };
+            let header = name.data.get(..NAME_REGISTRY_HEADER_LEN)?;
+            let is_owned_domain = name.owner == Pubkey::from_str_const(NAME_SERVICE_PROGRAM_ID)
+                && &header[..32] == Pubkey::from_str_const(SOL_TLD_ACCOUNT).as_ref()
+                && &header[32..64] == wallet.as_ref();
+
+            is_owned_domain
+                .then(|| reverse_lookup_name(&reverse.data))
+                .flatten()
+                .map(|name| (wallet, format!("{name}.sol")))
+        })
+        .collect();
+
+    Ok(domains)
+}
+
+/// Primary domains of `addresses` to show next to them, when `show-domains`
+/// is enabled. The lookup only adds to the output, so a failed one is
+/// reported and skipped.
+pub async fn domains_for_display(
+    ctx: &ScillaContext,
+    addresses: &[Pubkey],
+) -> HashMap<Pubkey, String> {
+    if !ctx.show_domains() || addresses.is_empty() {
+        return HashMap::new();
+    }
+
+    primary_domains(ctx.rpc(), addresses)
+        .await
+        .unwrap_or_else(|e| {
+            stdout().warning(format!("Skipped .sol domain lookup: {e}"));
+            HashMap::new()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_domain_account() -> anyhow::Result<()> {
+        assert_eq!(
+            domain_account("bonfida.sol")?.to_string(),
+            "Crf8hzfthWGbGbLTVCiqRqV5MVnbpHB1L9KQMd6gsinb"
+        );
+        assert_eq!(
+            domain_account("dex.bonfida.sol")?.to_string(),
+            "HoFfFXqFHAC8RP3duuQNzag1ieUwJRBv1HtRNiWFq4Qu"
+        );
+        assert!(domain_account("a.b.bonfida.sol").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reverse_lookup_name() {
+        let mut data = vec![0; NAME_REGISTRY_HEADER_LEN];
+        data.extend(7u32.to_le_bytes());
+        data.extend(b"bonfida");
+
+        assert_eq!(reverse_lookup_name(&data).as_deref(), Some("bonfida"));
+        assert_eq!(reverse_lookup_name(&data[..data.len() - 1]), None);
+        assert_eq!(reverse_lookup_name(&[0; 12]), None);
+    }
+
+    #[test]
+    fn test_address_input() -> anyhow::Result<()> {
+        let pubkey = Pubkey::new_from_array([1; 32]);
+
+        assert_eq!(
+            pubkey.to_string().parse::<AddressInput>()?,
+            AddressInput::Pubkey(pubkey)
+        );
+        assert_eq!(
+            " Alice.SOL ".parse::<AddressInput>()?,
+            AddressInput::Domain("alice.sol".to_string())
+        );
+        assert!(".sol".parse::<AddressInput>().is_err());
+        assert!("alice".parse::<AddressInput>().is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,371 @@
+use {
+    crate::{context::ScillaContext, ui::render::stdout},
+    anyhow::{anyhow, bail},
+    console::Term,
+    futures::{Stream, StreamExt, future, stream},
+    solana_account::Account,
+    solana_account_decoder_client_types::UiAccountEncoding,
+    solana_client::nonblocking::pubsub_client::PubsubClient,
+    solana_commitment_config::CommitmentConfig,
+    solana_pubkey::Pubkey,
+    solana_rpc_client_api::{
+        config::{
+            RpcAccountInfoConfig, RpcSignatureSubscribeConfig, RpcTransactionLogsConfig,
+            RpcTransactionLogsFilter,
+        },
+        response::RpcSignatureResult,
+    },
+    solana_signature::Signature,
+};
+
+/// A new state of a watched account.
+#[derive(Debug, Clone)]
+pub struct AccountUpdate {
+    pub slot: u64,
+    pub account: Account,
+}
+
+/// A transaction mentioning a watched account.
+#[derive(Debug, Clone)]
+pub struct TransactionUpdate {
+    pub slot: u64,
+    pub signature: Signature,
+    pub failed: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum StreamUpdate {
+    Account(AccountUpdate),
+    Transaction(TransactionUpdate),
+}
+
+/// Logs of a transaction invoking a watched program.
+#[derive(Debug, Clone)]
+pub struct LogsUpdate {
+    pub slot: u64,
+    pub signature: Signature,
+    pub failed: bool,
+    pub logs: Vec<String>,
+}
+
+/// Outcome of a transaction that reached the awaited commitment.
+#[derive(Debug, Clone)]
+pub struct SignatureUpdate {
+    pub slot: u64,
+    pub error: Option<String>,
+}
+
+/// Streams changes to `address` and the transactions mentioning it to
+/// `on_update` until a key is pressed.
+///
+/// Updates come from the configured Yellowstone gRPC endpoint when there is
+/// one, falling back to the RPC websocket when it can't be reached.
+pub async fn watch_address(
+    ctx: &ScillaContext,
+    address: &Pubkey,
+    on_update: impl FnMut(StreamUpdate) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    if let Some(geyser) = ctx.geyser() {
+        #[cfg(feature = "geyser")]
+        match geyser::subscribe(geyser, ctx.rpc().commitment(), address).await {
+            Ok(updates) => {
+                stdout().note(format!(
+                    "Streaming from Yellowstone gRPC at {} (press any key to stop)",
+                    geyser.endpoint
+                ));
+                return until_key_pressed(updates, on_update).await;
+            }
+            Err(e) => stdout().warning(format!(
+                "Yellowstone gRPC at {} is unavailable ({e}), falling back to websocket",
+                geyser.endpoint
+            )),
+        }
+
+        #[cfg(not(feature = "geyser"))]
+        stdout().warning(format!(
+            "Scilla was built without the `geyser` feature, ignoring {} and using the websocket",
+            geyser.endpoint
+        ));
+    }
+
+    let client = PubsubClient::new(&ctx.websocket_url()?).await?;
+    let account_config = RpcAccountInfoConfig {
+        encoding: Some(UiAccountEncoding::Base64),
+        commitment: Some(ctx.rpc().commitment()),
+        ..RpcAccountInfoConfig::default()
+    };
+    let (accounts, unsubscribe_account) = client
+        .account_subscribe(address, Some(account_config))
+        .await?;
+    let (logs, unsubscribe_logs) = client
+        .logs_subscribe(
+            RpcTransactionLogsFilter::Mentions(vec![address.to_string()]),
+            RpcTransactionLogsConfig {
+                commitment: Some(ctx.rpc().commitment()),
+            },
+        )
+        .await?;
+
+    let accounts = accounts.map(|response| -> anyhow::Result<StreamUpdate> {
+        let account = response
+            .value
+            .decode()
+            .ok_or_else(|| anyhow!("Could not decode account update"))?;
+        Ok(StreamUpdate::Account(AccountUpdate {
+            slot: response.context.slot,
+            account,
+        }))
+    });
+    let transactions = logs.map(|response| -> anyhow::Result<StreamUpdate> {
+        Ok(StreamUpdate::Transaction(TransactionUpdate {
+            slot: response.context.slot,
+            signature: response.value.signature.parse()?,
+            failed: response.value.err.is_some(),
+        }))
+    });
+
+    stdout().note("Streaming from the RPC websocket (press any key to stop)");
+    let result = until_key_pressed(stream::select(accounts, transactions), on_update).await;
+
+    unsubscribe_account().await;
+    unsubscribe_logs().await;
+    result
+}
+
+/// Streams the logs of every transaction invoking `program_id` to
+/// `on_update` until a key is pressed.
+pub async fn watch_program_logs(
+    ctx: &ScillaContext,
+    program_id: &Pubkey,
+    on_update: impl FnMut(LogsUpdate) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let client = PubsubClient::new(&ctx.websocket_url()?).await?;
+    let (logs, unsubscribe) = client
+        .logs_subscribe(
+            RpcTransactionLogsFilter::Mentions(vec![program_id.to_string()]),
+            RpcTransactionLogsConfig {
+                commitment: Some(ctx.rpc().commitment()),
+            },
+        )
+        .await?;
+
+    let logs = logs.map(|response| -> anyhow::Result<LogsUpdate> {
+        Ok(LogsUpdate {
+            slot: response.context.slot,
+            signature: response.value.signature.parse()?,
+            failed: response.value.err.is_some(),
+            logs: response.value.logs,
+        })
+    });
+
+    stdout().note(format!(
+        "Tailing logs of {program_id} from the RPC websocket (press any key to stop)"
+    ));
+    let result = until_key_pressed(logs, on_update).await;
+
+    unsubscribe().await;
+    result
+}
+
+/// Streams every slot the RPC node processes to `on_update` until a key is
+/// pressed.
+pub async fn watch_slots(
+    ctx: &ScillaContext,
+    on_update: impl FnMut(u64) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let client = PubsubClient::new(&ctx.websocket_url()?).await?;
+    let (slots, unsubscribe) = client.slot_subscribe().await?;
+
+    let result = until_key_pressed(slots.map(|slot_info| Ok(slot_info.slot)), on_update).await;
+
+    unsubscribe().await;
+    result
+}
+
+/// Waits for `signature` to reach `commitment` and hands its outcome to
+/// `on_landed`, keeping it on screen until a key is pressed. Pressing a key
+/// earlier stops waiting.
+pub async fn wait_for_signature(
+    ctx: &ScillaContext,
+    signature: &Signature,
+    commitment: CommitmentConfig,
+    on_landed: impl FnMut(SignatureUpdate) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let client = PubsubClient::new(&ctx.websocket_url()?).await?;
+    let (notifications, unsubscribe) = client
+        .signature_subscribe(
+            signature,
+            Some(RpcSignatureSubscribeConfig {
+                commitment: Some(commitment),
+                enable_received_notification: Some(false),
+            }),
+        )
+        .await?;
+
+    // The node sends a single notification, after which the stream is kept
+    // open so the key press, not the notification, ends the wait
+    let landed = notifications
+        .filter_map(|response| {
+            future::ready(match response.value {
+                RpcSignatureResult::ProcessedSignature(result) => Some(Ok(SignatureUpdate {
+                    slot: response.context.slot,
+                    error: result.err.map(|err| format!("{err:?}")),
+                })),
+                RpcSignatureResult::ReceivedSignature(_) => None,
+            })
+        })
+        .take(1)
+        .chain(stream::pending());
+
+    stdout().note(format!(
+        "Waiting for {signature} to be {:?} (press any key to stop)",
+        commitment.commitment
+    ));
+    let result = until_key_pressed(Box::pin(landed), on_landed).await;
+
+    unsubscribe().await;
+    result
+}
+
+/// Hands every item of `updates` to `on_update` until a key is pressed, the
+/// stream ends or either of them fails.
+async fn until_key_pressed<T>(
+    mut updates: impl Stream<Item = anyhow::Result<T>> + Unpin,
+    mut on_update: impl FnMut(T) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let mut stop = tokio::task::spawn_blocking(|| Term::stdout().read_key());
+
+    loop {
+        tokio::select! {
+            update = updates.next() => match update {
+                Some(update) => on_update(update?)?,
+                None => bail!("The update stream was closed by the server"),
+            },
+            _ = &mut stop => return Ok(()),
+        }
+    }
+}
+
+#[cfg(feature = "geyser")]
+mod geyser {
+    use {
+        super::{AccountUpdate, StreamUpdate, TransactionUpdate},
+        crate::config::GeyserConfig,
+        anyhow::anyhow,
+        futures::{SinkExt, Stream, StreamExt, future},
+        solana_account::Account,
+        solana_commitment_config::{CommitmentConfig, CommitmentLevel},
+        solana_pubkey::Pubkey,
+        solana_signature::Signature,
+        std::collections::HashMap,
+        yellowstone_grpc_client::{ClientTlsConfig, GeyserGrpcClient},
+        yellowstone_grpc_proto::prelude::{
+            CommitmentLevel as GeyserCommitment, SubscribeRequest, SubscribeRequestFilterAccounts,
+            SubscribeRequestFilterTransactions, SubscribeRequestPing, SubscribeUpdate,
+            subscribe_update::UpdateOneof,
+        },
+    };
+
+    /// Filter name the subscribed updates are tagged with.
+    const FILTER: &str = "scilla";
+
+    fn commitment_level(commitment: CommitmentConfig) -> GeyserCommitment {
+        match commitment.commitment {
+            CommitmentLevel::Processed => GeyserCommitment::Processed,
+            CommitmentLevel::Confirmed => GeyserCommitment::Confirmed,
+            CommitmentLevel::Finalized => GeyserCommitment::Finalized,
+        }
+    }
+
+    /// Subscribes to account and transaction updates of `address`. The
+    /// returned stream answers the server's pings to keep the subscription
+    /// alive through idle periods.
+    pub async fn subscribe(
+        config: &GeyserConfig,
+        commitment: CommitmentConfig,
+        address: &Pubkey,
+    ) -> anyhow::Result<impl Stream<Item = anyhow::Result<StreamUpdate>> + Unpin> {
+        let mut client = GeyserGrpcClient::build_from_shared(config.endpoint.clone())?
+            .x_token(config.x_token.clone())?
+            .tls_config(ClientTlsConfig::new().with_native_roots())?
+            .connect()
+            .await?;
+
+        let request = SubscribeRequest {
+            accounts: HashMap::from([(
+                FILTER.to_string(),
+                SubscribeRequestFilterAccounts {
+                    account: vec![address.to_string()],
+                    ..Default::default()
+                },
+            )]),
+            transactions: HashMap::from([(
+                FILTER.to_string(),
+                SubscribeRequestFilterTransactions {
+                    vote: Some(false),
+                    account_include: vec![address.to_string()],
+                    ..Default::default()
+                },
+            )]),
+            commitment: Some(commitment_level(commitment) as i32),
+            ..Default::default()
+        };
+        let (mut sink, updates) = client.subscribe_with_request(Some(request)).await?;
+
+        let updates = updates.filter_map(move |update| {
+            let update = match update {
+                Ok(SubscribeUpdate {
+                    update_oneof: Some(UpdateOneof::Ping(_)),
+                    ..
+                }) => {
+                    let ping = SubscribeRequest {
+                        ping: Some(SubscribeRequestPing { id: 1 }),
+                        ..Default::default()
+                    };
+                    // Best effort, a missed pong only risks the server
+                    // closing the stream, which is reported then
+                    let _ = sink.start_send_unpin(ping);
+                    None
+                }
+                Ok(update) => stream_update(update).transpose(),
+                Err(status) => Some(Err(anyhow!("{}", status.message()))),
+            };
+            future::ready(update)
+        });
+
+        Ok(Box::pin(updates))
+    }
+
+    fn stream_update(update: SubscribeUpdate) -> anyhow::Result<Option<StreamUpdate>> {
+        let update = match update.update_oneof {
+            Some(UpdateOneof::Account(update)) => {
+                let Some(info) = update.account else {
+                    return Ok(None);
+                };
+                StreamUpdate::Account(AccountUpdate {
+                    slot: update.slot,
+                    account: Account {
+                        lamports: info.lamports,
+                        data: info.data,
+                        owner: Pubkey::try_from(info.owner.as_slice())?,
+                        executable: info.executable,
+                        rent_epoch: info.rent_epoch,
+                    },
+                })
+            }
+            Some(UpdateOneof::Transaction(update)) => {
+                let Some(info) = update.transaction else {
+                    return Ok(None);
+                };
+                StreamUpdate::Transaction(TransactionUpdate {
+                    slot: update.slot,
+                    signature: Signature::try_from(info.signature.as_slice())?,
+                    failed: info.meta.is_some_and(|meta| meta.err.is_some()),
+                })
+            }
+            _ => return Ok(None),
+        };
+
+        Ok(Some(update))
+    }
+}
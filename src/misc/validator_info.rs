@@ -0,0 +1,107 @@
+use {
+    crate::{
+        constants::VALIDATOR_INFO_KEY, context::ScillaContext, misc::helpers::fetch_owned_accounts,
+    },
+    solana_account_decoder_client_types::UiAccountEncoding,
+    solana_pubkey::Pubkey,
+    solana_rpc_client_api::{
+        config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+        filter::{Memcmp, RpcFilterType},
+    },
+    solana_sdk_ids::config,
+    std::collections::HashMap,
+};
+
+/// Validator info is stored in config accounts with two keys: the validator
+/// info key, then the validator identity that signed it.
+const VALIDATOR_INFO_KEY_COUNT: u8 = 2;
+
+/// Size of a config key: the pubkey and whether it signs.
+const CONFIG_KEY_LEN: usize = 33;
+
+/// Identity and published name of a validator info account.
+///
+/// The account holds the config keys as a short vector, followed by the
+/// info JSON as a bincode string.
+pub fn parse_validator_info(data: &[u8]) -> Option<(Pubkey, String)> {
+    let (&count, data) = data.split_first()?;
+    if count != VALIDATOR_INFO_KEY_COUNT {
+        return None;
+    }
+    let keys_len = CONFIG_KEY_LEN * usize::from(count);
+    let (keys, info) = (data.get(..keys_len)?, data.get(keys_len..)?);
+
+    if keys[..32] != Pubkey::from_str_const(VALIDATOR_INFO_KEY).to_bytes() {
+        return None;
+    }
+    let identity = Pubkey::try_from(&keys[CONFIG_KEY_LEN..CONFIG_KEY_LEN + 32]).ok()?;
+
+    let len = u64::from_le_bytes(info.get(..8)?.try_into().ok()?) as usize;
+    let json = info.get(8..8usize.checked_add(len)?)?;
+    let info: serde_json::Value = serde_json::from_slice(json).ok()?;
+    let name = info.get("name")?.as_str()?.trim();
+
+    (!name.is_empty()).then(|| (identity, name.to_string()))
+}
+
+/// Names validators published for their identities with validator-info.
+pub async fn validator_names(ctx: &ScillaContext) -> anyhow::Result<HashMap<Pubkey, String>> {
+    // Only config accounts whose first key is the validator info key
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            1,
+            Pubkey::from_str_const(VALIDATOR_INFO_KEY).as_ref(),
+        ))]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(ctx.rpc().commitment()),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+
+    let accounts = fetch_owned_accounts(ctx, &config::id(), config).await?;
+
+    Ok(accounts
+        .iter()
+        .filter_map(|(_, account)| parse_validator_info(&account.data))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validator_info_data(identity: &Pubkey, json: &str) -> Vec<u8> {
+        let mut data = vec![VALIDATOR_INFO_KEY_COUNT];
+        data.extend(Pubkey::from_str_const(VALIDATOR_INFO_KEY).to_bytes());
+        data.push(0);
+        data.extend(identity.to_bytes());
+        data.push(1);
+        data.extend((json.len() as u64).to_le_bytes());
+        data.extend(json.as_bytes());
+        data
+    }
+
+    #[test]
+    fn test_parse_validator_info() {
+        let identity = Pubkey::new_from_array([7; 32]);
+
+        let data = validator_info_data(
+            &identity,
+            r#"{"name":" Scilla Validator ","website":"https://example.com"}"#,
+        );
+        assert_eq!(
+            parse_validator_info(&data),
+            Some((identity, "Scilla Validator".to_string()))
+        );
+
+        let unnamed = validator_info_data(&identity, r#"{"website":"https://example.com"}"#);
+        assert_eq!(parse_validator_info(&unnamed), None);
+
+        let mut other_config = data.clone();
+        other_config[1] = 0;
+        assert_eq!(parse_validator_info(&other_config), None);
+        assert_eq!(parse_validator_info(&data[..data.len() - 1]), None);
+    }
+}
@@ -0,0 +1,124 @@
+use {
+    crate::{
+        config::{DestinationWhitelist, WhitelistMode},
+        context::ScillaContext,
+        prompt::prompt_confirmation,
+        ui::{print_error, render::stdout},
+    },
+    anyhow::{anyhow, bail},
+    solana_pubkey::Pubkey,
+    std::collections::HashSet,
+};
+
+/// The configured destination whitelist with its addresses parsed.
+#[derive(Debug, Default)]
+pub struct Whitelist {
+    addresses: HashSet<Pubkey>,
+    mode: WhitelistMode,
+}
+
+impl Whitelist {
+    pub fn mode(&self) -> WhitelistMode {
+        self.mode
+    }
+
+    /// Whether funds may go to `destination` without a second thought. Every
+    /// destination is allowed while the list is empty.
+    pub fn allows(&self, destination: &Pubkey) -> bool {
+        self.addresses.is_empty() || self.addresses.contains(destination)
+    }
+
+    /// Whether `destination` is turned away outright rather than warned about.
+    pub fn refuses(&self, destination: &Pubkey) -> bool {
+        self.mode == WhitelistMode::Refuse && !self.allows(destination)
+    }
+}
+
+impl TryFrom<&DestinationWhitelist> for Whitelist {
+    type Error = anyhow::Error;
+
+    fn try_from(whitelist: &DestinationWhitelist) -> anyhow::Result<Self> {
+        let addresses = whitelist
+            .addresses
+            .iter()
+            .map(|address| {
+                address
+                    .parse()
+                    .map_err(|e| anyhow!("Invalid whitelist address {address}: {e}"))
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(Self {
+            addresses,
+            mode: whitelist.mode,
+        })
+    }
+}
+
+/// Checks `destination` before funds are sent to it. Destinations off the
+/// whitelist are refused, or in warn mode sent to once confirmed. Returns
+/// whether to go ahead.
+pub fn confirm_destination(ctx: &ScillaContext, destination: &Pubkey) -> bool {
+    match check_destination(ctx, destination) {
+        Ok(()) => true,
+        Err(e) => {
+            print_error(e);
+            false
+        }
+    }
+}
+
+/// [`confirm_destination`] failing with the reason instead of printing it.
+pub fn check_destination(ctx: &ScillaContext, destination: &Pubkey) -> anyhow::Result<()> {
+    let whitelist = ctx.whitelist();
+    if whitelist.allows(destination) {
+        return Ok(());
+    }
+
+    match whitelist.mode() {
+        WhitelistMode::Refuse => {
+            bail!("{destination} is not on the destination whitelist, refusing to send")
+        }
+        WhitelistMode::Warn => {
+            stdout().warning(format!("{destination} is not on the destination whitelist"));
+            if !prompt_confirmation("Send to it anyway?") {
+                bail!("Cancelled, nothing was sent to {destination}");
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_whitelist() -> anyhow::Result<()> {
+        let listed = Pubkey::new_from_array([1; 32]);
+        let unlisted = Pubkey::new_from_array([2; 32]);
+
+        let mut config = DestinationWhitelist {
+            addresses: vec![listed.to_string()],
+            mode: WhitelistMode::Warn,
+        };
+        let whitelist = Whitelist::try_from(&config)?;
+        assert!(whitelist.allows(&listed));
+        assert!(!whitelist.allows(&unlisted));
+        assert!(!whitelist.refuses(&unlisted));
+
+        config.mode = WhitelistMode::Refuse;
+        let whitelist = Whitelist::try_from(&config)?;
+        assert!(whitelist.refuses(&unlisted));
+        assert!(!whitelist.refuses(&listed));
+
+        // An empty list turns the check off
+        config.addresses.clear();
+        assert!(Whitelist::try_from(&config)?.allows(&unlisted));
+
+        config.addresses.push("not-an-address".to_string());
+        assert!(Whitelist::try_from(&config).is_err());
+
+        Ok(())
+    }
+}
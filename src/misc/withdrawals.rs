@@ -0,0 +1,422 @@
+use {
+    crate::{
+        misc::{
+            csv::parse_rows,
+            helpers::{SolAmount, lamports_to_sol},
+        },
+        ui::{new_table, render::header_cell},
+    },
+    anyhow::{Context, anyhow, bail},
+    comfy_table::{Cell, Table},
+    solana_pubkey::Pubkey,
+    solana_signature::Signature,
+    solana_transaction::Hash,
+    std::{
+        collections::{HashMap, HashSet},
+        fmt,
+        fs::{self, OpenOptions},
+        io::Write,
+        path::{Path, PathBuf},
+    },
+};
+
+/// One line of a withdrawal queue: who gets paid, how much and the
+/// reference id the payout is tracked by.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Withdrawal {
+    pub address: Pubkey,
+    pub lamports: u64,
+    pub reference: String,
+}
+
+/// Parses a withdrawal queue, one `address,amount,reference` line per
+/// withdrawal with the amount in SOL, as described in [`parse_rows`].
+pub fn parse_queue(contents: &str) -> anyhow::Result<Vec<Withdrawal>> {
+    let mut references = HashSet::new();
+
+    parse_rows(contents, "withdrawal queue", "address", |_, line| {
+        let withdrawal = parse_line(line)?;
+        if !references.insert(withdrawal.reference.clone()) {
+            bail!("duplicate reference {}", withdrawal.reference);
+        }
+        Ok(withdrawal)
+    })
+}
+
+fn parse_line(line: &str) -> anyhow::Result<Withdrawal> {
+    let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+    let [address, amount, reference] = fields[..] else {
+        bail!("expected address,amount,reference");
+    };
+
+    let address = address
+        .parse()
+        .map_err(|_| anyhow!("invalid address {address}"))?;
+    let amount: SolAmount = amount.parse()?;
+    if reference.is_empty() {
+        bail!("missing reference id");
+    }
+
+    Ok(Withdrawal {
+        address,
+        lamports: amount.to_lamports(),
+        reference: reference.to_string(),
+    })
+}
+
+/// Where a withdrawal stands, as recorded in its [`WithdrawalJournal`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum WithdrawalStatus {
+    Pending,
+    /// Signed and sent, not yet seen confirmed. Kept with its blockhash so a
+    /// resumed run can tell whether it may still land.
+    Submitted {
+        signature: Signature,
+        blockhash: Hash,
+    },
+    Confirmed(Signature),
+    Failed {
+        signature: Option<Signature>,
+        reason: String,
+    },
+    /// Never sent, as the recipient didn't pass validation
+    Rejected(String),
+}
+
+impl WithdrawalStatus {
+    fn name(&self) -> &'static str {
+        match self {
+            WithdrawalStatus::Pending => "pending",
+            WithdrawalStatus::Submitted { .. } => "submitted",
+            WithdrawalStatus::Confirmed(_) => "confirmed",
+            WithdrawalStatus::Failed { .. } => "failed",
+            WithdrawalStatus::Rejected(_) => "rejected",
+        }
+    }
+
+    pub fn signature(&self) -> Option<&Signature> {
+        match self {
+            WithdrawalStatus::Submitted { signature, .. }
+            | WithdrawalStatus::Confirmed(signature)
+            | WithdrawalStatus::Failed {
+                signature: Some(signature),
+                ..
+            } => Some(signature),
+            _ => None,
+        }
+    }
+
+    fn detail(&self) -> String {
+        match self {
+            WithdrawalStatus::Submitted { blockhash, .. } => blockhash.to_string(),
+            WithdrawalStatus::Failed { reason, .. } | WithdrawalStatus::Rejected(reason) => {
+                // Kept on one line, as it is the last field of a journal line
+                reason.replace(['\r', '\n'], " ")
+            }
+            _ => String::new(),
+        }
+    }
+
+    fn parse(name: &str, signature: &str, detail: &str) -> anyhow::Result<Self> {
+        let signature = match signature {
+            "" => None,
+            signature => Some(
+                signature
+                    .parse::<Signature>()
+                    .map_err(|_| anyhow!("invalid signature {signature}"))?,
+            ),
+        };
+
+        let status = match (name, signature) {
+            ("pending", _) => WithdrawalStatus::Pending,
+            ("submitted", Some(signature)) => WithdrawalStatus::Submitted {
+                signature,
+                blockhash: detail
+                    .parse()
+                    .map_err(|_| anyhow!("invalid blockhash {detail}"))?,
+            },
+            ("confirmed", Some(signature)) => WithdrawalStatus::Confirmed(signature),
+            ("failed", signature) => WithdrawalStatus::Failed {
+                signature,
+                reason: detail.to_string(),
+            },
+            ("rejected", _) => WithdrawalStatus::Rejected(detail.to_string()),
+            _ => bail!("invalid status {name}"),
+        };
+
+        Ok(status)
+    }
+}
+
+impl fmt::Display for WithdrawalStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Per-withdrawal progress of a queue, kept in an append-only file next to
+/// it so an interrupted run can pick up where it stopped.
+///
+/// Every change is written before acting on it: withdrawals are recorded as
+/// submitted before their transaction is sent, so nothing is paid twice.
+pub struct WithdrawalJournal {
+    path: PathBuf,
+    statuses: HashMap<String, WithdrawalStatus>,
+}
+
+impl WithdrawalJournal {
+    /// Opens the journal kept for the queue at `queue_path`, reading back
+    /// the progress of earlier runs.
+    pub fn open(queue_path: &Path) -> anyhow::Result<Self> {
+        let path = queue_path.with_extension("status");
+        let mut statuses = HashMap::new();
+
+        if path.exists() {
+            let contents = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+
+            for (idx, line) in contents.lines().enumerate() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let fields: Vec<&str> = line.splitn(4, ',').collect();
+                let [reference, name, signature, detail] = fields[..] else {
+                    bail!("{} line {}: malformed entry", path.display(), idx + 1);
+                };
+
+                let status = WithdrawalStatus::parse(name, signature, detail)
+                    .with_context(|| format!("{} line {}", path.display(), idx + 1))?;
+                // Later entries supersede earlier ones
+                statuses.insert(reference.to_string(), status);
+            }
+        }
+
+        Ok(Self { path, statuses })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn status(&self, reference: &str) -> &WithdrawalStatus {
+        self.statuses
+            .get(reference)
+            .unwrap_or(&WithdrawalStatus::Pending)
+    }
+
+    /// Appends `status` for `reference` and flushes it to disk.
+    pub fn record(&mut self, reference: &str, status: WithdrawalStatus) -> anyhow::Result<()> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open {}", self.path.display()))?;
+
+        writeln!(
+            file,
+            "{reference},{},{},{}",
+            status.name(),
+            status
+                .signature()
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+            status.detail()
+        )?;
+        file.sync_all()?;
+
+        self.statuses.insert(reference.to_string(), status);
+        Ok(())
+    }
+}
+
+/// Writes the reconciliation report for `withdrawals`, one CSV row per
+/// withdrawal with its final status and signature, next to the queue.
+pub fn write_report(
+    queue_path: &Path,
+    withdrawals: &[Withdrawal],
+    journal: &WithdrawalJournal,
+) -> anyhow::Result<PathBuf> {
+    let path = queue_path.with_extension("report.csv");
+    fs::write(&path, report_csv(withdrawals, journal))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+fn report_csv(withdrawals: &[Withdrawal], journal: &WithdrawalJournal) -> String {
+    let mut csv = String::from("reference,address,amount_sol,status,signature,detail\n");
+    for withdrawal in withdrawals {
+        let status = journal.status(&withdrawal.reference);
+        csv.push_str(&format!(
+            "{},{},{:.9},{},{},{}\n",
+            withdrawal.reference,
+            withdrawal.address,
+            lamports_to_sol(withdrawal.lamports),
+            status,
+            status
+                .signature()
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+            match status {
+                WithdrawalStatus::Submitted { .. } => String::new(),
+                status => status.detail().replace(',', ";"),
+            }
+        ));
+    }
+    csv
+}
+
+/// Count and SOL per status, totalled over the whole queue.
+pub fn summary_table(withdrawals: &[Withdrawal], journal: &WithdrawalJournal) -> Table {
+    let mut table = new_table();
+    table.set_header(vec![
+        header_cell("Status"),
+        header_cell("Withdrawals"),
+        header_cell("SOL"),
+    ]);
+
+    for name in ["confirmed", "submitted", "pending", "failed", "rejected"] {
+        let matching: Vec<_> = withdrawals
+            .iter()
+            .filter(|withdrawal| journal.status(&withdrawal.reference).name() == name)
+            .collect();
+        if matching.is_empty() {
+            continue;
+        }
+
+        let lamports: u64 = matching.iter().map(|withdrawal| withdrawal.lamports).sum();
+        table.add_row(vec![
+            Cell::new(name),
+            Cell::new(matching.len()),
+            Cell::new(format!("{:.9}", lamports_to_sol(lamports))),
+        ]);
+    }
+
+    let total: u64 = withdrawals
+        .iter()
+        .map(|withdrawal| withdrawal.lamports)
+        .sum();
+    table.add_row(vec![
+        Cell::new("total"),
+        Cell::new(withdrawals.len()),
+        Cell::new(format!("{:.9}", lamports_to_sol(total))),
+    ]);
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::ui::render::capture, insta::assert_snapshot, tempfile::TempDir};
+
+    fn withdrawal(n: u8, lamports: u64) -> Withdrawal {
+        Withdrawal {
+            address: Pubkey::new_from_array([n; 32]),
+            lamports,
+            reference: format!("wd-{n}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_queue_skips_header_and_comments() -> anyhow::Result<()> {
+        let queue = format!(
+            "address,amount,reference\n# first payout\n{},1.5,wd-1\n\n{},0.25,wd-2\n",
+            Pubkey::new_from_array([1; 32]),
+            Pubkey::new_from_array([2; 32]),
+        );
+
+        let withdrawals = parse_queue(&queue)?;
+
+        assert_eq!(
+            withdrawals,
+            vec![withdrawal(1, 1_500_000_000), withdrawal(2, 250_000_000)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_queue_reports_every_invalid_line() {
+        let queue = format!(
+            "{pk},1,wd-1\nnot-an-address,1,wd-2\n{pk},-1,wd-3\n{pk},1,wd-1\n{pk},1\n",
+            pk = Pubkey::new_from_array([1; 32]),
+        );
+
+        let error = parse_queue(&queue).unwrap_err().to_string();
+
+        assert!(error.contains("line 2: invalid address"));
+        assert!(error.contains("line 3: Amount must be a positive"));
+        assert!(error.contains("line 4: duplicate reference wd-1"));
+        assert!(error.contains("line 5: expected address,amount,reference"));
+    }
+
+    #[test]
+    fn test_journal_resumes_latest_status() -> anyhow::Result<()> {
+        let dir = TempDir::new()?;
+        let queue_path = dir.path().join("payouts.csv");
+        let signature = Signature::from([7; 64]);
+
+        let mut journal = WithdrawalJournal::open(&queue_path)?;
+        journal.record(
+            "wd-1",
+            WithdrawalStatus::Submitted {
+                signature,
+                blockhash: Hash::new_from_array([3; 32]),
+            },
+        )?;
+        journal.record("wd-1", WithdrawalStatus::Confirmed(signature))?;
+        journal.record(
+            "wd-2",
+            WithdrawalStatus::Rejected("owned by a program,\nnot a wallet".to_string()),
+        )?;
+
+        let resumed = WithdrawalJournal::open(&queue_path)?;
+
+        assert_eq!(resumed.path(), dir.path().join("payouts.status"));
+        assert_eq!(
+            resumed.status("wd-1"),
+            &WithdrawalStatus::Confirmed(signature)
+        );
+        assert_eq!(
+            resumed.status("wd-2"),
+            &WithdrawalStatus::Rejected("owned by a program, not a wallet".to_string())
+        );
+        assert_eq!(resumed.status("wd-3"), &WithdrawalStatus::Pending);
+        Ok(())
+    }
+
+    #[test]
+    fn test_reconciliation_report() -> anyhow::Result<()> {
+        let dir = TempDir::new()?;
+        let queue_path = dir.path().join("payouts.csv");
+        let withdrawals = vec![
+            withdrawal(1, 1_500_000_000),
+            withdrawal(2, 250_000_000),
+            withdrawal(3, 40_000_000),
+            withdrawal(4, 10_000_000),
+        ];
+
+        let mut journal = WithdrawalJournal::open(&queue_path)?;
+        journal.record(
+            "wd-1",
+            WithdrawalStatus::Confirmed(Signature::from([7; 64])),
+        )?;
+        journal.record(
+            "wd-2",
+            WithdrawalStatus::Failed {
+                signature: Some(Signature::from([8; 64])),
+                reason: "insufficient funds for rent".to_string(),
+            },
+        )?;
+        journal.record(
+            "wd-3",
+            WithdrawalStatus::Rejected("owned by a program, not a wallet".to_string()),
+        )?;
+
+        let output = capture(|out| {
+            out.line(report_csv(&withdrawals, &journal).trim_end());
+            out.table(summary_table(&withdrawals, &journal));
+        });
+
+        assert_snapshot!("reconciliation_report", output);
+        Ok(())
+    }
+}
@@ -1,42 +1,62 @@
+pub mod answers;
+pub mod macros;
+
 use {
     crate::{
         commands::{
             Command, CommandGroup, account::AccountCommand, cluster::ClusterCommand,
             config::ConfigCommand, program::ProgramCommand, stake::StakeCommand,
-            transaction::TransactionCommand, vote::VoteCommand,
+            token::TokenCommand, transaction::TransactionCommand, vote::VoteCommand,
+            watch::WatchCommand,
         },
         constants::{DEVNET_RPC, MAINNET_RPC, TESTNET_RPC},
         context::ScillaContext,
-        ui::print_error,
+        misc::sns::{AddressInput, resolve_domain},
+        prompt::{
+            answers::{Ask, exit_if_scripted},
+            macros::{is_recording, start_step},
+        },
+        ui::{print_error, render::stdout, show_spinner, suspend_spinner},
     },
-    console::style,
     inquire::{Confirm, InquireError, Select, Text},
-    std::{fmt::Display, path::PathBuf, process::exit, str::FromStr},
+    solana_pubkey::Pubkey,
+    std::{
+        fmt::Display,
+        path::{Path, PathBuf},
+        process::exit,
+        str::FromStr,
+    },
 };
 pub fn prompt_for_command() -> anyhow::Result<Command> {
+    start_step();
+
     let top_level = Select::new(
         "Choose a command group:",
         vec![
             CommandGroup::Account,
+            CommandGroup::Token,
             CommandGroup::Program,
             CommandGroup::Cluster,
             CommandGroup::Stake,
             CommandGroup::Vote,
             CommandGroup::Transaction,
+            CommandGroup::Watch,
             CommandGroup::ScillaConfig,
             CommandGroup::Exit,
         ],
     )
-    .prompt()?;
+    .ask()?;
 
     let command = match top_level {
         CommandGroup::Cluster => Command::Cluster(prompt_cluster()?),
         CommandGroup::Stake => Command::Stake(prompt_stake()?),
         CommandGroup::Account => Command::Account(prompt_account()?),
+        CommandGroup::Token => Command::Token(prompt_token()?),
         CommandGroup::Program => Command::Program(prompt_program()?),
         CommandGroup::Vote => Command::Vote(prompt_vote()?),
         CommandGroup::ScillaConfig => Command::ScillaConfig(prompt_config()?),
         CommandGroup::Transaction => Command::Transaction(prompt_transaction()?),
+        CommandGroup::Watch => Command::Watch(prompt_watch()?),
         CommandGroup::Exit => Command::Exit,
     };
 
@@ -51,6 +71,7 @@ fn prompt_cluster() -> anyhow::Result<ClusterCommand> {
             ClusterCommand::CurrentSlot,
             ClusterCommand::BlockHeight,
             ClusterCommand::BlockTime,
+            ClusterCommand::SlotLeaders,
             ClusterCommand::Validators,
             ClusterCommand::ClusterVersion,
             ClusterCommand::SupplyInfo,
@@ -58,7 +79,7 @@ fn prompt_cluster() -> anyhow::Result<ClusterCommand> {
             ClusterCommand::GoBack,
         ],
     )
-    .prompt()?;
+    .ask()?;
 
     Ok(choice)
 }
@@ -70,15 +91,19 @@ fn prompt_stake() -> anyhow::Result<StakeCommand> {
             StakeCommand::Create,
             StakeCommand::Delegate,
             StakeCommand::Deactivate,
+            StakeCommand::DeactivateMany,
             StakeCommand::Withdraw,
             StakeCommand::Merge,
             StakeCommand::Split,
             StakeCommand::Show,
+            StakeCommand::ByAuthority,
             StakeCommand::History,
+            StakeCommand::ValidatorHistory,
+            StakeCommand::RealizedApy,
             StakeCommand::GoBack,
         ],
     )
-    .prompt()?;
+    .ask()?;
 
     Ok(choice)
 }
@@ -88,17 +113,41 @@ fn prompt_account() -> anyhow::Result<AccountCommand> {
         "Account Command:",
         vec![
             AccountCommand::FetchAccount,
+            AccountCommand::FetchAccounts,
             AccountCommand::Balance,
+            AccountCommand::WatchAccount,
             AccountCommand::Transfer,
+            AccountCommand::BatchTransfer,
+            AccountCommand::RunTemplate,
+            AccountCommand::ProcessWithdrawals,
             AccountCommand::Airdrop,
             AccountCommand::LargestAccounts,
             AccountCommand::NonceAccount,
             AccountCommand::Rent,
+            AccountCommand::SignIn,
+            AccountCommand::VerifySignIn,
             AccountCommand::GoBack,
         ],
     )
     .with_page_size(10)
-    .prompt()?;
+    .ask()?;
+
+    Ok(choice)
+}
+
+fn prompt_token() -> anyhow::Result<TokenCommand> {
+    let choice = Select::new(
+        "Token Command:",
+        vec![
+            TokenCommand::Balances,
+            TokenCommand::Transfer,
+            TokenCommand::CreateMint,
+            TokenCommand::MintTo,
+            TokenCommand::Show,
+            TokenCommand::GoBack,
+        ],
+    )
+    .ask()?;
 
     Ok(choice)
 }
@@ -106,9 +155,20 @@ fn prompt_account() -> anyhow::Result<AccountCommand> {
 fn prompt_program() -> anyhow::Result<ProgramCommand> {
     let choice = Select::new(
         "Program Command:",
-        vec![ProgramCommand::Deploy, ProgramCommand::GoBack],
+        vec![
+            ProgramCommand::Deploy,
+            ProgramCommand::Upgrade,
+            ProgramCommand::Show,
+            ProgramCommand::Accounts,
+            ProgramCommand::TransferAuthority,
+            ProgramCommand::SetBufferAuthority,
+            ProgramCommand::Verify,
+            ProgramCommand::CloseBuffers,
+            ProgramCommand::Close,
+            ProgramCommand::GoBack,
+        ],
     )
-    .prompt()?;
+    .ask()?;
 
     Ok(choice)
 }
@@ -119,13 +179,14 @@ fn prompt_vote() -> anyhow::Result<VoteCommand> {
         vec![
             VoteCommand::CreateVoteAccount,
             VoteCommand::AuthorizeVoter,
+            VoteCommand::RotateVoter,
             VoteCommand::WithdrawFromVoteAccount,
             VoteCommand::ShowVoteAccount,
             VoteCommand::CloseVoteAccount,
             VoteCommand::GoBack,
         ],
     )
-    .prompt()?;
+    .ask()?;
 
     Ok(choice)
 }
@@ -137,11 +198,31 @@ fn prompt_transaction() -> anyhow::Result<TransactionCommand> {
             TransactionCommand::CheckConfirmation,
             TransactionCommand::FetchStatus,
             TransactionCommand::FetchTransaction,
+            TransactionCommand::Inspect,
             TransactionCommand::SendTransaction,
+            TransactionCommand::OfflineSigning,
+            TransactionCommand::SignOffline,
+            TransactionCommand::SubmitSigned,
             TransactionCommand::GoBack,
         ],
     )
-    .prompt()?;
+    .ask()?;
+
+    Ok(choice)
+}
+
+fn prompt_watch() -> anyhow::Result<WatchCommand> {
+    let choice = Select::new(
+        "Watch Command:",
+        vec![
+            WatchCommand::Account,
+            WatchCommand::ProgramLogs,
+            WatchCommand::Slots,
+            WatchCommand::Signature,
+            WatchCommand::GoBack,
+        ],
+    )
+    .ask()?;
 
     Ok(choice)
 }
@@ -152,25 +233,50 @@ fn prompt_config() -> anyhow::Result<ConfigCommand> {
         vec![
             ConfigCommand::Show,
             ConfigCommand::Edit,
+            ConfigCommand::SwitchProfile,
+            ConfigCommand::SaveProfile,
+            ConfigCommand::Doctor,
+            ConfigCommand::Mnemonic,
+            ConfigCommand::VerifyMnemonic,
+            if is_recording() {
+                ConfigCommand::StopRecording
+            } else {
+                ConfigCommand::RecordMacro
+            },
+            ConfigCommand::ReplayMacro,
             ConfigCommand::GoBack,
         ],
     )
-    .prompt()?;
+    .ask()?;
 
     Ok(choice)
 }
 
 pub fn prompt_input_data<T>(msg: &str) -> T
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    prompt_input_data_with_default(msg, None)
+}
+
+/// Like [`prompt_input_data`], pre-filling the answer with `default` if set.
+pub fn prompt_input_data_with_default<T>(msg: &str, default: Option<&str>) -> T
 where
     T: FromStr,
     T::Err: std::fmt::Display,
 {
     loop {
-        let input = match Text::new(msg).prompt() {
+        let mut text = Text::new(msg);
+        if let Some(default) = default {
+            text = text.with_default(default);
+        }
+
+        let input = match text.ask() {
             Ok(v) => v,
             Err(e) => match e {
                 InquireError::OperationInterrupted | InquireError::OperationCanceled => {
-                    println!("{}", style("Operation cancelled. Exiting.").yellow().bold());
+                    stdout().warning("Operation cancelled. Exiting.");
                     exit(0);
                 }
                 _ => {
@@ -182,21 +288,105 @@ where
 
         match input.parse::<T>() {
             Ok(value) => return value,
-            Err(e) => print_error(format!("Parse error : {e}. Please try again.")),
+            Err(e) => {
+                print_error(format!("Parse error : {e}. Please try again."));
+                exit_if_scripted();
+            }
+        }
+    }
+}
+
+/// Like [`prompt_input_data_with_default`], where an empty answer gives
+/// `None`.
+pub fn prompt_optional_data_with_default<T>(msg: &str, default: Option<&str>) -> Option<T>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    let OptionalInput(value) = prompt_input_data_with_default(msg, default);
+    value
+}
+
+struct OptionalInput<T>(Option<T>);
+
+impl<T: FromStr> FromStr for OptionalInput<T> {
+    type Err = T::Err;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "" => Ok(Self(None)),
+            value => value.parse().map(|value| Self(Some(value))),
+        }
+    }
+}
+
+/// Asks for an address, also accepting a Solana Name Service domain such as
+/// `alice.sol`. A domain is resolved on-chain and the address it points to
+/// is confirmed before being used.
+pub async fn prompt_pubkey(ctx: &ScillaContext, msg: &str) -> Pubkey {
+    prompt_pubkey_with_default(ctx, msg, None).await
+}
+
+/// Like [`prompt_pubkey`], pre-filling the answer with `default` if set.
+pub async fn prompt_pubkey_with_default(
+    ctx: &ScillaContext,
+    msg: &str,
+    default: Option<&str>,
+) -> Pubkey {
+    loop {
+        let input: AddressInput = prompt_input_data_with_default(msg, default);
+        if let Some(pubkey) = resolve_address_input(ctx, input).await {
+            return pubkey;
+        }
+    }
+}
+
+/// Like [`prompt_pubkey_with_default`], where an empty answer gives `None`.
+pub async fn prompt_optional_pubkey_with_default(
+    ctx: &ScillaContext,
+    msg: &str,
+    default: Option<&str>,
+) -> Option<Pubkey> {
+    loop {
+        let input: AddressInput = prompt_optional_data_with_default(msg, default)?;
+        if let Some(pubkey) = resolve_address_input(ctx, input).await {
+            return Some(pubkey);
         }
     }
 }
 
+/// The address behind `input`, or `None` if its domain couldn't be resolved
+/// or the user turned the resolved address down.
+async fn resolve_address_input(ctx: &ScillaContext, input: AddressInput) -> Option<Pubkey> {
+    let domain = match input {
+        AddressInput::Pubkey(pubkey) => return Some(pubkey),
+        AddressInput::Domain(domain) => domain,
+    };
+
+    let Some(pubkey) = show_spinner(
+        &format!("Resolving {domain}…"),
+        resolve_domain(ctx.rpc(), &domain),
+    )
+    .await
+    else {
+        exit_if_scripted();
+        return None;
+    };
+
+    stdout().note(format!("{domain} resolves to {pubkey}"));
+    prompt_confirmation(&format!("Use {pubkey} for {domain}?")).then_some(pubkey)
+}
+
 pub fn prompt_select_data<T>(msg: &str, options: Vec<T>) -> T
 where
     T: Display + Clone,
 {
     loop {
-        match Select::new(msg, options.clone()).prompt() {
+        match Select::new(msg, options.clone()).ask() {
             Ok(v) => return v,
             Err(e) => match e {
                 InquireError::OperationInterrupted | InquireError::OperationCanceled => {
-                    println!("{}", style("Operation cancelled. Exiting.").yellow().bold());
+                    stdout().warning("Operation cancelled. Exiting.");
                     exit(0);
                 }
                 _ => {
@@ -209,18 +399,39 @@ where
 }
 
 pub fn prompt_keypair_path(msg: &str, ctx: &ScillaContext) -> PathBuf {
-    let default_path = ctx.keypair_path().display().to_string();
+    prompt_path_with_default(
+        msg,
+        ctx.keypair_path(),
+        "Press Enter to use the default keypair",
+    )
+}
+
+/// Keypair prompt for withdraw authorities, defaulting to the configured
+/// `withdraw-authority-path` before falling back to the main keypair.
+pub fn prompt_withdraw_authority_path(msg: &str, ctx: &ScillaContext) -> PathBuf {
+    match &ctx.defaults().withdraw_authority_path {
+        Some(path) => prompt_path_with_default(
+            msg,
+            path,
+            "Press Enter to use the configured withdraw authority",
+        ),
+        None => prompt_keypair_path(msg, ctx),
+    }
+}
+
+fn prompt_path_with_default(msg: &str, default: &Path, help: &str) -> PathBuf {
+    let default_path = default.display().to_string();
 
     loop {
         let input = match Text::new(msg)
             .with_default(&default_path)
-            .with_help_message("Press Enter to use the default keypair")
-            .prompt()
+            .with_help_message(help)
+            .ask()
         {
             Ok(v) => v,
             Err(e) => match e {
                 InquireError::OperationInterrupted | InquireError::OperationCanceled => {
-                    println!("{}", style("Operation cancelled. Exiting.").yellow().bold());
+                    stdout().warning("Operation cancelled. Exiting.");
                     exit(0);
                 }
                 _ => {
@@ -240,13 +451,20 @@ pub fn prompt_keypair_path(msg: &str, ctx: &ScillaContext) -> PathBuf {
             Ok(value) => return value,
             Err(e) => {
                 print_error(format!("Invalid path: {e}. Please try again."));
+                exit_if_scripted();
             }
         }
     }
 }
 
+/// Asks how often a show command should refresh, `0` (the default) shows it
+/// once.
+pub fn prompt_refresh_interval() -> u64 {
+    prompt_input_data_with_default("Refresh every N seconds (0 to show once):", Some("0"))
+}
+
 pub fn prompt_confirmation(msg: &str) -> bool {
-    Confirm::new(msg).prompt().unwrap_or(false)
+    suspend_spinner(|| Confirm::new(msg).ask().unwrap_or(false))
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -281,6 +499,6 @@ impl Network {
 }
 
 pub fn prompt_network_rpc_url() -> anyhow::Result<String> {
-    let network = Select::new("Select network:", Network::all()).prompt()?;
+    let network = Select::new("Select network:", Network::all()).ask()?;
     Ok(network.rpc_url().to_string())
 }
@@ -0,0 +1,372 @@
+use {
+    crate::{
+        constants::ANSWERS_ENV_VAR,
+        prompt::macros::{
+            abandon_replayed_step, forget_last_answer, next_replayed_answer, record_answer,
+        },
+        ui::render::stdout,
+    },
+    anyhow::{anyhow, bail},
+    inquire::{
+        Confirm, InquireError, MultiSelect, Password, Select, Text, error::InquireResult,
+        list_option::ListOption,
+    },
+    std::{
+        env,
+        fmt::Display,
+        fs::File,
+        io::{self, BufRead, BufReader},
+        process::exit,
+        sync::Mutex,
+    },
+};
+
+/// Answers loaded by [`load_answers_from_env`], consumed one per prompt.
+static ANSWERS: Mutex<Option<AnswerScript>> = Mutex::new(None);
+
+/// Answers read line by line, one per prompt in the order they are asked.
+///
+/// Lines starting with `#` are comments. An empty line is an empty answer,
+/// which accepts the prompt's default like pressing Enter does.
+struct AnswerScript {
+    lines: Box<dyn BufRead + Send>,
+    answered: usize,
+}
+
+impl AnswerScript {
+    fn new(lines: impl BufRead + Send + 'static) -> Self {
+        Self {
+            lines: Box::new(lines),
+            answered: 0,
+        }
+    }
+
+    fn next(&mut self, message: &str) -> anyhow::Result<String> {
+        loop {
+            let mut line = String::new();
+            if self.lines.read_line(&mut line)? == 0 {
+                bail!(
+                    "Ran out of answers after {} at prompt \"{message}\"",
+                    self.answered
+                );
+            }
+
+            let line = line.trim_end_matches(['\r', '\n']);
+            if line.trim_start().starts_with('#') {
+                continue;
+            }
+
+            self.answered += 1;
+            return Ok(line.trim().to_string());
+        }
+    }
+}
+
+/// Reads prompt answers from the file named by `SCILLA_ANSWERS`, or from
+/// stdin when it is `-`, so interactive flows can run without a terminal.
+pub fn load_answers_from_env() -> anyhow::Result<()> {
+    let Some(source) = env::var_os(ANSWERS_ENV_VAR) else {
+        return Ok(());
+    };
+
+    let script = if source == "-" {
+        AnswerScript::new(BufReader::new(io::stdin()))
+    } else {
+        let file = File::open(&source).map_err(|e| {
+            anyhow!(
+                "Failed to open answers file {}: {e}",
+                source.to_string_lossy()
+            )
+        })?;
+        AnswerScript::new(BufReader::new(file))
+    };
+
+    *ANSWERS.lock().expect("answers lock poisoned") = Some(script);
+    Ok(())
+}
+
+/// Whether prompts are answered from a script instead of the terminal.
+pub fn is_scripted() -> bool {
+    ANSWERS.lock().expect("answers lock poisoned").is_some()
+}
+
+/// A scripted answer that can't be used would otherwise be asked again,
+/// silently consuming the answers meant for later prompts, so stop instead.
+///
+/// A rejected answer is left out of the macro being recorded. When it came
+/// from a replayed macro step, the rest of the step is asked at the terminal.
+pub fn exit_if_scripted() {
+    forget_last_answer();
+    if abandon_replayed_step() {
+        stdout().warning("Macro answer rejected, asking the rest of this step instead");
+        return;
+    }
+    if is_scripted() {
+        exit(1);
+    }
+}
+
+/// The next answer of the replayed macro step or the answers script, or
+/// `None` when prompts go to the terminal.
+fn next_answer(message: &str) -> Option<String> {
+    next_replayed_answer().or_else(|| next_script_answer(message))
+}
+
+/// The next answer of the answers script. Secrets are never part of a
+/// macro, so they skip the replayed step.
+fn next_script_answer(message: &str) -> Option<String> {
+    let mut answers = ANSWERS.lock().expect("answers lock poisoned");
+    let script = answers.as_mut()?;
+
+    match script.next(message) {
+        Ok(answer) => Some(answer),
+        Err(e) => {
+            drop(answers);
+            fail(e)
+        }
+    }
+}
+
+fn fail(e: anyhow::Error) -> ! {
+    stdout().error(format!("{e:#}"));
+    exit(1)
+}
+
+/// Echoes a scripted answer so transcripts read like an interactive session.
+fn echo(message: &str, answer: impl Display) {
+    stdout().note(format!("? {message} {answer}"));
+}
+
+/// Index of the option matching `answer`, by its text or 1-based position.
+fn pick_option<T: Display>(options: &[T], answer: &str) -> anyhow::Result<usize> {
+    if let Some(index) = options
+        .iter()
+        .position(|option| option.to_string().eq_ignore_ascii_case(answer))
+    {
+        return Ok(index);
+    }
+
+    match answer.parse::<usize>() {
+        Ok(position) if (1..=options.len()).contains(&position) => Ok(position - 1),
+        _ => bail!(
+            "\"{answer}\" is not one of: {}",
+            options
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// Indices picked by a comma separated list of options, `all` or `none`.
+fn pick_options<T: Display>(options: &[T], answer: &str) -> anyhow::Result<Vec<usize>> {
+    match answer.to_ascii_lowercase().as_str() {
+        "all" => Ok((0..options.len()).collect()),
+        "none" => Ok(Vec::new()),
+        _ => answer
+            .split(',')
+            .map(|part| pick_option(options, part.trim()))
+            .collect(),
+    }
+}
+
+/// Prompts that take their answer from the answers script when one is
+/// loaded, and from the terminal otherwise.
+pub trait Ask {
+    type Output;
+
+    fn ask(self) -> InquireResult<Self::Output>;
+}
+
+impl Ask for Text<'_, '_> {
+    type Output = String;
+
+    fn ask(self) -> InquireResult<String> {
+        let answer = match next_answer(self.message) {
+            Some(answer) => {
+                let answer = match self.default {
+                    Some(default) if answer.is_empty() => default.to_string(),
+                    _ => answer,
+                };
+                echo(self.message, &answer);
+                answer
+            }
+            None => self.prompt()?,
+        };
+
+        record_answer(&answer);
+        Ok(answer)
+    }
+}
+
+impl Ask for Password<'_> {
+    type Output = String;
+
+    fn ask(self) -> InquireResult<String> {
+        // Never recorded, so a replay asks for it again
+        let Some(answer) = next_script_answer(self.message) else {
+            return self.prompt();
+        };
+
+        echo(self.message, "********");
+        Ok(answer)
+    }
+}
+
+impl Ask for Confirm<'_> {
+    type Output = bool;
+
+    fn ask(self) -> InquireResult<bool> {
+        let confirmed = match next_answer(self.message) {
+            Some(answer) => {
+                let confirmed = match (answer.as_str(), self.default) {
+                    ("", Some(default)) => default,
+                    (answer, _) => (self.parser)(answer).unwrap_or_else(|()| {
+                        fail(anyhow!(
+                            "\"{answer}\" is not a yes/no answer for \"{}\"",
+                            self.message
+                        ))
+                    }),
+                };
+                echo(self.message, if confirmed { "Yes" } else { "No" });
+                confirmed
+            }
+            None => self.prompt()?,
+        };
+
+        record_answer(if confirmed { "yes" } else { "no" });
+        Ok(confirmed)
+    }
+}
+
+impl<T: Display> Ask for Select<'_, T> {
+    type Output = T;
+
+    fn ask(self) -> InquireResult<T> {
+        self.ask_raw().map(|option| option.value)
+    }
+}
+
+impl<T: Display> Ask for MultiSelect<'_, T> {
+    type Output = Vec<T>;
+
+    fn ask(self) -> InquireResult<Vec<T>> {
+        self.ask_raw()
+            .map(|options| options.into_iter().map(|option| option.value).collect())
+    }
+}
+
+/// [`Ask`] for list prompts, keeping the index of the chosen options.
+pub trait AskRaw {
+    type Output;
+
+    fn ask_raw(self) -> InquireResult<Self::Output>;
+}
+
+impl<T: Display> AskRaw for Select<'_, T> {
+    type Output = ListOption<T>;
+
+    fn ask_raw(self) -> InquireResult<ListOption<T>> {
+        let Some(answer) = next_answer(self.message) else {
+            let option = self.raw_prompt()?;
+            record_answer(&option.value.to_string());
+            return Ok(option);
+        };
+
+        let index = if answer.is_empty() {
+            self.starting_cursor
+        } else {
+            pick_option(&self.options, &answer)
+                .unwrap_or_else(|e| fail(e.context(format!("Answering \"{}\"", self.message))))
+        };
+
+        let Some(value) = self.options.into_iter().nth(index) else {
+            return Err(InquireError::InvalidConfiguration(
+                "Available options can not be empty".into(),
+            ));
+        };
+        echo(self.message, &value);
+        record_answer(&value.to_string());
+        Ok(ListOption::new(index, value))
+    }
+}
+
+impl<T: Display> AskRaw for MultiSelect<'_, T> {
+    type Output = Vec<ListOption<T>>;
+
+    fn ask_raw(self) -> InquireResult<Vec<ListOption<T>>> {
+        let Some(answer) = next_answer(self.message) else {
+            let chosen = self.raw_prompt()?;
+            record_answer(&chosen_answer(&chosen));
+            return Ok(chosen);
+        };
+
+        let indices = if answer.is_empty() {
+            self.default.clone().unwrap_or_default()
+        } else {
+            pick_options(&self.options, &answer)
+                .unwrap_or_else(|e| fail(e.context(format!("Answering \"{}\"", self.message))))
+        };
+
+        let chosen: Vec<_> = self
+            .options
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| indices.contains(index))
+            .map(|(index, value)| ListOption::new(index, value))
+            .collect();
+        let answer = chosen_answer(&chosen);
+        echo(self.message, &answer);
+        record_answer(&answer);
+        Ok(chosen)
+    }
+}
+
+/// Answer picking the `chosen` options of a multi-select prompt.
+fn chosen_answer<T: Display>(chosen: &[ListOption<T>]) -> String {
+    if chosen.is_empty() {
+        return "none".to_string();
+    }
+
+    chosen
+        .iter()
+        .map(|option| option.value.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::io::Cursor};
+
+    #[test]
+    fn test_answer_script_skips_comments_and_keeps_empty_answers() -> anyhow::Result<()> {
+        let mut script = AnswerScript::new(Cursor::new(
+            "# pick the group\nStake\n\n  # indented comment\n 1.5 \n",
+        ));
+
+        assert_eq!(script.next("group")?, "Stake");
+        assert_eq!(script.next("default")?, "");
+        assert_eq!(script.next("amount")?, "1.5");
+        assert!(script.next("missing").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pick_option_by_text_or_position() -> anyhow::Result<()> {
+        let options = ["Account", "Program", "Cluster"];
+
+        assert_eq!(pick_option(&options, "program")?, 1);
+        assert_eq!(pick_option(&options, "3")?, 2);
+        assert!(pick_option(&options, "4").is_err());
+        assert!(pick_option(&options, "Vote").is_err());
+
+        assert_eq!(pick_options(&options, "1, cluster")?, vec![0, 2]);
+        assert_eq!(pick_options(&options, "all")?, vec![0, 1, 2]);
+        assert!(pick_options(&options, "none")?.is_empty());
+
+        Ok(())
+    }
+}
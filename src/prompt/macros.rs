@@ -0,0 +1,219 @@
+use {
+    anyhow::{Context, bail},
+    std::{collections::VecDeque, fs, path::PathBuf, sync::Mutex},
+};
+
+/// Starts a step in a macro file. Answer scripts read it as a comment, so a
+/// macro also runs as a `SCILLA_ANSWERS` script.
+const STEP_MARKER: &str = "## ";
+
+/// Recording started by [`start_recording`], if any.
+static RECORDING: Mutex<Option<Recording>> = Mutex::new(None);
+
+/// Step being replayed by [`replay_step`], if any.
+static REPLAYING: Mutex<Option<Replay>> = Mutex::new(None);
+
+struct Recording {
+    path: PathBuf,
+    steps: Vec<MacroStep>,
+}
+
+struct Replay {
+    answers: VecDeque<String>,
+    /// Whether the last answer given came from the step
+    answered_last: bool,
+}
+
+/// The answers given to one command, from picking its command group on.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MacroStep {
+    pub answers: Vec<String>,
+}
+
+impl MacroStep {
+    /// Command group and command the step runs, its first two answers.
+    pub fn title(&self) -> String {
+        self.answers
+            .iter()
+            .take(2)
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(" > ")
+    }
+}
+
+/// Reads the steps of a macro file written by [`format_macro`].
+pub fn parse_macro(text: &str) -> anyhow::Result<Vec<MacroStep>> {
+    let mut steps: Vec<MacroStep> = Vec::new();
+
+    for line in text.lines() {
+        if line.starts_with(STEP_MARKER) {
+            steps.push(MacroStep::default());
+            continue;
+        }
+        if line.trim_start().starts_with('#') {
+            continue;
+        }
+
+        let Some(step) = steps.last_mut() else {
+            bail!("Macro answer \"{line}\" comes before the first step");
+        };
+        step.answers.push(line.trim().to_string());
+    }
+
+    if steps.is_empty() {
+        bail!("Macro has no steps");
+    }
+    Ok(steps)
+}
+
+/// Macro file of `steps`: every answer on its own line, as in an answers
+/// script, with a marker line before each step.
+pub fn format_macro(steps: &[MacroStep]) -> String {
+    let mut text = "# Scilla macro, replay it with ScillaConfig > Replay Macro\n".to_string();
+    for step in steps {
+        text.push_str(&format!("{STEP_MARKER}{}\n", step.title()));
+        for answer in &step.answers {
+            text.push_str(answer);
+            text.push('\n');
+        }
+    }
+    text
+}
+
+/// Records the answers of every command run from now on, until
+/// [`stop_recording`] saves them to `path`.
+pub fn start_recording(path: PathBuf) {
+    *RECORDING.lock().expect("recording lock poisoned") = Some(Recording {
+        path,
+        steps: Vec::new(),
+    });
+}
+
+pub fn is_recording() -> bool {
+    RECORDING.lock().expect("recording lock poisoned").is_some()
+}
+
+/// Saves the recorded steps, leaving out the last one that stopped the
+/// recording. Returns where they were saved and how many there were.
+pub fn stop_recording() -> anyhow::Result<Option<(PathBuf, usize)>> {
+    let Some(mut recording) = RECORDING.lock().expect("recording lock poisoned").take() else {
+        return Ok(None);
+    };
+    recording.steps.pop();
+
+    if recording.steps.is_empty() {
+        bail!("No commands were recorded");
+    }
+    fs::write(&recording.path, format_macro(&recording.steps))
+        .with_context(|| format!("Failed to write {}", recording.path.display()))?;
+
+    Ok(Some((recording.path, recording.steps.len())))
+}
+
+/// Starts a new step of the recording, called before every command.
+pub fn start_step() {
+    if let Some(recording) = RECORDING.lock().expect("recording lock poisoned").as_mut() {
+        recording.steps.push(MacroStep::default());
+    }
+}
+
+/// Adds an answer to the step being recorded.
+pub(super) fn record_answer(answer: &str) {
+    if let Some(step) = RECORDING
+        .lock()
+        .expect("recording lock poisoned")
+        .as_mut()
+        .and_then(|recording| recording.steps.last_mut())
+    {
+        step.answers.push(answer.to_string());
+    }
+}
+
+/// Drops the last recorded answer after it was rejected, so a replay
+/// doesn't give it again.
+pub(super) fn forget_last_answer() {
+    if let Some(step) = RECORDING
+        .lock()
+        .expect("recording lock poisoned")
+        .as_mut()
+        .and_then(|recording| recording.steps.last_mut())
+    {
+        step.answers.pop();
+    }
+}
+
+/// Answers the next prompts with the answers of `step`, until
+/// [`finish_step`]. Prompts left over when they run out go to the terminal.
+pub fn replay_step(step: &MacroStep) {
+    *REPLAYING.lock().expect("replay lock poisoned") = Some(Replay {
+        answers: step.answers.iter().cloned().collect(),
+        answered_last: false,
+    });
+}
+
+/// Ends the replayed step, returning how many of its answers were not used.
+pub fn finish_step() -> usize {
+    REPLAYING
+        .lock()
+        .expect("replay lock poisoned")
+        .take()
+        .map_or(0, |replay| replay.answers.len())
+}
+
+/// The next answer of the replayed step, if there are any left.
+pub(super) fn next_replayed_answer() -> Option<String> {
+    let mut replaying = REPLAYING.lock().expect("replay lock poisoned");
+    let replay = replaying.as_mut()?;
+
+    let answer = replay.answers.pop_front();
+    replay.answered_last = answer.is_some();
+    answer
+}
+
+/// Drops the rest of the replayed step when its last answer was rejected,
+/// leaving the remaining prompts to the terminal. Returns whether it did.
+pub(super) fn abandon_replayed_step() -> bool {
+    let mut replaying = REPLAYING.lock().expect("replay lock poisoned");
+    match replaying.as_mut() {
+        Some(replay) if replay.answered_last => {
+            replay.answers.clear();
+            replay.answered_last = false;
+            true
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_macro_round_trip() -> anyhow::Result<()> {
+        let steps = vec![
+            MacroStep {
+                answers: vec![
+                    "Account".to_string(),
+                    "Transfer SOL".to_string(),
+                    "Vote111111111111111111111111111111111111111".to_string(),
+                    "1.5".to_string(),
+                    // Memo left at its default
+                    String::new(),
+                ],
+            },
+            MacroStep {
+                answers: vec!["Cluster".to_string(), "Epoch Info".to_string()],
+            },
+        ];
+
+        let text = format_macro(&steps);
+        assert_eq!(parse_macro(&text)?, steps);
+        assert_eq!(steps[0].title(), "Account > Transfer SOL");
+
+        assert!(parse_macro("# no steps\n").is_err());
+        assert!(parse_macro("Account\n## Account\n").is_err());
+
+        Ok(())
+    }
+}
@@ -1,12 +1,140 @@
+pub mod multi_select;
+pub mod render;
+pub mod table;
+
 use {
-    console::style,
+    crate::{
+        config::Cluster,
+        misc::{helpers::short_pubkey, rpc_auth::display_url},
+        ui::render::Renderer,
+    },
+    comfy_table::{
+        Table,
+        presets::{ASCII_FULL, UTF8_FULL},
+    },
+    console::{Term, style},
     indicatif::{ProgressBar, ProgressStyle},
+    inquire::ui::RenderConfig,
+    solana_pubkey::Pubkey,
+    std::{
+        env,
+        io::Write,
+        sync::{
+            Mutex,
+            atomic::{AtomicBool, Ordering},
+        },
+        time::Duration,
+    },
 };
 
-pub async fn show_spinner<F, T>(message: &str, fut: F)
+/// Spinner currently drawn by [`show_spinner`], if any.
+static ACTIVE_SPINNER: Mutex<Option<ProgressBar>> = Mutex::new(None);
+
+/// Set by [`set_plain_output`].
+static PLAIN_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+/// Set by [`set_json_output`].
+static JSON_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+/// Set by [`set_unattended`].
+static UNATTENDED: AtomicBool = AtomicBool::new(false);
+
+/// Turns colors off and switches tables to ASCII when `plain` is set or the
+/// `NO_COLOR` environment variable is, so output captured into logs, tickets
+/// or CI artifacts stays readable.
+pub fn set_plain_output(plain: bool) {
+    let plain = plain || env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+
+    PLAIN_OUTPUT.store(plain, Ordering::Relaxed);
+    console::set_colors_enabled(!plain);
+    console::set_colors_enabled_stderr(!plain);
+    inquire::set_global_render_config(if plain {
+        RenderConfig::empty()
+    } else {
+        RenderConfig::default()
+    });
+}
+
+/// Prints output as one JSON object per line instead of tables and styled
+/// text. Implies plain output.
+pub fn set_json_output(json: bool) {
+    JSON_OUTPUT.store(json, Ordering::Relaxed);
+    if json {
+        set_plain_output(true);
+    }
+}
+
+pub fn is_json_output() -> bool {
+    JSON_OUTPUT.load(Ordering::Relaxed)
+}
+
+/// Marks a single command run from command line arguments, with nobody at
+/// the terminal to page through tables or read echoed answers.
+pub fn set_unattended() {
+    UNATTENDED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_unattended() -> bool {
+    UNATTENDED.load(Ordering::Relaxed)
+}
+
+/// Table with the preset for the current output mode. Plain tables are drawn
+/// with ASCII borders and never styled.
+pub fn new_table() -> Table {
+    let mut table = Table::new();
+    if PLAIN_OUTPUT.load(Ordering::Relaxed) {
+        table.load_preset(ASCII_FULL).force_no_tty();
+    } else {
+        table.load_preset(UTF8_FULL);
+    }
+    table
+}
+
+/// Runs `fut` behind a spinner, printing its error if it fails. The output is
+/// handed back so follow-up prompts can run once the spinner has finished.
+pub async fn show_spinner<F, T>(message: &str, fut: F) -> Option<T>
+where
+    F: std::future::Future<Output = anyhow::Result<T>>,
+{
+    let spinner = start_spinner(message);
+
+    let result = fut.await;
+    ACTIVE_SPINNER.lock().unwrap().take();
+
+    match &result {
+        Ok(_) => spinner.finish_with_message("✅ Done"),
+        Err(e) => {
+            spinner
+                .finish_with_message(format!("{}", style(format!("Error : {}", e)).red().bold()));
+            // The spinner draws on stderr, out of the JSON stream
+            if is_json_output() {
+                render::stdout().error(format!("{e:#}"));
+            }
+        }
+    }
+
+    result.ok()
+}
+
+/// Like [`show_spinner`], but leaves the error to the caller to report.
+pub async fn try_spinner<F, T>(message: &str, fut: F) -> anyhow::Result<T>
 where
     F: std::future::Future<Output = anyhow::Result<T>>,
 {
+    let spinner = start_spinner(message);
+
+    let result = fut.await;
+    ACTIVE_SPINNER.lock().unwrap().take();
+
+    match &result {
+        Ok(_) => spinner.finish_with_message("✅ Done"),
+        Err(_) => spinner.finish_and_clear(),
+    }
+
+    result
+}
+
+fn start_spinner(message: &str) -> ProgressBar {
     let spinner = ProgressBar::new_spinner();
     spinner.set_style(
         ProgressStyle::with_template("{spinner:.cyan} {msg}")
@@ -15,17 +143,208 @@ where
     );
     spinner.enable_steady_tick(std::time::Duration::from_millis(100));
     spinner.set_message(message.to_string());
+    *ACTIVE_SPINNER.lock().unwrap() = Some(spinner.clone());
+    spinner
+}
 
-    let result = fut.await;
+/// Checklist for flows sending several transactions in a row. Steps run in
+/// order, each behind its own spinner, and a failure reports which step to
+/// resume from.
+pub struct StepChecklist {
+    steps: Vec<String>,
+    next: usize,
+}
 
-    match &result {
-        Ok(_) => spinner.finish_with_message("✅ Done"),
-        Err(e) => {
-            spinner.finish_with_message(format!("{}", style(format!("Error : {}", e)).red().bold()))
+impl StepChecklist {
+    pub fn new<S: Into<String>>(title: &str, steps: impl IntoIterator<Item = S>) -> Self {
+        let checklist = Self {
+            steps: steps.into_iter().map(Into::into).collect(),
+            next: 0,
+        };
+        checklist.write_plan(&mut render::stdout(), title);
+        checklist
+    }
+
+    fn write_plan<W: Write>(&self, out: &mut Renderer<W>, title: &str) {
+        out.title(title);
+        for (idx, step) in self.steps.iter().enumerate() {
+            out.note(format!("  ○ {}. {step}", idx + 1));
+        }
+    }
+
+    fn write_resume_hint<W: Write>(&self, out: &mut Renderer<W>, failed: usize) {
+        for pending in failed + 1..self.steps.len() {
+            out.note(format!("  ○ {}", self.label(pending)));
+        }
+        out.warning(format!(
+            "Resume from step {}: {}",
+            failed + 1,
+            self.steps[failed]
+        ));
+    }
+
+    fn label(&self, idx: usize) -> String {
+        format!("[{}/{}] {}", idx + 1, self.steps.len(), self.steps[idx])
+    }
+
+    /// Runs `fut` as the next step of the checklist.
+    pub async fn run<F, T>(&mut self, fut: F) -> anyhow::Result<T>
+    where
+        F: std::future::Future<Output = anyhow::Result<T>>,
+    {
+        let idx = self.next;
+        let label = self.label(idx);
+        let spinner = start_spinner(&label);
+
+        let result = fut.await;
+        ACTIVE_SPINNER.lock().unwrap().take();
+
+        match &result {
+            Ok(_) => {
+                self.next += 1;
+                spinner.finish_with_message(format!("{} {label}", style("✔").green()));
+            }
+            Err(_) => {
+                // The error itself is left to the caller to report
+                spinner.finish_with_message(format!(
+                    "{} {}",
+                    style("✘").red(),
+                    style(label).red().bold()
+                ));
+                self.write_resume_hint(&mut render::stdout(), idx);
+            }
+        }
+
+        result
+    }
+}
+
+/// Runs `render` once behind a spinner or, when `refresh_secs` is non-zero,
+/// keeps redrawing its output in place every `refresh_secs` until a key is
+/// pressed.
+pub async fn show_or_watch<F, Fut>(message: &str, refresh_secs: u64, mut render: F)
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = anyhow::Result<()>>,
+{
+    if refresh_secs == 0 {
+        show_spinner(message, render()).await;
+        return;
+    }
+
+    let term = Term::stdout();
+    let mut stop = tokio::task::spawn_blocking(|| Term::stdout().read_key());
+
+    loop {
+        term.clear_screen().ok();
+        render::stdout().note(format!(
+            "Refreshing every {refresh_secs}s, last update {} (press any key to stop)",
+            chrono::Local::now().format("%H:%M:%S")
+        ));
+
+        if let Err(e) = render().await {
+            print_error(e);
         }
+
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_secs(refresh_secs)) => {}
+            _ = &mut stop => break,
+        }
+    }
+}
+
+/// Replaces the message of the active spinner, so a future running behind
+/// [`show_spinner`] can report its progress in place.
+pub fn set_spinner_message(message: impl Into<String>) {
+    if let Some(spinner) = ACTIVE_SPINNER.lock().unwrap().as_ref() {
+        spinner.set_message(message.into());
+    }
+}
+
+/// Hides the active spinner while `f` runs, so prompts issued from inside a
+/// spinner-wrapped future are not overdrawn.
+pub fn suspend_spinner<R>(f: impl FnOnce() -> R) -> R {
+    // Taken out while suspended so nested calls don't suspend it twice
+    let spinner = ACTIVE_SPINNER.lock().unwrap().take();
+    match spinner {
+        Some(spinner) => {
+            let result = spinner.suspend(f);
+            *ACTIVE_SPINNER.lock().unwrap() = Some(spinner);
+            result
+        }
+        None => f(),
     }
 }
 
 pub fn print_error(message: impl std::fmt::Display) {
-    println!("{}", style(message).red().bold());
+    render::stdout().error(message);
+}
+
+/// Cluster, RPC and wallet shown above the main menu, in red on mainnet
+/// where transactions spend real SOL.
+pub fn write_menu_header<W: Write>(
+    out: &mut Renderer<W>,
+    cluster: Option<Cluster>,
+    rpc_url: &str,
+    wallet: &Pubkey,
+) {
+    let header = format!(
+        "\n● {} │ {} │ {}",
+        cluster.map_or("UNKNOWN CLUSTER".to_string(), |cluster| cluster
+            .name()
+            .to_uppercase()),
+        display_url(rpc_url),
+        short_pubkey(wallet)
+    );
+
+    match cluster {
+        Some(Cluster::Mainnet) => out.error(header),
+        Some(Cluster::Devnet | Cluster::Testnet) => out.success(header),
+        Some(Cluster::Custom) | None => out.warning(header),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::ui::render::capture, insta::assert_snapshot};
+
+    #[test]
+    fn test_step_checklist_resume_hint() {
+        let checklist = StepChecklist {
+            steps: vec![
+                "Create buffer account".to_string(),
+                "Write program data (3 chunks via TPU)".to_string(),
+                "Deploy program".to_string(),
+            ],
+            next: 1,
+        };
+
+        let output = capture(|out| {
+            checklist.write_plan(out, "DEPLOYMENT");
+            checklist.write_resume_hint(out, 1);
+        });
+
+        assert_snapshot!("step_checklist_resume_hint", output);
+    }
+
+    #[test]
+    fn test_menu_header() {
+        let wallet = Pubkey::new_from_array([1; 32]);
+
+        let output = capture(|out| {
+            write_menu_header(
+                out,
+                Some(Cluster::Mainnet),
+                "https://mainnet.helius-rpc.com/?api-key=secret",
+                &wallet,
+            );
+            write_menu_header(out, None, "http://localhost:8899", &wallet);
+        });
+
+        assert_eq!(
+            output,
+            "\n● MAINNET │ https://mainnet.helius-rpc.com/ │ 4vJ9...LKi\n\
+             \n● UNKNOWN CLUSTER │ http://localhost:8899 │ 4vJ9...LKi\n"
+        );
+    }
 }
@@ -0,0 +1,183 @@
+use {
+    crate::{
+        misc::helpers::lamports_to_sol,
+        prompt::{answers::AskRaw, prompt_select_data},
+        ui::{
+            new_table, print_error,
+            render::{self, Renderer},
+            suspend_spinner,
+        },
+    },
+    comfy_table::Cell,
+    inquire::MultiSelect,
+    std::{fmt, io::Write},
+};
+
+/// An entry offered to [`prompt_batch_selection`].
+pub struct BatchItem<T> {
+    pub value: T,
+    pub label: String,
+    pub details: String,
+    /// SOL moved or freed if this item is acted on.
+    pub lamports: u64,
+}
+
+impl<T> BatchItem<T> {
+    pub fn new(
+        value: T,
+        label: impl Into<String>,
+        details: impl Into<String>,
+        lamports: u64,
+    ) -> Self {
+        Self {
+            value,
+            label: label.into(),
+            details: details.into(),
+            lamports,
+        }
+    }
+}
+
+impl<T> fmt::Display for BatchItem<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}  {}  ({:.9} SOL)",
+            self.label,
+            self.details,
+            lamports_to_sol(self.lamports)
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+enum BatchDecision {
+    Confirm(usize),
+    Edit,
+    Cancel,
+}
+
+impl fmt::Display for BatchDecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BatchDecision::Confirm(count) => write!(f, "Confirm ({count} selected)"),
+            BatchDecision::Edit => write!(f, "Change selection"),
+            BatchDecision::Cancel => write!(f, "Cancel"),
+        }
+    }
+}
+
+/// Lets the user check/uncheck `items` before a batch operation, then shows
+/// what was picked with the total SOL affected and asks for confirmation.
+///
+/// Every item starts checked. Returns the values of the confirmed items, or
+/// nothing if the user cancels.
+pub fn prompt_batch_selection<T>(action: &str, items: Vec<BatchItem<T>>) -> anyhow::Result<Vec<T>> {
+    let mut selected: Vec<usize> = (0..items.len()).collect();
+
+    let confirmed = suspend_spinner(|| -> anyhow::Result<bool> {
+        loop {
+            let chosen = MultiSelect::new(
+                &format!("Select accounts to {action}:"),
+                items.iter().collect(),
+            )
+            .with_default(&selected)
+            .with_help_message("space to toggle, → all, ← none, enter to continue")
+            .ask_raw()?;
+            selected = chosen.into_iter().map(|option| option.index).collect();
+
+            if selected.is_empty() {
+                print_error("Nothing selected");
+                return Ok(false);
+            }
+
+            write_selection(&mut render::stdout(), &items, &selected);
+
+            match prompt_select_data(
+                &format!("Ready to {action} the selected accounts:"),
+                vec![
+                    BatchDecision::Confirm(selected.len()),
+                    BatchDecision::Edit,
+                    BatchDecision::Cancel,
+                ],
+            ) {
+                BatchDecision::Confirm(_) => return Ok(true),
+                BatchDecision::Edit => continue,
+                BatchDecision::Cancel => return Ok(false),
+            }
+        }
+    })?;
+
+    if !confirmed {
+        render::stdout().warning("No accounts were changed.");
+        return Ok(Vec::new());
+    }
+
+    Ok(items
+        .into_iter()
+        .enumerate()
+        .filter(|(idx, _)| selected.contains(idx))
+        .map(|(_, item)| item.value)
+        .collect())
+}
+
+fn selected_lamports<T>(items: &[BatchItem<T>], selected: &[usize]) -> u64 {
+    selected.iter().map(|idx| items[*idx].lamports).sum()
+}
+
+fn write_selection<T, W: Write>(out: &mut Renderer<W>, items: &[BatchItem<T>], selected: &[usize]) {
+    let mut table = new_table();
+    table.set_header(
+        ["#", "Account", "Details", "SOL"]
+            .into_iter()
+            .map(|header| Cell::new(header).add_attribute(comfy_table::Attribute::Bold)),
+    );
+
+    for (row, idx) in selected.iter().enumerate() {
+        let item = &items[*idx];
+        table.add_row(vec![
+            Cell::new(row + 1),
+            Cell::new(&item.label),
+            Cell::new(&item.details),
+            Cell::new(format!("{:.9}", lamports_to_sol(item.lamports))),
+        ]);
+    }
+
+    out.table(table);
+    out.success(format!(
+        "Selected {} of {} accounts, {:.9} SOL affected",
+        selected.len(),
+        items.len(),
+        lamports_to_sol(selected_lamports(items, selected))
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::ui::render::capture, insta::assert_snapshot};
+
+    #[test]
+    fn test_selected_lamports_only_counts_checked_items() {
+        let items = vec![
+            BatchItem::new(1, "a", "", 1_000),
+            BatchItem::new(2, "b", "", 20_000),
+            BatchItem::new(3, "c", "", 300_000),
+        ];
+
+        assert_eq!(selected_lamports(&items, &[0, 2]), 301_000);
+        assert_eq!(selected_lamports(&items, &[]), 0);
+    }
+
+    #[test]
+    fn test_write_selection() {
+        let items = vec![
+            BatchItem::new(1, "Buffer1", "2048 bytes of program data", 15_144_960),
+            BatchItem::new(2, "Buffer2", "512 bytes of program data", 4_454_400),
+            BatchItem::new(3, "Buffer3", "64 bytes of program data", 1_336_320),
+        ];
+
+        let output = capture(|out| write_selection(out, &items, &[0, 2]));
+
+        assert_snapshot!("write_selection", output);
+    }
+}
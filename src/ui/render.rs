@@ -0,0 +1,229 @@
+use {
+    crate::ui::{is_json_output, new_table},
+    comfy_table::{Attribute, Cell, Color, Table},
+    console::{colors_enabled, strip_ansi_codes, style},
+    serde_json::{Map, Value, json},
+    std::{
+        fmt::Display,
+        io::{self, Stdout, Write},
+    },
+};
+
+/// Writes command output to any [`Write`] instead of through `println!`, so
+/// what a command shows can be captured and snapshot tested.
+pub struct Renderer<W: Write> {
+    out: W,
+    /// Emit ANSI styling. Off for captured output and in plain output mode.
+    styled: bool,
+    /// Write every message and table as a JSON object on its own line.
+    json: bool,
+}
+
+/// Renderer for the terminal, styled unless colors are turned off.
+pub fn stdout() -> Renderer<Stdout> {
+    Renderer::new(io::stdout(), colors_enabled()).with_json(is_json_output())
+}
+
+impl<W: Write> Renderer<W> {
+    pub fn new(out: W, styled: bool) -> Self {
+        Self {
+            out,
+            styled,
+            json: false,
+        }
+    }
+
+    pub fn with_json(mut self, json: bool) -> Self {
+        self.json = json;
+        self
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+
+    fn write_json(&mut self, value: Value) {
+        writeln!(self.out, "{value}").expect("failed to write output");
+    }
+
+    /// `{"type": kind, "text": ...}`, with any styling the caller applied
+    /// stripped.
+    fn write_event(&mut self, kind: &str, text: impl Display) {
+        let text = strip_ansi_codes(&text.to_string()).into_owned();
+        self.write_json(json!({ "type": kind, "text": text }));
+    }
+
+    /// Writes `text` as is, followed by a newline.
+    pub fn line(&mut self, text: impl Display) {
+        if self.json {
+            return self.write_event("line", text);
+        }
+        writeln!(self.out, "{text}").expect("failed to write output");
+    }
+
+    /// Section title, separated from the output above by a blank line.
+    pub fn title(&mut self, title: impl Display) {
+        if self.json {
+            return self.write_event("title", title);
+        }
+        let title = style(title).green().bold().force_styling(self.styled);
+        self.line(format_args!("\n{title}"));
+    }
+
+    pub fn table(&mut self, mut table: Table) {
+        if self.json {
+            return self.write_json(table_json(&table));
+        }
+        if !self.styled {
+            table.force_no_tty();
+        }
+        self.line(table);
+    }
+
+    pub fn success(&mut self, message: impl Display) {
+        if self.json {
+            return self.write_event("success", message);
+        }
+        let message = style(message).green().bold().force_styling(self.styled);
+        self.line(message);
+    }
+
+    pub fn warning(&mut self, message: impl Display) {
+        if self.json {
+            return self.write_event("warning", message);
+        }
+        let message = style(message).yellow().force_styling(self.styled);
+        self.line(message);
+    }
+
+    pub fn error(&mut self, message: impl Display) {
+        if self.json {
+            return self.write_event("error", message);
+        }
+        let message = style(message).red().bold().force_styling(self.styled);
+        self.line(message);
+    }
+
+    /// Secondary details such as signatures and addresses.
+    pub fn note(&mut self, message: impl Display) {
+        if self.json {
+            return self.write_event("note", message);
+        }
+        let message = style(message).dim().force_styling(self.styled);
+        self.line(message);
+    }
+}
+
+/// Bold cyan header cell used by the `Field | Value` tables.
+pub fn header_cell(text: &str) -> Cell {
+    Cell::new(text)
+        .add_attribute(Attribute::Bold)
+        .fg(Color::Cyan)
+}
+
+/// The two column `Field | Value` table most commands display.
+pub fn field_table<V: Display>(rows: impl IntoIterator<Item = (&'static str, V)>) -> Table {
+    let mut table = new_table();
+    table.set_header(vec![header_cell("Field"), header_cell("Value")]);
+    for (field, value) in rows {
+        table.add_row(vec![Cell::new(field), Cell::new(value)]);
+    }
+    table
+}
+
+/// `Field | Value` tables as `{"type": "fields", "fields": {...}}`, others as
+/// `{"type": "table", "headers": [...], "rows": [[...]]}`.
+fn table_json(table: &Table) -> Value {
+    let cells = |row: &comfy_table::Row| -> Vec<String> {
+        row.cell_iter()
+            .map(|cell| strip_ansi_codes(&cell.content()).into_owned())
+            .collect()
+    };
+    let headers = table.header().map(cells).unwrap_or_default();
+    let rows: Vec<Vec<String>> = table.row_iter().map(cells).collect();
+
+    if headers == ["Field", "Value"] {
+        let fields: Map<String, Value> = rows
+            .into_iter()
+            .filter_map(|row| match <[String; 2]>::try_from(row) {
+                Ok([field, value]) => Some((field, Value::String(value))),
+                Err(_) => None,
+            })
+            .collect();
+        return json!({ "type": "fields", "fields": fields });
+    }
+
+    json!({ "type": "table", "headers": headers, "rows": rows })
+}
+
+/// Renders without styling into a string, for snapshot tests.
+#[cfg(test)]
+pub fn capture(render: impl FnOnce(&mut Renderer<Vec<u8>>)) -> String {
+    let mut renderer = Renderer::new(Vec::new(), false);
+    render(&mut renderer);
+    String::from_utf8(renderer.into_inner()).expect("rendered output should be UTF-8")
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, insta::assert_snapshot};
+
+    #[test]
+    fn test_messages() {
+        let output = capture(|out| {
+            out.success("Transfer successful!");
+            out.warning("Deactivation cancelled.");
+            out.error("Insufficient balance");
+            out.note("Signature: 5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnb");
+            out.line("Plain line");
+        });
+
+        assert_snapshot!("messages", output);
+    }
+
+    #[test]
+    fn test_titled_field_table() {
+        let output = capture(|out| {
+            out.line("Before");
+            out.title("EPOCH INFORMATION");
+            out.table(field_table([("Epoch", "812"), ("Slot Index", "1024")]));
+        });
+
+        assert_snapshot!("titled_field_table", output);
+    }
+
+    #[test]
+    fn test_json_output() {
+        let mut out = Renderer::new(Vec::new(), false).with_json(true);
+        out.title("EPOCH INFORMATION");
+        out.table(field_table([("Epoch", "812"), ("Slot Index", "1024")]));
+        let mut table = new_table();
+        table.set_header(vec!["Address", "Lamports"]);
+        table.add_row(vec!["Vote111111111111111111111111111111111111111", "42"]);
+        out.table(table);
+        out.warning(
+            style("Deactivation cancelled.")
+                .yellow()
+                .force_styling(true),
+        );
+        let output = String::from_utf8(out.into_inner()).expect("rendered output should be UTF-8");
+
+        let events: Vec<Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("every line should be JSON"))
+            .collect();
+        assert_eq!(
+            events,
+            [
+                json!({ "type": "title", "text": "EPOCH INFORMATION" }),
+                json!({ "type": "fields", "fields": { "Epoch": "812", "Slot Index": "1024" } }),
+                json!({
+                    "type": "table",
+                    "headers": ["Address", "Lamports"],
+                    "rows": [["Vote111111111111111111111111111111111111111", "42"]],
+                }),
+                json!({ "type": "warning", "text": "Deactivation cancelled." }),
+            ]
+        );
+    }
+}
@@ -0,0 +1,383 @@
+use {
+    crate::{
+        constants::{MIN_TABLE_CELL_WIDTH, TABLE_PAGE_SIZE},
+        prompt::{answers::AskRaw, prompt_input_data, prompt_select_data},
+        ui::{
+            is_json_output, is_unattended, new_table, print_error,
+            render::{self, Renderer, header_cell},
+            suspend_spinner,
+        },
+    },
+    comfy_table::{Cell, Table},
+    console::Term,
+    inquire::MultiSelect,
+    std::{fmt, io::Write},
+};
+
+/// Table renderer shared by commands listing many rows or wide values.
+///
+/// Rows are paginated and long values are truncated to fit the terminal.
+/// When either happens the user gets a menu to flip pages, hide columns,
+/// expand a row to its full values or turn truncation off.
+pub struct PagedTable {
+    title: String,
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+    page_size: usize,
+}
+
+/// What is currently visible of a [`PagedTable`].
+#[derive(Debug, Clone)]
+struct TableView {
+    page: usize,
+    visible: Vec<bool>,
+    truncate: bool,
+}
+
+#[derive(Debug, Clone)]
+enum TableAction {
+    NextPage,
+    PreviousPage,
+    Columns,
+    ExpandRow,
+    ToggleTruncation(bool),
+    Custom(String),
+    Done,
+}
+
+impl fmt::Display for TableAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TableAction::NextPage => write!(f, "Next page"),
+            TableAction::PreviousPage => write!(f, "Previous page"),
+            TableAction::Columns => write!(f, "Show/hide columns"),
+            TableAction::ExpandRow => write!(f, "Expand a row"),
+            TableAction::ToggleTruncation(true) => write!(f, "Show full values"),
+            TableAction::ToggleTruncation(false) => write!(f, "Truncate long values"),
+            TableAction::Custom(label) => write!(f, "{label}"),
+            TableAction::Done => write!(f, "Done"),
+        }
+    }
+}
+
+impl PagedTable {
+    pub fn new<S: Into<String>>(
+        title: impl Into<String>,
+        headers: impl IntoIterator<Item = S>,
+    ) -> Self {
+        Self {
+            title: title.into(),
+            headers: headers.into_iter().map(Into::into).collect(),
+            rows: Vec::new(),
+            page_size: TABLE_PAGE_SIZE,
+        }
+    }
+
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    pub fn add_row<S: Into<String>>(&mut self, row: impl IntoIterator<Item = S>) {
+        self.rows.push(row.into_iter().map(Into::into).collect());
+    }
+
+    fn pages(&self) -> usize {
+        self.rows.len().div_ceil(self.page_size).max(1)
+    }
+
+    /// Widest a cell may be so that all visible columns fit `term_width`.
+    fn max_cell_width(&self, visible: &[bool], term_width: usize) -> usize {
+        let columns = visible.iter().filter(|v| **v).count().max(1);
+        // Every column adds a border and one space of padding on each side
+        let available = term_width.saturating_sub(3 * columns + 1);
+        (available / columns).max(MIN_TABLE_CELL_WIDTH)
+    }
+
+    /// Renders one page, returning whether any value had to be truncated.
+    fn render(&self, view: &TableView, term_width: usize) -> (Table, bool) {
+        let max_width = self.max_cell_width(&view.visible, term_width);
+        let mut truncated = false;
+
+        let mut table = new_table();
+        table.set_header(
+            self.headers
+                .iter()
+                .zip(&view.visible)
+                .filter(|(_, visible)| **visible)
+                .map(|(header, _)| Cell::new(header).add_attribute(comfy_table::Attribute::Bold)),
+        );
+
+        let start = view.page * self.page_size;
+        for row in self.rows.iter().skip(start).take(self.page_size) {
+            table.add_row(
+                row.iter()
+                    .zip(&view.visible)
+                    .filter(|(_, visible)| **visible)
+                    .map(|(value, _)| {
+                        if view.truncate && value.chars().count() > max_width {
+                            truncated = true;
+                            Cell::new(truncate_middle(value, max_width))
+                        } else {
+                            Cell::new(value)
+                        }
+                    }),
+            );
+        }
+
+        (table, truncated)
+    }
+
+    fn write_page<W: Write>(
+        &self,
+        out: &mut Renderer<W>,
+        view: &TableView,
+        term_width: usize,
+    ) -> bool {
+        let (table, truncated) = self.render(view, term_width);
+
+        let title = if self.pages() > 1 {
+            format!(
+                "{} (page {}/{}, {} rows)",
+                self.title,
+                view.page + 1,
+                self.pages(),
+                self.rows.len()
+            )
+        } else {
+            self.title.clone()
+        };
+
+        out.title(title);
+        out.table(table);
+
+        truncated
+    }
+
+    /// Prints the table, only prompting when it spans several pages or has
+    /// truncated values.
+    pub fn show(&self) -> anyhow::Result<()> {
+        self.browse(None, |_| Ok(()))
+    }
+
+    /// Like [`PagedTable::show`], with an extra menu entry calling `action`
+    /// with the 1-based row number the user picks.
+    pub fn show_with_action(
+        &self,
+        label: &str,
+        action: impl FnMut(usize) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        self.browse(Some(label), action)
+    }
+
+    fn browse(
+        &self,
+        custom: Option<&str>,
+        mut action: impl FnMut(usize) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        // Without a terminal there is nothing to fit, nor anyone to answer
+        let Some((_, term_width)) = Term::stdout()
+            .size_checked()
+            .filter(|_| !is_unattended() && !is_json_output())
+        else {
+            let mut view = TableView {
+                page: 0,
+                visible: vec![true; self.headers.len()],
+                truncate: false,
+            };
+            for page in 0..self.pages() {
+                view.page = page;
+                self.write_page(&mut render::stdout(), &view, usize::MAX);
+            }
+            return Ok(());
+        };
+
+        let mut view = TableView {
+            page: 0,
+            visible: vec![true; self.headers.len()],
+            truncate: true,
+        };
+
+        suspend_spinner(|| {
+            loop {
+                let truncated = self.write_page(&mut render::stdout(), &view, term_width as usize);
+
+                if self.pages() == 1 && !truncated && view.truncate && custom.is_none() {
+                    return Ok(());
+                }
+
+                let mut actions = Vec::new();
+                if view.page + 1 < self.pages() {
+                    actions.push(TableAction::NextPage);
+                }
+                if view.page > 0 {
+                    actions.push(TableAction::PreviousPage);
+                }
+                if let Some(label) = custom {
+                    actions.push(TableAction::Custom(label.to_string()));
+                }
+                actions.extend([
+                    TableAction::ExpandRow,
+                    TableAction::Columns,
+                    TableAction::ToggleTruncation(view.truncate),
+                    TableAction::Done,
+                ]);
+
+                match prompt_select_data(&format!("{}:", self.title), actions) {
+                    TableAction::NextPage => view.page += 1,
+                    TableAction::PreviousPage => view.page -= 1,
+                    TableAction::Columns => {
+                        let selected: Vec<usize> = (0..self.headers.len())
+                            .filter(|i| view.visible[*i])
+                            .collect();
+                        let chosen = MultiSelect::new("Columns to show:", self.headers.clone())
+                            .with_default(&selected)
+                            .ask_raw()?;
+
+                        if chosen.is_empty() {
+                            print_error("At least one column must stay visible");
+                            continue;
+                        }
+                        view.visible = vec![false; self.headers.len()];
+                        for option in chosen {
+                            view.visible[option.index] = true;
+                        }
+                    }
+                    TableAction::ExpandRow => match self.prompt_row() {
+                        Some(row) => self.write_row(&mut render::stdout(), row),
+                        None => continue,
+                    },
+                    TableAction::ToggleTruncation(_) => view.truncate = !view.truncate,
+                    TableAction::Custom(_) => {
+                        if let Some(row) = self.prompt_row()
+                            && let Err(e) = action(row + 1)
+                        {
+                            print_error(e);
+                        }
+                    }
+                    TableAction::Done => return Ok(()),
+                }
+            }
+        })
+    }
+
+    /// Asks for a 1-based row number and returns its index.
+    fn prompt_row(&self) -> Option<usize> {
+        let row: usize = prompt_input_data(&format!("Enter row # (1-{}):", self.rows.len()));
+
+        match row.checked_sub(1).filter(|i| *i < self.rows.len()) {
+            Some(index) => Some(index),
+            None => {
+                print_error(format!("Row # must be between 1 and {}", self.rows.len()));
+                None
+            }
+        }
+    }
+
+    fn write_row<W: Write>(&self, out: &mut Renderer<W>, index: usize) {
+        let mut table = new_table();
+        table.set_header(vec![header_cell("Field"), header_cell("Value")]);
+
+        for (header, value) in self.headers.iter().zip(&self.rows[index]) {
+            table.add_row(vec![Cell::new(header), Cell::new(value)]);
+        }
+
+        out.table(table);
+    }
+}
+
+/// Shortens `value` to `max_width` characters by eliding its middle, which
+/// keeps both ends of addresses and signatures recognisable.
+pub fn truncate_middle(value: &str, max_width: usize) -> String {
+    let len = value.chars().count();
+    if len <= max_width {
+        return value.to_string();
+    }
+
+    let keep = max_width.saturating_sub(1);
+    let head = keep.div_ceil(2);
+    let tail = keep - head;
+
+    let mut truncated: String = value.chars().take(head).collect();
+    truncated.push('…');
+    truncated.extend(value.chars().skip(len - tail));
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::ui::render::capture, insta::assert_snapshot};
+
+    #[test]
+    fn test_truncate_middle() {
+        assert_eq!(truncate_middle("short", 10), "short");
+        assert_eq!(
+            truncate_middle("7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU", 11),
+            "7xKXt…sgAsU"
+        );
+    }
+
+    #[test]
+    fn test_render_hides_columns_and_paginates() {
+        let mut table = PagedTable::new("TEST", ["#", "Address", "Balance"]).with_page_size(2);
+        for i in 1..=3 {
+            table.add_row([i.to_string(), format!("address-{i}"), format!("{i}.0")]);
+        }
+
+        let view = TableView {
+            page: 1,
+            visible: vec![true, false, true],
+            truncate: true,
+        };
+        let (rendered, truncated) = table.render(&view, 80);
+        let rendered = rendered.to_string();
+
+        assert!(!truncated);
+        assert!(rendered.contains("3.0"));
+        assert!(!rendered.contains("address-3"));
+        assert!(!rendered.contains("1.0"));
+    }
+
+    #[test]
+    fn test_render_truncates_to_terminal_width() {
+        let mut table = PagedTable::new("TEST", ["Signature"]);
+        table.add_row(["x".repeat(200)]);
+
+        let view = TableView {
+            page: 0,
+            visible: vec![true],
+            truncate: true,
+        };
+
+        let full = TableView {
+            truncate: false,
+            ..view.clone()
+        };
+
+        assert!(table.render(&view, 40).1);
+        assert!(!table.render(&full, 40).1);
+    }
+
+    #[test]
+    fn test_write_page_and_row() {
+        let mut table = PagedTable::new("LARGEST ACCOUNTS", ["#", "Address", "Balance (SOL)"])
+            .with_page_size(2);
+        for i in 1..=3 {
+            table.add_row([i.to_string(), format!("address-{i}"), format!("{i}.00")]);
+        }
+
+        let view = TableView {
+            page: 1,
+            visible: vec![true, true, true],
+            truncate: true,
+        };
+
+        let output = capture(|out| {
+            out.line("$ largest accounts");
+            table.write_page(out, &view, 80);
+            table.write_row(out, 0);
+        });
+
+        assert_snapshot!("write_page_and_row", output);
+    }
+}